@@ -4,6 +4,8 @@ pub const MAX_NUMBER_OF_INTENT_SIGNATURES: usize = 16;
 
 pub const MAX_NUMBER_OF_BLOBS: usize = 64;
 
+pub const MAX_NUMBER_OF_CHILD_INTENTS: usize = 16;
+
 /// The minimum value of tip percentage
 ///
 /// 100 means 100%
@@ -0,0 +1,40 @@
+use crate::sbor::rust::prelude::*;
+use radix_engine_common::crypto::*;
+
+/// Metered cryptographic utilities, so that blueprints don't need to implement them in WASM.
+///
+/// Signatures are verified against the hash of a message, as is the case for transaction signatures.
+pub trait ClientCryptoUtilsApi<E> {
+    fn blake2b_256_hash(&mut self, data: Vec<u8>) -> Result<Hash, E>;
+
+    fn keccak256_hash(&mut self, data: Vec<u8>) -> Result<Hash, E>;
+
+    fn sha256_hash(&mut self, data: Vec<u8>) -> Result<Hash, E>;
+
+    fn ed25519_verify(
+        &mut self,
+        message_hash: &Hash,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> Result<bool, E>;
+
+    fn secp256k1_verify(
+        &mut self,
+        message_hash: &Hash,
+        public_key: &Secp256k1PublicKey,
+        signature: &Secp256k1Signature,
+    ) -> Result<bool, E>;
+
+    fn secp256k1_recover(
+        &mut self,
+        message_hash: &Hash,
+        signature: &Secp256k1Signature,
+    ) -> Result<Option<Secp256k1PublicKey>, E>;
+
+    /// Verifies an aggregated signature over distinct messages, using the `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite.
+    fn bls12381_v1_aggregate_verify(
+        &mut self,
+        pub_keys_and_msgs: Vec<(Bls12381G1PublicKey, Vec<u8>)>,
+        signature: &Bls12381G2Signature,
+    ) -> Result<bool, E>;
+}
@@ -1,3 +1,4 @@
 pub mod costing_api;
+pub mod crypto_utils_api;
 pub mod execution_trace_api;
 pub mod transaction_runtime_api;
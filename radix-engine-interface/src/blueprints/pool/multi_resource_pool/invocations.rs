@@ -6,6 +6,8 @@ use radix_engine_common::math::*;
 use radix_engine_common::prelude::*;
 use radix_engine_common::*;
 
+pub const MULTI_RESOURCE_POOL_BLUEPRINT: &str = "MultiResourcePool";
+
 define_type_info_marker!(Some(POOL_PACKAGE), MultiResourcePool);
 
 define_invocation! {
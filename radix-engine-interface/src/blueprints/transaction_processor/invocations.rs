@@ -6,7 +6,9 @@ pub const TRANSACTION_PROCESSOR_BLUEPRINT: &str = "TransactionProcessor";
 
 pub const TRANSACTION_PROCESSOR_RUN_IDENT: &str = "run";
 
-// TransactionProcessorInput in the engine
+pub const TRANSACTION_PROCESSOR_RUN_CHILD_IDENT: &str = "run_child";
+
+// TransactionProcessorInput and TransactionProcessorRunChildInput in the engine
 
 pub type TransactionProcessorRunOutput = Vec<InstructionOutput>;
 
@@ -6,7 +6,9 @@ use radix_engine_store_interface::interface::{
 use radix_engine_stores::{
     memory_db::InMemorySubstateDatabase,
     rocks_db::RocksdbSubstateStore,
-    rocks_db_with_merkle_tree::{BlockBasedOptions, Options, RocksDBWithMerkleTreeSubstateStore},
+    rocks_db_with_merkle_tree::{
+        BlockBasedOptions, Options, RocksDBWithMerkleTreeSubstateStore, StateTreePruningConfig,
+    },
 };
 use std::{cell::RefCell, collections::BTreeMap, path::PathBuf, time::Duration};
 
@@ -56,7 +58,11 @@ impl SubstateStoreWithMetrics<RocksDBWithMerkleTreeSubstateStore> {
         opt.set_block_based_table_factory(&factory_opts);
 
         Self {
-            db: RocksDBWithMerkleTreeSubstateStore::with_options(&opt, path, true),
+            db: RocksDBWithMerkleTreeSubstateStore::with_options(
+                &opt,
+                path,
+                Some(StateTreePruningConfig::immediate()),
+            ),
             commit_set_metrics: RefCell::new(BTreeMap::new()),
             commit_delete_metrics: RefCell::new(BTreeMap::new()),
             read_metrics: RefCell::new(BTreeMap::new()),
@@ -102,11 +108,12 @@ impl<S: SubstateDatabase + CommittableSubstateDatabase> SubstateDatabase
         }
     }
 
-    fn list_entries(
+    fn list_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
-        self.db.list_entries(partition_key)
+        self.db.list_entries_from(partition_key, from_sort_key)
     }
 }
 
@@ -1,7 +1,9 @@
 mod accounter;
+mod prefetch;
 mod traverse;
 mod vault_finder;
 
 pub use accounter::*;
+pub use prefetch::*;
 pub use traverse::*;
 pub use vault_finder::*;
@@ -0,0 +1,139 @@
+use super::{StateTreeTraverser, StateTreeVisitor};
+use radix_engine::prelude::*;
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
+use radix_engine_interface::blueprints::account::{
+    ACCOUNT_LOCK_CONTINGENT_FEE_IDENT, ACCOUNT_LOCK_FEE_AND_WITHDRAW_IDENT,
+    ACCOUNT_LOCK_FEE_AND_WITHDRAW_NON_FUNGIBLES_IDENT, ACCOUNT_LOCK_FEE_IDENT,
+};
+use radix_engine_store_interface::db_key_mapper::{DatabaseKeyMapper, SpreadPrefixKeyMapper};
+use radix_engine_store_interface::interface::{DbSubstateKey, SubstateDatabase};
+use transaction::model::{DynamicGlobalAddress, Executable, InstructionV1};
+
+/// The depth of the owned nodes' subtree traversed from each component called by a transaction,
+/// i.e. covering the vaults owned by the component directly or via its collections (e.g. the
+/// resource vaults of an account).
+pub const PREFETCH_TRAVERSAL_MAX_DEPTH: u32 = 1;
+
+/// The nodes which a transaction is likely to touch, derived statically (i.e. without executing
+/// it) by [`derive_prefetch_hints()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchHints {
+    /// The likely-touched nodes: the fee payers first, then the called components (each followed
+    /// by its owned nodes), and then all the other nodes referenced by the transaction.
+    pub nodes: IndexSet<NodeId>,
+}
+
+impl PrefetchHints {
+    /// Returns the keys of the substates read on practically every access to the hinted nodes:
+    /// their type info, and the first field of their main partition (e.g. a vault's balance or a
+    /// component's state).
+    pub fn to_substate_keys(&self) -> Vec<DbSubstateKey> {
+        self.nodes
+            .iter()
+            .flat_map(|node_id| {
+                [
+                    (TYPE_INFO_FIELD_PARTITION, TypeInfoField::TypeInfo.into()),
+                    (MAIN_BASE_PARTITION, SubstateKey::Field(0u8)),
+                ]
+                .map(|(partition_number, substate_key)| {
+                    (
+                        SpreadPrefixKeyMapper::to_db_partition_key(node_id, partition_number),
+                        SpreadPrefixKeyMapper::to_db_sort_key(&substate_key),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Derives the nodes which the given transaction is likely to touch, from:
+/// - the addresses of the components called by its manifest (with the fee payers, i.e. the
+///   targets of the `lock_fee` family of methods, first),
+/// - the nodes owned by these components (found by a [`StateTreeTraverser`] of a limited depth,
+///   see [`PREFETCH_TRAVERSAL_MAX_DEPTH`]),
+/// - all other nodes referenced by the transaction (e.g. resources and packages).
+///
+/// Note: the traversal itself reads the given database.
+pub fn derive_prefetch_hints<S: SubstateDatabase>(
+    substate_db: &S,
+    executable: &Executable,
+) -> PrefetchHints {
+    let mut fee_payers = index_set_new();
+    let mut called_components = index_set_new();
+    // Note: the transaction processor rejects an undecodable manifest, hence no hints for it
+    let instructions = manifest_decode::<Vec<InstructionV1>>(executable.encoded_instructions())
+        .unwrap_or_default();
+    for instruction in instructions {
+        if let InstructionV1::CallMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            ..
+        } = instruction
+        {
+            if is_lock_fee_method(&method_name) {
+                fee_payers.insert(address.into_node_id());
+            } else {
+                called_components.insert(address.into_node_id());
+            }
+        }
+    }
+
+    let system_db_reader = SystemDatabaseReader::new(substate_db);
+    let mut node_collector = NodeCollector {
+        nodes: index_set_new(),
+    };
+    for node_id in fee_payers.into_iter().chain(called_components) {
+        if node_collector.nodes.contains(&node_id) {
+            continue;
+        }
+        // (the component may not exist yet, e.g. a virtual account)
+        if system_db_reader.get_type_info(&node_id).is_err() {
+            node_collector.nodes.insert(node_id);
+            continue;
+        }
+        StateTreeTraverser::new(
+            substate_db,
+            &mut node_collector,
+            PREFETCH_TRAVERSAL_MAX_DEPTH,
+        )
+        .traverse_subtree(None, node_id);
+    }
+
+    let mut nodes = node_collector.nodes;
+    for reference in executable.references() {
+        nodes.insert(reference.0);
+    }
+    PrefetchHints { nodes }
+}
+
+/// Prefetches the substates of the nodes which the given transaction is likely to touch (see
+/// [`derive_prefetch_hints()`]), to be run before the transaction's execution.
+pub fn prefetch_for_transaction<S: SubstateDatabase>(substate_db: &S, executable: &Executable) {
+    let substate_keys = derive_prefetch_hints(substate_db, executable).to_substate_keys();
+    substate_db.prefetch(&substate_keys);
+}
+
+fn is_lock_fee_method(method_name: &str) -> bool {
+    [
+        ACCOUNT_LOCK_FEE_IDENT,
+        ACCOUNT_LOCK_CONTINGENT_FEE_IDENT,
+        ACCOUNT_LOCK_FEE_AND_WITHDRAW_IDENT,
+        ACCOUNT_LOCK_FEE_AND_WITHDRAW_NON_FUNGIBLES_IDENT,
+    ]
+    .contains(&method_name)
+}
+
+struct NodeCollector {
+    nodes: IndexSet<NodeId>,
+}
+
+impl StateTreeVisitor for NodeCollector {
+    fn visit_node_id(
+        &mut self,
+        _parent_id: Option<&(NodeId, PartitionNumber, SubstateKey)>,
+        node_id: &NodeId,
+        _depth: u32,
+    ) {
+        self.nodes.insert(*node_id);
+    }
+}
@@ -1,3 +1,4 @@
+use core::ops::RangeInclusive;
 use radix_engine_common::Sbor;
 use utils::prelude::index_map_new;
 use utils::prelude::vec;
@@ -140,12 +141,52 @@ pub trait SubstateDatabase {
         sort_key: &DbSortKey,
     ) -> Option<DbSubstateValue>;
 
+    /// Iterates over all entries of the given partition (starting either from the beginning, or
+    /// from the given [`DbSortKey`]), in a lexicographical order (ascending) of the
+    /// [`DbSortKey`]s.
+    /// Note: If the exact given starting key does not exist, the iteration starts with its
+    /// immediate successor.
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_>;
+
     /// Iterates over all entries of the given partition, in a lexicographical order (ascending)
     /// of the [`DbSortKey`]s.
+    /// This is a convenience method, equivalent to [`Self::list_entries_from()`] without a
+    /// starting key.
     fn list_entries(
         &self,
         partition_key: &DbPartitionKey,
-    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_>;
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        self.list_entries_from(partition_key, None)
+    }
+
+    /// Iterates over the entries of the given partition which have [`DbSortKey`]s within the
+    /// given range (i.e. from the inclusive start, up to the exclusive end; a missing bound means
+    /// no bound), in a lexicographical order (ascending) of the [`DbSortKey`]s.
+    fn list_entries_in_range(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+        to_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let iter = self.list_entries_from(partition_key, from_sort_key);
+        match to_sort_key.cloned() {
+            Some(to_sort_key) => {
+                Box::new(iter.take_while(move |(sort_key, _)| *sort_key < to_sort_key))
+            }
+            None => iter,
+        }
+    }
+
+    /// Hints that the given substates are likely to be read soon (e.g. by an upcoming
+    /// transaction), so that the database may load them in bulk ahead of the individual reads.
+    /// The default implementation does nothing.
+    /// Note: this is only an optimization - the results of any subsequent reads must not depend on
+    /// whether (or which) substates were prefetched.
+    fn prefetch(&self, _substate_keys: &[DbSubstateKey]) {}
 }
 
 /// A write interface between Track and a database vendor.
@@ -159,3 +200,93 @@ pub trait ListableSubstateDatabase {
     /// Iterates over all partition keys, in an arbitrary order.
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_>;
 }
+
+/// A read interface of a database capable of serving substates as of a past state version.
+/// The range of readable versions is typically bounded by the database's retention policy.
+pub trait VersionedSubstateDatabase {
+    /// Returns the range of state versions which can currently be read.
+    /// The upper bound is always the current state version.
+    fn get_readable_versions(&self) -> RangeInclusive<u64>;
+
+    /// Reads a substate value by its partition and sort key, as of the given state version, or
+    /// [`Option::None`] if it did not exist at that version.
+    fn get_substate_at_version(
+        &self,
+        state_version: u64,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Result<Option<DbSubstateValue>, VersionedReadError>;
+
+    /// Lists all entries of the given partition (starting either from the beginning, or from the
+    /// given [`DbSortKey`]) as of the given state version, in a lexicographical order (ascending)
+    /// of the [`DbSortKey`]s.
+    fn list_entries_at_version(
+        &self,
+        state_version: u64,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Result<Box<dyn Iterator<Item = PartitionEntry> + '_>, VersionedReadError>;
+}
+
+/// An error of a read from a [`VersionedSubstateDatabase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedReadError {
+    /// The given state version is outside of the readable range.
+    VersionNotReadable {
+        state_version: u64,
+        readable_versions: RangeInclusive<u64>,
+    },
+    /// The history of a substate which existed at the given state version is missing from the
+    /// database (i.e. the database is inconsistent).
+    MissingHistory { state_version: u64 },
+}
+
+/// A [`SubstateDatabase`] view of a [`VersionedSubstateDatabase`] fixed at a specific state
+/// version, e.g. to execute a preview against a historical state.
+/// Since the [`SubstateDatabase`] reads are infallible, the view panics on a
+/// [`VersionedReadError`] (which, for a readable version, means an inconsistent database).
+pub struct SubstateDatabaseAtVersion<'d, D> {
+    database: &'d D,
+    state_version: u64,
+}
+
+impl<'d, D: VersionedSubstateDatabase> SubstateDatabaseAtVersion<'d, D> {
+    /// Creates a view at the given state version, or returns [`Option::None`] if that version is
+    /// not readable from the given database.
+    pub fn new(database: &'d D, state_version: u64) -> Option<Self> {
+        if database.get_readable_versions().contains(&state_version) {
+            Some(Self {
+                database,
+                state_version,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn state_version(&self) -> u64 {
+        self.state_version
+    }
+}
+
+impl<'d, D: VersionedSubstateDatabase> SubstateDatabase for SubstateDatabaseAtVersion<'d, D> {
+    fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        self.database
+            .get_substate_at_version(self.state_version, partition_key, sort_key)
+            .expect("historical read failed")
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        self.database
+            .list_entries_at_version(self.state_version, partition_key, from_sort_key)
+            .expect("historical read failed")
+    }
+}
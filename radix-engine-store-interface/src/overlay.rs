@@ -0,0 +1,170 @@
+use crate::interface::*;
+use itertools::{EitherOrBoth, Itertools};
+use utils::rust::boxed::Box;
+use utils::rust::collections::BTreeMap;
+use utils::rust::ops::Bound;
+
+/// A result of consecutive [`DatabaseUpdates`], accumulated in memory (i.e. before being committed
+/// to the database they were computed against).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseUpdatesOverlay {
+    partitions: BTreeMap<DbPartitionKey, PartitionOverlay>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct PartitionOverlay {
+    /// Whether the partition was reset (i.e. none of its values stored in the underlying database
+    /// is effective anymore).
+    reset: bool,
+    /// The values set (or deleted, if [`Option::None`]) by the accumulated updates.
+    substates: BTreeMap<DbSortKey, Option<DbSubstateValue>>,
+}
+
+impl DatabaseUpdatesOverlay {
+    pub fn is_empty(&self) -> bool {
+        self.partitions.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.partitions.clear();
+    }
+
+    /// Accumulates the given updates on top of the current ones.
+    pub fn apply(&mut self, database_updates: &DatabaseUpdates) {
+        for (node_key, node_updates) in &database_updates.node_updates {
+            for (partition_num, partition_updates) in &node_updates.partition_updates {
+                let overlay = self
+                    .partitions
+                    .entry(DbPartitionKey {
+                        node_key: node_key.clone(),
+                        partition_num: *partition_num,
+                    })
+                    .or_default();
+                match partition_updates {
+                    PartitionDatabaseUpdates::Delta { substate_updates } => {
+                        for (sort_key, update) in substate_updates {
+                            let value = match update {
+                                DatabaseUpdate::Set(value) => Some(value.clone()),
+                                DatabaseUpdate::Delete => None,
+                            };
+                            overlay.substates.insert(sort_key.clone(), value);
+                        }
+                    }
+                    PartitionDatabaseUpdates::Reset {
+                        new_substate_values,
+                    } => {
+                        overlay.reset = true;
+                        overlay.substates = new_substate_values
+                            .iter()
+                            .map(|(sort_key, value)| (sort_key.clone(), Some(value.clone())))
+                            .collect();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the accumulated value of the given substate: [`Option::None`] if it is not
+    /// affected by the accumulated updates, or `Some(None)` if it was deleted by them.
+    pub fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<Option<DbSubstateValue>> {
+        let overlay = self.partitions.get(partition_key)?;
+        match overlay.substates.get(sort_key) {
+            Some(value) => Some(value.clone()),
+            None => overlay.reset.then_some(None),
+        }
+    }
+
+    /// Returns whether any substate of the given partition is affected by the accumulated updates.
+    pub fn affects_partition(&self, partition_key: &DbPartitionKey) -> bool {
+        self.partitions.contains_key(partition_key)
+    }
+
+    /// Applies the accumulated values of the given partition on top of the given entries of the
+    /// underlying database (both starting from the same sort key).
+    pub fn overlay_entries<'a>(
+        &'a self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+        underlying_entries: Box<dyn Iterator<Item = PartitionEntry> + 'a>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + 'a> {
+        let Some(overlay) = self.partitions.get(partition_key) else {
+            return underlying_entries;
+        };
+        let underlying_entries: Box<dyn Iterator<Item = PartitionEntry> + 'a> = if overlay.reset {
+            Box::new(core::iter::empty())
+        } else {
+            underlying_entries
+        };
+        let from_bound = match from_sort_key {
+            Some(from_sort_key) => Bound::Included(from_sort_key.clone()),
+            None => Bound::Unbounded,
+        };
+        let overlaid_entries = overlay.substates.range((from_bound, Bound::Unbounded));
+        Box::new(
+            underlying_entries
+                .merge_join_by(
+                    overlaid_entries,
+                    |(underlying_sort_key, _), (sort_key, _)| underlying_sort_key.cmp(sort_key),
+                )
+                .filter_map(|entry| match entry {
+                    EitherOrBoth::Left(underlying_entry) => Some(underlying_entry),
+                    EitherOrBoth::Right((sort_key, value))
+                    | EitherOrBoth::Both(_, (sort_key, value)) => {
+                        value.clone().map(|value| (sort_key.clone(), value))
+                    }
+                }),
+        )
+    }
+
+    /// Iterates over the keys of all partitions affected by the accumulated updates.
+    pub fn list_partition_keys(&self) -> impl Iterator<Item = &DbPartitionKey> {
+        self.partitions.keys()
+    }
+}
+
+/// A [`SubstateDatabase`] view of the given database with the given (not yet committed)
+/// [`DatabaseUpdatesOverlay`] applied on top of it.
+pub struct OverlaidSubstateDatabase<'a, D> {
+    database: &'a D,
+    overlay: &'a DatabaseUpdatesOverlay,
+}
+
+impl<'a, D: SubstateDatabase> OverlaidSubstateDatabase<'a, D> {
+    pub fn new(database: &'a D, overlay: &'a DatabaseUpdatesOverlay) -> Self {
+        Self { database, overlay }
+    }
+}
+
+impl<'a, D: SubstateDatabase> SubstateDatabase for OverlaidSubstateDatabase<'a, D> {
+    fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        match self.overlay.get_substate(partition_key, sort_key) {
+            Some(overlaid_value) => overlaid_value,
+            None => self.database.get_substate(partition_key, sort_key),
+        }
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        self.overlay.overlay_entries(
+            partition_key,
+            from_sort_key,
+            self.database
+                .list_entries_from(partition_key, from_sort_key),
+        )
+    }
+
+    fn prefetch(&self, substate_keys: &[DbSubstateKey]) {
+        self.database.prefetch(substate_keys)
+    }
+}
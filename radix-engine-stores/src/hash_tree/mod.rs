@@ -1,3 +1,4 @@
+use crate::hash_tree::proof::{SubstateProof, TierProof};
 use crate::hash_tree::tree_store::StaleTreePart;
 use crate::hash_tree::types::{LeafKey, LeafNode, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use jellyfish::JellyfishMerkleTree;
@@ -14,6 +15,7 @@ use utils::rust::collections::{index_map_new, IndexMap};
 use utils::rust::vec::Vec;
 
 pub mod hash_tree_facade;
+pub mod proof;
 pub mod tree_store;
 
 // The sources copied from Aptos (the `jellyfish` and `types` modules) contain support for
@@ -70,6 +72,99 @@ pub fn put_at_next_version<S: TreeStore>(
     .unwrap_or(SPARSE_MERKLE_PLACEHOLDER_HASH)
 }
 
+/// Inserts new sets of nodes at versions `node_root_version` + 1, `node_root_version` + 2, ...
+/// (one for each of the given consecutive updates) into the "3-Tier JMT" persisted within the
+/// given `TreeStore`.
+/// The result is equivalent to calling [`put_at_next_version()`] for each of the updates and then
+/// pruning all the stale nodes created by these calls; however, the intermediate versions are
+/// computed in memory, so the given store only receives the nodes reachable from the last
+/// version's root, and the stale tree parts which existed before this call (i.e. the upper nodes
+/// shared by the subsequent versions are written only once).
+/// Hence, the intermediate versions are not readable from the store (only their root hashes are
+/// known).
+/// Returns the hashes of the newly-created roots, in the order of the given updates.
+///
+/// # Panics
+/// Panics if a root node for `node_root_version` does not exist. The caller should use `None` to
+/// denote an empty, initial state of the tree (i.e. inserting starting at version 1).
+pub fn put_at_next_versions<S: TreeStore>(
+    node_tier_store: &mut S,
+    node_root_version: Option<Version>,
+    database_updates_batch: &[DatabaseUpdates],
+) -> Vec<Hash> {
+    let mut batching_store = BatchingTreeStore::new(node_tier_store);
+    let mut root_hashes = Vec::new();
+    for (index, database_updates) in database_updates_batch.iter().enumerate() {
+        let parent_version = match node_root_version {
+            Some(node_root_version) => Some(node_root_version + index as Version),
+            None => Some(index as Version).filter(|version| *version > 0),
+        };
+        root_hashes.push(put_at_next_version(
+            &mut batching_store,
+            parent_version,
+            database_updates,
+        ));
+    }
+    batching_store.flush();
+    root_hashes
+}
+
+/// Inserts the given substate hashes (grouped by their partitions) into an empty "3-Tier JMT",
+/// as its first version (which does not have to be `1`).
+/// The result is equivalent to calling [`put_at_next_version()`] with `None` and the substate
+/// values; however, only their hashes have to be known (e.g. when the values are streamed into the
+/// database separately).
+/// Returns the hash of the newly-created root.
+pub fn put_substate_hashes_at_version<S: TreeStore>(
+    node_tier_store: &mut S,
+    version: Version,
+    substate_hashes: &IndexMap<DbPartitionKey, IndexMap<DbSortKey, Hash>>,
+) -> Hash {
+    let mut partition_hashes_by_node =
+        index_map_new::<&DbNodeKey, Vec<(DbPartitionNum, &IndexMap<DbSortKey, Hash>)>>();
+    for (partition_key, partition_hashes) in substate_hashes {
+        partition_hashes_by_node
+            .entry(&partition_key.node_key)
+            .or_default()
+            .push((partition_key.partition_num, partition_hashes));
+    }
+    let mut node_hash_changes = Vec::new();
+    for (node_key, node_partition_hashes) in partition_hashes_by_node {
+        let mut partition_tier_store = NestedTreeStore::new(node_tier_store, node_key.clone());
+        let mut partition_hash_changes = Vec::new();
+        for (partition_num, partition_hashes) in node_partition_hashes {
+            let mut substate_tier_store =
+                NestedTreeStore::new(&mut partition_tier_store, vec![partition_num]);
+            partition_hash_changes.push(LeafHashChange {
+                key_bytes: vec![partition_num],
+                hash_change: put_leaf_hash_changes(
+                    &mut substate_tier_store,
+                    None,
+                    version,
+                    partition_hashes
+                        .iter()
+                        .map(|(sort_key, substate_hash)| LeafHashChange {
+                            key_bytes: sort_key.0.clone(),
+                            hash_change: Some(*substate_hash),
+                        })
+                        .collect(),
+                ),
+            });
+        }
+        node_hash_changes.push(LeafHashChange {
+            key_bytes: node_key.clone(),
+            hash_change: put_leaf_hash_changes(
+                &mut partition_tier_store,
+                None,
+                version,
+                partition_hash_changes,
+            ),
+        });
+    }
+    put_leaf_hash_changes(node_tier_store, None, version, node_hash_changes)
+        .unwrap_or(SPARSE_MERKLE_PLACEHOLDER_HASH)
+}
+
 pub fn list_substate_hashes_at_version<S: ReadableTreeStore>(
     node_tier_store: &mut S,
     node_root_version: Version,
@@ -107,8 +202,233 @@ pub fn list_substate_hashes_at_version<S: ReadableTreeStore>(
     by_db_partition
 }
 
+/// A substate's leaf in the Substate-Tier JMT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstateLeaf {
+    /// A hash of the substate's value.
+    pub value_hash: Hash,
+    /// A version at which the substate's value has most recently changed.
+    pub last_hash_change_version: Version,
+}
+
+/// Looks up the given substate's leaf in the "3-Tier JMT", as of the given version.
+/// Returns [`Option::None`] if the substate did not exist at that version.
+///
+/// # Panics
+/// Panics if the tree nodes of the given version are not (or no longer) present in the store.
+pub fn get_substate_leaf_at_version<S: ReadableTreeStore>(
+    node_tier_store: &S,
+    node_root_version: Version,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> Option<SubstateLeaf> {
+    let substate_root_version =
+        get_substate_tier_root_version(node_tier_store, node_root_version, partition_key)?;
+    let mut node_tier_store = node_tier_store;
+    let mut partition_tier_store =
+        NestedTreeStore::new(&mut node_tier_store, partition_key.node_key.clone());
+    let substate_tier_store =
+        NestedTreeStore::new(&mut partition_tier_store, vec![partition_key.partition_num]);
+    get_leaf_payload(&substate_tier_store, substate_root_version, &sort_key.0).map(
+        |(value_hash, last_hash_change_version)| SubstateLeaf {
+            value_hash,
+            last_hash_change_version,
+        },
+    )
+}
+
+/// Lists the leaves of all substates of the given partition in the "3-Tier JMT", as of the given
+/// version (in an arbitrary order).
+///
+/// # Panics
+/// Panics if the tree nodes of the given version are not (or no longer) present in the store.
+pub fn list_substate_leaves_at_version<S: ReadableTreeStore>(
+    node_tier_store: &S,
+    node_root_version: Version,
+    partition_key: &DbPartitionKey,
+) -> Vec<(DbSortKey, SubstateLeaf)> {
+    let Some(substate_root_version) =
+        get_substate_tier_root_version(node_tier_store, node_root_version, partition_key)
+    else {
+        return vec![];
+    };
+    let mut node_tier_store = node_tier_store;
+    let mut partition_tier_store =
+        NestedTreeStore::new(&mut node_tier_store, partition_key.node_key.clone());
+    let mut substate_tier_store =
+        NestedTreeStore::new(&mut partition_tier_store, vec![partition_key.partition_num]);
+    list_leaves(&mut substate_tier_store, substate_root_version)
+        .into_iter()
+        .map(|leaf| {
+            (
+                DbSortKey(leaf.leaf_key().bytes.clone()),
+                SubstateLeaf {
+                    value_hash: leaf.value_hash(),
+                    last_hash_change_version: *leaf.payload(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Generates a proof of the given substate's value (or of its absence) in the "3-Tier JMT", as of
+/// the given version (see [`proof::verify_substate_proof()`]).
+/// The caller should use `None` to denote an empty, initial state of the tree.
+///
+/// # Panics
+/// Panics if the tree nodes of the given version are not (or no longer) present in the store.
+pub fn get_substate_proof_at_version<S: ReadableTreeStore>(
+    node_tier_store: &S,
+    node_root_version: Option<Version>,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> SubstateProof {
+    let Some(node_root_version) = node_root_version else {
+        return SubstateProof::of_empty_tree();
+    };
+    let mut tier_proofs = Vec::new();
+    let (partition_root_version, node_tier_proof) =
+        get_leaf_payload_with_proof(node_tier_store, node_root_version, &partition_key.node_key);
+    tier_proofs.push(node_tier_proof);
+    let Some(partition_root_version) = partition_root_version else {
+        return SubstateProof { tier_proofs };
+    };
+
+    let mut node_tier_store = node_tier_store;
+    let mut partition_tier_store =
+        NestedTreeStore::new(&mut node_tier_store, partition_key.node_key.clone());
+    let (substate_root_version, partition_tier_proof) = get_leaf_payload_with_proof(
+        &partition_tier_store,
+        partition_root_version,
+        &[partition_key.partition_num],
+    );
+    tier_proofs.push(partition_tier_proof);
+    let Some(substate_root_version) = substate_root_version else {
+        return SubstateProof { tier_proofs };
+    };
+
+    let substate_tier_store =
+        NestedTreeStore::new(&mut partition_tier_store, vec![partition_key.partition_num]);
+    let (_, substate_tier_proof) =
+        get_leaf_payload_with_proof(&substate_tier_store, substate_root_version, &sort_key.0);
+    tier_proofs.push(substate_tier_proof);
+    SubstateProof { tier_proofs }
+}
+
 // only internals below
 
+/// A `TreeStore` overlay which keeps the nodes inserted by a batch of versions in memory, and
+/// discards the ones which become stale before the batch is flushed.
+struct BatchingTreeStore<'s, S> {
+    underlying: &'s mut S,
+    new_nodes: IndexMap<NodeKey, TreeNode>,
+    stale_tree_parts: Vec<StaleTreePart>,
+}
+
+impl<'s, S: TreeStore> BatchingTreeStore<'s, S> {
+    fn new(underlying: &'s mut S) -> Self {
+        Self {
+            underlying,
+            new_nodes: index_map_new(),
+            stale_tree_parts: Vec::new(),
+        }
+    }
+
+    fn flush(self) {
+        for (key, node) in self.new_nodes {
+            self.underlying.insert_node(key, node);
+        }
+        for part in self.stale_tree_parts {
+            self.underlying.record_stale_tree_part(part);
+        }
+    }
+
+    fn discard_subtree(&mut self, key: NodeKey) {
+        match self.new_nodes.swap_remove(&key) {
+            // a node inserted before the batch cannot have children inserted by the batch
+            None => self.stale_tree_parts.push(StaleTreePart::Subtree(key)),
+            Some(TreeNode::Internal(internal)) => {
+                for child in internal.children {
+                    self.discard_subtree(key.gen_child_node_key(child.version, child.nibble));
+                }
+            }
+            Some(TreeNode::Leaf(_)) | Some(TreeNode::Null) => {}
+        }
+    }
+}
+
+impl<'s, S: TreeStore> ReadableTreeStore for BatchingTreeStore<'s, S> {
+    fn get_node(&self, key: &NodeKey) -> Option<TreeNode> {
+        self.new_nodes
+            .get(key)
+            .cloned()
+            .or_else(|| self.underlying.get_node(key))
+    }
+}
+
+impl<'s, S: TreeStore> WriteableTreeStore for BatchingTreeStore<'s, S> {
+    fn insert_node(&mut self, key: NodeKey, node: TreeNode) {
+        self.new_nodes.insert(key, node);
+    }
+
+    fn record_stale_tree_part(&mut self, part: StaleTreePart) {
+        match part {
+            StaleTreePart::Node(key) => {
+                if self.new_nodes.swap_remove(&key).is_none() {
+                    self.stale_tree_parts.push(StaleTreePart::Node(key));
+                }
+            }
+            StaleTreePart::Subtree(key) => self.discard_subtree(key),
+        }
+    }
+}
+
+fn get_substate_tier_root_version<S: ReadableTreeStore>(
+    node_tier_store: &S,
+    node_root_version: Version,
+    partition_key: &DbPartitionKey,
+) -> Option<Version> {
+    let (_, partition_root_version) =
+        get_leaf_payload(node_tier_store, node_root_version, &partition_key.node_key)?;
+    let mut node_tier_store = node_tier_store;
+    let partition_tier_store =
+        NestedTreeStore::new(&mut node_tier_store, partition_key.node_key.clone());
+    let (_, substate_root_version) = get_leaf_payload(
+        &partition_tier_store,
+        partition_root_version,
+        &[partition_key.partition_num],
+    )?;
+    Some(substate_root_version)
+}
+
+fn get_leaf_payload<S: ReadableTreeStore>(
+    store: &S,
+    version: Version,
+    leaf_bytes: &[u8],
+) -> Option<(Hash, Version)> {
+    JellyfishMerkleTree::new(store)
+        .get_with_proof(&LeafKey::new(leaf_bytes), version)
+        .unwrap()
+        .0
+        .map(|(value_hash, last_hash_change_version, _version)| {
+            (value_hash, last_hash_change_version)
+        })
+}
+
+fn get_leaf_payload_with_proof<S: ReadableTreeStore>(
+    store: &S,
+    version: Version,
+    leaf_bytes: &[u8],
+) -> (Option<Version>, TierProof) {
+    let (leaf, proof) = JellyfishMerkleTree::new(store)
+        .get_with_proof(&LeafKey::new(leaf_bytes), version)
+        .unwrap();
+    (
+        leaf.map(|(_value_hash, last_hash_change_version, _version)| last_hash_change_version),
+        TierProof::from(proof),
+    )
+}
+
 fn list_leaves<S: ReadableTreeStore>(
     tree_store: &mut S,
     version: Version,
@@ -224,13 +544,9 @@ fn get_lower_tier_root_version<S: ReadableTreeStore>(
     version: Option<Version>,
     leaf_bytes: &[u8],
 ) -> Option<Version> {
-    version.and_then(|version| {
-        let leaf_node_data = JellyfishMerkleTree::new(store)
-            .get_with_proof(&LeafKey::new(leaf_bytes), version)
-            .unwrap()
-            .0;
-        leaf_node_data.map(|(_hash, last_hash_change_version, _version)| last_hash_change_version)
-    })
+    version
+        .and_then(|version| get_leaf_payload(store, version, leaf_bytes))
+        .map(|(_hash, last_hash_change_version)| last_hash_change_version)
 }
 
 struct LeafHashChange {
@@ -0,0 +1,173 @@
+use crate::hash_tree::types::{
+    IteratedLeafKey, LeafKey, SparseMerkleProof, SPARSE_MERKLE_PLACEHOLDER_HASH,
+};
+use radix_engine_common::crypto::{hash, Hash};
+use radix_engine_common::Sbor;
+use radix_engine_store_interface::interface::{DbPartitionKey, DbSortKey, DbSubstateValue};
+use utils::rust::vec;
+use utils::rust::vec::Vec;
+
+/// A proof that a substate has a specific value (or that it does not exist) in the "3-Tier JMT"
+/// with a specific root hash.
+/// The proof consists of a chain of per-tier proofs: each of them authenticates a leaf (or its
+/// absence) within a single tier's tree, and the leaf's value hash is the root hash of the
+/// tree at the next (lower) tier.
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub struct SubstateProof {
+    /// Proofs of the subsequent tiers, starting from the ReNode-Tier (i.e. the one with the root
+    /// of the entire tree).
+    /// A lower tier's proof is only present if the higher tier's proof includes its leaf, i.e. a
+    /// proof of absence may end at any tier.
+    pub tier_proofs: Vec<TierProof>,
+}
+
+impl SubstateProof {
+    /// A proof of absence of any substate in an empty tree.
+    pub fn of_empty_tree() -> Self {
+        Self {
+            tier_proofs: vec![TierProof {
+                leaf: None,
+                siblings: vec![],
+            }],
+        }
+    }
+}
+
+/// A proof of inclusion (or exclusion) of a single leaf key within a single tier's tree.
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub struct TierProof {
+    /// The leaf found at the end of the key's path:
+    /// - if its key is the requested key, this is an inclusion proof;
+    /// - if its key is different, this is an exclusion proof (i.e. the leaf is the only one in the
+    ///   subtree where the requested key would reside);
+    /// - if it is [`Option::None`], this is an exclusion proof (i.e. the subtree is empty).
+    pub leaf: Option<TierProofLeaf>,
+    /// All siblings on the leaf's path, including the placeholder ones, ordered from the bottom
+    /// level to the root level.
+    pub siblings: Vec<Hash>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub struct TierProofLeaf {
+    pub key_bytes: Vec<u8>,
+    pub value_hash: Hash,
+}
+
+impl From<SparseMerkleProof> for TierProof {
+    fn from(proof: SparseMerkleProof) -> Self {
+        Self {
+            leaf: proof.leaf().map(|leaf| TierProofLeaf {
+                key_bytes: leaf.key().bytes.clone(),
+                value_hash: *leaf.value_hash(),
+            }),
+            siblings: proof.siblings().to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Sbor)]
+pub enum SubstateProofError {
+    /// The number of tier proofs is outside of the `1..=3` range, or is inconsistent with the
+    /// claimed substate's existence.
+    InvalidTierCount(usize),
+    /// A tier proof has more siblings than there are bits in its leaf key.
+    TooManySiblings { tier: usize },
+    /// A tier proof's leaf contradicts the claimed inclusion (or exclusion) of the leaf key.
+    UnexpectedLeaf { tier: usize },
+    /// The root hash computed from the proof differs from the expected one.
+    RootHashMismatch { expected: Hash, actual: Hash },
+}
+
+/// Verifies that the given proof demonstrates the given substate's value (or its absence, if
+/// [`Option::None`]) under the given root hash of the "3-Tier JMT".
+/// This function only uses hashing (i.e. does not need any storage), and thus can be used e.g. by
+/// light clients which only trust the root hash.
+pub fn verify_substate_proof(
+    proof: &SubstateProof,
+    root_hash: &Hash,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+    value: Option<&DbSubstateValue>,
+) -> Result<(), SubstateProofError> {
+    let tier_count = proof.tier_proofs.len();
+    if tier_count == 0 || tier_count > 3 || (value.is_some() && tier_count < 3) {
+        return Err(SubstateProofError::InvalidTierCount(tier_count));
+    }
+    let partition_num_bytes = [partition_key.partition_num];
+    let tier_keys: [&[u8]; 3] = [&partition_key.node_key, &partition_num_bytes, &sort_key.0];
+    // the lowest proven tier's leaf is either the substate's one or is proven absent
+    let mut value_hash = if tier_count == 3 {
+        value.map(hash)
+    } else {
+        None
+    };
+    for tier in (0..tier_count).rev() {
+        value_hash = Some(compute_tier_root_hash(
+            tier,
+            &proof.tier_proofs[tier],
+            tier_keys[tier],
+            value_hash,
+        )?);
+    }
+    let actual = value_hash.expect("at least one tier is proven");
+    if actual != *root_hash {
+        return Err(SubstateProofError::RootHashMismatch {
+            expected: *root_hash,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// only internals below
+
+fn compute_tier_root_hash(
+    tier: usize,
+    proof: &TierProof,
+    key_bytes: &[u8],
+    value_hash: Option<Hash>,
+) -> Result<Hash, SubstateProofError> {
+    let key = LeafKey::new(key_bytes);
+    if proof.siblings.len() > key.bytes.len() * 8 {
+        return Err(SubstateProofError::TooManySiblings { tier });
+    }
+    let leaf_consistent = match (&value_hash, &proof.leaf) {
+        (Some(value_hash), Some(leaf)) => {
+            leaf.key_bytes == key.bytes && leaf.value_hash == *value_hash
+        }
+        (Some(_), None) => false,
+        (None, Some(leaf)) => {
+            // the other leaf must reside exactly where the requested key would
+            let leaf_key = LeafKey::new(&leaf.key_bytes);
+            leaf.key_bytes != key.bytes
+                && key
+                    .iter_bits()
+                    .zip(leaf_key.iter_bits())
+                    .take_while(|(bit, leaf_bit)| bit == leaf_bit)
+                    .count()
+                    >= proof.siblings.len()
+        }
+        (None, None) => true,
+    };
+    if !leaf_consistent {
+        return Err(SubstateProofError::UnexpectedLeaf { tier });
+    }
+
+    let path_bits = key
+        .iter_bits()
+        .take(proof.siblings.len())
+        .collect::<Vec<_>>();
+    let mut current_hash = proof
+        .leaf
+        .as_ref()
+        .map(|leaf| hash([leaf.key_bytes.as_slice(), &leaf.value_hash.0].concat()))
+        .unwrap_or(SPARSE_MERKLE_PLACEHOLDER_HASH);
+    for (sibling, bit) in proof.siblings.iter().zip(path_bits.into_iter().rev()) {
+        current_hash = if bit {
+            hash([sibling.0, current_hash.0].concat())
+        } else {
+            hash([current_hash.0, sibling.0].concat())
+        };
+    }
+    Ok(current_hash)
+}
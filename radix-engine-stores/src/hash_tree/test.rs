@@ -1,11 +1,16 @@
 use super::types::{Nibble, NibblePath, Version, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use crate::hash_tree::jellyfish::JellyfishMerkleTree;
-use crate::hash_tree::put_at_next_version;
+use crate::hash_tree::proof::{verify_substate_proof, SubstateProof, SubstateProofError};
 use crate::hash_tree::tree_store::{
     SerializedInMemoryTreeStore, StaleTreePart, TreeChildEntry, TreeInternalNode, TreeLeafNode,
     TreeNode, TreeStore, TypedInMemoryTreeStore,
 };
 use crate::hash_tree::types::{LeafKey, NodeKey};
+use crate::hash_tree::{
+    get_substate_leaf_at_version, get_substate_proof_at_version, list_substate_hashes_at_version,
+    list_substate_leaves_at_version, put_at_next_version, put_at_next_versions,
+    put_substate_hashes_at_version, SubstateLeaf,
+};
 use itertools::Itertools;
 use radix_engine_common::crypto::{hash, Hash};
 use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
@@ -349,6 +354,128 @@ fn records_stale_subtree_root_key_when_partition_removed() {
     );
 }
 
+#[test]
+fn batched_versions_match_pruned_consecutive_versions() {
+    let updates = (1..=12u8)
+        .map(|version| {
+            let mut changes = vec![
+                change(version % 3 + 1, version % 2, version % 5 + 1, Some(version)),
+                change(4, 1, version % 4 + 1, Some(version + 100)),
+            ];
+            if version % 3 == 0 {
+                changes.push(change(4, 1, (version + 1) % 4 + 1, None));
+            }
+            delta_updates(changes)
+        })
+        .chain([DatabaseUpdates {
+            node_updates: indexmap!(
+                from_seed(4) => NodeDatabaseUpdates {
+                    partition_updates: indexmap!(
+                        1 => PartitionDatabaseUpdates::Reset {
+                            new_substate_values: indexmap!(DbSortKey(from_seed(9)) => from_seed(9))
+                        }
+                    )
+                }
+            ),
+        }])
+        .collect::<Vec<_>>();
+    let mut reference_store = TypedInMemoryTreeStore::with_pruning();
+    let reference_root_hashes = updates
+        .iter()
+        .enumerate()
+        .map(|(index, database_updates)| {
+            put_at_next_version(
+                &mut reference_store,
+                Some(index as Version).filter(|version| *version > 0),
+                database_updates,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut batched_store = TypedInMemoryTreeStore::with_pruning();
+    let mut batched_root_hashes = put_at_next_versions(&mut batched_store, None, &updates[..5]);
+    batched_root_hashes.extend(put_at_next_versions(
+        &mut batched_store,
+        Some(5),
+        &updates[5..],
+    ));
+
+    assert_eq!(batched_root_hashes, reference_root_hashes);
+    assert_eq!(batched_store.tree_nodes, reference_store.tree_nodes);
+}
+
+#[test]
+fn substate_hashes_put_at_any_version_match_values_put_at_first_version() {
+    let changes = vec![
+        change(1, 6, 2, Some(30)),
+        change(1, 6, 3, Some(31)),
+        change(1, 9, 2, Some(32)),
+        change(4, 1, 7, Some(33)),
+    ];
+    let mut substate_hashes = index_map_new::<DbPartitionKey, IndexMap<DbSortKey, Hash>>();
+    for ((partition_key, sort_key), update) in &changes {
+        let DatabaseUpdate::Set(value) = update else {
+            panic!("only sets expected");
+        };
+        substate_hashes
+            .entry(partition_key.clone())
+            .or_default()
+            .insert(sort_key.clone(), hash(value));
+    }
+    let mut reference_store = TypedInMemoryTreeStore::new();
+    let reference_root_hash =
+        put_at_next_version(&mut reference_store, None, &delta_updates(changes));
+
+    let mut store = TypedInMemoryTreeStore::new();
+    let root_hash = put_substate_hashes_at_version(&mut store, 7, &substate_hashes);
+
+    assert_eq!(root_hash, reference_root_hash);
+    assert_eq!(
+        list_substate_hashes_at_version(&mut store, 7),
+        substate_hashes
+    );
+}
+
+#[test]
+fn batched_versions_only_report_stale_parts_of_previously_stored_nodes() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(4, 7, 6, Some(36)), change(4, 7, 7, Some(37))]);
+    let stored_node_keys = tester
+        .tree_store
+        .tree_nodes
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    put_at_next_versions(
+        &mut tester.tree_store,
+        Some(1),
+        &[
+            delta_updates(vec![change(4, 7, 6, Some(46))]),
+            delta_updates(vec![change(4, 7, 6, Some(56))]),
+        ],
+    );
+
+    let reported_node_keys = tester
+        .tree_store
+        .stale_part_buffer
+        .iter()
+        .map(|part| match part {
+            StaleTreePart::Node(key) | StaleTreePart::Subtree(key) => key.clone(),
+        })
+        .collect::<Vec<_>>();
+    assert!(!reported_node_keys.is_empty());
+    assert!(reported_node_keys
+        .iter()
+        .all(|key| stored_node_keys.contains(key)));
+    // the intermediate version's nodes were never stored
+    assert!(tester
+        .tree_store
+        .tree_nodes
+        .keys()
+        .all(|key| key.version() != 2));
+}
+
 #[test]
 fn sbor_uses_custom_direct_codecs_for_nibbles() {
     let nibbles = nibbles("a1a2a3");
@@ -418,6 +545,200 @@ fn serialized_keys_are_strictly_increasing() {
     assert!(min_next_key > max_previous_key);
 }
 
+#[test]
+fn looks_up_substate_leaves_at_past_versions() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(30)), change(1, 6, 3, Some(31))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(40))]);
+    tester.put_substate_changes(vec![change(1, 6, 3, None)]);
+
+    let partition_key = DbPartitionKey {
+        node_key: from_seed(1),
+        partition_num: 6,
+    };
+    let leaf_at = |version: Version, sort_key_seed: u8| {
+        get_substate_leaf_at_version(
+            &tester.tree_store,
+            version,
+            &partition_key,
+            &DbSortKey(from_seed(sort_key_seed)),
+        )
+    };
+    assert_eq!(leaf_at(1, 2), Some(leaf(30, 1)));
+    assert_eq!(leaf_at(2, 2), Some(leaf(40, 2)));
+    assert_eq!(leaf_at(2, 3), Some(leaf(31, 1)));
+    assert_eq!(leaf_at(3, 3), None);
+    assert_eq!(leaf_at(3, 4), None);
+
+    let leaves_at_2 = list_substate_leaves_at_version(&tester.tree_store, 2, &partition_key)
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    assert_eq!(
+        leaves_at_2,
+        hashmap!(
+            DbSortKey(from_seed(2)) => leaf(40, 2),
+            DbSortKey(from_seed(3)) => leaf(31, 1),
+        )
+    );
+    let other_partition_key = DbPartitionKey {
+        node_key: from_seed(1),
+        partition_num: 7,
+    };
+    assert!(
+        list_substate_leaves_at_version(&tester.tree_store, 2, &other_partition_key).is_empty()
+    );
+}
+
+#[test]
+fn proves_substate_inclusion_and_absence_at_each_tier() {
+    let mut tester = HashTreeTester::new_empty();
+    let root_hash = tester.put_substate_changes(vec![
+        change(1, 6, 2, Some(30)),
+        change(1, 6, 3, Some(31)),
+        change(1, 7, 2, Some(32)),
+        change(4, 6, 2, Some(33)),
+        change(5, 1, 9, Some(34)),
+    ]);
+    let prove = |node_key_seed: u8, partition_num: u8, sort_key_seed: u8| {
+        let partition_key = DbPartitionKey {
+            node_key: from_seed(node_key_seed),
+            partition_num,
+        };
+        let sort_key = DbSortKey(from_seed(sort_key_seed));
+        let proof = get_substate_proof_at_version(
+            &tester.tree_store,
+            tester.current_version,
+            &partition_key,
+            &sort_key,
+        );
+        (proof, partition_key, sort_key)
+    };
+
+    // existing substate
+    let (proof, partition_key, sort_key) = prove(1, 6, 3);
+    assert_eq!(proof.tier_proofs.len(), 3);
+    let value = from_seed(31);
+    assert_eq!(
+        verify_substate_proof(&proof, &root_hash, &partition_key, &sort_key, Some(&value)),
+        Ok(())
+    );
+    assert!(verify_substate_proof(
+        &proof,
+        &root_hash,
+        &partition_key,
+        &sort_key,
+        Some(&from_seed(30))
+    )
+    .is_err());
+    assert!(verify_substate_proof(&proof, &root_hash, &partition_key, &sort_key, None).is_err());
+    assert_eq!(
+        verify_substate_proof(
+            &proof,
+            &Hash([7; Hash::LENGTH]),
+            &partition_key,
+            &sort_key,
+            Some(&value)
+        ),
+        Err(SubstateProofError::RootHashMismatch {
+            expected: Hash([7; Hash::LENGTH]),
+            actual: root_hash
+        })
+    );
+    let other_sort_key = DbSortKey(from_seed(2));
+    assert!(verify_substate_proof(
+        &proof,
+        &root_hash,
+        &partition_key,
+        &other_sort_key,
+        Some(&value)
+    )
+    .is_err());
+
+    // absent substate, partition and node (i.e. proofs ending at different tiers)
+    for (node_key_seed, partition_num, sort_key_seed, expected_tier_count) in
+        [(1, 6, 4, 3), (1, 8, 2, 2), (3, 6, 2, 1)]
+    {
+        let (proof, partition_key, sort_key) = prove(node_key_seed, partition_num, sort_key_seed);
+        assert_eq!(proof.tier_proofs.len(), expected_tier_count);
+        assert_eq!(
+            verify_substate_proof(&proof, &root_hash, &partition_key, &sort_key, None),
+            Ok(())
+        );
+        assert!(verify_substate_proof(
+            &proof,
+            &root_hash,
+            &partition_key,
+            &sort_key,
+            Some(&from_seed(30))
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn proves_substate_absence_in_empty_tree() {
+    let tester = HashTreeTester::new_empty();
+    let partition_key = DbPartitionKey {
+        node_key: from_seed(1),
+        partition_num: 6,
+    };
+    let sort_key = DbSortKey(from_seed(2));
+    let proof = get_substate_proof_at_version(
+        &tester.tree_store,
+        tester.current_version,
+        &partition_key,
+        &sort_key,
+    );
+    assert_eq!(proof, SubstateProof::of_empty_tree());
+    assert_eq!(
+        verify_substate_proof(
+            &proof,
+            &SPARSE_MERKLE_PLACEHOLDER_HASH,
+            &partition_key,
+            &sort_key,
+            None
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn sbor_decodes_substate_proof_that_was_encoded() {
+    let mut tester = HashTreeTester::new_empty();
+    let root_hash =
+        tester.put_substate_changes(vec![change(1, 6, 2, Some(30)), change(3, 7, 1, Some(40))]);
+    let partition_key = DbPartitionKey {
+        node_key: from_seed(3),
+        partition_num: 7,
+    };
+    let sort_key = DbSortKey(from_seed(1));
+    let proof = get_substate_proof_at_version(
+        &tester.tree_store,
+        tester.current_version,
+        &partition_key,
+        &sort_key,
+    );
+    let decoded = scrypto_decode::<SubstateProof>(&scrypto_encode(&proof).unwrap()).unwrap();
+    assert_eq!(decoded, proof);
+    assert_eq!(
+        verify_substate_proof(
+            &decoded,
+            &root_hash,
+            &partition_key,
+            &sort_key,
+            Some(&from_seed(40))
+        ),
+        Ok(())
+    );
+}
+
+fn leaf(value_seed: u8, last_hash_change_version: Version) -> SubstateLeaf {
+    SubstateLeaf {
+        value_hash: hash(from_seed(value_seed)),
+        last_hash_change_version,
+    }
+}
+
 type SingleSubstateChange = (DbSubstateKey, DatabaseUpdate);
 
 fn change(
@@ -434,6 +755,12 @@ fn change(
     )
 }
 
+fn delta_updates(changes: impl IntoIterator<Item = SingleSubstateChange>) -> DatabaseUpdates {
+    DatabaseUpdates::from_delta_maps(
+        HashTreeTester::<TypedInMemoryTreeStore>::index_to_delta_maps(changes),
+    )
+}
+
 pub fn change_exact(
     node_key: Vec<u8>,
     partition_num: u8,
@@ -70,6 +70,12 @@ pub trait ReadableTreeStore {
     fn get_node(&self, key: &NodeKey) -> Option<TreeNode>;
 }
 
+impl<'a, S: ReadableTreeStore> ReadableTreeStore for &'a S {
+    fn get_node(&self, key: &NodeKey) -> Option<TreeNode> {
+        (*self).get_node(key)
+    }
+}
+
 /// The "write" part of a physical tree node storage SPI.
 pub trait WriteableTreeStore {
     /// Inserts the node under a new, unique key (i.e. never an update).
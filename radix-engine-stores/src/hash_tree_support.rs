@@ -1,9 +1,12 @@
+use crate::hash_tree::proof::SubstateProof;
 use crate::hash_tree::tree_store::{TypedInMemoryTreeStore, Version};
-use crate::hash_tree::{list_substate_hashes_at_version, put_at_next_version};
+use crate::hash_tree::{
+    get_substate_proof_at_version, list_substate_hashes_at_version, put_at_next_versions,
+};
 use radix_engine_common::prelude::*;
 use radix_engine_store_interface::interface::{
-    CommittableSubstateDatabase, DatabaseUpdates, DbPartitionKey, DbSortKey, DbSubstateValue,
-    ListableSubstateDatabase, PartitionEntry, SubstateDatabase,
+    CommittableSubstateDatabase, DatabaseUpdates, DbPartitionKey, DbSortKey, DbSubstateKey,
+    DbSubstateValue, ListableSubstateDatabase, PartitionEntry, SubstateDatabase,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -12,6 +15,7 @@ pub struct HashTreeUpdatingDatabase<D> {
     tree_store: TypedInMemoryTreeStore,
     current_version: Version,
     current_hash: Hash,
+    pending_updates: Vec<DatabaseUpdates>,
 }
 
 impl<D> HashTreeUpdatingDatabase<D> {
@@ -21,6 +25,7 @@ impl<D> HashTreeUpdatingDatabase<D> {
             tree_store: TypedInMemoryTreeStore::with_pruning(),
             current_version: 0,
             current_hash: Hash([0; Hash::LENGTH]),
+            pending_updates: Vec::new(),
         }
     }
 
@@ -36,13 +41,53 @@ impl<D> HashTreeUpdatingDatabase<D> {
         list_substate_hashes_at_version(&mut self.tree_store, self.current_version)
     }
 
-    fn update_with(&mut self, db_updates: &DatabaseUpdates) {
-        self.current_hash = put_at_next_version(
+    /// Generates a proof of the given substate's current value (or of its absence), verifiable
+    /// against the current root hash.
+    pub fn get_substate_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> SubstateProof {
+        get_substate_proof_at_version(
+            &self.tree_store,
+            Some(self.current_version).filter(|version| *version > 0),
+            partition_key,
+            sort_key,
+        )
+    }
+
+    /// Returns the number of commits made with [`Self::commit_without_hashing()`] since the last
+    /// [`Self::update_hash_tree()`] (i.e. not yet reflected by the current version and root hash).
+    pub fn count_pending_updates(&self) -> usize {
+        self.pending_updates.len()
+    }
+
+    /// Applies all the pending commits (see [`Self::commit_without_hashing()`]) to the hash tree,
+    /// as a single batch of consecutive versions (see [`put_at_next_versions()`]).
+    /// Returns the root hashes of all the new versions (i.e. the last one becomes current).
+    pub fn update_hash_tree(&mut self) -> Vec<Hash> {
+        if self.pending_updates.is_empty() {
+            return Vec::new();
+        }
+        let root_hashes = put_at_next_versions(
             &mut self.tree_store,
             Some(self.current_version).filter(|version| *version > 0),
-            db_updates,
+            &self.pending_updates,
         );
-        self.current_version += 1;
+        self.current_version += self.pending_updates.len() as Version;
+        self.current_hash = *root_hashes.last().unwrap();
+        self.pending_updates.clear();
+        root_hashes
+    }
+}
+
+impl<D: CommittableSubstateDatabase> HashTreeUpdatingDatabase<D> {
+    /// Commits the given updates to the underlying database, but defers their application to the
+    /// hash tree until the next [`Self::update_hash_tree()`] (which is much cheaper for a batch of
+    /// many consecutive commits than for each of them separately).
+    pub fn commit_without_hashing(&mut self, database_updates: &DatabaseUpdates) {
+        self.underlying.commit(database_updates);
+        self.pending_updates.push(database_updates.clone());
     }
 }
 
@@ -55,11 +100,17 @@ impl<D: SubstateDatabase> SubstateDatabase for HashTreeUpdatingDatabase<D> {
         self.underlying.get_substate(partition_key, sort_key)
     }
 
-    fn list_entries(
+    fn list_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
-        self.underlying.list_entries(partition_key)
+        self.underlying
+            .list_entries_from(partition_key, from_sort_key)
+    }
+
+    fn prefetch(&self, substate_keys: &[DbSubstateKey]) {
+        self.underlying.prefetch(substate_keys)
     }
 }
 
@@ -71,7 +122,7 @@ impl<D: ListableSubstateDatabase> ListableSubstateDatabase for HashTreeUpdatingD
 
 impl<D: CommittableSubstateDatabase> CommittableSubstateDatabase for HashTreeUpdatingDatabase<D> {
     fn commit(&mut self, database_updates: &DatabaseUpdates) {
-        self.underlying.commit(database_updates);
-        self.update_with(database_updates);
+        self.commit_without_hashing(database_updates);
+        self.update_hash_tree();
     }
 }
@@ -7,9 +7,13 @@ compile_error!("Feature `std` and `alloc` can't be enabled at the same time.");
 
 pub mod hash_tree;
 pub mod memory_db;
+#[cfg(feature = "std")]
+pub mod prefetch;
 #[cfg(feature = "rocksdb")]
 pub mod rocks_db;
 #[cfg(feature = "rocksdb")]
 pub mod rocks_db_with_merkle_tree;
+#[cfg(feature = "std")]
+pub mod snapshot;
 
 pub mod hash_tree_support;
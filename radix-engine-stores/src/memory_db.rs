@@ -1,4 +1,5 @@
 use radix_engine_store_interface::interface::*;
+use sbor::rust::ops::Bound;
 use sbor::rust::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -26,15 +27,20 @@ impl SubstateDatabase for InMemorySubstateDatabase {
             .cloned()
     }
 
-    fn list_entries(
+    fn list_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let from_bound = match from_sort_key {
+            Some(from_sort_key) => Bound::Included(from_sort_key.clone()),
+            None => Bound::Unbounded,
+        };
         let iter = self
             .partitions
             .get(partition_key)
             .into_iter()
-            .flat_map(|partition| partition.iter())
+            .flat_map(move |partition| partition.range((from_bound.clone(), Bound::Unbounded)))
             .map(|(key, substate)| (key.clone(), substate.clone()));
 
         Box::new(iter)
@@ -0,0 +1,45 @@
+use radix_engine_store_interface::interface::*;
+use sbor::rust::prelude::*;
+use std::sync::Mutex;
+
+/// The substate values bulk-loaded by a database's [`SubstateDatabase::prefetch()`], to be served
+/// by the subsequent individual reads.
+/// Only the most recently prefetched substates are held (i.e. each prefetch replaces the previous
+/// one), and the holder must [`Self::clear()`] them on every commit.
+#[derive(Default)]
+pub struct PrefetchedSubstates {
+    values: Mutex<HashMap<DbSubstateKey, Option<DbSubstateValue>>>,
+}
+
+impl PrefetchedSubstates {
+    /// Replaces the held substates with the given ones.
+    pub fn replace(
+        &self,
+        entries: impl IntoIterator<Item = (DbSubstateKey, Option<DbSubstateValue>)>,
+    ) {
+        let mut values = self.values.lock().unwrap();
+        values.clear();
+        values.extend(entries);
+    }
+
+    /// Returns the prefetched value of the given substate ([`Option::None`] if it was not
+    /// prefetched, or `Some(None)` if it was prefetched and found missing).
+    pub fn get(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<Option<DbSubstateValue>> {
+        let values = self.values.lock().unwrap();
+        if values.is_empty() {
+            return None;
+        }
+        values
+            .get(&(partition_key.clone(), sort_key.clone()))
+            .cloned()
+    }
+
+    /// Drops all the held substates (e.g. since they may be outdated after a commit).
+    pub fn clear(&mut self) {
+        self.values.get_mut().unwrap().clear();
+    }
+}
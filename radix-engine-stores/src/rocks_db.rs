@@ -1,25 +1,50 @@
+use crate::prefetch::PrefetchedSubstates;
 use itertools::Itertools;
+use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
+use radix_engine_common::prelude::Hash;
+use radix_engine_derive::ScryptoSbor;
 use radix_engine_store_interface::interface::*;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
 use rocksdb::{
     ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode,
-    SingleThreaded, DB,
+    SingleThreaded, WriteBatch, DB,
 };
 use sbor::rust::prelude::*;
 use std::path::PathBuf;
 use utils::copy_u8_array;
 
+/// Metadata of the most recent commit, persisted atomically together with the committed changes.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct CommitMetadata {
+    /// The number of commits applied to the store so far (i.e. `1` after the first commit).
+    pub version: u64,
+    /// The hash of the last committed transaction, if it was known to the committer.
+    pub transaction_hash: Option<Hash>,
+}
+
 pub struct RocksdbSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
+    prefetched: PrefetchedSubstates,
 }
 
 impl RocksdbSubstateStore {
-    // Technically we don't need CFs here at all; however, delete range API is only available for CF
+    // Technically we don't need a dedicated CF for substates; however, delete range API is only
+    // available for CF
     const THE_ONLY_CF: &str = "the_only";
+    const META_CF: &str = "meta";
+
+    const COMMIT_METADATA_KEY: &[u8] = b"commit_metadata";
 
     pub fn standard(root: PathBuf) -> Self {
         Self::with_options(&Options::default(), root)
     }
+
+    /// Opens the store.
+    /// Each commit is written as a single atomic batch (i.e. after a crash, RocksDB's recovery
+    /// either restores the entire commit, or none of it), so the [`CommitMetadata`] always
+    /// describes the exact last applied commit. Checking that it matches the ledger being applied
+    /// (e.g. by re-reading the transaction of [`CommitMetadata::transaction_hash`]) is up to the
+    /// caller, as done by `replay execute`.
     pub fn with_options(options: &Options, root: PathBuf) -> Self {
         let mut options = options.clone();
         options.create_if_missing(true);
@@ -27,17 +52,105 @@ impl RocksdbSubstateStore {
         let db = DB::open_cf_descriptors(
             &options,
             root.as_path(),
-            vec![ColumnFamilyDescriptor::new(
-                Self::THE_ONLY_CF,
-                Options::default(),
-            )],
+            [Self::THE_ONLY_CF, Self::META_CF]
+                .into_iter()
+                .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
+                .collect::<Vec<_>>(),
         )
         .unwrap();
-        Self { db }
+        Self {
+            db,
+            prefetched: PrefetchedSubstates::default(),
+        }
+    }
+
+    /// Returns the metadata of the most recent commit, or [`Option::None`] if nothing was
+    /// committed yet (or if the store was created before the commits were versioned).
+    pub fn get_commit_metadata(&self) -> Option<CommitMetadata> {
+        self.db
+            .get_cf(self.cf(Self::META_CF), Self::COMMIT_METADATA_KEY)
+            .expect("IO Error")
+            .map(|bytes| {
+                scrypto_decode::<CommitMetadata>(&bytes).expect("Corrupted commit metadata")
+            })
+    }
+
+    /// Returns the version of the most recent commit (`0` if nothing was committed yet).
+    pub fn get_current_version(&self) -> u64 {
+        self.get_commit_metadata()
+            .map(|metadata| metadata.version)
+            .unwrap_or(0)
+    }
+
+    /// Commits the changes made by the transaction of the given hash, recording it in the
+    /// [`CommitMetadata`] (see [`Self::get_commit_metadata()`]).
+    pub fn commit_transaction(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        transaction_hash: Hash,
+    ) {
+        self.commit_with_metadata(database_updates, Some(transaction_hash));
+    }
+
+    fn commit_with_metadata(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        transaction_hash: Option<Hash>,
+    ) {
+        self.prefetched.clear();
+        let mut batch = WriteBatch::default();
+        for (node_key, node_updates) in &database_updates.node_updates {
+            for (partition_num, partition_updates) in &node_updates.partition_updates {
+                let partition_key = DbPartitionKey {
+                    node_key: node_key.clone(),
+                    partition_num: *partition_num,
+                };
+                match partition_updates {
+                    PartitionDatabaseUpdates::Delta { substate_updates } => {
+                        for (sort_key, update) in substate_updates {
+                            let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
+                            match update {
+                                DatabaseUpdate::Set(value_bytes) => {
+                                    batch.put_cf(self.cf(Self::THE_ONLY_CF), key_bytes, value_bytes)
+                                }
+                                DatabaseUpdate::Delete => {
+                                    batch.delete_cf(self.cf(Self::THE_ONLY_CF), key_bytes)
+                                }
+                            }
+                        }
+                    }
+                    PartitionDatabaseUpdates::Reset {
+                        new_substate_values,
+                    } => {
+                        // Note: a plain `delete_range()` is missing from rocksdb's API, and
+                        // (at the moment of writing) this is the only reason of having CF.
+                        batch.delete_range_cf(
+                            self.cf(Self::THE_ONLY_CF),
+                            encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
+                            encode_to_rocksdb_bytes(&partition_key.next(), &DbSortKey(vec![])),
+                        );
+                        for (sort_key, value_bytes) in new_substate_values {
+                            let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
+                            batch.put_cf(self.cf(Self::THE_ONLY_CF), key_bytes, value_bytes);
+                        }
+                    }
+                }
+            }
+        }
+        batch.put_cf(
+            self.cf(Self::META_CF),
+            Self::COMMIT_METADATA_KEY,
+            scrypto_encode(&CommitMetadata {
+                version: self.get_current_version() + 1,
+                transaction_hash,
+            })
+            .unwrap(),
+        );
+        self.db.write(batch).expect("IO error");
     }
 
-    fn cf(&self) -> &ColumnFamily {
-        self.db.cf_handle(Self::THE_ONLY_CF).unwrap()
+    fn cf(&self, cf: &str) -> &ColumnFamily {
+        self.db.cf_handle(cf).unwrap()
     }
 }
 
@@ -47,20 +160,28 @@ impl SubstateDatabase for RocksdbSubstateStore {
         partition_key: &DbPartitionKey,
         sort_key: &DbSortKey,
     ) -> Option<DbSubstateValue> {
+        if let Some(prefetched_value) = self.prefetched.get(partition_key, sort_key) {
+            return prefetched_value;
+        }
         let key_bytes = encode_to_rocksdb_bytes(partition_key, sort_key);
-        self.db.get_cf(self.cf(), &key_bytes).expect("IO Error")
+        self.db
+            .get_cf(self.cf(Self::THE_ONLY_CF), &key_bytes)
+            .expect("IO Error")
     }
 
-    fn list_entries(
+    fn list_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
         let partition_key = partition_key.clone();
-        let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![]));
+        let empty_sort_key = DbSortKey(vec![]);
+        let from_sort_key = from_sort_key.unwrap_or(&empty_sort_key);
+        let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, from_sort_key);
         let iter = self
             .db
             .iterator_cf(
-                self.cf(),
+                self.cf(Self::THE_ONLY_CF),
                 IteratorMode::From(&start_key_bytes, Direction::Forward),
             )
             .map(|kv| {
@@ -73,51 +194,28 @@ impl SubstateDatabase for RocksdbSubstateStore {
 
         Box::new(iter)
     }
+
+    /// Loads the given substates using a single RocksDB `multi_get`, and holds them in memory
+    /// until the next prefetch or commit.
+    fn prefetch(&self, substate_keys: &[DbSubstateKey]) {
+        let cf = self.cf(Self::THE_ONLY_CF);
+        let values = self
+            .db
+            .multi_get_cf(substate_keys.iter().map(|(partition_key, sort_key)| {
+                (cf, encode_to_rocksdb_bytes(partition_key, sort_key))
+            }));
+        self.prefetched.replace(
+            substate_keys
+                .iter()
+                .cloned()
+                .zip(values.into_iter().map(|value| value.expect("IO Error"))),
+        );
+    }
 }
 
 impl CommittableSubstateDatabase for RocksdbSubstateStore {
     fn commit(&mut self, database_updates: &DatabaseUpdates) {
-        for (node_key, node_updates) in &database_updates.node_updates {
-            for (partition_num, partition_updates) in &node_updates.partition_updates {
-                let partition_key = DbPartitionKey {
-                    node_key: node_key.clone(),
-                    partition_num: *partition_num,
-                };
-                match partition_updates {
-                    PartitionDatabaseUpdates::Delta { substate_updates } => {
-                        for (sort_key, update) in substate_updates {
-                            let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
-                            match update {
-                                DatabaseUpdate::Set(value_bytes) => {
-                                    self.db.put_cf(self.cf(), key_bytes, value_bytes)
-                                }
-                                DatabaseUpdate::Delete => self.db.delete_cf(self.cf(), key_bytes),
-                            }
-                            .expect("IO error");
-                        }
-                    }
-                    PartitionDatabaseUpdates::Reset {
-                        new_substate_values,
-                    } => {
-                        // Note: a plain `delete_range()` is missing from rocksdb's API, and
-                        // (at the moment of writing) this is the only reason of having CF.
-                        self.db
-                            .delete_range_cf(
-                                self.cf(),
-                                encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
-                                encode_to_rocksdb_bytes(&partition_key.next(), &DbSortKey(vec![])),
-                            )
-                            .expect("IO error");
-                        for (sort_key, value_bytes) in new_substate_values {
-                            let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
-                            self.db
-                                .put_cf(self.cf(), key_bytes, value_bytes)
-                                .expect("IO error");
-                        }
-                    }
-                }
-            }
-        }
+        self.commit_with_metadata(database_updates, None);
     }
 }
 
@@ -125,7 +223,7 @@ impl ListableSubstateDatabase for RocksdbSubstateStore {
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
         Box::new(
             self.db
-                .iterator_cf(self.cf(), IteratorMode::Start)
+                .iterator_cf(self.cf(Self::THE_ONLY_CF), IteratorMode::Start)
                 .map(|kv| {
                     let (iter_key_bytes, _) = kv.as_ref().unwrap();
                     let (iter_key, _) = decode_from_rocksdb_bytes(iter_key_bytes);
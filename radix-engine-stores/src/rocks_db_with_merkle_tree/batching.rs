@@ -0,0 +1,53 @@
+use radix_engine_common::prelude::Hash;
+use radix_engine_store_interface::interface::*;
+use radix_engine_store_interface::overlay::DatabaseUpdatesOverlay;
+use sbor::prelude::*;
+
+/// The commits buffered in memory by a store with commit batching enabled, together with an
+/// overlay of the substate values resulting from them.
+pub struct PendingCommits {
+    max_batch_size: usize,
+    database_updates_batch: Vec<DatabaseUpdates>,
+    last_transaction_hash: Option<Hash>,
+    overlay: DatabaseUpdatesOverlay,
+}
+
+impl PendingCommits {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self {
+            max_batch_size,
+            database_updates_batch: Vec::new(),
+            last_transaction_hash: None,
+            overlay: DatabaseUpdatesOverlay::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.database_updates_batch.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.database_updates_batch.len() >= self.max_batch_size
+    }
+
+    pub fn push(&mut self, database_updates: &DatabaseUpdates, transaction_hash: Option<Hash>) {
+        self.overlay.apply(database_updates);
+        self.database_updates_batch.push(database_updates.clone());
+        self.last_transaction_hash = transaction_hash;
+    }
+
+    /// Removes all the buffered commits, returning their updates and the hash of the last
+    /// committed transaction (if known).
+    pub fn take(&mut self) -> (Vec<DatabaseUpdates>, Option<Hash>) {
+        self.overlay.clear();
+        (
+            std::mem::take(&mut self.database_updates_batch),
+            self.last_transaction_hash.take(),
+        )
+    }
+
+    /// Returns the overlay of the substate values resulting from the buffered commits.
+    pub fn overlay(&self) -> &DatabaseUpdatesOverlay {
+        &self.overlay
+    }
+}
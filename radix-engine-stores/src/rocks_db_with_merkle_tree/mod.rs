@@ -1,9 +1,14 @@
+use crate::hash_tree::proof::SubstateProof;
 use crate::hash_tree::tree_store::{
-    encode_key, NodeKey, ReadableTreeStore, StaleTreePart, TreeNode, TreeNodeV1, VersionedTreeNode,
+    encode_key, NodeKey, ReadableTreeStore, StaleTreePart, TreeNode, VersionedTreeNode,
 };
+use crate::hash_tree::{
+    get_substate_leaf_at_version, get_substate_proof_at_version, list_substate_leaves_at_version,
+};
+use crate::prefetch::PrefetchedSubstates;
 use itertools::Itertools;
 use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
-use radix_engine_common::prelude::Hash;
+use radix_engine_common::prelude::{hash, Hash};
 use radix_engine_derive::ScryptoSbor;
 use radix_engine_store_interface::interface::*;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
@@ -12,20 +17,40 @@ use rocksdb::{
     SingleThreaded, WriteBatch, DB,
 };
 use sbor::prelude::*;
+use std::io::Read;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use utils::copy_u8_array;
 
+mod batching;
+mod pruning;
 mod state_tree;
 use crate::rocks_db::{decode_from_rocksdb_bytes, encode_to_rocksdb_bytes};
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotSummary};
+use batching::*;
+pub use pruning::*;
 use state_tree::*;
 
+#[cfg(test)]
+mod test;
+
 const META_CF: &str = "meta";
 const SUBSTATES_CF: &str = "substates";
 const MERKLE_NODES_CF: &str = "merkle_nodes";
 const STALE_MERKLE_TREE_PARTS_CF: &str = "stale_merkle_tree_parts";
+const SUBSTATE_HISTORY_CF: &str = "substate_history";
+const SUPERSEDED_SUBSTATE_HISTORY_CF: &str = "superseded_substate_history";
+
+const METADATA_KEY: &[u8] = &[];
+const PRUNING_STATS_KEY: &[u8] = b"pruning_stats";
+const SUBSTATE_HISTORY_SINCE_KEY: &[u8] = b"substate_history_since";
+const LAST_TRANSACTION_HASH_KEY: &[u8] = b"last_transaction_hash";
 
 pub struct RocksDBWithMerkleTreeSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
-    pruning_enabled: bool,
+    pruning_config: Option<StateTreePruningConfig>,
+    pending_commits: Option<PendingCommits>,
+    prefetched: PrefetchedSubstates,
 }
 
 impl RocksDBWithMerkleTreeSubstateStore {
@@ -42,10 +67,24 @@ impl RocksDBWithMerkleTreeSubstateStore {
         let mut options = Options::default();
         options.create_if_missing(true);
         options.create_missing_column_families(true);
-        Self::with_options(&options, root, true)
+        Self::with_options(&options, root, Some(StateTreePruningConfig::immediate()))
     }
 
-    pub fn with_options(options: &Options, root: PathBuf, pruning_enabled: bool) -> Self {
+    /// Opens the store with the given RocksDB options and the state tree's GC configuration.
+    /// If the GC is disabled (i.e. `None`), the stale tree parts are still recorded (indexed by the
+    /// state version which made them stale), so that they can be pruned after a later re-open.
+    ///
+    /// Whenever the configuration retains any historical versions (or disables the GC), the store
+    /// also records the history of substate values, which allows to read the substates as of any
+    /// retained version (see [`VersionedSubstateDatabase`]). Note: the history is only available
+    /// if it was recorded since the first commit (i.e. it cannot be enabled on an existing store),
+    /// and it becomes unavailable (and gets deleted) as soon as a commit is made with immediate
+    /// pruning.
+    pub fn with_options(
+        options: &Options,
+        root: PathBuf,
+        pruning_config: Option<StateTreePruningConfig>,
+    ) -> Self {
         let db = DB::open_cf_descriptors(
             options,
             root.as_path(),
@@ -54,6 +93,8 @@ impl RocksDBWithMerkleTreeSubstateStore {
                 SUBSTATES_CF,
                 MERKLE_NODES_CF,
                 STALE_MERKLE_TREE_PARTS_CF,
+                SUBSTATE_HISTORY_CF,
+                SUPERSEDED_SUBSTATE_HISTORY_CF,
             ]
             .into_iter()
             .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
@@ -62,17 +103,50 @@ impl RocksDBWithMerkleTreeSubstateStore {
         .unwrap();
         Self {
             db,
-            pruning_enabled,
+            pruning_config,
+            pending_commits: None,
+            prefetched: PrefetchedSubstates::default(),
         }
     }
 
+    /// Enables buffering of up to the given number of consecutive commits in memory, and writing
+    /// them (together with a single state tree update) only when the buffer becomes full, or on an
+    /// explicit [`Self::flush_pending_commits()`] (which also happens on drop).
+    /// This greatly reduces the write amplification of bulk commits (e.g. replaying the ledger),
+    /// at the cost of the following:
+    /// - the substate reads see the pending commits, but the versioned reads, proofs and
+    ///   the current version/root hash only reflect the flushed ones,
+    /// - the intermediate versions of a flushed batch are not readable (i.e. the substate history
+    ///   becomes unavailable after a batch of more than a single commit is flushed),
+    /// - the pending commits are lost on a crash (however, the store remains consistent, at the
+    ///   last flushed version).
+    pub fn with_commit_batching(mut self, max_batch_size: usize) -> Self {
+        assert!(max_batch_size > 0, "max batch size must be positive");
+        self.flush_pending_commits();
+        self.pending_commits = Some(PendingCommits::new(max_batch_size));
+        self
+    }
+
+    /// Writes all the commits buffered by [`Self::with_commit_batching()`] (if any) as a single
+    /// atomic batch.
+    pub fn flush_pending_commits(&mut self) {
+        let Some(pending_commits) = self.pending_commits.as_mut() else {
+            return;
+        };
+        if pending_commits.is_empty() {
+            return;
+        }
+        let (database_updates_batch, transaction_hash) = pending_commits.take();
+        self.write_commits(&database_updates_batch, transaction_hash);
+    }
+
     fn cf(&self, cf: &str) -> &ColumnFamily {
         self.db.cf_handle(cf).unwrap()
     }
 
     pub fn get_current_version(&self) -> u64 {
         self.db
-            .get_cf(self.cf(META_CF), &[])
+            .get_cf(self.cf(META_CF), METADATA_KEY)
             .unwrap()
             .map(|bytes| {
                 scrypto_decode::<Metadata>(&bytes)
@@ -84,7 +158,7 @@ impl RocksDBWithMerkleTreeSubstateStore {
 
     pub fn get_current_root_hash(&self) -> Hash {
         self.db
-            .get_cf(self.cf(META_CF), &[])
+            .get_cf(self.cf(META_CF), METADATA_KEY)
             .unwrap()
             .map(|bytes| {
                 scrypto_decode::<Metadata>(&bytes)
@@ -93,6 +167,358 @@ impl RocksDBWithMerkleTreeSubstateStore {
             })
             .unwrap_or(Hash([0u8; Hash::LENGTH]))
     }
+
+    /// Returns the hash of the transaction committed at the current version, or [`Option::None`]
+    /// if it is not known (i.e. the last commit was not made by [`Self::commit_transaction()`]).
+    pub fn get_last_transaction_hash(&self) -> Option<Hash> {
+        self.db
+            .get_cf(self.cf(META_CF), LAST_TRANSACTION_HASH_KEY)
+            .unwrap()
+            .map(|bytes| scrypto_decode::<Hash>(&bytes).unwrap())
+    }
+
+    /// Commits the changes made by the transaction of the given hash, and records the hash
+    /// atomically with them (see [`Self::get_last_transaction_hash()`]), so that a process
+    /// applying a sequence of transactions can resume exactly after the last committed one.
+    pub fn commit_transaction(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        transaction_hash: Hash,
+    ) {
+        self.commit_with_transaction_hash(database_updates, Some(transaction_hash));
+    }
+
+    /// Generates a proof of the given substate's current value (or of its absence), verifiable
+    /// against the current root hash.
+    pub fn get_substate_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> SubstateProof {
+        get_substate_proof_at_version(
+            self,
+            Some(self.get_current_version()).filter(|version| *version > 0),
+            partition_key,
+            sort_key,
+        )
+    }
+
+    /// Loads the given snapshot into this (empty) store, resulting in the snapshot's state version,
+    /// and verifies the resulting root hash (if the snapshot has one).
+    /// The substate values are written chunk by chunk (i.e. only their hashes are kept in memory,
+    /// until the state tree is computed and committed at the end, together with the metadata).
+    /// Note: on error, the store may contain a part of the snapshot's substates, and should be
+    /// discarded.
+    pub fn import_snapshot<R: Read>(
+        &mut self,
+        reader: R,
+    ) -> Result<SnapshotSummary, SnapshotError> {
+        self.flush_pending_commits();
+        self.prefetched.clear();
+        if self.get_current_version() != 0 {
+            return Err(SnapshotError::DatabaseNotEmpty);
+        }
+        let mut snapshot_reader = SnapshotReader::new(reader)?;
+        let Some(state_version) = snapshot_reader.header().state_version.filter(|v| *v > 0) else {
+            return Err(SnapshotError::MissingStateVersion);
+        };
+        let records_substate_history = self.records_substate_history();
+        let mut substate_hashes = index_map_new::<DbPartitionKey, IndexMap<DbSortKey, Hash>>();
+        while let Some(chunk_updates) = snapshot_reader.next_chunk()? {
+            let mut batch = WriteBatch::default();
+            for (node_key, node_updates) in chunk_updates.node_updates {
+                for (partition_num, partition_updates) in node_updates.partition_updates {
+                    let PartitionDatabaseUpdates::Delta { substate_updates } = partition_updates
+                    else {
+                        panic!("snapshot chunks only contain deltas");
+                    };
+                    let partition_key = DbPartitionKey {
+                        node_key: node_key.clone(),
+                        partition_num,
+                    };
+                    let partition_hashes =
+                        substate_hashes.entry(partition_key.clone()).or_default();
+                    for (sort_key, update) in substate_updates {
+                        let DatabaseUpdate::Set(value_bytes) = update else {
+                            panic!("snapshot chunks only contain upserts");
+                        };
+                        if records_substate_history {
+                            // the imported values make a complete history since the snapshot's
+                            // version
+                            batch.put_cf(
+                                self.cf(SUBSTATE_HISTORY_CF),
+                                encode_history_key(state_version, &partition_key, &sort_key),
+                                &value_bytes,
+                            );
+                        }
+                        batch.put_cf(
+                            self.cf(SUBSTATES_CF),
+                            encode_to_rocksdb_bytes(&partition_key, &sort_key),
+                            &value_bytes,
+                        );
+                        partition_hashes.insert(sort_key, hash(&value_bytes));
+                    }
+                }
+            }
+            self.db.write(batch).unwrap();
+        }
+        let summary = snapshot_reader.summary().expect("all chunks read");
+
+        let (state_hash_tree_update, root_hash) =
+            compute_initial_state_tree(self, state_version, &substate_hashes);
+        if let Some(expected) = summary.header.state_root_hash {
+            if root_hash != expected {
+                return Err(SnapshotError::StateRootHashMismatch {
+                    expected,
+                    actual: root_hash,
+                });
+            }
+        }
+        let mut batch = WriteBatch::default();
+        for (key, node) in state_hash_tree_update.new_nodes {
+            batch.put_cf(
+                self.cf(MERKLE_NODES_CF),
+                encode_key(&key),
+                scrypto_encode(&VersionedTreeNode::new_latest(node)).unwrap(),
+            );
+        }
+        if records_substate_history {
+            batch.put_cf(
+                self.cf(META_CF),
+                SUBSTATE_HISTORY_SINCE_KEY,
+                scrypto_encode(&state_version).unwrap(),
+            );
+        }
+        batch.put_cf(
+            self.cf(META_CF),
+            METADATA_KEY,
+            scrypto_encode(&Metadata {
+                current_state_version: state_version,
+                current_state_root_hash: root_hash,
+            })
+            .unwrap(),
+        );
+        self.db.write(batch).unwrap();
+        Ok(summary)
+    }
+
+    pub fn get_pruning_config(&self) -> Option<StateTreePruningConfig> {
+        self.pruning_config
+    }
+
+    pub fn get_pruning_stats(&self) -> StateTreePruningStats {
+        self.db
+            .get_cf(self.cf(META_CF), PRUNING_STATS_KEY)
+            .unwrap()
+            .map(|bytes| scrypto_decode::<StateTreePruningStats>(&bytes).unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Counts the state versions whose recorded stale tree parts still await pruning (including
+    /// the ones within the retained window).
+    /// Note: this iterates over the entire stale tree parts index.
+    pub fn count_unpruned_versions(&self) -> u64 {
+        self.db
+            .iterator_cf(self.cf(STALE_MERKLE_TREE_PARTS_CF), IteratorMode::Start)
+            .count() as u64
+    }
+
+    /// Deletes the stale tree parts recorded by at most `max_versions` oldest state versions which
+    /// fall out of the configured retained window.
+    /// This is invoked automatically after every commit (see
+    /// [`StateTreePruningConfig::max_versions_per_gc_step`]), but may also be used to catch up with
+    /// a backlog (e.g. at startup). Does nothing if the pruning is disabled.
+    /// Returns the number of state versions pruned by this call.
+    pub fn prune_stale_tree_parts(&mut self, max_versions: u64) -> u64 {
+        let Some(pruning_config) = self.pruning_config else {
+            return 0;
+        };
+        if max_versions == 0 {
+            return 0;
+        }
+        let max_prunable_version = self
+            .get_current_version()
+            .saturating_sub(pruning_config.retained_versions);
+
+        let versions_to_prune = self
+            .db
+            .iterator_cf(self.cf(STALE_MERKLE_TREE_PARTS_CF), IteratorMode::Start)
+            .map(|kv| {
+                let (version_bytes, parts_bytes) = kv.unwrap();
+                (
+                    u64::from_be_bytes(copy_u8_array(&version_bytes)),
+                    parts_bytes,
+                )
+            })
+            .take_while(|(version, _)| *version <= max_prunable_version)
+            .take(usize::try_from(max_versions).unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+        // all state versions below this one will be unreadable after this GC step
+        let pruned_up_to_version = match versions_to_prune.last() {
+            Some((version, _)) if versions_to_prune.len() as u64 == max_versions => *version,
+            _ => max_prunable_version,
+        };
+
+        let mut stats = self.get_pruning_stats();
+        if pruned_up_to_version <= stats.last_pruned_state_version {
+            return 0;
+        }
+
+        // Note: the deletes are only applied when the batch is written, so all the reads below
+        // (i.e. traversal of stale subtrees and look-ups of superseded history) see the state trees
+        // of all the versions being pruned.
+        let mut batch = WriteBatch::default();
+        for (version, parts_bytes) in &versions_to_prune {
+            let parts = scrypto_decode::<Vec<StaleTreePart>>(parts_bytes).unwrap();
+            stats.pruned_stale_parts += parts.len() as u64;
+            for part in parts {
+                let node_keys = collect_stale_node_keys(part, |encoded_key| {
+                    self.get_node_by_encoded_key(encoded_key)
+                });
+                stats.deleted_nodes += node_keys.len() as u64;
+                for node_key in node_keys {
+                    batch.delete_cf(self.cf(MERKLE_NODES_CF), node_key);
+                }
+            }
+            batch.delete_cf(self.cf(STALE_MERKLE_TREE_PARTS_CF), version.to_be_bytes());
+            stats.pruned_versions += 1;
+        }
+        self.prune_superseded_substate_history(&mut batch, pruned_up_to_version);
+        stats.last_pruned_state_version = pruned_up_to_version;
+        batch.put_cf(
+            self.cf(META_CF),
+            PRUNING_STATS_KEY,
+            scrypto_encode(&stats).unwrap(),
+        );
+        self.db.write(batch).unwrap();
+
+        versions_to_prune.len() as u64
+    }
+
+    /// Deletes the substate history entries which were superseded at any version up to the given
+    /// one (i.e. the values which are only needed to read versions lower than that).
+    fn prune_superseded_substate_history(&self, batch: &mut WriteBatch, up_to_version: u64) {
+        let superseded_by_version = self
+            .db
+            .iterator_cf(self.cf(SUPERSEDED_SUBSTATE_HISTORY_CF), IteratorMode::Start)
+            .map(|kv| {
+                let (version_bytes, superseded_bytes) = kv.unwrap();
+                (
+                    u64::from_be_bytes(copy_u8_array(&version_bytes)),
+                    superseded_bytes,
+                )
+            })
+            .take_while(|(version, _)| *version <= up_to_version)
+            .collect::<Vec<_>>();
+        for (version, superseded_bytes) in superseded_by_version {
+            batch.delete_cf(
+                self.cf(SUPERSEDED_SUBSTATE_HISTORY_CF),
+                version.to_be_bytes(),
+            );
+            // the superseded values are the ones which were current at the previous version
+            let previous_version = version - 1;
+            if previous_version == 0 {
+                continue;
+            }
+            let superseded =
+                scrypto_decode::<Vec<SupersededSubstateHistory>>(&superseded_bytes).unwrap();
+            for superseded in superseded {
+                match superseded {
+                    SupersededSubstateHistory::Substate(partition_key, sort_key) => {
+                        if let Some(leaf) = get_substate_leaf_at_version(
+                            self,
+                            previous_version,
+                            &partition_key,
+                            &sort_key,
+                        ) {
+                            batch.delete_cf(
+                                self.cf(SUBSTATE_HISTORY_CF),
+                                encode_history_key(
+                                    leaf.last_hash_change_version,
+                                    &partition_key,
+                                    &sort_key,
+                                ),
+                            );
+                        }
+                    }
+                    SupersededSubstateHistory::Partition(partition_key) => {
+                        for (sort_key, leaf) in
+                            list_substate_leaves_at_version(self, previous_version, &partition_key)
+                        {
+                            batch.delete_cf(
+                                self.cf(SUBSTATE_HISTORY_CF),
+                                encode_history_key(
+                                    leaf.last_hash_change_version,
+                                    &partition_key,
+                                    &sort_key,
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the first state version since which the substate history is recorded, if it is
+    /// available at all.
+    fn get_substate_history_since(&self) -> Option<u64> {
+        self.db
+            .get_cf(self.cf(META_CF), SUBSTATE_HISTORY_SINCE_KEY)
+            .unwrap()
+            .map(|bytes| scrypto_decode::<u64>(&bytes).unwrap())
+    }
+
+    /// Resolves whether the commit on top of the given parent version should record the substate
+    /// history, and (if so) since which version the history is available.
+    fn resolve_substate_history_since(&self, parent_state_version: u64) -> Option<u64> {
+        if !self.records_substate_history() {
+            return None;
+        }
+        self.get_substate_history_since()
+            .or_else(|| (parent_state_version == 0).then_some(parent_state_version + 1))
+    }
+
+    fn records_substate_history(&self) -> bool {
+        self.pruning_config
+            .map(|pruning_config| pruning_config.retained_versions > 0)
+            .unwrap_or(true)
+    }
+
+    fn get_historical_substate(
+        &self,
+        state_version: u64,
+        value_version: u64,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Result<DbSubstateValue, VersionedReadError> {
+        self.db
+            .get_cf(
+                self.cf(SUBSTATE_HISTORY_CF),
+                encode_history_key(value_version, partition_key, sort_key),
+            )
+            .unwrap()
+            .ok_or(VersionedReadError::MissingHistory { state_version })
+    }
+
+    fn check_readable(&self, state_version: u64) -> Result<(), VersionedReadError> {
+        let readable_versions = self.get_readable_versions();
+        if !readable_versions.contains(&state_version) {
+            return Err(VersionedReadError::VersionNotReadable {
+                state_version,
+                readable_versions,
+            });
+        }
+        Ok(())
+    }
+
+    fn get_node_by_encoded_key(&self, encoded_key: &[u8]) -> Option<TreeNode> {
+        self.db
+            .get_cf(self.cf(MERKLE_NODES_CF), encoded_key)
+            .unwrap()
+            .map(|bytes| scrypto_decode::<VersionedTreeNode>(&bytes).unwrap())
+            .map(|versioned| versioned.into_latest())
+    }
 }
 
 impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
@@ -101,18 +527,78 @@ impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
         partition_key: &DbPartitionKey,
         sort_key: &DbSortKey,
     ) -> Option<DbSubstateValue> {
+        if let Some(pending_commits) = &self.pending_commits {
+            if let Some(pending_value) = pending_commits
+                .overlay()
+                .get_substate(partition_key, sort_key)
+            {
+                return pending_value;
+            }
+        }
+        self.get_stored_substate(partition_key, sort_key)
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let stored_entries = self.list_stored_entries_from(partition_key, from_sort_key);
+        match &self.pending_commits {
+            Some(pending_commits) => pending_commits.overlay().overlay_entries(
+                partition_key,
+                from_sort_key,
+                stored_entries,
+            ),
+            None => stored_entries,
+        }
+    }
+
+    /// Loads the given substates (as of the current, i.e. flushed, version) using a single RocksDB
+    /// `multi_get`, and holds them in memory until the next prefetch or flush.
+    /// Note: the pending commits (if any) still take precedence over the prefetched values.
+    fn prefetch(&self, substate_keys: &[DbSubstateKey]) {
+        let cf = self.cf(SUBSTATES_CF);
+        let values = self
+            .db
+            .multi_get_cf(substate_keys.iter().map(|(partition_key, sort_key)| {
+                (cf, encode_to_rocksdb_bytes(partition_key, sort_key))
+            }));
+        self.prefetched.replace(
+            substate_keys
+                .iter()
+                .cloned()
+                .zip(values.into_iter().map(|value| value.expect("IO Error"))),
+        );
+    }
+}
+
+impl RocksDBWithMerkleTreeSubstateStore {
+    /// Reads the substate's value as of the current (i.e. flushed) version.
+    fn get_stored_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        if let Some(prefetched_value) = self.prefetched.get(partition_key, sort_key) {
+            return prefetched_value;
+        }
         let key_bytes = encode_to_rocksdb_bytes(partition_key, sort_key);
         self.db
             .get_cf(self.cf(SUBSTATES_CF), &key_bytes)
             .expect("IO Error")
     }
 
-    fn list_entries(
+    /// Lists the partition's entries as of the current (i.e. flushed) version.
+    fn list_stored_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
         let partition_key = partition_key.clone();
-        let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![]));
+        let empty_sort_key = DbSortKey(vec![]);
+        let from_sort_key = from_sort_key.unwrap_or(&empty_sort_key);
+        let start_key_bytes = encode_to_rocksdb_bytes(&partition_key, from_sort_key);
         let iter = self
             .db
             .iterator_cf(
@@ -133,10 +619,39 @@ impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
 
 impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     fn commit(&mut self, database_updates: &DatabaseUpdates) {
+        self.commit_with_transaction_hash(database_updates, None);
+    }
+}
+
+impl RocksDBWithMerkleTreeSubstateStore {
+    fn commit_with_transaction_hash(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        transaction_hash: Option<Hash>,
+    ) {
+        match self.pending_commits.as_mut() {
+            Some(pending_commits) => {
+                pending_commits.push(database_updates, transaction_hash);
+                if pending_commits.is_full() {
+                    self.flush_pending_commits();
+                }
+            }
+            None => self.write_commits(std::slice::from_ref(database_updates), transaction_hash),
+        }
+    }
+
+    /// Writes the given consecutive commits (and the hash of the last committed transaction, if
+    /// known) as a single atomic batch.
+    fn write_commits(
+        &mut self,
+        database_updates_batch: &[DatabaseUpdates],
+        transaction_hash: Option<Hash>,
+    ) {
+        self.prefetched.clear();
         // read required info about current database state (here I fake it a bit)
         let metadata = self
             .db
-            .get_cf(self.cf(META_CF), [])
+            .get_cf(self.cf(META_CF), METADATA_KEY)
             .unwrap()
             .map(|bytes| scrypto_decode::<Metadata>(&bytes).unwrap())
             .unwrap_or_else(|| Metadata {
@@ -144,13 +659,16 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                 current_state_root_hash: Hash([0u8; Hash::LENGTH]),
             });
         let parent_state_version = metadata.current_state_version;
-        let next_state_version = parent_state_version + 1;
+        let next_state_version = parent_state_version + database_updates_batch.len() as u64;
 
         // prepare a batch write (we use the same approach in the actual Node)
         let mut batch = WriteBatch::default();
 
         // put regular substate changes
-        for (node_key, node_updates) in &database_updates.node_updates {
+        for (node_key, node_updates) in database_updates_batch
+            .iter()
+            .flat_map(|database_updates| &database_updates.node_updates)
+        {
             for (partition_num, partition_updates) in &node_updates.partition_updates {
                 let partition_key = DbPartitionKey {
                     node_key: node_key.clone(),
@@ -162,14 +680,12 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             match update {
                                 DatabaseUpdate::Set(value_bytes) => {
-                                    self.db
-                                        .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
+                                    batch.put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
                                 }
                                 DatabaseUpdate::Delete => {
-                                    self.db.delete_cf(self.cf(SUBSTATES_CF), key_bytes)
+                                    batch.delete_cf(self.cf(SUBSTATES_CF), key_bytes)
                                 }
                             }
-                            .expect("IO error");
                         }
                     }
                     PartitionDatabaseUpdates::Reset {
@@ -177,27 +693,101 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                     } => {
                         // Note: a plain `delete_range()` is missing from rocksdb's API, and
                         // (at the moment of writing) this is the only reason of having CF.
-                        self.db
-                            .delete_range_cf(
-                                self.cf(SUBSTATES_CF),
-                                encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
-                                encode_to_rocksdb_bytes(&partition_key.next(), &DbSortKey(vec![])),
-                            )
-                            .expect("IO error");
+                        batch.delete_range_cf(
+                            self.cf(SUBSTATES_CF),
+                            encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
+                            encode_to_rocksdb_bytes(&partition_key.next(), &DbSortKey(vec![])),
+                        );
                         for (sort_key, value_bytes) in new_substate_values {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
-                            self.db
-                                .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
-                                .expect("IO error");
+                            batch.put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes);
+                        }
+                    }
+                }
+            }
+        }
+
+        // record the history of substate values (indexed by the version at which they were set), if
+        // the historical versions are retained (only possible for a single commit, since the
+        // intermediate versions of a batch have no tree nodes to be read from)
+        let history_since_version = match database_updates_batch {
+            [_] => self.resolve_substate_history_since(parent_state_version),
+            _ => None,
+        };
+        if let Some(history_since_version) = history_since_version {
+            let database_updates = &database_updates_batch[0];
+            let mut superseded_history = Vec::new();
+            for (node_key, node_updates) in &database_updates.node_updates {
+                for (partition_num, partition_updates) in &node_updates.partition_updates {
+                    let partition_key = DbPartitionKey {
+                        node_key: node_key.clone(),
+                        partition_num: *partition_num,
+                    };
+                    match partition_updates {
+                        PartitionDatabaseUpdates::Delta { substate_updates } => {
+                            for (sort_key, update) in substate_updates {
+                                if let DatabaseUpdate::Set(value_bytes) = update {
+                                    batch.put_cf(
+                                        self.cf(SUBSTATE_HISTORY_CF),
+                                        encode_history_key(
+                                            next_state_version,
+                                            &partition_key,
+                                            sort_key,
+                                        ),
+                                        value_bytes,
+                                    );
+                                }
+                                superseded_history.push(SupersededSubstateHistory::Substate(
+                                    partition_key.clone(),
+                                    sort_key.clone(),
+                                ));
+                            }
+                        }
+                        PartitionDatabaseUpdates::Reset {
+                            new_substate_values,
+                        } => {
+                            for (sort_key, value_bytes) in new_substate_values {
+                                batch.put_cf(
+                                    self.cf(SUBSTATE_HISTORY_CF),
+                                    encode_history_key(
+                                        next_state_version,
+                                        &partition_key,
+                                        sort_key,
+                                    ),
+                                    value_bytes,
+                                );
+                            }
+                            superseded_history
+                                .push(SupersededSubstateHistory::Partition(partition_key));
                         }
                     }
                 }
             }
+            if !superseded_history.is_empty() {
+                batch.put_cf(
+                    self.cf(SUPERSEDED_SUBSTATE_HISTORY_CF),
+                    next_state_version.to_be_bytes(),
+                    scrypto_encode(&superseded_history).unwrap(),
+                );
+            }
+            batch.put_cf(
+                self.cf(META_CF),
+                SUBSTATE_HISTORY_SINCE_KEY,
+                scrypto_encode(&history_since_version).unwrap(),
+            );
+        } else if self.get_substate_history_since().is_some() {
+            // the history stops being recorded (e.g. after a switch to immediate pruning, or
+            // a batch of commits), so all of it becomes unreadable and can be deleted
+            batch.delete_cf(self.cf(META_CF), SUBSTATE_HISTORY_SINCE_KEY);
+            for cf in [SUBSTATE_HISTORY_CF, SUPERSEDED_SUBSTATE_HISTORY_CF] {
+                // (both are keyed by a big-endian state version first)
+                batch.delete_range_cf(self.cf(cf), 0u64.to_be_bytes(), u64::MAX.to_be_bytes());
+            }
         }
 
-        // derive and put new JMT nodes (also record references to stale parts, for later amortized background GC [not implemented here!])
-        let (state_hash_tree_update, new_root_hash) =
-            compute_state_tree_update(self, parent_state_version, database_updates);
+        // derive and put new JMT nodes (also record references to stale parts, for later amortized GC)
+        let (state_hash_tree_update, new_root_hashes) =
+            compute_state_tree_update(self, parent_state_version, database_updates_batch);
         for (key, node) in state_hash_tree_update.new_nodes {
             batch.put_cf(
                 self.cf(MERKLE_NODES_CF),
@@ -205,8 +795,7 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                 scrypto_encode(&VersionedTreeNode::new_latest(node)).unwrap(),
             );
         }
-        if !self.pruning_enabled {
-            // If pruning is not enabled, we store the stale nodes in DB.
+        if !state_hash_tree_update.stale_tree_parts.is_empty() {
             batch.put_cf(
                 self.cf(STALE_MERKLE_TREE_PARTS_CF),
                 next_state_version.to_be_bytes(),
@@ -217,67 +806,54 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
         // update the metadata
         batch.put_cf(
             self.cf(META_CF),
-            [],
+            METADATA_KEY,
             scrypto_encode(&Metadata {
                 current_state_version: next_state_version,
-                current_state_root_hash: new_root_hash,
+                current_state_root_hash: *new_root_hashes.last().unwrap(),
             })
             .unwrap(),
         );
 
-        // flush the batch
+        match transaction_hash {
+            Some(transaction_hash) => batch.put_cf(
+                self.cf(META_CF),
+                LAST_TRANSACTION_HASH_KEY,
+                scrypto_encode(&transaction_hash).unwrap(),
+            ),
+            None => batch.delete_cf(self.cf(META_CF), LAST_TRANSACTION_HASH_KEY),
+        }
+
+        // flush the batch (i.e. the entire commit is applied atomically, including the metadata)
         self.db.write(batch).unwrap();
 
-        if self.pruning_enabled {
-            for part in state_hash_tree_update.stale_tree_parts {
-                match part {
-                    StaleTreePart::Node(node_key) => {
-                        self.db
-                            .delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                            .unwrap();
-                    }
-                    StaleTreePart::Subtree(node_key) => {
-                        let mut queue = VecDeque::new();
-                        queue.push_back(node_key);
-
-                        while let Some(node_key) = queue.pop_front() {
-                            if let Some(bytes) = self
-                                .db
-                                .get_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                                .unwrap()
-                            {
-                                self.db
-                                    .delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                                    .unwrap();
-                                let value: VersionedTreeNode = scrypto_decode(&bytes).unwrap();
-                                match value.into_latest() {
-                                    TreeNodeV1::Internal(x) => {
-                                        for child in x.children {
-                                            queue.push_back(
-                                                node_key.gen_child_node_key(
-                                                    child.version,
-                                                    child.nibble,
-                                                ),
-                                            )
-                                        }
-                                    }
-                                    TreeNodeV1::Leaf(_) => {}
-                                    TreeNodeV1::Null => {}
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        // run a single GC step (the stale parts which fall out of the retained window get deleted)
+        if let Some(pruning_config) = self.pruning_config {
+            self.prune_stale_tree_parts(pruning_config.max_versions_per_gc_step);
         }
     }
 }
 
 impl ListableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
+        let Some(pending_commits) = &self.pending_commits else {
+            return self.list_stored_partition_keys();
+        };
+        // the pending commits may both create and empty the partitions
+        let partition_keys = self
+            .list_stored_partition_keys()
+            .chain(pending_commits.overlay().list_partition_keys().cloned())
+            .collect::<BTreeSet<_>>();
+        Box::new(partition_keys.into_iter().filter(move |partition_key| {
+            self.list_entries_from(partition_key, None).next().is_some()
+        }))
+    }
+}
+
+impl RocksDBWithMerkleTreeSubstateStore {
+    fn list_stored_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
         Box::new(
             self.db
-                .iterator(IteratorMode::Start)
+                .iterator_cf(self.cf(SUBSTATES_CF), IteratorMode::Start)
                 .map(|kv| {
                     let (iter_key_bytes, _) = kv.as_ref().unwrap();
                     let (iter_key, _) = decode_from_rocksdb_bytes(iter_key_bytes);
@@ -290,16 +866,108 @@ impl ListableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     }
 }
 
+impl VersionedSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
+    fn get_readable_versions(&self) -> RangeInclusive<u64> {
+        let current_version = self.get_current_version();
+        match self.get_substate_history_since() {
+            Some(history_since_version) => {
+                let first_retained_version = self.get_pruning_stats().last_pruned_state_version;
+                history_since_version.max(first_retained_version)..=current_version
+            }
+            None => current_version..=current_version,
+        }
+    }
+
+    fn get_substate_at_version(
+        &self,
+        state_version: u64,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Result<Option<DbSubstateValue>, VersionedReadError> {
+        self.check_readable(state_version)?;
+        if state_version == self.get_current_version() {
+            return Ok(self.get_stored_substate(partition_key, sort_key));
+        }
+        get_substate_leaf_at_version(self, state_version, partition_key, sort_key)
+            .map(|leaf| {
+                self.get_historical_substate(
+                    state_version,
+                    leaf.last_hash_change_version,
+                    partition_key,
+                    sort_key,
+                )
+            })
+            .transpose()
+    }
+
+    fn list_entries_at_version(
+        &self,
+        state_version: u64,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Result<Box<dyn Iterator<Item = PartitionEntry> + '_>, VersionedReadError> {
+        self.check_readable(state_version)?;
+        if state_version == self.get_current_version() {
+            return Ok(self.list_stored_entries_from(partition_key, from_sort_key));
+        }
+        let mut leaves = list_substate_leaves_at_version(self, state_version, partition_key);
+        if let Some(from_sort_key) = from_sort_key {
+            leaves.retain(|(sort_key, _)| sort_key >= from_sort_key);
+        }
+        leaves.sort_by(|(left_sort_key, _), (right_sort_key, _)| left_sort_key.cmp(right_sort_key));
+        // the values are read eagerly, so that a missing history entry is reported as an error
+        let entries = leaves
+            .into_iter()
+            .map(|(sort_key, leaf)| {
+                let value = self.get_historical_substate(
+                    state_version,
+                    leaf.last_hash_change_version,
+                    partition_key,
+                    &sort_key,
+                )?;
+                Ok((sort_key, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Box::new(entries.into_iter()))
+    }
+}
+
+impl Drop for RocksDBWithMerkleTreeSubstateStore {
+    fn drop(&mut self) {
+        // do not risk a double panic (and the pending commits are lost on a crash anyway)
+        if !std::thread::panicking() {
+            self.flush_pending_commits();
+        }
+    }
+}
+
 impl ReadableTreeStore for RocksDBWithMerkleTreeSubstateStore {
     fn get_node(&self, key: &NodeKey) -> Option<TreeNode> {
-        self.db
-            .get_cf(self.cf(MERKLE_NODES_CF), &encode_key(key))
-            .unwrap()
-            .map(|bytes| scrypto_decode::<VersionedTreeNode>(&bytes).unwrap())
-            .map(|versioned| versioned.into_latest())
+        self.get_node_by_encoded_key(&encode_key(key))
     }
 }
 
+/// A reference to the substate history entries which became superseded at some state version.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, ScryptoSbor)]
+enum SupersededSubstateHistory {
+    /// A single substate's value (i.e. it was upserted or deleted).
+    Substate(DbPartitionKey, DbSortKey),
+    /// Values of all the substates of a partition (i.e. it was reset).
+    Partition(DbPartitionKey),
+}
+
+fn encode_history_key(
+    version: u64,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> Vec<u8> {
+    [
+        version.to_be_bytes().as_slice(),
+        encode_to_rocksdb_bytes(partition_key, sort_key).as_slice(),
+    ]
+    .concat()
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, ScryptoSbor)]
 struct Metadata {
     current_state_version: u64,
@@ -0,0 +1,84 @@
+use crate::hash_tree::tree_store::{encode_key, NodeKey, StaleTreePart, TreeNode};
+use radix_engine_derive::ScryptoSbor;
+use sbor::rust::prelude::*;
+
+/// A configuration of the state tree's garbage collection, i.e. of the amortized process which
+/// deletes the nodes made stale by past commits, once they fall out of the retained window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTreePruningConfig {
+    /// A number of most recent historical state versions (i.e. not counting the current one) for
+    /// which the tree nodes must be retained.
+    /// A value of `0` means that only the current version's tree is guaranteed to be complete.
+    pub retained_versions: u64,
+    /// A maximum number of state versions' stale tree parts processed by a single GC step (run
+    /// after every commit).
+    /// Any value above `1` allows the GC to gradually catch up with a backlog (e.g. after the
+    /// retained window was shrunk, or after a period of running without pruning).
+    pub max_versions_per_gc_step: u64,
+}
+
+impl StateTreePruningConfig {
+    const DEFAULT_MAX_VERSIONS_PER_GC_STEP: u64 = 10;
+
+    /// A configuration which deletes the stale tree parts right after the commit which made them
+    /// stale (i.e. only the current version is readable from the tree).
+    pub fn immediate() -> Self {
+        Self::retaining(0)
+    }
+
+    /// A configuration which keeps the tree nodes of the given number of most recent historical
+    /// state versions.
+    pub fn retaining(retained_versions: u64) -> Self {
+        Self {
+            retained_versions,
+            max_versions_per_gc_step: Self::DEFAULT_MAX_VERSIONS_PER_GC_STEP,
+        }
+    }
+}
+
+/// Cumulative statistics of the state tree's garbage collection, persisted with the store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, ScryptoSbor)]
+pub struct StateTreePruningStats {
+    /// The most recent state version at which the recorded stale tree parts were deleted.
+    /// All historical state versions below this one are (at least partially) gone from the tree.
+    pub last_pruned_state_version: u64,
+    /// A total number of state versions for which the recorded stale tree parts were deleted.
+    pub pruned_versions: u64,
+    /// A total number of processed stale tree parts (i.e. single nodes or entire subtrees).
+    pub pruned_stale_parts: u64,
+    /// A total number of tree node deletions (note: a stale single node may have already been
+    /// deleted as part of a stale subtree, hence this is an upper bound).
+    pub deleted_nodes: u64,
+}
+
+/// Resolves the given stale tree part into the encoded keys of all the nodes it consists of,
+/// using the given node lookup to traverse the subtrees.
+/// Note: a single stale node is returned without a lookup, while the subtree traversal only
+/// returns the nodes which still exist in the storage.
+pub(super) fn collect_stale_node_keys<F>(part: StaleTreePart, get_node: F) -> Vec<Vec<u8>>
+where
+    F: Fn(&[u8]) -> Option<TreeNode>,
+{
+    match part {
+        StaleTreePart::Node(node_key) => vec![encode_key(&node_key)],
+        StaleTreePart::Subtree(node_key) => {
+            let mut encoded_keys = Vec::new();
+            let mut queue = VecDeque::<NodeKey>::new();
+            queue.push_back(node_key);
+            while let Some(node_key) = queue.pop_front() {
+                let encoded_key = encode_key(&node_key);
+                if let Some(node) = get_node(&encoded_key) {
+                    encoded_keys.push(encoded_key);
+                    if let TreeNode::Internal(internal) = node {
+                        for child in internal.children {
+                            queue.push_back(
+                                node_key.gen_child_node_key(child.version, child.nibble),
+                            );
+                        }
+                    }
+                }
+            }
+            encoded_keys
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use crate::hash_tree::put_at_next_version;
 use crate::hash_tree::tree_store::{
     NodeKey, ReadableTreeStore, StaleTreePart, TreeNode, WriteableTreeStore,
 };
+use crate::hash_tree::{put_at_next_versions, put_substate_hashes_at_version};
 use radix_engine_common::prelude::Hash;
-use radix_engine_store_interface::interface::DatabaseUpdates;
+use radix_engine_store_interface::interface::{DatabaseUpdates, DbPartitionKey, DbSortKey};
+use sbor::rust::prelude::IndexMap;
 
 struct CollectingTreeStore<'s, S> {
     readable_delegate: &'s S,
@@ -54,16 +55,30 @@ impl StateHashTreeDiff {
     }
 }
 
+/// Computes the tree update resulting from the given consecutive commits on top of the given
+/// parent version, returning the root hashes of all the new versions.
 pub fn compute_state_tree_update<S: ReadableTreeStore>(
     store: &S,
     parent_state_version: u64,
-    database_updates: &DatabaseUpdates,
-) -> (StateHashTreeDiff, Hash) {
+    database_updates_batch: &[DatabaseUpdates],
+) -> (StateHashTreeDiff, Vec<Hash>) {
     let mut collector = CollectingTreeStore::new(store);
-    let root_hash = put_at_next_version(
+    let root_hashes = put_at_next_versions(
         &mut collector,
         Some(parent_state_version).filter(|v| *v > 0),
-        database_updates,
+        database_updates_batch,
     );
+    (collector.into_diff(), root_hashes)
+}
+
+/// Computes the tree holding the given substate hashes at the given state version, to be put into
+/// an empty store (see [`put_substate_hashes_at_version()`]), returning its root hash.
+pub fn compute_initial_state_tree<S: ReadableTreeStore>(
+    store: &S,
+    state_version: u64,
+    substate_hashes: &IndexMap<DbPartitionKey, IndexMap<DbSortKey, Hash>>,
+) -> (StateHashTreeDiff, Hash) {
+    let mut collector = CollectingTreeStore::new(store);
+    let root_hash = put_substate_hashes_at_version(&mut collector, state_version, substate_hashes);
     (collector.into_diff(), root_hash)
 }
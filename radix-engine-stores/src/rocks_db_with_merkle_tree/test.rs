@@ -0,0 +1,439 @@
+use super::*;
+use crate::hash_tree::tree_store::TypedInMemoryTreeStore;
+use crate::hash_tree::{list_substate_hashes_at_version, put_at_next_version};
+use crate::memory_db::InMemorySubstateDatabase;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn immediate_gc_leaves_same_nodes_as_in_memory_pruning_store() {
+    let root = store_root("immediate_gc");
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::immediate()));
+    let mut reference = TypedInMemoryTreeStore::with_pruning();
+
+    for version in 1..=40 {
+        let database_updates = database_updates_at(version);
+        store.commit(&database_updates);
+        let reference_root_hash = put_at_next_version(
+            &mut reference,
+            Some(version - 1).filter(|v| *v > 0),
+            &database_updates,
+        );
+        assert_eq!(store.get_current_root_hash(), reference_root_hash);
+    }
+
+    assert_eq!(list_node_keys(&store), list_node_keys_of(&reference));
+    assert_eq!(store.count_unpruned_versions(), 0);
+    assert_eq!(store.get_pruning_stats().last_pruned_state_version, 40);
+}
+
+#[test]
+fn windowed_gc_retains_configured_historical_versions() {
+    let root = store_root("windowed_gc");
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::retaining(3)));
+    let mut reference = TypedInMemoryTreeStore::new();
+
+    for version in 1..=30 {
+        let database_updates = database_updates_at(version);
+        store.commit(&database_updates);
+        put_at_next_version(
+            &mut reference,
+            Some(version - 1).filter(|v| *v > 0),
+            &database_updates,
+        );
+    }
+
+    for version in 27..=30 {
+        assert_eq!(
+            list_substate_hashes_at_version(&mut store, version),
+            list_substate_hashes_at_version(&mut reference, version)
+        );
+    }
+    let stats = store.get_pruning_stats();
+    assert_eq!(stats.last_pruned_state_version, 27);
+    assert!(stats.deleted_nodes > 0);
+    assert_eq!(store.count_unpruned_versions(), 3);
+}
+
+#[test]
+fn gc_catches_up_with_backlog_recorded_while_disabled() {
+    let root = store_root("backlog_gc");
+    let mut reference = TypedInMemoryTreeStore::with_pruning();
+    {
+        let mut store = open_store(root.path(), None);
+        for version in 1..=20 {
+            let database_updates = database_updates_at(version);
+            store.commit(&database_updates);
+            put_at_next_version(
+                &mut reference,
+                Some(version - 1).filter(|v| *v > 0),
+                &database_updates,
+            );
+        }
+        // (the initial version cannot make anything stale)
+        assert_eq!(store.count_unpruned_versions(), 19);
+        assert_eq!(store.prune_stale_tree_parts(u64::MAX), 0);
+    }
+
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::immediate()));
+    assert_eq!(store.prune_stale_tree_parts(u64::MAX), 19);
+
+    assert_eq!(list_node_keys(&store), list_node_keys_of(&reference));
+    assert_eq!(store.get_pruning_stats().pruned_versions, 19);
+}
+
+#[test]
+fn historical_reads_match_past_states_within_retained_window() {
+    let root = store_root("historical_reads");
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::retaining(5)));
+    let mut reference = InMemorySubstateDatabase::standard();
+    let mut reference_snapshots = Vec::new();
+
+    for version in 1..=30 {
+        let database_updates = database_updates_at(version);
+        store.commit(&database_updates);
+        reference.commit(&database_updates);
+        reference_snapshots.push(reference.clone());
+    }
+
+    assert_eq!(store.get_readable_versions(), 25..=30);
+    for version in 25..=30 {
+        let reference_snapshot = &reference_snapshots[version as usize - 1];
+        let historical_state = SubstateDatabaseAtVersion::new(&store, version).unwrap();
+        let partition_keys = reference_snapshot
+            .list_partition_keys()
+            .chain(reference.list_partition_keys())
+            .collect::<BTreeSet<_>>();
+        for partition_key in partition_keys {
+            let entries = historical_state
+                .list_entries(&partition_key)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                entries,
+                reference_snapshot
+                    .list_entries(&partition_key)
+                    .collect::<Vec<_>>()
+            );
+            for (sort_key, value) in entries {
+                assert_eq!(
+                    historical_state.get_substate(&partition_key, &sort_key),
+                    Some(value)
+                );
+            }
+        }
+    }
+    assert!(SubstateDatabaseAtVersion::new(&store, 24).is_none());
+}
+
+#[test]
+fn range_listings_match_filtered_full_listings() {
+    let root = store_root("range_listings");
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::retaining(5)));
+    let mut reference = InMemorySubstateDatabase::standard();
+    for version in 1..=30 {
+        let database_updates = database_updates_at(version);
+        store.commit(&database_updates);
+        reference.commit(&database_updates);
+    }
+
+    let bounds = [None, Some(0), Some(3), Some(6), Some(20)]
+        .map(|bound| bound.map(|byte: u8| DbSortKey(vec![byte])));
+    let historical_state = SubstateDatabaseAtVersion::new(&store, 27).unwrap();
+    for partition_key in store.list_partition_keys().collect::<Vec<_>>() {
+        for from_sort_key in &bounds {
+            for to_sort_key in &bounds {
+                let in_range = |sort_key: &DbSortKey| {
+                    from_sort_key.as_ref().map_or(true, |from| sort_key >= from)
+                        && to_sort_key.as_ref().map_or(true, |to| sort_key < to)
+                };
+                let expected = |database: &dyn SubstateDatabase| {
+                    database
+                        .list_entries(&partition_key)
+                        .filter(|(sort_key, _)| in_range(sort_key))
+                        .collect::<Vec<_>>()
+                };
+                let actual = |database: &dyn SubstateDatabase| {
+                    database
+                        .list_entries_in_range(
+                            &partition_key,
+                            from_sort_key.as_ref(),
+                            to_sort_key.as_ref(),
+                        )
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(actual(&store), expected(&store));
+                assert_eq!(actual(&reference), expected(&reference));
+                assert_eq!(actual(&historical_state), expected(&historical_state));
+            }
+        }
+    }
+}
+
+#[test]
+fn last_transaction_hash_survives_reopen_until_overwritten() {
+    let root = store_root("last_transaction_hash");
+    {
+        let mut store = open_store(root.path(), None);
+        assert_eq!(store.get_last_transaction_hash(), None);
+        store.commit_transaction(&database_updates_at(1), Hash([1; Hash::LENGTH]));
+        store.commit_transaction(&database_updates_at(2), Hash([2; Hash::LENGTH]));
+    }
+
+    let mut store = open_store(root.path(), None);
+    assert_eq!(store.get_current_version(), 2);
+    assert_eq!(
+        store.get_last_transaction_hash(),
+        Some(Hash([2; Hash::LENGTH]))
+    );
+    store.commit(&database_updates_at(3));
+    assert_eq!(store.get_last_transaction_hash(), None);
+}
+
+#[test]
+fn batched_commits_are_readable_before_flush_and_match_unbatched_commits() {
+    let root = store_root("batched_commits");
+    let mut store =
+        open_store(root.path(), Some(StateTreePruningConfig::immediate())).with_commit_batching(8);
+    let unbatched_root = store_root("unbatched_commits");
+    let mut unbatched_store = open_store(
+        unbatched_root.path(),
+        Some(StateTreePruningConfig::immediate()),
+    );
+    let mut reference = InMemorySubstateDatabase::standard();
+
+    for version in 1..=30 {
+        let database_updates = database_updates_at(version);
+        store.commit_transaction(&database_updates, Hash([version as u8; Hash::LENGTH]));
+        unbatched_store.commit(&database_updates);
+        reference.commit(&database_updates);
+
+        assert_eq!(store.get_current_version(), version / 8 * 8);
+        let partition_keys = store.list_partition_keys().collect::<Vec<_>>();
+        assert_eq!(
+            partition_keys,
+            reference.list_partition_keys().collect::<Vec<_>>()
+        );
+        for partition_key in partition_keys {
+            let entries = store.list_entries(&partition_key).collect::<Vec<_>>();
+            assert_eq!(
+                entries,
+                reference.list_entries(&partition_key).collect::<Vec<_>>()
+            );
+            for (sort_key, value) in entries {
+                assert_eq!(store.get_substate(&partition_key, &sort_key), Some(value));
+            }
+        }
+    }
+
+    store.flush_pending_commits();
+    assert_eq!(store.get_current_version(), 30);
+    assert_eq!(
+        store.get_last_transaction_hash(),
+        Some(Hash([30; Hash::LENGTH]))
+    );
+    assert_eq!(
+        store.get_current_root_hash(),
+        unbatched_store.get_current_root_hash()
+    );
+    assert_eq!(list_node_keys(&store), list_node_keys(&unbatched_store));
+}
+
+#[test]
+fn pending_commits_are_flushed_on_drop() {
+    let root = store_root("pending_commits_on_drop");
+    let root_hash = {
+        let mut store = open_store(root.path(), None).with_commit_batching(100);
+        for version in 1..=5 {
+            store.commit(&database_updates_at(version));
+        }
+        assert_eq!(store.get_current_version(), 0);
+        let mut reference = TypedInMemoryTreeStore::new();
+        (1..=5)
+            .map(|version| {
+                put_at_next_version(
+                    &mut reference,
+                    Some(version - 1).filter(|v| *v > 0),
+                    &database_updates_at(version),
+                )
+            })
+            .last()
+            .unwrap()
+    };
+
+    let store = open_store(root.path(), None);
+    assert_eq!(store.get_current_version(), 5);
+    assert_eq!(store.get_current_root_hash(), root_hash);
+    // the intermediate versions of the batch have no history
+    assert_eq!(store.get_readable_versions(), 5..=5);
+}
+
+#[test]
+fn immediate_pruning_does_not_record_substate_history() {
+    let root = store_root("no_history");
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::immediate()));
+    for version in 1..=5 {
+        store.commit(&database_updates_at(version));
+    }
+
+    assert_eq!(store.get_readable_versions(), 5..=5);
+    assert_eq!(
+        store
+            .db
+            .iterator_cf(store.cf(SUBSTATE_HISTORY_CF), IteratorMode::Start)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn switching_to_immediate_pruning_deletes_substate_history() {
+    let root = store_root("history_cleanup");
+    {
+        let mut store = open_store(root.path(), Some(StateTreePruningConfig::retaining(5)));
+        for version in 1..=5 {
+            store.commit(&database_updates_at(version));
+        }
+        assert_eq!(store.get_readable_versions(), 1..=5);
+    }
+
+    let mut store = open_store(root.path(), Some(StateTreePruningConfig::immediate()));
+    store.commit(&database_updates_at(6));
+
+    assert_eq!(store.get_readable_versions(), 6..=6);
+    for cf in [SUBSTATE_HISTORY_CF, SUPERSEDED_SUBSTATE_HISTORY_CF] {
+        assert_eq!(
+            store
+                .db
+                .iterator_cf(store.cf(cf), IteratorMode::Start)
+                .count(),
+            0
+        );
+    }
+    let partition_key = store.list_partition_keys().next().unwrap();
+    assert_eq!(
+        store.get_substate_at_version(5, &partition_key, &DbSortKey(vec![0])),
+        Err(VersionedReadError::VersionNotReadable {
+            state_version: 5,
+            readable_versions: 6..=6,
+        })
+    );
+    assert!(store
+        .list_entries_at_version(5, &partition_key, None)
+        .is_err());
+}
+
+#[test]
+fn prefetched_substates_are_served_until_the_next_commit() {
+    let root = store_root("prefetch");
+    let mut store = open_store(root.path(), None);
+    let mut reference = InMemorySubstateDatabase::standard();
+    let substate_keys = (1..=5u8)
+        .flat_map(|node_byte| {
+            (0..3u8).flat_map(move |partition_num| {
+                (0..12u8).map(move |sort_byte| {
+                    (
+                        DbPartitionKey {
+                            node_key: vec![node_byte; 4],
+                            partition_num,
+                        },
+                        DbSortKey(vec![sort_byte]),
+                    )
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for version in 1..=20 {
+        store.prefetch(&substate_keys);
+        for (partition_key, sort_key) in &substate_keys {
+            assert_eq!(
+                store.get_substate(partition_key, sort_key),
+                reference.get_substate(partition_key, sort_key)
+            );
+        }
+
+        let database_updates = database_updates_at(version);
+        store.commit(&database_updates);
+        reference.commit(&database_updates);
+        for (partition_key, sort_key) in &substate_keys {
+            assert_eq!(
+                store.get_substate(partition_key, sort_key),
+                reference.get_substate(partition_key, sort_key)
+            );
+        }
+    }
+}
+
+fn store_root(test_name: &str) -> TempDir {
+    tempfile::Builder::new()
+        .prefix(&format!("rocks_db_with_merkle_tree_test_{}_", test_name))
+        .tempdir()
+        .unwrap()
+}
+
+fn open_store(
+    root: &Path,
+    pruning_config: Option<StateTreePruningConfig>,
+) -> RocksDBWithMerkleTreeSubstateStore {
+    let mut options = Options::default();
+    options.create_if_missing(true);
+    options.create_missing_column_families(true);
+    RocksDBWithMerkleTreeSubstateStore::with_options(&options, root.to_path_buf(), pruning_config)
+}
+
+fn list_node_keys(store: &RocksDBWithMerkleTreeSubstateStore) -> BTreeSet<Vec<u8>> {
+    store
+        .db
+        .iterator_cf(store.cf(MERKLE_NODES_CF), IteratorMode::Start)
+        .map(|kv| kv.unwrap().0.to_vec())
+        .collect()
+}
+
+fn list_node_keys_of(store: &TypedInMemoryTreeStore) -> BTreeSet<Vec<u8>> {
+    store.tree_nodes.keys().map(encode_key).collect()
+}
+
+/// Deterministically generates a mix of substate upserts, deletes and partition resets.
+fn database_updates_at(version: u64) -> DatabaseUpdates {
+    let mut database_updates = DatabaseUpdates::default();
+    let seed = version as u8;
+
+    let delta = database_updates
+        .node_updates
+        .entry(vec![seed % 5 + 1; 4])
+        .or_default()
+        .partition_updates
+        .entry(seed % 3)
+        .or_default();
+    if let PartitionDatabaseUpdates::Delta { substate_updates } = delta {
+        substate_updates.insert(DbSortKey(vec![seed % 7]), DatabaseUpdate::Set(vec![seed]));
+        substate_updates.insert(
+            DbSortKey(vec![seed % 11]),
+            DatabaseUpdate::Set(vec![seed; 2]),
+        );
+        if version % 4 == 0 {
+            substate_updates.insert(DbSortKey(vec![seed % 7 + 1]), DatabaseUpdate::Delete);
+        }
+    }
+
+    if version % 9 == 0 {
+        database_updates
+            .node_updates
+            .entry(vec![7; 4])
+            .or_default()
+            .partition_updates
+            .insert(
+                1,
+                PartitionDatabaseUpdates::Reset {
+                    new_substate_values: [
+                        (DbSortKey(vec![1]), vec![seed]),
+                        (DbSortKey(vec![2]), vec![seed, seed]),
+                    ]
+                    .into_iter()
+                    .collect(),
+                },
+            );
+    }
+
+    database_updates
+}
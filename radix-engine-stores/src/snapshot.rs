@@ -0,0 +1,398 @@
+use crate::hash_tree::put_substate_hashes_at_version;
+use crate::hash_tree::tree_store::TypedInMemoryTreeStore;
+use radix_engine_common::crypto::{hash, Hash};
+use radix_engine_common::data::scrypto::{scrypto_decode, scrypto_encode};
+use radix_engine_derive::ScryptoSbor;
+use radix_engine_store_interface::interface::*;
+use sbor::rust::prelude::*;
+use sbor::DecodeError;
+use std::io::{self, Read, Write};
+use std::mem;
+
+/// The bytes which every snapshot starts with.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"RESNAPSH";
+
+/// The version of the snapshot format (written right after the magic bytes).
+const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+
+/// A default approximate size (i.e. of all keys and values) of a single chunk of substates.
+pub const DEFAULT_SNAPSHOT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// The maximum size of a single encoded frame (i.e. a chunk of substates).
+/// The reader rejects larger frames before allocating any memory for them.
+pub const MAX_SNAPSHOT_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+/// A description of the state captured by a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, ScryptoSbor)]
+pub struct SnapshotHeader {
+    /// The state version of the exported database, if it is tracked.
+    pub state_version: Option<u64>,
+    /// The state root hash of the exported database, if it is tracked.
+    pub state_root_hash: Option<Hash>,
+}
+
+/// Statistics of a successfully written (or read) snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    pub header: SnapshotHeader,
+    pub chunk_count: u64,
+    pub partition_count: u64,
+    pub substate_count: u64,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    IOError(io::Error),
+    NotASnapshot,
+    UnsupportedFormatVersion(u8),
+    /// The chunk at the given index (where the header is chunk `0`) is corrupted.
+    ChecksumMismatch {
+        chunk_index: u64,
+    },
+    DecodeError(DecodeError),
+    /// The chunk at the given index exceeds [`MAX_SNAPSHOT_FRAME_SIZE`].
+    FrameTooLarge {
+        chunk_index: u64,
+        length: u64,
+    },
+    /// The snapshot's frames do not follow the `Header, Substates*, Trailer` sequence (e.g. the
+    /// snapshot was truncated).
+    UnexpectedFrame {
+        chunk_index: u64,
+    },
+    /// The trailer's counts differ from the actual contents.
+    TrailerMismatch {
+        expected_partition_count: u64,
+        actual_partition_count: u64,
+        expected_substate_count: u64,
+        actual_substate_count: u64,
+    },
+    /// A snapshot can only be imported into an empty database.
+    DatabaseNotEmpty,
+    /// The target database requires the snapshot to specify a state version.
+    MissingStateVersion,
+    StateRootHashMismatch {
+        expected: Hash,
+        actual: Hash,
+    },
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::IOError(error)
+    }
+}
+
+/// A single frame of the snapshot.
+/// Each frame is written as its length (a big-endian `u32`), its SBOR encoding and a hash of that
+/// encoding (used as a checksum).
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+enum SnapshotFrame {
+    Header(SnapshotHeader),
+    /// A chunk of substates, grouped by partitions (in the database's listing order).
+    /// Note: a single partition may span many chunks.
+    Substates(Vec<(DbPartitionKey, Vec<PartitionEntry>)>),
+    Trailer {
+        partition_count: u64,
+        substate_count: u64,
+    },
+}
+
+/// A streaming writer of a snapshot.
+pub struct SnapshotWriter<W: Write> {
+    writer: W,
+    header: SnapshotHeader,
+    max_chunk_size: usize,
+    chunk: Vec<(DbPartitionKey, Vec<PartitionEntry>)>,
+    chunk_size: usize,
+    chunk_count: u64,
+    last_partition_key: Option<DbPartitionKey>,
+    partition_count: u64,
+    substate_count: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    /// Writes the magic bytes and the header.
+    pub fn new(
+        mut writer: W,
+        header: SnapshotHeader,
+        max_chunk_size: usize,
+    ) -> Result<Self, SnapshotError> {
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&[SNAPSHOT_FORMAT_VERSION])?;
+        write_frame(&mut writer, 0, &SnapshotFrame::Header(header.clone()))?;
+        Ok(Self {
+            writer,
+            header,
+            max_chunk_size,
+            chunk: Vec::new(),
+            chunk_size: 0,
+            chunk_count: 0,
+            last_partition_key: None,
+            partition_count: 0,
+            substate_count: 0,
+        })
+    }
+
+    /// Appends the given substate.
+    /// The substates of a single partition must be appended consecutively.
+    pub fn append(
+        &mut self,
+        partition_key: &DbPartitionKey,
+        entry: PartitionEntry,
+    ) -> Result<(), SnapshotError> {
+        if self.last_partition_key.as_ref() != Some(partition_key) {
+            self.last_partition_key = Some(partition_key.clone());
+            self.partition_count += 1;
+        }
+        self.substate_count += 1;
+        self.chunk_size += entry.0 .0.len() + entry.1.len();
+        match self.chunk.last_mut() {
+            Some((chunk_partition_key, entries)) if chunk_partition_key == partition_key => {
+                entries.push(entry);
+            }
+            _ => {
+                self.chunk_size += partition_key.node_key.len() + 1;
+                self.chunk.push((partition_key.clone(), vec![entry]));
+            }
+        }
+        if self.chunk_size >= self.max_chunk_size {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining substates and the trailer.
+    pub fn finish(mut self) -> Result<SnapshotSummary, SnapshotError> {
+        self.flush_chunk()?;
+        write_frame(
+            &mut self.writer,
+            self.chunk_count + 1,
+            &SnapshotFrame::Trailer {
+                partition_count: self.partition_count,
+                substate_count: self.substate_count,
+            },
+        )?;
+        self.writer.flush()?;
+        Ok(SnapshotSummary {
+            header: self.header,
+            chunk_count: self.chunk_count,
+            partition_count: self.partition_count,
+            substate_count: self.substate_count,
+        })
+    }
+
+    fn flush_chunk(&mut self) -> Result<(), SnapshotError> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::take(&mut self.chunk);
+        write_frame(
+            &mut self.writer,
+            self.chunk_count + 1,
+            &SnapshotFrame::Substates(chunk),
+        )?;
+        self.chunk_size = 0;
+        self.chunk_count += 1;
+        Ok(())
+    }
+}
+
+/// A streaming reader of a snapshot, which verifies the checksums of all chunks as they are read.
+pub struct SnapshotReader<R: Read> {
+    reader: R,
+    header: SnapshotHeader,
+    chunk_count: u64,
+    last_partition_key: Option<DbPartitionKey>,
+    partition_count: u64,
+    substate_count: u64,
+    finished: bool,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// Reads the magic bytes and the header.
+    pub fn new(mut reader: R) -> Result<Self, SnapshotError> {
+        let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+        let mut format_version = [0u8; 1];
+        reader.read_exact(&mut format_version)?;
+        if format_version[0] != SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedFormatVersion(format_version[0]));
+        }
+        let SnapshotFrame::Header(header) = read_frame(&mut reader, 0)? else {
+            return Err(SnapshotError::UnexpectedFrame { chunk_index: 0 });
+        };
+        Ok(Self {
+            reader,
+            header,
+            chunk_count: 0,
+            last_partition_key: None,
+            partition_count: 0,
+            substate_count: 0,
+            finished: false,
+        })
+    }
+
+    pub fn header(&self) -> &SnapshotHeader {
+        &self.header
+    }
+
+    /// Reads the next chunk of substates, in a form which can be committed to a database.
+    /// Returns [`Option::None`] once the trailer is read and verified.
+    pub fn next_chunk(&mut self) -> Result<Option<DatabaseUpdates>, SnapshotError> {
+        if self.finished {
+            return Ok(None);
+        }
+        let chunk_index = self.chunk_count + 1;
+        match read_frame(&mut self.reader, chunk_index)? {
+            SnapshotFrame::Substates(partitions) => {
+                self.chunk_count += 1;
+                let mut delta_maps = index_map_new();
+                for (partition_key, entries) in partitions {
+                    if self.last_partition_key.as_ref() != Some(&partition_key) {
+                        self.last_partition_key = Some(partition_key.clone());
+                        self.partition_count += 1;
+                    }
+                    self.substate_count += entries.len() as u64;
+                    delta_maps.insert(
+                        partition_key,
+                        entries
+                            .into_iter()
+                            .map(|(sort_key, value)| (sort_key, DatabaseUpdate::Set(value)))
+                            .collect(),
+                    );
+                }
+                Ok(Some(DatabaseUpdates::from_delta_maps(delta_maps)))
+            }
+            SnapshotFrame::Trailer {
+                partition_count,
+                substate_count,
+            } => {
+                if partition_count != self.partition_count || substate_count != self.substate_count
+                {
+                    return Err(SnapshotError::TrailerMismatch {
+                        expected_partition_count: partition_count,
+                        actual_partition_count: self.partition_count,
+                        expected_substate_count: substate_count,
+                        actual_substate_count: self.substate_count,
+                    });
+                }
+                self.finished = true;
+                Ok(None)
+            }
+            SnapshotFrame::Header(_) => Err(SnapshotError::UnexpectedFrame { chunk_index }),
+        }
+    }
+
+    /// Returns the summary of the snapshot.
+    /// Only available after all the chunks were read.
+    pub fn summary(&self) -> Option<SnapshotSummary> {
+        self.finished.then(|| SnapshotSummary {
+            header: self.header.clone(),
+            chunk_count: self.chunk_count,
+            partition_count: self.partition_count,
+            substate_count: self.substate_count,
+        })
+    }
+}
+
+/// Writes all substates of the given database as a snapshot with the given header.
+pub fn export_snapshot<D: ListableSubstateDatabase + SubstateDatabase, W: Write>(
+    database: &D,
+    header: SnapshotHeader,
+    writer: W,
+) -> Result<SnapshotSummary, SnapshotError> {
+    let mut snapshot_writer = SnapshotWriter::new(writer, header, DEFAULT_SNAPSHOT_CHUNK_SIZE)?;
+    for partition_key in database.list_partition_keys() {
+        for entry in database.list_entries(&partition_key) {
+            snapshot_writer.append(&partition_key, entry)?;
+        }
+    }
+    snapshot_writer.finish()
+}
+
+/// Computes the state root hash of all substates of the given database (i.e. the same as a
+/// hash-tree-tracking database would have for that state), for databases which do not track it.
+/// Note: this builds the entire hash tree in memory.
+pub fn compute_state_root_hash<D: ListableSubstateDatabase + SubstateDatabase>(
+    database: &D,
+) -> Hash {
+    let substate_hashes = database
+        .list_partition_keys()
+        .map(|partition_key| {
+            let partition_hashes = database
+                .list_entries(&partition_key)
+                .map(|(sort_key, value)| (sort_key, hash(value)))
+                .collect();
+            (partition_key, partition_hashes)
+        })
+        .collect();
+    put_substate_hashes_at_version(&mut TypedInMemoryTreeStore::new(), 1, &substate_hashes)
+}
+
+/// Reads the given snapshot into the given (empty) database, committing it chunk by chunk.
+/// Note: the snapshot's header is returned (within the summary) for the caller to use, but the
+/// state version and root hash are not verified (since a plain database does not track them).
+pub fn import_snapshot<D: ListableSubstateDatabase + CommittableSubstateDatabase, R: Read>(
+    database: &mut D,
+    reader: R,
+) -> Result<SnapshotSummary, SnapshotError> {
+    if database.list_partition_keys().next().is_some() {
+        return Err(SnapshotError::DatabaseNotEmpty);
+    }
+    let mut snapshot_reader = SnapshotReader::new(reader)?;
+    while let Some(database_updates) = snapshot_reader.next_chunk()? {
+        database.commit(&database_updates);
+    }
+    Ok(snapshot_reader.summary().expect("all chunks read"))
+}
+
+fn write_frame<W: Write>(
+    writer: &mut W,
+    chunk_index: u64,
+    frame: &SnapshotFrame,
+) -> Result<(), SnapshotError> {
+    let frame_bytes = scrypto_encode(frame).expect("snapshot frame should be encodable");
+    if frame_bytes.len() > MAX_SNAPSHOT_FRAME_SIZE {
+        return Err(SnapshotError::FrameTooLarge {
+            chunk_index,
+            length: frame_bytes.len() as u64,
+        });
+    }
+    writer.write_all(&(frame_bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(&frame_bytes)?;
+    writer.write_all(&hash(&frame_bytes).0)?;
+    Ok(())
+}
+
+fn read_frame<R: Read>(reader: &mut R, chunk_index: u64) -> Result<SnapshotFrame, SnapshotError> {
+    let read_exact = |reader: &mut R, buffer: &mut [u8]| {
+        reader.read_exact(buffer).map_err(|error| {
+            if error.kind() == io::ErrorKind::UnexpectedEof {
+                SnapshotError::UnexpectedFrame { chunk_index }
+            } else {
+                SnapshotError::IOError(error)
+            }
+        })
+    };
+    let mut frame_len = [0u8; 4];
+    read_exact(reader, &mut frame_len)?;
+    let frame_len = u32::from_be_bytes(frame_len) as usize;
+    if frame_len > MAX_SNAPSHOT_FRAME_SIZE {
+        return Err(SnapshotError::FrameTooLarge {
+            chunk_index,
+            length: frame_len as u64,
+        });
+    }
+    let mut frame_bytes = vec![0u8; frame_len];
+    read_exact(reader, &mut frame_bytes)?;
+    let mut checksum = [0u8; Hash::LENGTH];
+    read_exact(reader, &mut checksum)?;
+    if hash(&frame_bytes).0 != checksum {
+        return Err(SnapshotError::ChecksumMismatch { chunk_index });
+    }
+    scrypto_decode(&frame_bytes).map_err(SnapshotError::DecodeError)
+}
@@ -113,6 +113,7 @@ fn bench_spin_loop(c: &mut Criterion) {
                 &TransactionCostingParameters {
                     free_credit_in_xrd: Decimal::try_from(PREVIEW_CREDIT_IN_XRD).unwrap(),
                     tip_percentage: DEFAULT_TIP_PERCENTAGE,
+                    max_fee_in_xrd: None,
                 },
                 false,
             );
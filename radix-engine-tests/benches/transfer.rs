@@ -18,6 +18,7 @@ fn bench_transfer(c: &mut Criterion) {
     let scrypto_vm = ScryptoVm {
         wasm_engine: DefaultWasmEngine::default(),
         wasm_validator_config: WasmValidatorConfigV1::new(),
+        instrumented_code_cache: None,
     };
     let native_vm = DefaultNativeVm::new();
     let vm = Vm::new(&scrypto_vm, native_vm);
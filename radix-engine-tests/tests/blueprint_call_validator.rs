@@ -0,0 +1,127 @@
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
+use radix_engine::types::*;
+use radix_engine::utils::{
+    validate_call_arguments, InstructionSchemaValidationError,
+    LocatedInstructionSchemaValidationError, PackageDefinitionsResolver,
+};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[test]
+fn validator_against_ledger_sees_valid_transfer_manifest_as_valid() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let (_, _, recipient) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee(account, 10)
+        .withdraw_from_account(account, XRD, 10)
+        .try_deposit_entire_worktop_or_abort(recipient, None)
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments(
+        &manifest.instructions,
+        &SystemDatabaseReader::new(test_runner.substate_db()),
+    );
+
+    // Assert
+    assert!(validation_result.is_ok());
+}
+
+#[test]
+fn validator_against_ledger_locates_mismatching_argument() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .call_method(account, "withdraw", manifest_args!(XRD, "ten"))
+        .call_method(account, "withdraw", manifest_args!())
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments(
+        &manifest.instructions,
+        &SystemDatabaseReader::new(test_runner.substate_db()),
+    );
+
+    // Assert
+    let errors = validation_result.unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[0],
+        LocatedInstructionSchemaValidationError {
+            instruction_index: 0,
+            cause: InstructionSchemaValidationError::ArgumentsMismatch {
+                argument_index: Some(1),
+                ..
+            },
+        }
+    ));
+    assert!(matches!(
+        &errors[1],
+        LocatedInstructionSchemaValidationError {
+            instruction_index: 1,
+            cause: InstructionSchemaValidationError::ArgumentsMismatch {
+                argument_index: None,
+                ..
+            },
+        }
+    ));
+}
+
+#[test]
+fn validator_against_ledger_resolves_virtual_accounts() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let virtual_account = ComponentAddress::virtual_account_from_public_key(
+        &Secp256k1PrivateKey::from_u64(7).unwrap().public_key(),
+    );
+    let manifest = ManifestBuilder::new()
+        .call_method(virtual_account, "my_made_up_method", manifest_args!())
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments(
+        &manifest.instructions,
+        &SystemDatabaseReader::new(test_runner.substate_db()),
+    );
+
+    // Assert
+    assert!(matches!(
+        validation_result.unwrap_err().as_slice(),
+        [LocatedInstructionSchemaValidationError {
+            cause: InstructionSchemaValidationError::MethodNotFound(..),
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn validator_against_package_definitions_locates_mismatching_argument() {
+    // Arrange
+    let account = ComponentAddress::virtual_account_from_public_key(
+        &Secp256k1PrivateKey::from_u64(1).unwrap().public_key(),
+    );
+    let manifest = ManifestBuilder::new()
+        .call_method(account, "withdraw", manifest_args!(XRD, "ten"))
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments(
+        &manifest.instructions,
+        &PackageDefinitionsResolver::with_native_packages(),
+    );
+
+    // Assert
+    assert!(matches!(
+        validation_result.unwrap_err().as_slice(),
+        [LocatedInstructionSchemaValidationError {
+            instruction_index: 0,
+            cause: InstructionSchemaValidationError::ArgumentsMismatch {
+                argument_index: Some(1),
+                ..
+            },
+        }]
+    ));
+}
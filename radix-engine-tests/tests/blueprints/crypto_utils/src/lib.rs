@@ -0,0 +1,46 @@
+use scrypto::prelude::*;
+
+#[blueprint]
+mod crypto_utils_test {
+    struct CryptoUtilsTest {}
+
+    impl CryptoUtilsTest {
+        pub fn hashes(data: Vec<u8>) -> (Hash, Hash, Hash) {
+            (
+                CryptoUtils::blake2b_256_hash(&data),
+                CryptoUtils::keccak256_hash(&data),
+                CryptoUtils::sha256_hash(&data),
+            )
+        }
+
+        pub fn ed25519_verify(
+            message_hash: Hash,
+            public_key: Ed25519PublicKey,
+            signature: Ed25519Signature,
+        ) -> bool {
+            CryptoUtils::ed25519_verify(&message_hash, &public_key, &signature)
+        }
+
+        pub fn secp256k1_verify(
+            message_hash: Hash,
+            public_key: Secp256k1PublicKey,
+            signature: Secp256k1Signature,
+        ) -> bool {
+            CryptoUtils::secp256k1_verify(&message_hash, &public_key, &signature)
+        }
+
+        pub fn secp256k1_recover(
+            message_hash: Hash,
+            signature: Secp256k1Signature,
+        ) -> Option<Secp256k1PublicKey> {
+            CryptoUtils::secp256k1_recover(&message_hash, &signature)
+        }
+
+        pub fn bls12381_v1_aggregate_verify(
+            pub_keys_and_msgs: Vec<(Bls12381G1PublicKey, Vec<u8>)>,
+            signature: Bls12381G2Signature,
+        ) -> bool {
+            CryptoUtils::bls12381_v1_aggregate_verify(&pub_keys_and_msgs, &signature)
+        }
+    }
+}
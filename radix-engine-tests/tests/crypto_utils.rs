@@ -0,0 +1,133 @@
+mod package_loader;
+
+use package_loader::PackageLoader;
+use radix_engine::types::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn call_function<T: ScryptoDecode>(
+    test_runner: &mut DefaultTestRunner,
+    package_address: PackageAddress,
+    function_name: &str,
+    args: ManifestArgs,
+) -> T {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(package_address, "CryptoUtilsTest", function_name, args)
+        .build();
+    let receipt = test_runner.execute_manifest(manifest, vec![]);
+    receipt.expect_commit_success().output(1)
+}
+
+#[test]
+fn test_crypto_utils_hashes() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("crypto_utils"));
+
+    // Act
+    let (blake2b, keccak256, sha256): (Hash, Hash, Hash) = call_function(
+        &mut test_runner,
+        package_address,
+        "hashes",
+        manifest_args!(Vec::<u8>::new()),
+    );
+
+    // Assert
+    assert_eq!(blake2b, hash([]));
+    assert_eq!(
+        keccak256,
+        Hash::from_str("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").unwrap()
+    );
+    assert_eq!(
+        sha256,
+        Hash::from_str("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap()
+    );
+}
+
+#[test]
+fn test_crypto_utils_secp256k1() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("crypto_utils"));
+    let private_key = Secp256k1PrivateKey::from_u64(1).unwrap();
+    let message_hash = hash("Hello Radix");
+    let signature = private_key.sign(&message_hash);
+
+    // Act
+    let valid: bool = call_function(
+        &mut test_runner,
+        package_address,
+        "secp256k1_verify",
+        manifest_args!(message_hash, private_key.public_key(), signature),
+    );
+    let invalid: bool = call_function(
+        &mut test_runner,
+        package_address,
+        "secp256k1_verify",
+        manifest_args!(hash("Hello World"), private_key.public_key(), signature),
+    );
+    let recovered: Option<Secp256k1PublicKey> = call_function(
+        &mut test_runner,
+        package_address,
+        "secp256k1_recover",
+        manifest_args!(message_hash, signature),
+    );
+
+    // Assert
+    assert!(valid);
+    assert!(!invalid);
+    assert_eq!(recovered, Some(private_key.public_key()));
+}
+
+#[test]
+fn test_crypto_utils_ed25519() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("crypto_utils"));
+    let private_key = Ed25519PrivateKey::from_u64(1).unwrap();
+    let message_hash = hash("Hello Radix");
+    let signature = private_key.sign(&message_hash);
+
+    // Act
+    let valid: bool = call_function(
+        &mut test_runner,
+        package_address,
+        "ed25519_verify",
+        manifest_args!(message_hash, private_key.public_key(), signature),
+    );
+    let invalid: bool = call_function(
+        &mut test_runner,
+        package_address,
+        "ed25519_verify",
+        manifest_args!(hash("Hello World"), private_key.public_key(), signature),
+    );
+
+    // Assert
+    assert!(valid);
+    assert!(!invalid);
+}
+
+#[test]
+fn test_crypto_utils_bls12381_rejects_invalid_points() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("crypto_utils"));
+
+    // Act
+    let valid: bool = call_function(
+        &mut test_runner,
+        package_address,
+        "bls12381_v1_aggregate_verify",
+        manifest_args!(
+            vec![(
+                Bls12381G1PublicKey([0u8; Bls12381G1PublicKey::LENGTH]),
+                b"Hello Radix".to_vec()
+            )],
+            Bls12381G2Signature([0u8; Bls12381G2Signature::LENGTH])
+        ),
+    );
+
+    // Assert
+    assert!(!valid);
+}
@@ -0,0 +1,118 @@
+mod package_loader;
+
+use package_loader::PackageLoader;
+use radix_engine::system::system_debugger::*;
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine::vm::wasm::DefaultWasmEngine;
+use radix_engine::vm::{NoExtension, Vm};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+type DefaultSystemDebugger<'a> = SystemDebugger<'a, Vm<'a, DefaultWasmEngine, NoExtension>>;
+
+/// The opt-in tools for inspecting the execution of a transaction.
+#[derive(Debug, Clone, Copy)]
+enum DebuggingTool {
+    WasmProfiling,
+    WasmBacktraces,
+    SystemDebugger,
+}
+
+struct SteppingHandler;
+
+impl DebuggerHandler for SteppingHandler {
+    fn on_break(
+        &mut self,
+        _reason: &BreakReason,
+        _inspector: &mut dyn DebuggerInspector,
+    ) -> DebuggerAction {
+        DebuggerAction::Step
+    }
+}
+
+fn execute(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    tool: Option<DebuggingTool>,
+) -> TransactionReceipt {
+    match tool {
+        Some(DebuggingTool::SystemDebugger) => test_runner
+            .execute_manifest_with_system::<_, DefaultSystemDebugger<'_>>(
+                manifest,
+                vec![],
+                SystemDebuggerInit {
+                    breakpoints: vec![Breakpoint::Instruction(0)],
+                    handler: Box::new(SteppingHandler),
+                },
+            ),
+        _ => test_runner.execute_manifest(manifest, vec![]),
+    }
+}
+
+/// Returns the execution costs of a nested call into WASM and of a WASM panic.
+fn execution_costs(tool: Option<DebuggingTool>) -> Vec<u32> {
+    let builder = TestRunnerBuilder::new();
+    let mut test_runner = match tool {
+        Some(DebuggingTool::WasmProfiling) => builder.with_wasm_profiling(),
+        Some(DebuggingTool::WasmBacktraces) => builder.with_wasm_backtraces(),
+        _ => builder,
+    }
+    .build();
+    let recursion_package = test_runner.publish_package_simple(PackageLoader::get("recursion"));
+    let logger_package = test_runner.publish_package_simple(PackageLoader::get("logger"));
+
+    let receipt = execute(
+        &mut test_runner,
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(
+                recursion_package,
+                "Caller",
+                "recursive",
+                manifest_args!(5u32),
+            )
+            .build(),
+        tool,
+    );
+    receipt.expect_commit_success();
+    let panic_receipt = execute(
+        &mut test_runner,
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(
+                logger_package,
+                "Logger",
+                "rust_panic",
+                manifest_args!("Hey".to_string()),
+            )
+            .build(),
+        tool,
+    );
+    panic_receipt.expect_commit_failure();
+
+    vec![
+        receipt.fee_summary.total_execution_cost_units_consumed,
+        panic_receipt
+            .fee_summary
+            .total_execution_cost_units_consumed,
+    ]
+}
+
+#[test]
+fn test_debugging_tools_do_not_change_execution_cost() {
+    // Arrange
+    let expected_execution_costs = execution_costs(None);
+
+    for tool in [
+        DebuggingTool::WasmProfiling,
+        DebuggingTool::WasmBacktraces,
+        DebuggingTool::SystemDebugger,
+    ] {
+        // Act
+        let execution_costs = execution_costs(Some(tool));
+
+        // Assert
+        assert_eq!(execution_costs, expected_execution_costs, "{:?}", tool);
+    }
+}
@@ -11,6 +11,8 @@ use radix_engine_common::constants::MAX_NUMBER_OF_BLUEPRINT_FIELDS;
 use radix_engine_interface::*;
 use sbor::basic_well_known_types::*;
 use scrypto_unit::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use transaction::prelude::*;
 
 #[test]
@@ -1522,3 +1524,128 @@ fn test_publishing_of_packages_with_invalid_names(name: &str) {
 fn name(len: usize, chr: char) -> String {
     (0..len).map(|_| chr).collect()
 }
+
+/// Counts the hits and inserts of the wrapped cache.
+struct CountingInstrumentedCodeCache {
+    inner: WasmDiskCache,
+    hits: AtomicUsize,
+    inserts: AtomicUsize,
+}
+
+impl InstrumentedCodeCache for CountingInstrumentedCodeCache {
+    fn get(&self, key: &InstrumentedCodeKey) -> Option<Vec<u8>> {
+        let instrumented_code = self.inner.get(key);
+        if instrumented_code.is_some() {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+        }
+        instrumented_code
+    }
+
+    fn insert(&self, key: &InstrumentedCodeKey, instrumented_code: &[u8]) {
+        self.inserts.fetch_add(1, Ordering::SeqCst);
+        self.inner.insert(key, instrumented_code)
+    }
+}
+
+#[test]
+fn publishing_a_package_populates_and_reuses_the_instrumented_code_cache() {
+    // Arrange
+    let cache_dir = std::env::temp_dir().join(format!(
+        "radix-engine-tests-instrumented-code-cache-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let cache = Arc::new(CountingInstrumentedCodeCache {
+        inner: WasmDiskCache::new(&cache_dir, 64 * 1024 * 1024).unwrap(),
+        hits: AtomicUsize::new(0),
+        inserts: AtomicUsize::new(0),
+    });
+    let (code, definition) = PackageLoader::get("package");
+
+    let counts = || {
+        (
+            cache.inserts.load(Ordering::SeqCst),
+            cache.hits.load(Ordering::SeqCst),
+        )
+    };
+    // Genesis publishes packages too, so only the changes made by each publish are checked
+    let mut test_runner1 = TestRunnerBuilder::new()
+        .with_instrumented_code_cache(cache.clone())
+        .build();
+    let mut test_runner2 = TestRunnerBuilder::new()
+        .with_instrumented_code_cache(cache.clone())
+        .build();
+
+    // Act
+    let (inserts_before, hits_before) = counts();
+    test_runner1.publish_package_simple((code.clone(), definition.clone()));
+    let (inserts_after_first_publish, hits_after_first_publish) = counts();
+    test_runner2.publish_package_simple((code, definition));
+    let (inserts_after_second_publish, hits_after_second_publish) = counts();
+
+    // Assert
+    assert_eq!(inserts_after_first_publish - inserts_before, 1);
+    assert_eq!(hits_after_first_publish - hits_before, 0);
+    assert_eq!(
+        inserts_after_second_publish - inserts_after_first_publish,
+        0
+    );
+    assert_eq!(hits_after_second_publish - hits_after_first_publish, 1);
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn calling_a_package_with_debugging_aids_reuses_the_instrumented_code_cache_across_engines() {
+    // Arrange
+    let cache_dir = std::env::temp_dir().join(format!(
+        "radix-engine-tests-debug-code-cache-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let cache = Arc::new(CountingInstrumentedCodeCache {
+        inner: WasmDiskCache::new(&cache_dir, 64 * 1024 * 1024).unwrap(),
+        hits: AtomicUsize::new(0),
+        inserts: AtomicUsize::new(0),
+    });
+    let counts = || {
+        (
+            cache.inserts.load(Ordering::SeqCst),
+            cache.hits.load(Ordering::SeqCst),
+        )
+    };
+    // As `replay` does, each test runner executes the same calls with a fresh engine
+    let mut test_runner1 = TestRunnerBuilder::new()
+        .with_instrumented_code_cache(cache.clone())
+        .with_wasm_profiling()
+        .build();
+    let mut test_runner2 = TestRunnerBuilder::new()
+        .with_instrumented_code_cache(cache.clone())
+        .with_wasm_profiling()
+        .build();
+    let package_address1 = test_runner1.publish_package_simple(PackageLoader::get("recursion"));
+    let package_address2 = test_runner2.publish_package_simple(PackageLoader::get("recursion"));
+    let call_recursive = |package_address: PackageAddress| {
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(package_address, "Caller", "recursive", manifest_args!(5u32))
+            .build()
+    };
+
+    // Act
+    let (inserts_before, hits_before) = counts();
+    test_runner1
+        .execute_manifest(call_recursive(package_address1), vec![])
+        .expect_commit_success();
+    let (inserts_after_first_call, hits_after_first_call) = counts();
+    test_runner2
+        .execute_manifest(call_recursive(package_address2), vec![])
+        .expect_commit_success();
+    let (inserts_after_second_call, hits_after_second_call) = counts();
+
+    // Assert
+    assert_eq!(inserts_after_first_call - inserts_before, 1);
+    assert_eq!(hits_after_first_call - hits_before, 0);
+    assert_eq!(inserts_after_second_call - inserts_after_first_call, 0);
+    assert_eq!(hits_after_second_call - hits_after_first_call, 1);
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
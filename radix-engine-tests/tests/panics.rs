@@ -159,6 +159,15 @@ impl KernelSubstateApi<SystemLockData> for MockKernel {
     ) -> Result<Vec<(SubstateKey, IndexedScryptoValue)>, RuntimeError> {
         panic1!()
     }
+
+    fn kernel_peek_substate(
+        &self,
+        _: &NodeId,
+        _: PartitionNumber,
+        _: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        panic1!()
+    }
 }
 
 impl KernelInvokeApi<Actor> for MockKernel {
@@ -192,4 +201,12 @@ impl<'g> KernelInternalApi<SystemConfig<Vm<'g, DefaultWasmEngine, NoExtension>>>
     fn kernel_read_proof(&mut self, _: &NodeId) -> Option<ProofSnapshot> {
         panic1!()
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&Actor> {
+        panic1!()
+    }
+
+    fn kernel_get_owned_nodes(&self, _: usize) -> Vec<NodeId> {
+        panic1!()
+    }
 }
@@ -0,0 +1,87 @@
+use radix_engine::transaction::*;
+use radix_engine::types::*;
+use radix_engine::vm::wasm::DefaultWasmEngine;
+use radix_engine::vm::{DefaultNativeVm, ScryptoVm, Vm};
+use radix_engine_store_interface::interface::CommittableSubstateDatabase;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[test]
+fn parallel_execution_results_in_same_receipts_and_state_as_sequential_execution() {
+    // Arrange
+    let mut sequential_test_runner = TestRunnerBuilder::new().build();
+    let mut parallel_test_runner = TestRunnerBuilder::new().build();
+    let accounts = (0..4)
+        .map(|_| {
+            parallel_test_runner.new_allocated_account();
+            sequential_test_runner.new_allocated_account()
+        })
+        .collect::<Vec<_>>();
+    let transactions = (0..16)
+        .map(|index| {
+            let (public_key, _, account) = &accounts[index % accounts.len()];
+            let (_, _, recipient) = &accounts[(index + 1) % accounts.len()];
+            // every fifth transaction fails (after paying the fee)
+            let amount = if index % 5 == 4 {
+                dec!(1000000000)
+            } else {
+                dec!(1)
+            };
+            let manifest = ManifestBuilder::new()
+                .lock_fee(*account, 10)
+                .withdraw_from_account(*account, XRD, amount)
+                .try_deposit_entire_worktop_or_abort(*recipient, None)
+                .build();
+            let prepared = TestTransaction::new_from_nonce(manifest, 1000 + index as u32)
+                .prepare()
+                .unwrap();
+            (prepared, NonFungibleGlobalId::from_public_key(public_key))
+        })
+        .collect::<Vec<_>>();
+    let executables = transactions
+        .iter()
+        .map(|(prepared, proof)| prepared.get_executable(btreeset!(proof.clone())))
+        .collect::<Vec<_>>();
+
+    // Act
+    let sequential_receipts = transactions
+        .iter()
+        .map(|(prepared, proof)| {
+            sequential_test_runner.execute_transaction(
+                prepared.get_executable(btreeset!(proof.clone())),
+                CostingParameters::default(),
+                ExecutionConfig::for_test_transaction(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+    let outcomes = execute_transactions_in_parallel(
+        parallel_test_runner.substate_db(),
+        Vm::new(&scrypto_vm, DefaultNativeVm::new()),
+        &CostingParameters::default(),
+        &ExecutionConfig::for_test_transaction(),
+        &executables,
+        4,
+    );
+    for outcome in &outcomes {
+        parallel_test_runner
+            .substate_db_mut()
+            .commit(&outcome.database_updates);
+    }
+
+    // Assert
+    assert_eq!(outcomes.len(), sequential_receipts.len());
+    for (outcome, sequential_receipt) in outcomes.iter().zip(&sequential_receipts) {
+        assert_eq!(
+            scrypto_encode(&outcome.result).unwrap(),
+            scrypto_encode(sequential_receipt).unwrap()
+        );
+    }
+    assert!(!outcomes[0].re_executed);
+    // (all transactions at least distribute their fees to the same validator rewards)
+    assert!(outcomes[1..].iter().any(|outcome| outcome.re_executed));
+    assert_eq!(
+        parallel_test_runner.substate_db(),
+        sequential_test_runner.substate_db()
+    );
+}
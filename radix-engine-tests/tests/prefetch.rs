@@ -0,0 +1,64 @@
+use radix_engine::types::*;
+use radix_engine_queries::query::derive_prefetch_hints;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+#[test]
+fn prefetch_hints_include_fee_payer_called_components_and_their_vaults() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let (_, _, recipient) = test_runner.new_allocated_account();
+    let account_vault = test_runner.get_component_vaults(account, XRD)[0];
+    let recipient_vault = test_runner.get_component_vaults(recipient, XRD)[0];
+    let manifest = ManifestBuilder::new()
+        .withdraw_from_account(account, XRD, 1)
+        .try_deposit_entire_worktop_or_abort(recipient, None)
+        .lock_fee(account, 10)
+        .build();
+    let prepared = TestTransaction::new_from_nonce(manifest, 1)
+        .prepare()
+        .unwrap();
+    let executable =
+        prepared.get_executable(btreeset!(NonFungibleGlobalId::from_public_key(&public_key)));
+
+    // Act
+    let hints = derive_prefetch_hints(test_runner.substate_db(), &executable);
+
+    // Assert
+    assert_eq!(hints.nodes.first(), Some(account.as_node_id()));
+    for node_id in [
+        &account_vault,
+        recipient.as_node_id(),
+        &recipient_vault,
+        XRD.as_node_id(),
+    ] {
+        assert!(hints.nodes.contains(node_id));
+    }
+    assert_eq!(hints.to_substate_keys().len(), 2 * hints.nodes.len());
+}
+
+#[test]
+fn prefetch_hints_include_components_which_do_not_exist_yet() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let virtual_account = ComponentAddress::virtual_account_from_public_key(
+        &Secp256k1PrivateKey::from_u64(7).unwrap().public_key(),
+    );
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .get_free_xrd_from_faucet()
+        .try_deposit_entire_worktop_or_abort(virtual_account, None)
+        .build();
+    let prepared = TestTransaction::new_from_nonce(manifest, 1)
+        .prepare()
+        .unwrap();
+    let executable = prepared.get_executable(btreeset!());
+
+    // Act
+    let hints = derive_prefetch_hints(test_runner.substate_db(), &executable);
+
+    // Assert
+    assert_eq!(hints.nodes.first(), Some(FAUCET.as_node_id()));
+    assert!(hints.nodes.contains(virtual_account.as_node_id()));
+}
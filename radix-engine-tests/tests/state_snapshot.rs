@@ -0,0 +1,194 @@
+use radix_engine::types::*;
+use radix_engine_store_interface::interface::{
+    DbPartitionKey, ListableSubstateDatabase, PartitionEntry, SubstateDatabase,
+};
+use radix_engine_stores::hash_tree_support::HashTreeUpdatingDatabase;
+use radix_engine_stores::memory_db::InMemorySubstateDatabase;
+use radix_engine_stores::snapshot::*;
+use scrypto_unit::*;
+
+#[test]
+fn imported_snapshot_reproduces_exported_state() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_state_hashing().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let source_db = test_runner.substate_db();
+    let header = SnapshotHeader {
+        state_version: Some(source_db.get_current_version()),
+        state_root_hash: Some(source_db.get_current_root_hash()),
+    };
+    let mut snapshot = Vec::new();
+    let exported = export_snapshot(source_db, header.clone(), &mut snapshot).unwrap();
+
+    // Act
+    let mut target_db = HashTreeUpdatingDatabase::new(InMemorySubstateDatabase::standard());
+    let imported = import_snapshot(&mut target_db, snapshot.as_slice()).unwrap();
+
+    // Assert
+    assert_eq!(imported, exported);
+    assert_eq!(imported.header, header);
+    assert_eq!(
+        target_db.get_current_root_hash(),
+        source_db.get_current_root_hash()
+    );
+    assert_eq!(
+        list_all_substates(&target_db),
+        list_all_substates(source_db)
+    );
+    assert!(target_db
+        .list_partition_keys()
+        .any(|partition_key| partition_key
+            .node_key
+            .ends_with(account.as_node_id().as_bytes())));
+}
+
+#[test]
+fn snapshot_split_into_many_chunks_can_be_imported() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().with_state_hashing().build();
+    let source_db = test_runner.substate_db();
+    let mut snapshot = Vec::new();
+    let mut writer = SnapshotWriter::new(&mut snapshot, SnapshotHeader::default(), 1024).unwrap();
+    for partition_key in source_db.list_partition_keys() {
+        for entry in source_db.list_entries(&partition_key) {
+            writer.append(&partition_key, entry).unwrap();
+        }
+    }
+    let exported = writer.finish().unwrap();
+
+    // Act
+    let mut target_db = HashTreeUpdatingDatabase::new(InMemorySubstateDatabase::standard());
+    let imported = import_snapshot(&mut target_db, snapshot.as_slice()).unwrap();
+
+    // Assert
+    assert!(exported.chunk_count > 1);
+    assert_eq!(imported, exported);
+    assert_eq!(
+        target_db.get_current_root_hash(),
+        source_db.get_current_root_hash()
+    );
+}
+
+#[test]
+fn corrupted_snapshot_is_rejected() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let mut snapshot = Vec::new();
+    export_snapshot(
+        test_runner.substate_db(),
+        SnapshotHeader::default(),
+        &mut snapshot,
+    )
+    .unwrap();
+    let last_byte_index = snapshot.len() - 1;
+    snapshot[last_byte_index] ^= 0xFF;
+
+    // Act
+    let result = import_snapshot(
+        &mut InMemorySubstateDatabase::standard(),
+        snapshot.as_slice(),
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(SnapshotError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn oversized_frame_is_rejected_before_being_read() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let mut snapshot = Vec::new();
+    export_snapshot(
+        test_runner.substate_db(),
+        SnapshotHeader::default(),
+        &mut snapshot,
+    )
+    .unwrap();
+    // the header frame's length directly follows the magic bytes and the format version
+    snapshot[9..13].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    // Act
+    let result = import_snapshot(
+        &mut InMemorySubstateDatabase::standard(),
+        snapshot.as_slice(),
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(SnapshotError::FrameTooLarge { chunk_index: 0, .. })
+    ));
+}
+
+#[test]
+fn computed_state_root_hash_matches_tracked_one() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_state_hashing().build();
+    test_runner.new_allocated_account();
+    let source_db = test_runner.substate_db();
+
+    // Act
+    let computed = compute_state_root_hash(source_db);
+
+    // Assert
+    assert_eq!(computed, source_db.get_current_root_hash());
+}
+
+#[test]
+fn truncated_snapshot_is_rejected() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let mut snapshot = Vec::new();
+    let mut writer = SnapshotWriter::new(&mut snapshot, SnapshotHeader::default(), 1024).unwrap();
+    let source_db = test_runner.substate_db();
+    for partition_key in source_db.list_partition_keys() {
+        for entry in source_db.list_entries(&partition_key) {
+            writer.append(&partition_key, entry).unwrap();
+        }
+    }
+    drop(writer); // i.e. no trailer
+
+    // Act
+    let result = import_snapshot(
+        &mut InMemorySubstateDatabase::standard(),
+        snapshot.as_slice(),
+    );
+
+    // Assert
+    assert!(matches!(result, Err(SnapshotError::UnexpectedFrame { .. })));
+}
+
+#[test]
+fn snapshot_cannot_be_imported_into_non_empty_database() {
+    // Arrange
+    let test_runner = TestRunnerBuilder::new().build();
+    let mut snapshot = Vec::new();
+    export_snapshot(
+        test_runner.substate_db(),
+        SnapshotHeader::default(),
+        &mut snapshot,
+    )
+    .unwrap();
+    let mut target_db = test_runner.substate_db().clone();
+
+    // Act
+    let result = import_snapshot(&mut target_db, snapshot.as_slice());
+
+    // Assert
+    assert!(matches!(result, Err(SnapshotError::DatabaseNotEmpty)));
+}
+
+fn list_all_substates<D: ListableSubstateDatabase + SubstateDatabase>(
+    database: &D,
+) -> Vec<(DbPartitionKey, Vec<PartitionEntry>)> {
+    database
+        .list_partition_keys()
+        .map(|partition_key| {
+            let entries = database.list_entries(&partition_key).collect();
+            (partition_key, entries)
+        })
+        .collect()
+}
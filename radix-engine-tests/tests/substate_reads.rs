@@ -0,0 +1,89 @@
+use radix_engine::track::SubstateReadSource;
+use radix_engine::transaction::*;
+use radix_engine::types::*;
+use radix_engine_interface::api::node_modules::ModuleConfig;
+use radix_engine_interface::metadata;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn execute_deposit_of_new_resource(
+    test_runner: &mut DefaultTestRunner,
+    account: ComponentAddress,
+    execution_config: ExecutionConfig,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_fungible_resource(
+            OwnerRole::None,
+            true,
+            0,
+            FungibleResourceRoles::default(),
+            metadata!(),
+            Some(5.into()),
+        )
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let nonce = test_runner.next_transaction_nonce();
+    test_runner.execute_transaction(
+        TestTransaction::new_from_nonce(manifest, nonce)
+            .prepare()
+            .unwrap()
+            .get_executable(btreeset!()),
+        CostingParameters::default(),
+        execution_config,
+    )
+}
+
+#[test]
+fn substate_reads_are_not_captured_by_default() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let receipt = execute_deposit_of_new_resource(
+        &mut test_runner,
+        account,
+        ExecutionConfig::for_test_transaction(),
+    );
+
+    // Assert
+    assert!(receipt.expect_commit_success().substate_reads.is_none());
+}
+
+#[test]
+fn substate_reads_distinguish_database_reads_from_reads_of_new_substates() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let receipt = execute_deposit_of_new_resource(
+        &mut test_runner,
+        account,
+        ExecutionConfig::for_test_transaction().with_substate_reads(true),
+    );
+
+    // Assert
+    let commit = receipt.expect_commit_success();
+    let substate_reads = commit.substate_reads.as_ref().unwrap();
+    let sources_of_node_reads = |node_id: &NodeId| {
+        substate_reads
+            .by_substate
+            .iter()
+            .filter(|((read_node_id, ..), _)| read_node_id == node_id)
+            .map(|(_, source)| *source)
+            .collect::<Vec<_>>()
+    };
+    assert!(sources_of_node_reads(FAUCET.as_node_id()).contains(&SubstateReadSource::Database));
+    assert!(sources_of_node_reads(account.as_node_id()).contains(&SubstateReadSource::Database));
+    let new_vaults = &commit.state_update_summary.new_vaults;
+    assert!(!new_vaults.is_empty());
+    for new_vault in new_vaults {
+        let sources = sources_of_node_reads(new_vault.as_node_id());
+        assert!(!sources.is_empty());
+        assert!(sources
+            .iter()
+            .all(|source| *source == SubstateReadSource::Transaction));
+    }
+}
@@ -0,0 +1,187 @@
+use radix_engine::system::actor::Actor;
+use radix_engine::system::system_debugger::*;
+use radix_engine::system::system_modules::execution_trace::BucketSnapshot;
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use radix_engine::vm::wasm::DefaultWasmEngine;
+use radix_engine::vm::{NoExtension, Vm};
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+type DefaultSystemDebugger<'a> = SystemDebugger<'a, Vm<'a, DefaultWasmEngine, NoExtension>>;
+
+#[derive(Debug)]
+struct Break {
+    reason: BreakReason,
+    actors: Vec<Actor>,
+    worktop: Option<Vec<BucketSnapshot>>,
+    fee_balance: Option<Decimal>,
+}
+
+struct RecordingHandler {
+    breaks: Rc<RefCell<Vec<Break>>>,
+    action: DebuggerAction,
+}
+
+impl DebuggerHandler for RecordingHandler {
+    fn on_break(
+        &mut self,
+        reason: &BreakReason,
+        inspector: &mut dyn DebuggerInspector,
+    ) -> DebuggerAction {
+        let breaks = &mut self.breaks.borrow_mut();
+        breaks.push(Break {
+            reason: reason.clone(),
+            actors: inspector.actors(),
+            worktop: inspector.worktop(),
+            fee_balance: inspector.fee_reserve().map(|r| r.fee_balance()),
+        });
+        // Stop stepping after a few breaks
+        if breaks.len() < 3 {
+            self.action
+        } else {
+            DebuggerAction::Continue
+        }
+    }
+}
+
+fn debug_manifest(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    breakpoints: Vec<Breakpoint>,
+    action: DebuggerAction,
+) -> (TransactionReceipt, Vec<Break>) {
+    let breaks = Rc::new(RefCell::new(Vec::new()));
+    let receipt = test_runner.execute_manifest_with_system::<_, DefaultSystemDebugger<'_>>(
+        manifest,
+        vec![],
+        SystemDebuggerInit {
+            breakpoints,
+            handler: Box::new(RecordingHandler {
+                breaks: breaks.clone(),
+                action,
+            }),
+        },
+    );
+    let breaks = breaks.replace(Vec::new());
+    (receipt, breaks)
+}
+
+fn free_xrd_manifest(account: ComponentAddress) -> TransactionManifestV1 {
+    ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .get_free_xrd_from_faucet()
+        .deposit_batch(account)
+        .build()
+}
+
+#[test]
+fn test_debugger_breaks_on_invocation() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let (receipt, breaks) = debug_manifest(
+        &mut test_runner,
+        free_xrd_manifest(account),
+        vec![Breakpoint::Invocation {
+            blueprint_id: BlueprintId::new(&FAUCET_PACKAGE, FAUCET_BLUEPRINT),
+            ident: Some("free".to_string()),
+        }],
+        DebuggerAction::Continue,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(breaks.len(), 1);
+    match &breaks[0].reason {
+        BreakReason::Invocation(Actor::Method(actor)) => {
+            assert_eq!(actor.node_id, FAUCET.into_node_id());
+            assert_eq!(actor.ident, "free");
+        }
+        reason => panic!("Unexpected break reason: {:?}", reason),
+    }
+    assert!(matches!(breaks[0].actors.first(), Some(Actor::Root)));
+    assert!(breaks[0].fee_balance.unwrap().is_positive());
+}
+
+#[test]
+fn test_debugger_breaks_on_instruction_and_inspects_worktop() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let (receipt, breaks) = debug_manifest(
+        &mut test_runner,
+        free_xrd_manifest(account),
+        vec![Breakpoint::Instruction(1), Breakpoint::Instruction(2)],
+        DebuggerAction::Continue,
+    );
+
+    // Assert
+    let result = receipt.expect_commit_success();
+    assert!(result.execution_trace.is_none());
+    assert_eq!(breaks.len(), 2);
+    assert_eq!(breaks[0].reason, BreakReason::Instruction(1));
+    assert_eq!(breaks[0].worktop, Some(vec![]));
+    assert_eq!(breaks[1].reason, BreakReason::Instruction(2));
+    assert_eq!(
+        breaks[1].worktop,
+        Some(vec![BucketSnapshot::Fungible {
+            resource_address: XRD,
+            liquid: dec!(10000),
+        }])
+    );
+}
+
+#[test]
+fn test_debugger_steps_through_invocations() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let (receipt, breaks) = debug_manifest(
+        &mut test_runner,
+        free_xrd_manifest(account),
+        vec![Breakpoint::Instruction(0)],
+        DebuggerAction::Step,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(breaks.len(), 3);
+    assert_eq!(breaks[0].reason, BreakReason::Instruction(0));
+    for b in &breaks[1..] {
+        assert!(
+            matches!(b.reason, BreakReason::Invocation(..)),
+            "{:?}",
+            b.reason
+        );
+    }
+}
+
+#[test]
+fn test_debugger_breaks_on_event() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+
+    // Act
+    let (receipt, breaks) = debug_manifest(
+        &mut test_runner,
+        free_xrd_manifest(account),
+        vec![Breakpoint::Event("LockFeeEvent".to_string())],
+        DebuggerAction::Continue,
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(breaks.len(), 1);
+    match &breaks[0].reason {
+        BreakReason::Event(EventTypeIdentifier(_, name)) => assert_eq!(name, "LockFeeEvent"),
+        reason => panic!("Unexpected break reason: {:?}", reason),
+    }
+}
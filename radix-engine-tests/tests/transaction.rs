@@ -219,6 +219,7 @@ fn transaction_processor_produces_expected_error_for_undecodable_instructions()
                 intent_hash: Hash([0; 32]),
             },
             epoch_range: Default::default(),
+            proposer_timestamp_range: None,
             pre_allocated_addresses: Default::default(),
             payload_size: 4,
             num_of_signature_validations: 0,
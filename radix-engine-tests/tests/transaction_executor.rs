@@ -97,6 +97,7 @@ fn test_normal_transaction_flow() {
     let scrypto_vm = ScryptoVm {
         wasm_engine: DefaultWasmEngine::default(),
         wasm_validator_config: WasmValidatorConfigV1::new(),
+        instrumented_code_cache: None,
     };
     let native_vm = DefaultNativeVm::new();
     let vm = Vm::new(&scrypto_vm, native_vm);
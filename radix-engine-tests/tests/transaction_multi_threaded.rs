@@ -24,6 +24,7 @@ mod multi_threaded_test {
         let scrypto_vm = ScryptoVm {
             wasm_engine: DefaultWasmEngine::default(),
             wasm_validator_config: WasmValidatorConfigV1::new(),
+            instrumented_code_cache: None,
         };
         let native_vm = DefaultNativeVm::new();
         let vm = Vm {
@@ -0,0 +1,451 @@
+use radix_engine::blueprints::consensus_manager::ConsensusManagerField;
+use radix_engine::blueprints::transaction_processor::TransactionProcessorError;
+use radix_engine::errors::{ApplicationError, RejectionReason, RuntimeError, SystemModuleError};
+use radix_engine::system::system_modules::auth::AuthError;
+use radix_engine::system::system_modules::costing::{CostingError, FeeReserveError};
+use radix_engine::transaction::{CostingParameters, ExecutionConfig, TransactionReceipt};
+use radix_engine::types::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+use transaction::validation::*;
+
+#[test]
+fn transaction_yielding_to_child_intent_can_be_committed_once() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let current_epoch = test_runner.get_current_epoch();
+    let child_intent = create_child_intent(current_epoch, 1);
+
+    // Act
+    let transaction =
+        create_transaction(create_header(current_epoch, 1), vec![child_intent.clone()]);
+    let receipt = execute(&mut test_runner, &transaction);
+
+    // Assert
+    receipt.expect_commit_success();
+
+    // Act
+    let transaction = create_transaction(create_header(current_epoch, 2), vec![child_intent]);
+    let receipt = execute(&mut test_runner, &transaction);
+
+    // Assert
+    receipt.expect_specific_rejection(|e| match e {
+        RejectionReason::IntentHashPreviouslyCommitted => true,
+        _ => false,
+    });
+}
+
+#[test]
+fn transaction_is_rejected_outside_of_proposer_timestamp_range() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let current_epoch = test_runner.get_current_epoch();
+    test_runner.advance_to_round_at_timestamp(Round::of(1), 1_000_000);
+
+    // Act
+    let mut header = create_header(current_epoch, 1);
+    header.min_proposer_timestamp_inclusive = Some(Instant::new(2_000));
+    let receipt = execute(&mut test_runner, &create_transaction(header, vec![]));
+
+    // Assert
+    receipt.expect_specific_rejection(|e| match e {
+        RejectionReason::TransactionProposerTimestampNotYetValid {
+            valid_from,
+            current_time,
+        } => {
+            valid_from.seconds_since_unix_epoch == 2_000
+                && current_time.seconds_since_unix_epoch == 1_000
+        }
+        _ => false,
+    });
+
+    // Act
+    let mut header = create_header(current_epoch, 2);
+    header.max_proposer_timestamp_exclusive = Some(Instant::new(1_000));
+    let receipt = execute(&mut test_runner, &create_transaction(header, vec![]));
+
+    // Assert
+    receipt.expect_specific_rejection(|e| match e {
+        RejectionReason::TransactionProposerTimestampNoLongerValid { .. } => true,
+        _ => false,
+    });
+
+    // Act
+    let mut header = create_header(current_epoch, 3);
+    header.min_proposer_timestamp_inclusive = Some(Instant::new(1_000));
+    header.max_proposer_timestamp_exclusive = Some(Instant::new(1_001));
+    let receipt = execute(&mut test_runner, &create_transaction(header, vec![]));
+
+    // Assert
+    receipt.expect_commit_success();
+}
+
+#[test]
+fn proposer_timestamp_is_only_read_when_there_is_a_range_to_check() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let current_epoch = test_runner.get_current_epoch();
+    let is_proposer_timestamp_read = |receipt: &TransactionReceipt| {
+        receipt
+            .expect_commit_success()
+            .substate_reads
+            .as_ref()
+            .unwrap()
+            .by_substate
+            .contains_key(&(
+                CONSENSUS_MANAGER.into_node_id(),
+                MAIN_BASE_PARTITION,
+                ConsensusManagerField::ProposerMilliTimestamp.into(),
+            ))
+    };
+
+    // Act
+    let receipt = execute_with_config(
+        &mut test_runner,
+        &create_transaction(create_header(current_epoch, 1), vec![]),
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator())
+            .with_substate_reads(true),
+    );
+
+    // Assert
+    assert!(!is_proposer_timestamp_read(&receipt));
+
+    // Act
+    let mut header = create_header(current_epoch, 2);
+    header.min_proposer_timestamp_inclusive = Some(Instant::new(0));
+    let receipt = execute_with_config(
+        &mut test_runner,
+        &create_transaction(header, vec![]),
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator())
+            .with_substate_reads(true),
+    );
+
+    // Assert
+    assert!(is_proposer_timestamp_read(&receipt));
+}
+
+#[test]
+fn transaction_exceeding_max_fee_is_not_committed() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let current_epoch = test_runner.get_current_epoch();
+
+    // Act
+    let mut header = create_header(current_epoch, 1);
+    header.max_fee_in_xrd = Some(dec!("0.000001"));
+    let receipt = execute(&mut test_runner, &create_transaction(header, vec![]));
+
+    // Assert
+    receipt.expect_specific_rejection(|e| match e {
+        RejectionReason::ErrorBeforeLoanAndDeferredCostsRepaid(
+            RuntimeError::SystemModuleError(SystemModuleError::CostingError(
+                CostingError::FeeReserveError(FeeReserveError::MaxFeeExceeded { .. }),
+            )),
+        ) => true,
+        _ => false,
+    });
+}
+
+#[test]
+fn bucket_can_be_yielded_to_child_intent_and_back() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (_, _, account) = test_runner.new_allocated_account();
+    let balance = test_runner.get_component_balance(account, XRD);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .get_free_xrd_from_faucet()
+        .take_all_from_worktop(XRD, "xrd")
+        .with_name_lookup(|builder, lookup| builder.yield_to_child(0, (lookup.bucket("xrd"),)))
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let child_manifest = ManifestBuilder::new()
+        .take_all_from_worktop(XRD, "xrd")
+        .with_name_lookup(|builder, lookup| builder.yield_to_parent((lookup.bucket("xrd"),)))
+        .build();
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest,
+        btreeset!(),
+        vec![(child_manifest, btreeset!())],
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_component_balance(account, XRD),
+        balance + dec!(10000)
+    );
+}
+
+#[test]
+fn child_intent_signer_proof_is_only_in_child_intent_auth_zone() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let (public_key, _, account) = test_runner.new_allocated_account();
+    let (_, _, recipient) = test_runner.new_allocated_account();
+    let balance = test_runner.get_component_balance(recipient, XRD);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .try_deposit_entire_worktop_or_abort(recipient, None)
+        .build();
+    let child_manifest = ManifestBuilder::new()
+        .withdraw_from_account(account, XRD, 10)
+        .take_all_from_worktop(XRD, "xrd")
+        .with_name_lookup(|builder, lookup| builder.yield_to_parent((lookup.bucket("xrd"),)))
+        .build();
+    let signer = NonFungibleGlobalId::from_public_key(&public_key);
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest.clone(),
+        btreeset!(),
+        vec![(child_manifest.clone(), btreeset!(signer.clone()))],
+    );
+
+    // Assert
+    receipt.expect_commit_success();
+    assert_eq!(
+        test_runner.get_component_balance(recipient, XRD),
+        balance + dec!(10)
+    );
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest,
+        btreeset!(signer),
+        vec![(child_manifest, btreeset!())],
+    );
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(
+                ..
+            )))
+        )
+    });
+}
+
+#[test]
+fn child_intent_which_is_not_yielded_to_fails_the_transaction() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let manifest = ManifestBuilder::new().lock_fee_from_faucet().build();
+    let child_manifest = ManifestBuilder::new().yield_to_parent(()).build();
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest,
+        btreeset!(),
+        vec![(child_manifest, btreeset!())],
+    );
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::ApplicationError(ApplicationError::TransactionProcessorError(
+                TransactionProcessorError::ChildIntentNotRun(0)
+            ))
+        )
+    });
+}
+
+#[test]
+fn child_intent_which_does_not_yield_to_parent_fails_the_transaction() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .build();
+    let child_manifest = ManifestBuilder::new().build();
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest,
+        btreeset!(),
+        vec![(child_manifest, btreeset!())],
+    );
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::ApplicationError(ApplicationError::TransactionProcessorError(
+                TransactionProcessorError::MissingYieldToParent
+            ))
+        )
+    });
+}
+
+#[test]
+fn child_intent_cannot_be_yielded_to_twice() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .yield_to_child(0, ())
+        .yield_to_child(0, ())
+        .build();
+    let child_manifest = ManifestBuilder::new().yield_to_parent(()).build();
+
+    // Act
+    let receipt = execute_with_child_intents(
+        &mut test_runner,
+        manifest,
+        btreeset!(),
+        vec![(child_manifest, btreeset!())],
+    );
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::ApplicationError(ApplicationError::TransactionProcessorError(
+                TransactionProcessorError::ChildIntentNotFound(0)
+            ))
+        )
+    });
+}
+
+fn execute(
+    test_runner: &mut DefaultTestRunner,
+    transaction: &NotarizedTransactionV2,
+) -> TransactionReceipt {
+    execute_with_config(
+        test_runner,
+        transaction,
+        ExecutionConfig::for_notarized_transaction(NetworkDefinition::simulator()),
+    )
+}
+
+fn execute_with_config(
+    test_runner: &mut DefaultTestRunner,
+    transaction: &NotarizedTransactionV2,
+    execution_config: ExecutionConfig,
+) -> TransactionReceipt {
+    let validator = NotarizedTransactionValidator::new(ValidationConfig::simulator());
+    let validated = validator
+        .validate_notarized_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+        .unwrap();
+    test_runner.execute_transaction(
+        validated.get_executable(),
+        CostingParameters::default(),
+        execution_config,
+    )
+}
+
+/// Executes the manifest with the given child intents, bypassing the validation of the
+/// transaction, so that the engine's own checks of child intents can be exercised.
+fn execute_with_child_intents(
+    test_runner: &mut DefaultTestRunner,
+    manifest: TransactionManifestV1,
+    initial_proofs: BTreeSet<NonFungibleGlobalId>,
+    child_intents: Vec<(TransactionManifestV1, BTreeSet<NonFungibleGlobalId>)>,
+) -> TransactionReceipt {
+    let nonce = test_runner.next_transaction_nonce();
+    let prepared = TestTransaction::new_from_nonce(manifest, nonce)
+        .prepare()
+        .unwrap();
+    let prepared_child_intents: Vec<_> = child_intents
+        .into_iter()
+        .map(|(child_manifest, initial_proofs)| {
+            let nonce = test_runner.next_transaction_nonce();
+            let prepared = TestTransaction::new_from_nonce(child_manifest, nonce)
+                .prepare()
+                .unwrap();
+            (prepared, initial_proofs)
+        })
+        .collect();
+    let executable = prepared.get_executable(initial_proofs).with_child_intents(
+        prepared_child_intents
+            .iter()
+            .map(|(prepared, initial_proofs)| ExecutableChildIntent {
+                encoded_instructions: &prepared.encoded_instructions,
+                references: &prepared.references,
+                blobs: &prepared.blobs,
+                intent_hash: TransactionIntentHash::NotToCheck {
+                    intent_hash: prepared.hash,
+                },
+                epoch_range: None,
+                proposer_timestamp_range: None,
+                auth_zone_params: AuthZoneParams {
+                    initial_proofs: initial_proofs.clone(),
+                    virtual_resources: BTreeSet::new(),
+                },
+            })
+            .collect(),
+    );
+    test_runner.execute_transaction(
+        executable,
+        CostingParameters::default(),
+        ExecutionConfig::for_test_transaction(),
+    )
+}
+
+fn create_header(current_epoch: Epoch, intent_discriminator: u64) -> TransactionHeaderV2 {
+    TransactionHeaderV2 {
+        network_id: NetworkDefinition::simulator().id,
+        start_epoch_inclusive: current_epoch,
+        end_epoch_exclusive: current_epoch.after(10).unwrap(),
+        min_proposer_timestamp_inclusive: None,
+        max_proposer_timestamp_exclusive: None,
+        max_fee_in_xrd: None,
+        intent_discriminator,
+        notary_public_key: Secp256k1PrivateKey::from_u64(3)
+            .unwrap()
+            .public_key()
+            .into(),
+        notary_is_signatory: false,
+        tip_percentage: 5,
+    }
+}
+
+fn create_child_intent(current_epoch: Epoch, intent_discriminator: u64) -> SignedChildIntentV2 {
+    ChildIntentBuilder::new()
+        .header(ChildIntentHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch.after(10).unwrap(),
+            min_proposer_timestamp_inclusive: None,
+            max_proposer_timestamp_exclusive: None,
+            intent_discriminator,
+        })
+        .manifest(ManifestBuilder::new().yield_to_parent(()).build())
+        .sign(&Secp256k1PrivateKey::from_u64(2).unwrap())
+        .build()
+}
+
+fn create_transaction(
+    header: TransactionHeaderV2,
+    child_intents: Vec<SignedChildIntentV2>,
+) -> NotarizedTransactionV2 {
+    let manifest = child_intents
+        .iter()
+        .enumerate()
+        .fold(
+            ManifestBuilder::new().lock_fee_from_faucet(),
+            |builder, (child_index, _)| builder.yield_to_child(child_index as u32, ()),
+        )
+        .build();
+    let mut builder = TransactionV2Builder::new()
+        .header(header)
+        .manifest(manifest);
+    for child_intent in child_intents {
+        builder = builder.add_child_intent(child_intent);
+    }
+    builder
+        .sign(&Secp256k1PrivateKey::from_u64(1).unwrap())
+        .notarize(&Secp256k1PrivateKey::from_u64(3).unwrap())
+        .build()
+}
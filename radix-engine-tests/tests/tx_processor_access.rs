@@ -1,11 +1,13 @@
 mod package_loader;
 
 use package_loader::PackageLoader;
-use radix_engine::errors::{RuntimeError, SystemModuleError};
+use radix_engine::blueprints::transaction_processor::TransactionProcessorError;
+use radix_engine::errors::{ApplicationError, RuntimeError, SystemModuleError};
 use radix_engine::system::system_modules::auth::AuthError;
 use radix_engine::types::*;
 use radix_engine_interface::blueprints::transaction_processor::{
-    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_IDENT,
+    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_CHILD_IDENT,
+    TRANSACTION_PROCESSOR_RUN_IDENT,
 };
 use scrypto::prelude::FromPublicKey;
 use scrypto_unit::*;
@@ -19,6 +21,12 @@ pub struct ManifestTransactionProcessorRunInput {
     pub blobs: IndexMap<Hash, Vec<u8>>,
 }
 
+#[derive(Debug, Eq, PartialEq, ManifestSbor)]
+pub struct ManifestTransactionProcessorYieldToChildInput {
+    pub child_index: u32,
+    pub args: (),
+}
+
 #[test]
 fn should_not_be_able_to_call_tx_processor_in_tx_processor() {
     // Arrange
@@ -54,6 +62,37 @@ fn should_not_be_able_to_call_tx_processor_in_tx_processor() {
     });
 }
 
+#[test]
+fn should_not_be_able_to_run_child_intent_with_call_function() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            TRANSACTION_PROCESSOR_PACKAGE,
+            TRANSACTION_PROCESSOR_BLUEPRINT,
+            TRANSACTION_PROCESSOR_RUN_CHILD_IDENT,
+            ManifestTransactionProcessorYieldToChildInput {
+                child_index: 0,
+                args: (),
+            },
+        )
+        .build();
+    let result = test_runner.execute_manifest(manifest, vec![]);
+
+    // Assert
+    result.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::ApplicationError(ApplicationError::TransactionProcessorError(
+                TransactionProcessorError::RunChildNotPermitted
+            ))
+        )
+    });
+}
+
 #[test]
 fn calling_transaction_processor_from_scrypto_should_not_panic() {
     // Arrange
@@ -0,0 +1,141 @@
+mod package_loader;
+
+use package_loader::PackageLoader;
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn call_logger(
+    test_runner: &mut DefaultTestRunner,
+    package_address: PackageAddress,
+    function_name: &str,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "Logger",
+            function_name,
+            manifest_args!("Hey".to_string()),
+        )
+        .build();
+    test_runner.execute_manifest(manifest, vec![])
+}
+
+#[test]
+fn test_wasm_backtrace_is_not_collected_by_default() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("logger"));
+
+    // Act
+    let receipt = call_logger(&mut test_runner, package_address, "rust_panic");
+
+    // Assert
+    receipt.expect_commit_failure();
+    assert!(receipt.wasm_backtrace.is_none());
+}
+
+#[test]
+fn test_wasm_backtrace_is_not_collected_on_success() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_wasm_backtraces().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("logger"));
+
+    // Act
+    let receipt = call_logger(&mut test_runner, package_address, "emit_log");
+
+    // Assert
+    receipt.expect_commit_success();
+    assert!(receipt.wasm_backtrace.is_none());
+}
+
+#[test]
+fn test_wasm_backtrace_captures_the_call_stack_of_a_panic() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_wasm_backtraces().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("logger"));
+
+    // Act
+    let receipt = call_logger(&mut test_runner, package_address, "rust_panic");
+
+    // Assert
+    receipt.expect_commit_failure();
+    let wasm_backtrace = receipt.wasm_backtrace.as_ref().unwrap();
+    assert_eq!(wasm_backtrace.frames.len(), 1);
+    let frame = &wasm_backtrace.frames[0];
+    assert_eq!(frame.package_address, package_address);
+    assert_eq!(frame.export_name, "Logger_rust_panic");
+    assert!(!frame.function_indices.is_empty());
+
+    let display = test_runner.display_receipt(&receipt);
+    assert!(display.contains("Backtrace:"), "{}", display);
+    assert!(display.contains("::Logger_rust_panic"), "{}", display);
+}
+
+fn encode_u32(mut value: u32, bytes: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn encode_name(name: &str, bytes: &mut Vec<u8>) {
+    encode_u32(name.len() as u32, bytes);
+    bytes.extend_from_slice(name.as_bytes());
+}
+
+/// Appends a `name` section to the code, naming the first `count` functions with mangled Rust
+/// names, the way the sidecar written by `scrypto build --function-names` names them.
+fn with_mangled_function_names(code: &[u8], count: u32) -> Vec<u8> {
+    let mut function_names = Vec::new();
+    encode_u32(count, &mut function_names);
+    for index in 0..count {
+        let function_name = format!("func{}", index);
+        encode_u32(index, &mut function_names);
+        encode_name(
+            &format!(
+                "_ZN6logger6Logger{}{}17h0123456789abcdefE",
+                function_name.len(),
+                function_name
+            ),
+            &mut function_names,
+        );
+    }
+
+    let mut content = Vec::new();
+    encode_name("name", &mut content);
+    content.push(0x01);
+    encode_u32(function_names.len() as u32, &mut content);
+    content.extend(function_names);
+
+    let mut sidecar_code = code.to_vec();
+    sidecar_code.push(0x00);
+    encode_u32(content.len() as u32, &mut sidecar_code);
+    sidecar_code.extend(content);
+    sidecar_code
+}
+
+#[test]
+fn test_wasm_backtrace_is_symbolized_through_the_function_names_sidecar() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_wasm_backtraces().build();
+    let (code, definition) = PackageLoader::get("logger");
+    test_runner.add_wasm_function_names_sidecar(&code, with_mangled_function_names(&code, 4096));
+    let package_address = test_runner.publish_package_simple((code, definition));
+
+    // Act
+    let receipt = call_logger(&mut test_runner, package_address, "rust_panic");
+
+    // Assert
+    receipt.expect_commit_failure();
+    let display = test_runner.display_receipt(&receipt);
+    assert!(display.contains("logger::Logger::func"), "{}", display);
+    assert!(!display.contains("17h0123456789abcdef"), "{}", display);
+}
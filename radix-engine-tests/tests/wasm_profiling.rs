@@ -0,0 +1,69 @@
+mod package_loader;
+
+use package_loader::PackageLoader;
+use radix_engine::transaction::TransactionReceipt;
+use radix_engine::types::*;
+use scrypto_unit::*;
+use transaction::prelude::*;
+
+fn call_recursive(
+    test_runner: &mut DefaultTestRunner,
+    package_address: PackageAddress,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(package_address, "Caller", "recursive", manifest_args!(5u32))
+        .build();
+    test_runner.execute_manifest(manifest, vec![])
+}
+
+#[test]
+fn test_wasm_profile_is_not_collected_by_default() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("recursion"));
+
+    // Act
+    let receipt = call_recursive(&mut test_runner, package_address);
+
+    // Assert
+    receipt.expect_commit_success();
+    assert!(receipt.wasm_execution_profile.is_none());
+}
+
+#[test]
+fn test_wasm_profile_attributes_execution_units_to_functions() {
+    // Arrange
+    let mut test_runner = TestRunnerBuilder::new().with_wasm_profiling().build();
+    let package_address = test_runner.publish_package_simple(PackageLoader::get("recursion"));
+
+    // Act
+    let receipt = call_recursive(&mut test_runner, package_address);
+
+    // Assert
+    receipt.expect_commit_success();
+    let wasm_execution_profile = receipt.wasm_execution_profile.as_ref().unwrap();
+    assert!(!wasm_execution_profile.is_empty());
+    let mut max_depth = 0;
+    for frame in wasm_execution_profile.wasm_execution_units.keys() {
+        for invocation in &frame.invocation_stack {
+            assert_eq!(invocation.package_address, package_address);
+            assert_eq!(invocation.export_name, "Caller_recursive");
+        }
+        max_depth = max_depth.max(frame.invocation_stack.len());
+    }
+    assert_eq!(max_depth, 5);
+
+    let folded_stacks = test_runner.wasm_execution_profile_to_folded_stacks(wasm_execution_profile);
+    let prefix = format!("{}::Caller_recursive;", package_address.to_hex());
+    assert_eq!(
+        folded_stacks.lines().count(),
+        wasm_execution_profile.wasm_execution_units.len()
+    );
+    assert!(folded_stacks.contains(&prefix.repeat(5)));
+    for line in folded_stacks.lines() {
+        assert!(line.starts_with(&prefix), "{}", line);
+        let (_, units) = line.rsplit_once(' ').unwrap();
+        assert!(units.parse::<u64>().unwrap() > 0);
+    }
+}
@@ -30,6 +30,7 @@ use crate::system::system_callback::{SystemConfig, SystemLockData};
 use crate::system::system_callback_api::SystemCallbackObject;
 use crate::system::system_modules::auth::{AuthError, ResolvedPermission};
 use crate::system::system_type_checker::SystemMapper;
+use crate::vm::wasm::InstrumentedCodeCache;
 use crate::vm::VmPackageValidation;
 
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
@@ -929,6 +930,7 @@ impl PackageNativePackage {
     pub fn invoke_export<Y>(
         export_name: &str,
         input: &IndexedScryptoValue,
+        instrumented_code_cache: Option<&dyn InstrumentedCodeCache>,
         api: &mut Y,
     ) -> Result<IndexedScryptoValue, RuntimeError>
     where
@@ -955,7 +957,13 @@ impl PackageNativePackage {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
 
-                let rtn = Self::publish_wasm(input.code, input.definition, input.metadata, api)?;
+                let rtn = Self::publish_wasm(
+                    input.code,
+                    input.definition,
+                    input.metadata,
+                    instrumented_code_cache,
+                    api,
+                )?;
 
                 Ok(IndexedScryptoValue::from_typed(&rtn))
             }
@@ -970,6 +978,7 @@ impl PackageNativePackage {
                     input.definition,
                     input.metadata,
                     input.owner_role,
+                    instrumented_code_cache,
                     api,
                 )?;
 
@@ -1137,6 +1146,7 @@ impl PackageNativePackage {
         vm_type: VmType,
         original_code: Vec<u8>,
         system_instructions: BTreeMap<String, Vec<SystemInstruction>>,
+        instrumented_code_cache: Option<&dyn InstrumentedCodeCache>,
     ) -> Result<PackageStructure, RuntimeError> {
         // Validate schema
         validate_package_schema(&definition.blueprints)
@@ -1151,8 +1161,12 @@ impl PackageNativePackage {
             .map_err(|e| RuntimeError::ApplicationError(ApplicationError::PackageError(e)))?;
 
         // Validate VM specific properties
-        let instrumented_code =
-            VmPackageValidation::validate(&definition, vm_type, &original_code)?;
+        let instrumented_code = VmPackageValidation::validate(
+            &definition,
+            vm_type,
+            &original_code,
+            instrumented_code_cache,
+        )?;
 
         // Build Package structure
         let mut definitions = index_map_new();
@@ -1337,6 +1351,7 @@ impl PackageNativePackage {
             VmType::Native,
             native_package_code_id.to_be_bytes().to_vec(),
             Default::default(),
+            None,
         )?;
         let role_assignment = RoleAssignment::create(OwnerRole::None, indexmap!(), api)?;
         let metadata = Metadata::create_with_data(metadata_init, api)?;
@@ -1354,6 +1369,7 @@ impl PackageNativePackage {
         code: Vec<u8>,
         definition: PackageDefinition,
         metadata_init: MetadataInit,
+        instrumented_code_cache: Option<&dyn InstrumentedCodeCache>,
         api: &mut Y,
     ) -> Result<(PackageAddress, Bucket), RuntimeError>
     where
@@ -1365,6 +1381,7 @@ impl PackageNativePackage {
             VmType::ScryptoV1,
             code,
             Default::default(),
+            instrumented_code_cache,
         )?;
 
         let (address_reservation, address) = api.allocate_global_address(BlueprintId {
@@ -1399,6 +1416,7 @@ impl PackageNativePackage {
         definition: PackageDefinition,
         metadata_init: MetadataInit,
         owner_role: OwnerRole,
+        instrumented_code_cache: Option<&dyn InstrumentedCodeCache>,
         api: &mut Y,
     ) -> Result<PackageAddress, RuntimeError>
     where
@@ -1410,6 +1428,7 @@ impl PackageNativePackage {
             VmType::ScryptoV1,
             code,
             Default::default(),
+            instrumented_code_cache,
         )?;
         let metadata = Metadata::create_with_data(metadata_init, api)?;
         let role_assignment = SecurifiedPackage::create_advanced(owner_role, api)?;
@@ -15,6 +15,8 @@ use radix_engine_interface::schema::{
 };
 
 use super::TransactionProcessorBlueprint;
+use super::TransactionProcessorRunChildInput;
+use super::TransactionProcessorRunChildOutput;
 use super::TransactionProcessorRunInput;
 
 pub struct TransactionProcessorNativePackage;
@@ -39,6 +41,21 @@ impl TransactionProcessorNativePackage {
                 export: TRANSACTION_PROCESSOR_RUN_IDENT.to_string(),
             },
         );
+        functions.insert(
+            TRANSACTION_PROCESSOR_RUN_CHILD_IDENT.to_string(),
+            FunctionSchemaInit {
+                receiver: None,
+                input: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<TransactionProcessorRunChildInput>(),
+                ),
+                output: TypeRef::Static(
+                    aggregator
+                        .add_child_type_and_descendents::<TransactionProcessorRunChildOutput>(),
+                ),
+                export: TRANSACTION_PROCESSOR_RUN_CHILD_IDENT.to_string(),
+            },
+        );
 
         let schema = generate_full_schema(aggregator);
         let blueprints = indexmap!(
@@ -99,6 +116,21 @@ impl TransactionProcessorNativePackage {
 
                 Ok(IndexedScryptoValue::from_typed(&rtn))
             }
+            TRANSACTION_PROCESSOR_RUN_CHILD_IDENT => {
+                let input: TransactionProcessorRunChildInput = input.as_typed().map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+                })?;
+
+                let rtn = TransactionProcessorBlueprint::run_child(
+                    input.manifest_encoded_instructions,
+                    input.references,
+                    input.blobs,
+                    input.args,
+                    api,
+                )?;
+
+                Ok(IndexedScryptoValue::from_typed(&rtn))
+            }
             _ => Err(RuntimeError::ApplicationError(
                 ApplicationError::ExportDoesNotExist(export_name.to_string()),
             )),
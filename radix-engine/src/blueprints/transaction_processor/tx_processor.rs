@@ -39,6 +39,47 @@ pub struct TransactionProcessorRunInputEfficientEncodable<'a> {
     pub blobs: &'a IndexMap<Hash, Vec<u8>>,
 }
 
+/// A child intent of the transaction, held by the system until its parent yields to it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TransactionProcessorChildIntent {
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub references: Vec<Reference>,
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+}
+
+/// The input with which the root transaction processor yields to a child intent.
+/// The system replaces it with a [`TransactionProcessorRunChildInput`] of the child intent it holds
+/// (i.e. the child's instructions never come from the caller).
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct TransactionProcessorYieldToChildInput {
+    pub child_index: u32,
+    pub args: ScryptoValue,
+}
+
+#[derive(Debug, Eq, PartialEq, ScryptoSbor)]
+pub struct TransactionProcessorRunChildInput {
+    pub child_index: u32,
+    pub manifest_encoded_instructions: Vec<u8>,
+    pub references: Vec<Reference>, // Required so that the kernel passes the references to the child's processor frame
+    pub blobs: IndexMap<Hash, Vec<u8>>,
+    pub args: ScryptoValue,
+}
+
+/// Whether the function is the transaction processor's `run_child`, the input of which is always
+/// prepared by the system.
+pub fn is_transaction_processor_run_child(blueprint_id: &BlueprintId, ident: &str) -> bool {
+    blueprint_id
+        .package_address
+        .eq(&TRANSACTION_PROCESSOR_PACKAGE)
+        && blueprint_id
+            .blueprint_name
+            .eq(TRANSACTION_PROCESSOR_BLUEPRINT)
+        && ident.eq(TRANSACTION_PROCESSOR_RUN_CHILD_IDENT)
+}
+
+/// The value the child intent yielded to its parent
+pub type TransactionProcessorRunChildOutput = ScryptoValue;
+
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
 pub enum TransactionProcessorError {
     BucketNotFound(u32),
@@ -53,6 +94,14 @@ pub enum TransactionProcessorError {
     AuthZoneIsEmpty,
     InvocationOutputDecodeError(DecodeError),
     ArgsEncodeError(EncodeError),
+    ChildIntentNotFound(u32),
+    ChildIntentNotRun(usize),
+    YieldToChildNotPermitted,
+    YieldToParentNotPermitted,
+    InstructionAfterYieldToParent,
+    MissingYieldToParent,
+    YieldedNonBucket(NodeId),
+    RunChildNotPermitted,
 }
 
 impl From<TransactionProcessorError> for RuntimeError {
@@ -103,7 +152,54 @@ impl TransactionProcessorBlueprint {
     where
         Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
     {
-        // Create a worktop
+        let mut worktop = Self::create_worktop(api)?;
+        let instructions = Self::decode_instructions(&manifest_encoded_instructions)?;
+        let mut processor = TransactionProcessor::new(blobs, global_address_reservations);
+
+        let outputs = Self::process_instructions(instructions, &mut processor, &mut worktop, api)?;
+
+        worktop.drop(api)?;
+
+        Ok(outputs)
+    }
+
+    /// Runs a child intent, which starts with the given args on its worktop, and returns the
+    /// value it yields to its parent.
+    pub(crate) fn run_child<Y, L: Default>(
+        manifest_encoded_instructions: Vec<u8>,
+        _references: Vec<Reference>, // Required so that the kernel passes the references to the processor frame
+        blobs: IndexMap<Hash, Vec<u8>>,
+        args: ScryptoValue,
+        api: &mut Y,
+    ) -> Result<TransactionProcessorRunChildOutput, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
+        let mut worktop = Self::create_worktop(api)?;
+        let instructions = Self::decode_instructions(&manifest_encoded_instructions)?;
+        let mut processor = TransactionProcessor::new(blobs, vec![]);
+        processor.is_child_intent = true;
+
+        let args = IndexedScryptoValue::from_typed(&args);
+        TransactionProcessor::ensure_only_buckets(&args, api)?;
+        processor.handle_call_return_data(&args, &worktop, api)?;
+
+        Self::process_instructions(instructions, &mut processor, &mut worktop, api)?;
+
+        let yielded_value = processor
+            .yielded_value
+            .take()
+            .ok_or(TransactionProcessorError::MissingYieldToParent)?;
+
+        worktop.drop(api)?;
+
+        Ok(yielded_value)
+    }
+
+    fn create_worktop<Y, L: Default>(api: &mut Y) -> Result<Worktop, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
         let worktop_node_id = api.kernel_allocate_node_id(EntityType::InternalGenericComponent)?;
         api.kernel_create_node(
             worktop_node_id,
@@ -127,19 +223,38 @@ impl TransactionProcessorBlueprint {
         )?;
         api.kernel_pin_node(worktop_node_id)?;
 
-        let mut worktop = Worktop(Own(worktop_node_id));
-        let instructions = manifest_decode::<Vec<InstructionV1>>(&manifest_encoded_instructions)
-            .map_err(|e| {
-                // This error should never occur if being called from root since this is constructed
-                // by the transaction executor. This error is more to protect against application
-                // space calling this function if/when possible
-                RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
-            })?;
-        let mut processor = TransactionProcessor::new(blobs, global_address_reservations);
+        Ok(Worktop(Own(worktop_node_id)))
+    }
+
+    fn decode_instructions(
+        manifest_encoded_instructions: &[u8],
+    ) -> Result<Vec<InstructionV1>, RuntimeError> {
+        manifest_decode::<Vec<InstructionV1>>(manifest_encoded_instructions).map_err(|e| {
+            // This error should never occur if being called from root since this is constructed
+            // by the transaction executor. This error is more to protect against application
+            // space calling this function if/when possible
+            RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
+        })
+    }
+
+    fn process_instructions<Y, L: Default>(
+        instructions: Vec<InstructionV1>,
+        processor: &mut TransactionProcessor,
+        worktop: &mut Worktop,
+        api: &mut Y,
+    ) -> Result<Vec<InstructionOutput>, RuntimeError>
+    where
+        Y: KernelNodeApi + KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
         let mut outputs = Vec::new();
         for (index, inst) in instructions.into_iter().enumerate() {
             api.update_instruction_index(index)?;
 
+            // A child intent ends with its yield to its parent
+            if processor.yielded_value.is_some() {
+                return Err(TransactionProcessorError::InstructionAfterYieldToParent.into());
+            }
+
             let result = match inst {
                 InstructionV1::TakeAllFromWorktop { resource_address } => {
                     let bucket = worktop.take_all(resource_address, api)?;
@@ -269,7 +384,7 @@ impl TransactionProcessorBlueprint {
                     let rtn = bucket.burn(api)?;
 
                     let result = IndexedScryptoValue::from_typed(&rtn);
-                    processor.handle_call_return_data(&result, &worktop, api)?;
+                    processor.handle_call_return_data(&result, worktop, api)?;
                     InstructionOutput::CallReturn(result.into())
                 }
                 InstructionV1::CloneProof { proof_id } => {
@@ -290,7 +405,14 @@ impl TransactionProcessorBlueprint {
                     args,
                 } => {
                     let package_address = processor.resolve_package_address(package_address)?;
-                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                    // Child intents can only be run with YIELD_TO_CHILD
+                    if is_transaction_processor_run_child(
+                        &BlueprintId::new(&package_address, &blueprint_name),
+                        &function_name,
+                    ) {
+                        return Err(TransactionProcessorError::RunChildNotPermitted.into());
+                    }
+                    handle_invocation(api, processor, worktop, args, |api, args| {
                         api.call_function(
                             package_address,
                             &blueprint_name,
@@ -306,7 +428,7 @@ impl TransactionProcessorBlueprint {
                     args,
                 } => {
                     let address = processor.resolve_global_address(address)?;
-                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                    handle_invocation(api, processor, worktop, args, |api, args| {
                         api.call_method(
                             address.as_node_id(),
                             &method_name,
@@ -321,7 +443,7 @@ impl TransactionProcessorBlueprint {
                     args,
                 } => {
                     let address = processor.resolve_global_address(address)?;
-                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                    handle_invocation(api, processor, worktop, args, |api, args| {
                         api.call_module_method(
                             address.as_node_id(),
                             AttachedModuleId::Royalty,
@@ -337,7 +459,7 @@ impl TransactionProcessorBlueprint {
                     args,
                 } => {
                     let address = processor.resolve_global_address(address)?;
-                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                    handle_invocation(api, processor, worktop, args, |api, args| {
                         api.call_module_method(
                             address.as_node_id(),
                             AttachedModuleId::Metadata,
@@ -353,7 +475,7 @@ impl TransactionProcessorBlueprint {
                     args,
                 } => {
                     let address = processor.resolve_global_address(address)?;
-                    handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                    handle_invocation(api, processor, worktop, args, |api, args| {
                         api.call_module_method(
                             address.as_node_id(),
                             AttachedModuleId::RoleAssignment,
@@ -367,7 +489,7 @@ impl TransactionProcessorBlueprint {
                     address,
                     method_name,
                     args,
-                } => handle_invocation(api, &mut processor, &mut worktop, args, |api, args| {
+                } => handle_invocation(api, processor, worktop, args, |api, args| {
                     api.call_direct_access_method(
                         address.as_node_id(),
                         &method_name,
@@ -402,12 +524,56 @@ impl TransactionProcessorBlueprint {
 
                     InstructionOutput::None
                 }
+                InstructionV1::YieldToChild { child_index, args } => {
+                    if processor.is_child_intent {
+                        return Err(TransactionProcessorError::YieldToChildNotPermitted.into());
+                    }
+                    handle_invocation(api, processor, worktop, args, |api, args| {
+                        TransactionProcessor::ensure_only_buckets(
+                            &IndexedScryptoValue::from_typed(&args),
+                            api,
+                        )?;
+                        let rtn = api.call_function(
+                            TRANSACTION_PROCESSOR_PACKAGE,
+                            TRANSACTION_PROCESSOR_BLUEPRINT,
+                            TRANSACTION_PROCESSOR_RUN_CHILD_IDENT,
+                            scrypto_encode(&TransactionProcessorYieldToChildInput {
+                                child_index,
+                                args,
+                            })
+                            .map_err(TransactionProcessorError::ArgsEncodeError)?,
+                        )?;
+                        TransactionProcessor::ensure_only_buckets(
+                            &IndexedScryptoValue::from_slice(&rtn)
+                                .map_err(TransactionProcessorError::InvocationOutputDecodeError)?,
+                            api,
+                        )?;
+                        Ok(rtn)
+                    })?
+                }
+                InstructionV1::YieldToParent { args } => {
+                    if !processor.is_child_intent {
+                        return Err(TransactionProcessorError::YieldToParentNotPermitted.into());
+                    }
+                    let value = {
+                        let mut processor_with_api = TransactionProcessorWithApi {
+                            worktop,
+                            processor,
+                            api,
+                        };
+                        transform(args, &mut processor_with_api)?
+                    };
+                    TransactionProcessor::ensure_only_buckets(
+                        &IndexedScryptoValue::from_typed(&value),
+                        api,
+                    )?;
+                    processor.yielded_value = Some(value);
+                    InstructionOutput::None
+                }
             };
             outputs.push(result);
         }
 
-        worktop.drop(api)?;
-
         Ok(outputs)
     }
 }
@@ -419,6 +585,8 @@ struct TransactionProcessor {
     address_mapping: NonIterMap<u32, NodeId>,
     id_allocator: ManifestIdAllocator,
     blobs_by_hash: IndexMap<Hash, Vec<u8>>,
+    is_child_intent: bool,
+    yielded_value: Option<ScryptoValue>,
 }
 
 impl TransactionProcessor {
@@ -433,6 +601,8 @@ impl TransactionProcessor {
             address_reservation_mapping: NonIterMap::new(),
             address_mapping: NonIterMap::new(),
             id_allocator: ManifestIdAllocator::new(),
+            is_child_intent: false,
+            yielded_value: None,
         };
 
         for address_reservation in global_address_reservations {
@@ -595,6 +765,37 @@ impl TransactionProcessor {
         }
     }
 
+    /// Values passed between intents may only own buckets.
+    fn ensure_only_buckets<Y, L: Default>(
+        value: &IndexedScryptoValue,
+        api: &mut Y,
+    ) -> Result<(), RuntimeError>
+    where
+        Y: KernelSubstateApi<L> + ClientApi<RuntimeError>,
+    {
+        for node_id in value.owned_nodes() {
+            let info = TypeInfoBlueprint::get_type(node_id, api)?;
+            let is_bucket = match info {
+                TypeInfoSubstate::Object(info) => match (
+                    info.blueprint_info.blueprint_id.package_address,
+                    info.blueprint_info.blueprint_id.blueprint_name.as_str(),
+                ) {
+                    (RESOURCE_PACKAGE, FUNGIBLE_BUCKET_BLUEPRINT)
+                    | (RESOURCE_PACKAGE, NON_FUNGIBLE_BUCKET_BLUEPRINT) => true,
+                    _ => false,
+                },
+                TypeInfoSubstate::KeyValueStore(_)
+                | TypeInfoSubstate::GlobalAddressReservation(_)
+                | TypeInfoSubstate::GlobalAddressPhantom(_) => false,
+            };
+            if !is_bucket {
+                return Err(TransactionProcessorError::YieldedNonBucket(node_id.clone()).into());
+            }
+        }
+
+        Ok(())
+    }
+
     fn handle_call_return_data<Y, L: Default>(
         &mut self,
         value: &IndexedScryptoValue,
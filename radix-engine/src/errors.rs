@@ -54,6 +54,15 @@ pub enum RejectionReason {
         valid_until: Option<Epoch>,
         current_epoch: Epoch,
     },
+    TransactionProposerTimestampNotYetValid {
+        valid_from: Instant,
+        current_time: Instant,
+    },
+    TransactionProposerTimestampNoLongerValid {
+        valid_until: Instant,
+        current_time: Instant,
+    },
+    TransactionProposerTimestampUnavailable,
     IntentHashPreviouslyCommitted,
     IntentHashPreviouslyCancelled,
 }
@@ -28,6 +28,10 @@ impl Heap {
         self.nodes.is_empty()
     }
 
+    pub fn contains_node(&self, node_id: &NodeId) -> bool {
+        self.nodes.contains_key(node_id)
+    }
+
     pub fn remove_partition<E, F: FnMut(&Heap, IOAccess) -> Result<(), E>>(
         &mut self,
         node_id: &NodeId,
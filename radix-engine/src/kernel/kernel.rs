@@ -458,6 +458,23 @@ where
         let mut read_only = as_read_only!(self);
         read_only.kernel_read_proof(proof_id)
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&M::CallFrameData> {
+        self.prev_frame_stack
+            .iter()
+            .chain(core::iter::once(&self.current_frame))
+            .map(|call_frame| call_frame.data())
+            .collect()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.prev_frame_stack
+            .iter()
+            .chain(core::iter::once(&self.current_frame))
+            .find(|call_frame| call_frame.depth() == depth)
+            .map(|call_frame| call_frame.owned_nodes())
+            .unwrap_or_default()
+    }
 }
 
 struct KernelReadOnly<'g, M>
@@ -644,6 +661,24 @@ where
             })
         }
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&M::CallFrameData> {
+        // Only the current and the caller frames are visible here
+        self.prev_frame
+            .into_iter()
+            .chain(core::iter::once(self.current_frame))
+            .map(|call_frame| call_frame.data())
+            .collect()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.prev_frame
+            .into_iter()
+            .chain(core::iter::once(self.current_frame))
+            .find(|call_frame| call_frame.depth() == depth)
+            .map(|call_frame| call_frame.owned_nodes())
+            .unwrap_or_default()
+    }
 }
 
 impl<'g, M, S> KernelSubstateApi<M::LockData> for Kernel<'g, M, S>
@@ -1068,6 +1103,24 @@ where
 
         Ok(substates)
     }
+
+    fn kernel_peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        if self.substate_io.heap.contains_node(node_id) {
+            self.substate_io
+                .heap
+                .get_substate(node_id, partition_num, substate_key)
+                .cloned()
+        } else {
+            self.substate_io
+                .store
+                .peek_substate(node_id, partition_num, substate_key)
+        }
+    }
 }
 
 impl<'g, M, S> KernelInvokeApi<M::CallFrameData> for Kernel<'g, M, S>
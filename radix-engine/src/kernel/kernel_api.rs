@@ -149,6 +149,15 @@ pub trait KernelSubstateApi<L> {
         partition_num: PartitionNumber,
         count: u32,
     ) -> Result<Vec<(SubstateKey, IndexedScryptoValue)>, RuntimeError>;
+
+    /// Reads a substate, be it on heap or in store, without locking it nor reporting any IO
+    /// access. Meant for debugging only.
+    fn kernel_peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue>;
 }
 
 #[derive(Debug)]
@@ -197,6 +206,12 @@ pub trait KernelInternalApi<M: KernelCallbackObject> {
     /* Super unstable interface, specifically for `ExecutionTrace` kernel module */
     fn kernel_read_bucket(&mut self, bucket_id: &NodeId) -> Option<BucketSnapshot>;
     fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot>;
+
+    /* Read-only inspection of the kernel state, specifically for debugging */
+    /// Returns the data of all call frames, from the root frame up to the current one.
+    fn kernel_get_call_frame_stack(&self) -> Vec<&M::CallFrameData>;
+    /// Returns the nodes owned by the call frame at the given depth, if it is visible.
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId>;
 }
 
 pub trait KernelApi<M: KernelCallbackObject>:
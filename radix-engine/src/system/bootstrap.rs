@@ -577,6 +577,7 @@ pub fn create_system_bootstrap_flash(
                 VmType::Native,
                 native_code_id.to_be_bytes().to_vec(),
                 system_instructions,
+                None,
             )
             .unwrap_or_else(|err| {
                 panic!(
@@ -687,10 +688,12 @@ impl SubstateDatabase for FlashedSubstateDatabase {
             })
     }
 
-    fn list_entries(
+    fn list_entries_from(
         &self,
         partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
     ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        let from_sort_key = from_sort_key.cloned();
         let DbPartitionKey {
             node_key,
             partition_num,
@@ -716,6 +719,12 @@ impl SubstateDatabase for FlashedSubstateDatabase {
                         } => Box::new(new_substate_values.iter()),
                     };
                     effective_entries.map(|(sort_key, value)| (sort_key.clone(), value.clone()))
+                })
+                .filter(move |(sort_key, _)| {
+                    from_sort_key
+                        .as_ref()
+                        .map(|from_sort_key| sort_key >= from_sort_key)
+                        .unwrap_or(true)
                 }),
         )
     }
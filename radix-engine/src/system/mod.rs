@@ -11,6 +11,7 @@ pub mod system;
 pub mod system_callback;
 pub mod system_callback_api;
 pub mod system_db_reader;
+pub mod system_debugger;
 pub mod system_modules;
 pub mod system_substates;
 pub mod system_type_checker;
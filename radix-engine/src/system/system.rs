@@ -2,6 +2,10 @@ use super::id_allocation::IDAllocation;
 use super::system_modules::costing::ExecutionCostingEntry;
 use crate::blueprints::package::PackageBlueprintVersionDefinitionEntrySubstate;
 use crate::blueprints::resource::fungible_vault::LockFeeEvent;
+use crate::blueprints::transaction_processor::{
+    is_transaction_processor_run_child, TransactionProcessorError,
+    TransactionProcessorRunChildInput, TransactionProcessorYieldToChildInput,
+};
 use crate::errors::{
     ApplicationError, CannotGlobalizeError, CreateObjectError, InvalidDropAccess,
     InvalidGlobalizeAccess, InvalidModuleType, RuntimeError, SystemError, SystemModuleError,
@@ -27,6 +31,7 @@ use crate::system::system_type_checker::{
 use crate::system::type_info::{TypeInfoBlueprint, TypeInfoSubstate};
 use crate::track::interface::NodeSubstates;
 use crate::types::*;
+use crate::utils::{keccak256_hash, sha256_hash, verify_bls12381_v1_aggregate};
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::actor_index_api::ClientActorIndexApi;
 use radix_engine_interface::api::field_api::{FieldHandle, LockFlags};
@@ -40,6 +45,9 @@ use radix_engine_interface::api::object_api::ModuleId;
 use radix_engine_interface::api::*;
 use radix_engine_interface::blueprints::package::*;
 use radix_engine_interface::blueprints::resource::*;
+use radix_engine_interface::blueprints::transaction_processor::{
+    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_IDENT,
+};
 use radix_engine_interface::schema::{Condition, KeyValueStoreGenericSubstitutions};
 #[cfg(not(feature = "alloc"))]
 use radix_engine_macros::*;
@@ -47,6 +55,7 @@ use radix_engine_store_interface::db_key_mapper::SubstateKeyContent;
 use resources_tracker_macro::trace_resources;
 use sbor::rust::string::ToString;
 use sbor::rust::vec::Vec;
+use transaction::validation::{recover_secp256k1, verify_ed25519, verify_secp256k1};
 
 /// Provided to upper layer for invoking lower layer service
 pub struct SystemService<'a, Y: KernelApi<SystemConfig<V>>, V: SystemCallbackObject> {
@@ -1081,6 +1090,54 @@ where
             .clone()
     }
 
+    /// Replaces the input with which the root transaction processor yields to a child intent by
+    /// the input to run the child intent, which is taken (so is run at most once) from the system.
+    fn prepare_run_child_input(
+        &mut self,
+        args: &IndexedScryptoValue,
+    ) -> Result<IndexedScryptoValue, RuntimeError> {
+        let is_root_transaction_processor = match self.current_actor() {
+            Actor::Function(FunctionActor {
+                blueprint_id,
+                ident,
+                ..
+            }) => {
+                blueprint_id
+                    .package_address
+                    .eq(&TRANSACTION_PROCESSOR_PACKAGE)
+                    && blueprint_id
+                        .blueprint_name
+                        .eq(TRANSACTION_PROCESSOR_BLUEPRINT)
+                    && ident.eq(TRANSACTION_PROCESSOR_RUN_IDENT)
+            }
+            _ => false,
+        };
+        if !is_root_transaction_processor || self.api.kernel_get_current_depth() != 1 {
+            return Err(TransactionProcessorError::RunChildNotPermitted.into());
+        }
+
+        let TransactionProcessorYieldToChildInput { child_index, args } = args
+            .as_typed()
+            .map_err(|e| RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e)))?;
+        let child_intent = self
+            .api
+            .kernel_get_system()
+            .modules
+            .transaction_runtime
+            .take_child_intent(child_index)
+            .ok_or(TransactionProcessorError::ChildIntentNotFound(child_index))?;
+
+        Ok(IndexedScryptoValue::from_typed(
+            &TransactionProcessorRunChildInput {
+                child_index,
+                manifest_encoded_instructions: child_intent.manifest_encoded_instructions,
+                references: child_intent.references,
+                blobs: child_intent.blobs,
+                args,
+            },
+        ))
+    }
+
     pub fn get_object_info(&mut self, node_id: &NodeId) -> Result<ObjectInfo, RuntimeError> {
         let type_info = TypeInfoBlueprint::get_type(&node_id, self.api)?;
         let object_info = match type_info {
@@ -2158,7 +2215,13 @@ where
             RuntimeError::SystemUpstreamError(SystemUpstreamError::InputDecodeError(e))
         })?;
         let blueprint_id = BlueprintId::new(&package_address, blueprint_name);
-        let auth_zone = SystemModuleMixer::on_call_function(self, &blueprint_id, function_name)?;
+        let args = if is_transaction_processor_run_child(&blueprint_id, function_name) {
+            self.prepare_run_child_input(&args)?
+        } else {
+            args
+        };
+        let auth_zone =
+            SystemModuleMixer::on_call_function(self, &blueprint_id, function_name, &args)?;
 
         let rtn = self
             .api
@@ -2752,10 +2815,11 @@ where
     // No costing should be applied
     #[trace_resources]
     fn update_instruction_index(&mut self, new_index: usize) -> Result<(), RuntimeError> {
+        let depth = self.api.kernel_get_current_depth();
         self.api
             .kernel_get_system()
             .modules
-            .update_instruction_index(new_index);
+            .update_instruction_index(depth, new_index);
         Ok(())
     }
 }
@@ -2849,6 +2913,106 @@ where
     }
 }
 
+#[cfg_attr(
+    feature = "std",
+    catch_unwind(crate::utils::catch_unwind_system_panic_transformer)
+)]
+impl<'a, Y, V> ClientCryptoUtilsApi<RuntimeError> for SystemService<'a, Y, V>
+where
+    Y: KernelApi<SystemConfig<V>>,
+    V: SystemCallbackObject,
+{
+    #[trace_resources]
+    fn blake2b_256_hash(&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Blake2b256Hash { size: data.len() })?;
+
+        Ok(blake2b_256_hash(data))
+    }
+
+    #[trace_resources]
+    fn keccak256_hash(&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Keccak256Hash { size: data.len() })?;
+
+        Ok(keccak256_hash(data))
+    }
+
+    #[trace_resources]
+    fn sha256_hash(&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Sha256Hash { size: data.len() })?;
+
+        Ok(sha256_hash(data))
+    }
+
+    #[trace_resources]
+    fn ed25519_verify(
+        &mut self,
+        message_hash: &Hash,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> Result<bool, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Ed25519Verify)?;
+
+        Ok(verify_ed25519(message_hash, public_key, signature))
+    }
+
+    #[trace_resources]
+    fn secp256k1_verify(
+        &mut self,
+        message_hash: &Hash,
+        public_key: &Secp256k1PublicKey,
+        signature: &Secp256k1Signature,
+    ) -> Result<bool, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Secp256k1Verify)?;
+
+        Ok(verify_secp256k1(message_hash, public_key, signature))
+    }
+
+    #[trace_resources]
+    fn secp256k1_recover(
+        &mut self,
+        message_hash: &Hash,
+        signature: &Secp256k1Signature,
+    ) -> Result<Option<Secp256k1PublicKey>, RuntimeError> {
+        self.api
+            .kernel_get_system()
+            .modules
+            .apply_execution_cost(ExecutionCostingEntry::Secp256k1Recover)?;
+
+        Ok(recover_secp256k1(message_hash, signature))
+    }
+
+    #[trace_resources]
+    fn bls12381_v1_aggregate_verify(
+        &mut self,
+        pub_keys_and_msgs: Vec<(Bls12381G1PublicKey, Vec<u8>)>,
+        signature: &Bls12381G2Signature,
+    ) -> Result<bool, RuntimeError> {
+        self.api.kernel_get_system().modules.apply_execution_cost(
+            ExecutionCostingEntry::Bls12381V1AggregateVerify {
+                num_of_pairs: pub_keys_and_msgs.len(),
+                size: pub_keys_and_msgs.iter().map(|(_, msg)| msg.len()).sum(),
+            },
+        )?;
+
+        Ok(verify_bls12381_v1_aggregate(&pub_keys_and_msgs, signature))
+    }
+}
+
 #[cfg_attr(
     feature = "std",
     catch_unwind(crate::utils::catch_unwind_system_panic_transformer)
@@ -3012,6 +3176,17 @@ where
         self.api
             .kernel_drain_substates::<K>(node_id, partition_num, limit)
     }
+
+    #[cfg_attr(feature = "std", catch_unwind_ignore)]
+    fn kernel_peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        self.api
+            .kernel_peek_substate(node_id, partition_num, substate_key)
+    }
 }
 
 impl<'a, Y, V> KernelInternalApi<SystemConfig<V>> for SystemService<'a, Y, V>
@@ -3038,4 +3213,12 @@ where
     fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
         self.api.kernel_read_proof(proof_id)
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&Actor> {
+        self.api.kernel_get_call_frame_stack()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
 }
@@ -3,7 +3,9 @@ use crate::blueprints::account::ACCOUNT_CREATE_VIRTUAL_ED25519_ID;
 use crate::blueprints::account::ACCOUNT_CREATE_VIRTUAL_SECP256K1_ID;
 use crate::blueprints::identity::IDENTITY_CREATE_VIRTUAL_ED25519_ID;
 use crate::blueprints::identity::IDENTITY_CREATE_VIRTUAL_SECP256K1_ID;
-use crate::blueprints::transaction_processor::TransactionProcessorRunInputEfficientEncodable;
+use crate::blueprints::transaction_processor::{
+    TransactionProcessorError, TransactionProcessorRunInputEfficientEncodable,
+};
 use crate::errors::*;
 use crate::kernel::call_frame::CallFrameMessage;
 use crate::kernel::kernel_api::{KernelApi, KernelInvocation};
@@ -147,6 +149,16 @@ impl<C: SystemCallbackObject> KernelCallbackObject for SystemConfig<C> {
             .unwrap(),
         )?;
 
+        // Every child intent must have been yielded to by the transaction
+        if let Some(child_index) = system
+            .kernel_get_system()
+            .modules
+            .transaction_runtime
+            .first_child_intent_not_run()
+        {
+            return Err(TransactionProcessorError::ChildIntentNotRun(child_index).into());
+        }
+
         Ok(rtn)
     }
 
@@ -0,0 +1,871 @@
+use crate::blueprints::resource::WorktopSubstate;
+use crate::errors::RuntimeError;
+use crate::kernel::call_frame::{CallFrameMessage, NodeVisibility};
+use crate::kernel::kernel_api::{
+    DroppedNode, KernelApi, KernelInternalApi, KernelInvocation, KernelInvokeApi, KernelNodeApi,
+    KernelSubstateApi, SystemState,
+};
+use crate::kernel::kernel_callback_api::{
+    CloseSubstateEvent, CreateNodeEvent, DrainSubstatesEvent, DropNodeEvent, KernelCallbackObject,
+    MoveModuleEvent, OpenSubstateEvent, ReadSubstateEvent, RemoveSubstateEvent, ScanKeysEvent,
+    ScanSortedSubstatesEvent, SetSubstateEvent, WriteSubstateEvent,
+};
+use crate::system::actor::{Actor, FunctionActor, MethodActor};
+use crate::system::system_callback::{SystemConfig, SystemLockData};
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::system::system_modules::costing::SystemLoanFeeReserve;
+use crate::system::system_modules::execution_trace::{BucketSnapshot, ProofSnapshot};
+use crate::system::system_modules::transaction_runtime::Event;
+use crate::system::system_substates::FieldSubstate;
+use crate::system::type_info::TypeInfoSubstate;
+use crate::track::interface::NodeSubstates;
+use crate::transaction::WrappedSystem;
+use crate::types::*;
+use radix_engine_interface::blueprints::resource::WORKTOP_BLUEPRINT;
+use radix_engine_interface::blueprints::transaction_processor::{
+    TRANSACTION_PROCESSOR_BLUEPRINT, TRANSACTION_PROCESSOR_RUN_IDENT,
+};
+use radix_engine_store_interface::db_key_mapper::SubstateKeyContent;
+use transaction::prelude::PreAllocatedAddress;
+
+/// A condition upon which the [`SystemDebugger`] pauses the execution of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before invoking the given function or method of a blueprint, or any of them if `ident`
+    /// is `None`.
+    Invocation {
+        blueprint_id: BlueprintId,
+        ident: Option<String>,
+    },
+    /// Before the first invocation made while processing the manifest instruction at the
+    /// given index.
+    Instruction(usize),
+    /// At the first invocation boundary after an event with the given name is emitted.
+    Event(String),
+}
+
+impl Breakpoint {
+    fn matches_invocation(&self, actor: &Actor) -> bool {
+        match self {
+            Breakpoint::Invocation {
+                blueprint_id,
+                ident,
+            } => {
+                let actor_ident = match actor {
+                    Actor::Method(MethodActor { ident, .. })
+                    | Actor::Function(FunctionActor { ident, .. }) => ident,
+                    Actor::BlueprintHook(..) | Actor::Root => return false,
+                };
+                actor.blueprint_id().as_ref() == Some(blueprint_id)
+                    && ident.as_ref().map_or(true, |ident| ident.eq(actor_ident))
+            }
+            Breakpoint::Instruction(..) | Breakpoint::Event(..) => false,
+        }
+    }
+}
+
+/// Why the execution has been paused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakReason {
+    /// The given actor is about to be invoked
+    Invocation(Actor),
+    /// The transaction processor is processing the manifest instruction at the given index
+    Instruction(usize),
+    /// An event has been emitted
+    Event(EventTypeIdentifier),
+}
+
+/// How to resume the execution after a break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebuggerAction {
+    /// Runs until the next breakpoint
+    Continue,
+    /// Runs until the next invocation
+    Step,
+}
+
+/// Read-only view of the state of a paused transaction.
+pub trait DebuggerInspector {
+    /// The actors of all call frames, from the root frame up to the current one.
+    fn actors(&self) -> Vec<Actor>;
+
+    /// The nodes owned by the call frame at the given depth.
+    fn owned_nodes(&self, depth: usize) -> Vec<NodeId>;
+
+    /// The value of a substate, be it on heap or in track.
+    fn peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue>;
+
+    fn read_bucket(&mut self, bucket_id: &NodeId) -> Option<BucketSnapshot>;
+
+    fn read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot>;
+
+    /// The fee reserve, if costing is enabled.
+    fn fee_reserve(&mut self) -> Option<&SystemLoanFeeReserve>;
+
+    /// The index of the root manifest instruction being processed, if processing has started.
+    fn instruction_index(&mut self) -> Option<usize>;
+
+    fn events(&mut self) -> &Vec<Event>;
+
+    fn logs(&mut self) -> &Vec<(Level, String)>;
+
+    /// The buckets held by the transaction worktop, if any call frame owns one.
+    fn worktop(&mut self) -> Option<Vec<BucketSnapshot>> {
+        let worktop_blueprint_id = BlueprintId::new(&RESOURCE_PACKAGE, WORKTOP_BLUEPRINT);
+        for depth in 0..self.actors().len() {
+            for node_id in self.owned_nodes(depth) {
+                let is_worktop = self
+                    .peek_substate(
+                        &node_id,
+                        TYPE_INFO_FIELD_PARTITION,
+                        &TypeInfoField::TypeInfo.into(),
+                    )
+                    .and_then(|substate| substate.as_typed::<TypeInfoSubstate>().ok())
+                    .map_or(false, |type_info| match type_info {
+                        TypeInfoSubstate::Object(info) => {
+                            info.blueprint_info.blueprint_id.eq(&worktop_blueprint_id)
+                        }
+                        _ => false,
+                    });
+                if !is_worktop {
+                    continue;
+                }
+
+                let worktop = self
+                    .peek_substate(&node_id, MAIN_BASE_PARTITION, &WorktopField::Worktop.into())?
+                    .as_typed::<FieldSubstate<WorktopSubstate>>()
+                    .ok()?
+                    .into_payload();
+                return Some(
+                    worktop
+                        .resources
+                        .values()
+                        .filter_map(|bucket| self.read_bucket(bucket.as_node_id()))
+                        .collect(),
+                );
+            }
+        }
+        None
+    }
+}
+
+/// Called by the [`SystemDebugger`] whenever the execution is paused.
+pub trait DebuggerHandler {
+    fn on_break(
+        &mut self,
+        reason: &BreakReason,
+        inspector: &mut dyn DebuggerInspector,
+    ) -> DebuggerAction;
+}
+
+pub struct SystemDebuggerInit<'h> {
+    pub breakpoints: Vec<Breakpoint>,
+    pub handler: Box<dyn DebuggerHandler + 'h>,
+}
+
+/// Wraps the system to pause the execution of a transaction on [`Breakpoint`]s, handing a
+/// [`DebuggerInspector`] over to a [`DebuggerHandler`].
+///
+/// Breaks only happen at invocation boundaries; the handler can't alter the execution.
+pub struct SystemDebugger<'h, C: SystemCallbackObject> {
+    callback_object: SystemConfig<C>,
+    breakpoints: Vec<Breakpoint>,
+    handler: Option<Box<dyn DebuggerHandler + 'h>>,
+    is_stepping: bool,
+    last_instruction_index: Option<usize>,
+    seen_events: usize,
+}
+
+impl<'h, C: SystemCallbackObject> WrappedSystem<C> for SystemDebugger<'h, C> {
+    type Init = SystemDebuggerInit<'h>;
+
+    fn create(config: SystemConfig<C>, init: SystemDebuggerInit<'h>) -> Self {
+        Self {
+            callback_object: config,
+            breakpoints: init.breakpoints,
+            handler: Some(init.handler),
+            is_stepping: false,
+            last_instruction_index: None,
+            seen_events: 0,
+        }
+    }
+
+    fn system_mut(&mut self) -> &mut SystemConfig<C> {
+        &mut self.callback_object
+    }
+
+    fn to_system(self) -> SystemConfig<C> {
+        self.callback_object
+    }
+}
+
+impl<'h, C: SystemCallbackObject> SystemDebugger<'h, C> {
+    /// Checks the breakpoints, given the actor about to be invoked if any, and hands the
+    /// control over to the handler if one is hit.
+    fn check_breakpoints<Y>(invoked_actor: Option<&Actor>, api: &mut Y)
+    where
+        Y: KernelApi<Self>,
+    {
+        // Only the root manifest counts, not the ones of child intents
+        let is_root_transaction_processor = api.kernel_get_current_depth() == 1;
+        let is_processing_instruction = match api.kernel_get_system_state().current_call_frame {
+            Actor::Function(FunctionActor {
+                blueprint_id,
+                ident,
+                ..
+            }) => {
+                is_root_transaction_processor
+                    && blueprint_id.package_address == TRANSACTION_PROCESSOR_PACKAGE
+                    && blueprint_id.blueprint_name == TRANSACTION_PROCESSOR_BLUEPRINT
+                    && ident == TRANSACTION_PROCESSOR_RUN_IDENT
+            }
+            _ => false,
+        };
+
+        let debugger = api.kernel_get_system_state().system;
+        let mut reasons = Vec::new();
+
+        let events = debugger.callback_object.modules.events();
+        for event in events.iter().skip(debugger.seen_events) {
+            let EventTypeIdentifier(_, event_name) = &event.type_identifier;
+            if debugger
+                .breakpoints
+                .iter()
+                .any(|b| matches!(b, Breakpoint::Event(name) if name.eq(event_name)))
+            {
+                reasons.push(BreakReason::Event(event.type_identifier.clone()));
+            }
+        }
+        debugger.seen_events = events.len();
+
+        if let Some(actor) = invoked_actor {
+            if is_processing_instruction {
+                let instruction_index = debugger.callback_object.modules.root_instruction_index();
+                if instruction_index != debugger.last_instruction_index {
+                    debugger.last_instruction_index = instruction_index;
+                    if let Some(index) = instruction_index {
+                        if debugger
+                            .breakpoints
+                            .contains(&Breakpoint::Instruction(index))
+                        {
+                            reasons.push(BreakReason::Instruction(index));
+                        }
+                    }
+                }
+            }
+
+            if debugger.is_stepping
+                || debugger
+                    .breakpoints
+                    .iter()
+                    .any(|b| b.matches_invocation(actor))
+            {
+                reasons.push(BreakReason::Invocation(actor.clone()));
+            }
+        }
+
+        if reasons.is_empty() {
+            return;
+        }
+
+        let mut handler = match debugger.handler.take() {
+            Some(handler) => handler,
+            None => return,
+        };
+        let mut action = DebuggerAction::Continue;
+        for reason in &reasons {
+            let mut inspector = SystemDebuggerInspector {
+                api: &mut *api,
+                phantom: PhantomData::default(),
+            };
+            action = handler.on_break(reason, &mut inspector);
+        }
+
+        let debugger = api.kernel_get_system_state().system;
+        debugger.is_stepping = action == DebuggerAction::Step;
+        debugger.handler = Some(handler);
+    }
+}
+
+struct SystemDebuggerInspector<
+    'a,
+    'h,
+    C: SystemCallbackObject + 'a,
+    Y: KernelApi<SystemDebugger<'h, C>>,
+> {
+    api: &'a mut Y,
+    phantom: PhantomData<(&'h (), C)>,
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>> DebuggerInspector
+    for SystemDebuggerInspector<'a, 'h, C, Y>
+{
+    fn actors(&self) -> Vec<Actor> {
+        self.api
+            .kernel_get_call_frame_stack()
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
+
+    fn peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        self.api
+            .kernel_peek_substate(node_id, partition_num, substate_key)
+    }
+
+    fn read_bucket(&mut self, bucket_id: &NodeId) -> Option<BucketSnapshot> {
+        self.api.kernel_read_bucket(bucket_id)
+    }
+
+    fn read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
+        self.api.kernel_read_proof(proof_id)
+    }
+
+    fn fee_reserve(&mut self) -> Option<&SystemLoanFeeReserve> {
+        let debugger = self.api.kernel_get_system_state().system;
+        debugger.callback_object.modules.fee_reserve()
+    }
+
+    fn instruction_index(&mut self) -> Option<usize> {
+        let debugger = self.api.kernel_get_system_state().system;
+        debugger.callback_object.modules.root_instruction_index()
+    }
+
+    fn events(&mut self) -> &Vec<Event> {
+        let debugger = self.api.kernel_get_system_state().system;
+        debugger.callback_object.modules.events()
+    }
+
+    fn logs(&mut self) -> &Vec<(Level, String)> {
+        let debugger = self.api.kernel_get_system_state().system;
+        debugger.callback_object.modules.logs()
+    }
+}
+
+macro_rules! wrapped_api {
+    ($api:ident) => {
+        WrappedKernelApi {
+            api: $api,
+            phantom: PhantomData::default(),
+        }
+    };
+}
+
+macro_rules! wrapped_internal_api {
+    ($api:ident) => {
+        WrappedKernelInternalApi {
+            api: $api,
+            phantom: PhantomData::default(),
+        }
+    };
+}
+
+impl<'h, C: SystemCallbackObject> KernelCallbackObject for SystemDebugger<'h, C> {
+    type LockData = SystemLockData;
+    type CallFrameData = Actor;
+
+    fn on_init<Y>(api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_init(&mut api)
+    }
+
+    fn start<Y>(
+        api: &mut Y,
+        manifest_encoded_instructions: &[u8],
+        pre_allocated_addresses: &Vec<PreAllocatedAddress>,
+        references: &IndexSet<Reference>,
+        blobs: &IndexMap<Hash, Vec<u8>>,
+    ) -> Result<Vec<u8>, RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::start(
+            &mut api,
+            manifest_encoded_instructions,
+            pre_allocated_addresses,
+            references,
+            blobs,
+        )
+    }
+
+    fn on_teardown<Y>(api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_teardown(&mut api)
+    }
+
+    fn on_pin_node(&mut self, node_id: &NodeId) -> Result<(), RuntimeError> {
+        self.callback_object.on_pin_node(node_id)
+    }
+
+    fn on_create_node<Y>(api: &mut Y, event: CreateNodeEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_create_node(&mut api, event)
+    }
+
+    fn on_drop_node<Y>(api: &mut Y, event: DropNodeEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_drop_node(&mut api, event)
+    }
+
+    fn on_move_module<Y>(api: &mut Y, event: MoveModuleEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_move_module(&mut api, event)
+    }
+
+    fn on_open_substate<Y>(api: &mut Y, event: OpenSubstateEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_open_substate(&mut api, event)
+    }
+
+    fn on_close_substate<Y>(api: &mut Y, event: CloseSubstateEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_close_substate(&mut api, event)
+    }
+
+    fn on_read_substate<Y>(api: &mut Y, event: ReadSubstateEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_read_substate(&mut api, event)
+    }
+
+    fn on_write_substate<Y>(api: &mut Y, event: WriteSubstateEvent) -> Result<(), RuntimeError>
+    where
+        Y: KernelInternalApi<Self>,
+    {
+        let mut api = wrapped_internal_api!(api);
+        SystemConfig::<C>::on_write_substate(&mut api, event)
+    }
+
+    fn on_set_substate(&mut self, event: SetSubstateEvent) -> Result<(), RuntimeError> {
+        self.callback_object.on_set_substate(event)
+    }
+
+    fn on_remove_substate(&mut self, event: RemoveSubstateEvent) -> Result<(), RuntimeError> {
+        self.callback_object.on_remove_substate(event)
+    }
+
+    fn on_scan_keys(&mut self, event: ScanKeysEvent) -> Result<(), RuntimeError> {
+        self.callback_object.on_scan_keys(event)
+    }
+
+    fn on_drain_substates(&mut self, event: DrainSubstatesEvent) -> Result<(), RuntimeError> {
+        self.callback_object.on_drain_substates(event)
+    }
+
+    fn on_scan_sorted_substates(
+        &mut self,
+        event: ScanSortedSubstatesEvent,
+    ) -> Result<(), RuntimeError> {
+        self.callback_object.on_scan_sorted_substates(event)
+    }
+
+    fn before_invoke<Y>(
+        invocation: &KernelInvocation<Self::CallFrameData>,
+        api: &mut Y,
+    ) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        Self::check_breakpoints(Some(&invocation.call_frame_data), api);
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::before_invoke(invocation, &mut api)
+    }
+
+    fn after_invoke<Y>(output: &IndexedScryptoValue, api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        Self::check_breakpoints(None, api);
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::after_invoke(output, &mut api)
+    }
+
+    fn on_execution_start<Y>(api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_execution_start(&mut api)
+    }
+
+    fn on_execution_finish<Y>(message: &CallFrameMessage, api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_execution_finish(message, &mut api)
+    }
+
+    fn on_allocate_node_id<Y>(entity_type: EntityType, api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_allocate_node_id(entity_type, &mut api)
+    }
+
+    fn invoke_upstream<Y>(
+        args: &IndexedScryptoValue,
+        api: &mut Y,
+    ) -> Result<IndexedScryptoValue, RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::invoke_upstream(args, &mut api)
+    }
+
+    fn auto_drop<Y>(nodes: Vec<NodeId>, api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::auto_drop(nodes, &mut api)
+    }
+
+    fn on_mark_substate_as_transient(
+        &mut self,
+        node_id: &NodeId,
+        partition_number: &PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Result<(), RuntimeError> {
+        self.callback_object
+            .on_mark_substate_as_transient(node_id, partition_number, substate_key)
+    }
+
+    fn on_substate_lock_fault<Y>(
+        node_id: NodeId,
+        partition_num: PartitionNumber,
+        offset: &SubstateKey,
+        api: &mut Y,
+    ) -> Result<bool, RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_substate_lock_fault(node_id, partition_num, offset, &mut api)
+    }
+
+    fn on_drop_node_mut<Y>(node_id: &NodeId, api: &mut Y) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_drop_node_mut(node_id, &mut api)
+    }
+
+    fn on_move_node<Y>(
+        node_id: &NodeId,
+        is_moving_down: bool,
+        is_to_barrier: bool,
+        destination_blueprint_id: Option<BlueprintId>,
+        api: &mut Y,
+    ) -> Result<(), RuntimeError>
+    where
+        Y: KernelApi<Self>,
+    {
+        let mut api = wrapped_api!(api);
+        SystemConfig::<C>::on_move_node(
+            node_id,
+            is_moving_down,
+            is_to_barrier,
+            destination_blueprint_id,
+            &mut api,
+        )
+    }
+}
+
+struct WrappedKernelApi<'a, 'h, C: SystemCallbackObject + 'a, Y: KernelApi<SystemDebugger<'h, C>>> {
+    api: &'a mut Y,
+    phantom: PhantomData<(&'h (), C)>,
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>> KernelNodeApi
+    for WrappedKernelApi<'a, 'h, C, Y>
+{
+    fn kernel_pin_node(&mut self, node_id: NodeId) -> Result<(), RuntimeError> {
+        self.api.kernel_pin_node(node_id)
+    }
+
+    fn kernel_allocate_node_id(&mut self, entity_type: EntityType) -> Result<NodeId, RuntimeError> {
+        self.api.kernel_allocate_node_id(entity_type)
+    }
+
+    fn kernel_create_node(
+        &mut self,
+        node_id: NodeId,
+        node_substates: NodeSubstates,
+    ) -> Result<(), RuntimeError> {
+        self.api.kernel_create_node(node_id, node_substates)
+    }
+
+    fn kernel_create_node_from(
+        &mut self,
+        node_id: NodeId,
+        partitions: BTreeMap<PartitionNumber, (NodeId, PartitionNumber)>,
+    ) -> Result<(), RuntimeError> {
+        self.api.kernel_create_node_from(node_id, partitions)
+    }
+
+    fn kernel_drop_node(&mut self, node_id: &NodeId) -> Result<DroppedNode, RuntimeError> {
+        self.api.kernel_drop_node(node_id)
+    }
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>>
+    KernelSubstateApi<SystemLockData> for WrappedKernelApi<'a, 'h, C, Y>
+{
+    fn kernel_mark_substate_as_transient(
+        &mut self,
+        node_id: NodeId,
+        partition_num: PartitionNumber,
+        key: SubstateKey,
+    ) -> Result<(), RuntimeError> {
+        self.api
+            .kernel_mark_substate_as_transient(node_id, partition_num, key)
+    }
+
+    fn kernel_open_substate_with_default<F: FnOnce() -> IndexedScryptoValue>(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+        flags: LockFlags,
+        default: Option<F>,
+        lock_data: SystemLockData,
+    ) -> Result<SubstateHandle, RuntimeError> {
+        self.api.kernel_open_substate_with_default(
+            node_id,
+            partition_num,
+            substate_key,
+            flags,
+            default,
+            lock_data,
+        )
+    }
+
+    fn kernel_get_lock_data(
+        &mut self,
+        lock_handle: SubstateHandle,
+    ) -> Result<SystemLockData, RuntimeError> {
+        self.api.kernel_get_lock_data(lock_handle)
+    }
+
+    fn kernel_close_substate(&mut self, lock_handle: SubstateHandle) -> Result<(), RuntimeError> {
+        self.api.kernel_close_substate(lock_handle)
+    }
+
+    fn kernel_read_substate(
+        &mut self,
+        lock_handle: SubstateHandle,
+    ) -> Result<&IndexedScryptoValue, RuntimeError> {
+        self.api.kernel_read_substate(lock_handle)
+    }
+
+    fn kernel_write_substate(
+        &mut self,
+        lock_handle: SubstateHandle,
+        value: IndexedScryptoValue,
+    ) -> Result<(), RuntimeError> {
+        self.api.kernel_write_substate(lock_handle, value)
+    }
+
+    fn kernel_set_substate(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: SubstateKey,
+        value: IndexedScryptoValue,
+    ) -> Result<(), RuntimeError> {
+        self.api
+            .kernel_set_substate(node_id, partition_num, substate_key, value)
+    }
+
+    fn kernel_remove_substate(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Result<Option<IndexedScryptoValue>, RuntimeError> {
+        self.api
+            .kernel_remove_substate(node_id, partition_num, substate_key)
+    }
+
+    fn kernel_scan_sorted_substates(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        count: u32,
+    ) -> Result<Vec<(SortedKey, IndexedScryptoValue)>, RuntimeError> {
+        self.api
+            .kernel_scan_sorted_substates(node_id, partition_num, count)
+    }
+
+    fn kernel_scan_keys<K: SubstateKeyContent + 'static>(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        count: u32,
+    ) -> Result<Vec<SubstateKey>, RuntimeError> {
+        self.api
+            .kernel_scan_keys::<K>(node_id, partition_num, count)
+    }
+
+    fn kernel_drain_substates<K: SubstateKeyContent + 'static>(
+        &mut self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        count: u32,
+    ) -> Result<Vec<(SubstateKey, IndexedScryptoValue)>, RuntimeError> {
+        self.api
+            .kernel_drain_substates::<K>(node_id, partition_num, count)
+    }
+
+    fn kernel_peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        self.api
+            .kernel_peek_substate(node_id, partition_num, substate_key)
+    }
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>> KernelInvokeApi<Actor>
+    for WrappedKernelApi<'a, 'h, C, Y>
+{
+    fn kernel_invoke(
+        &mut self,
+        invocation: Box<KernelInvocation<Actor>>,
+    ) -> Result<IndexedScryptoValue, RuntimeError> {
+        self.api.kernel_invoke(invocation)
+    }
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>>
+    KernelInternalApi<SystemConfig<C>> for WrappedKernelApi<'a, 'h, C, Y>
+{
+    fn kernel_get_system_state(&mut self) -> SystemState<'_, SystemConfig<C>> {
+        let state = self.api.kernel_get_system_state();
+        SystemState {
+            system: &mut state.system.callback_object,
+            caller_call_frame: state.caller_call_frame,
+            current_call_frame: state.current_call_frame,
+        }
+    }
+
+    fn kernel_get_current_depth(&self) -> usize {
+        self.api.kernel_get_current_depth()
+    }
+
+    fn kernel_get_node_visibility(&self, node_id: &NodeId) -> NodeVisibility {
+        self.api.kernel_get_node_visibility(node_id)
+    }
+
+    fn kernel_read_bucket(&mut self, bucket_id: &NodeId) -> Option<BucketSnapshot> {
+        self.api.kernel_read_bucket(bucket_id)
+    }
+
+    fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
+        self.api.kernel_read_proof(proof_id)
+    }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&Actor> {
+        self.api.kernel_get_call_frame_stack()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelApi<SystemDebugger<'h, C>>>
+    KernelApi<SystemConfig<C>> for WrappedKernelApi<'a, 'h, C, Y>
+{
+}
+
+struct WrappedKernelInternalApi<
+    'a,
+    'h,
+    C: SystemCallbackObject + 'a,
+    Y: KernelInternalApi<SystemDebugger<'h, C>>,
+> {
+    api: &'a mut Y,
+    phantom: PhantomData<(&'h (), C)>,
+}
+
+impl<'a, 'h, C: SystemCallbackObject, Y: KernelInternalApi<SystemDebugger<'h, C>>>
+    KernelInternalApi<SystemConfig<C>> for WrappedKernelInternalApi<'a, 'h, C, Y>
+{
+    fn kernel_get_system_state(&mut self) -> SystemState<'_, SystemConfig<C>> {
+        let state = self.api.kernel_get_system_state();
+        SystemState {
+            system: &mut state.system.callback_object,
+            caller_call_frame: state.caller_call_frame,
+            current_call_frame: state.current_call_frame,
+        }
+    }
+
+    fn kernel_get_current_depth(&self) -> usize {
+        self.api.kernel_get_current_depth()
+    }
+
+    fn kernel_get_node_visibility(&self, node_id: &NodeId) -> NodeVisibility {
+        self.api.kernel_get_node_visibility(node_id)
+    }
+
+    fn kernel_read_bucket(&mut self, bucket_id: &NodeId) -> Option<BucketSnapshot> {
+        self.api.kernel_read_bucket(bucket_id)
+    }
+
+    fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
+        self.api.kernel_read_proof(proof_id)
+    }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&Actor> {
+        self.api.kernel_get_call_frame_stack()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
+}
@@ -1,6 +1,9 @@
 use super::Authorization;
 use crate::blueprints::package::PackageAuthNativeBlueprint;
 use crate::blueprints::resource::AuthZone;
+use crate::blueprints::transaction_processor::{
+    is_transaction_processor_run_child, TransactionProcessorRunChildInput,
+};
 use crate::errors::*;
 use crate::internal_prelude::*;
 use crate::kernel::call_frame::ReferenceOrigin;
@@ -48,6 +51,8 @@ pub struct Unauthorized {
 #[derive(Debug, Clone)]
 pub struct AuthModule {
     pub params: AuthZoneParams,
+    /// The auth zone params of each child intent, by child index
+    pub child_params: Vec<AuthZoneParams>,
 }
 
 pub enum AuthorizationCheckResult {
@@ -75,11 +80,16 @@ impl AuthModule {
         api: &mut SystemService<Y, V>,
         blueprint_id: &BlueprintId,
         ident: &str,
+        args: &IndexedScryptoValue,
     ) -> Result<NodeId, RuntimeError>
     where
         V: SystemCallbackObject,
         Y: KernelApi<SystemConfig<V>>,
     {
+        // A child intent is run by the root transaction processor, in a transaction processor
+        // frame of its own, which gets the child's auth zone params in place of the root's
+        let child_params = Self::child_intent_params(api, blueprint_id, ident, args);
+
         // Create AuthZone
         let auth_zone = {
             // TODO: Remove special casing use of transaction processor and just have virtual resources
@@ -98,6 +108,11 @@ impl AuthModule {
                         auth_module.params.virtual_resources.clone(),
                         auth_module.params.initial_proofs.clone(),
                     )
+                } else if let Some(child_params) = &child_params {
+                    (
+                        child_params.virtual_resources.clone(),
+                        child_params.initial_proofs.clone(),
+                    )
                 } else {
                     (BTreeSet::new(), BTreeSet::new())
                 };
@@ -108,12 +123,16 @@ impl AuthModule {
         // Check authorization
         {
             // Step 1: Resolve method to permission
-            let permission = PackageAuthNativeBlueprint::resolve_function_permission(
-                blueprint_id.package_address.as_node_id(),
-                &BlueprintVersionKey::new_default(blueprint_id.blueprint_name.as_str()),
-                ident,
-                api.api,
-            )?;
+            let permission = if child_params.is_some() {
+                ResolvedPermission::AllowAll
+            } else {
+                PackageAuthNativeBlueprint::resolve_function_permission(
+                    blueprint_id.package_address.as_node_id(),
+                    &BlueprintVersionKey::new_default(blueprint_id.blueprint_name.as_str()),
+                    ident,
+                    api.api,
+                )?
+            };
 
             // Step 2: Check permission
             let fn_identifier = FnIdentifier {
@@ -126,6 +145,33 @@ impl AuthModule {
         Ok(auth_zone)
     }
 
+    /// Returns the auth zone params of the child intent being run, if this is the root
+    /// transaction processor running one of the transaction's child intents.
+    fn child_intent_params<V, Y>(
+        api: &mut SystemService<Y, V>,
+        blueprint_id: &BlueprintId,
+        ident: &str,
+        args: &IndexedScryptoValue,
+    ) -> Option<AuthZoneParams>
+    where
+        V: SystemCallbackObject,
+        Y: KernelApi<SystemConfig<V>>,
+    {
+        if !is_transaction_processor_run_child(blueprint_id, ident)
+            || api.kernel_get_current_depth() != 1
+        {
+            return None;
+        }
+
+        let input: TransactionProcessorRunChildInput = args.as_typed().ok()?;
+        api.kernel_get_system()
+            .modules
+            .auth
+            .child_params
+            .get(input.child_index as usize)
+            .cloned()
+    }
+
     pub fn on_call_function_finish<V, Y>(
         api: &mut SystemService<Y, V>,
         auth_zone: NodeId,
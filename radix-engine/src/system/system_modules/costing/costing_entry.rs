@@ -110,6 +110,24 @@ pub enum ExecutionCostingEntry<'a> {
     Panic {
         size: usize,
     },
+
+    /* crypto utils */
+    Blake2b256Hash {
+        size: usize,
+    },
+    Keccak256Hash {
+        size: usize,
+    },
+    Sha256Hash {
+        size: usize,
+    },
+    Ed25519Verify,
+    Secp256k1Verify,
+    Secp256k1Recover,
+    Bls12381V1AggregateVerify {
+        num_of_pairs: usize,
+        size: usize,
+    },
 }
 
 #[derive(Debug, IntoStaticStr)]
@@ -172,6 +190,15 @@ impl<'a> ExecutionCostingEntry<'a> {
             ExecutionCostingEntry::EmitEvent { size } => ft.emit_event_cost(*size),
             ExecutionCostingEntry::EmitLog { size } => ft.emit_log_cost(*size),
             ExecutionCostingEntry::Panic { size } => ft.panic_cost(*size),
+            ExecutionCostingEntry::Blake2b256Hash { size } => ft.blake2b_256_hash_cost(*size),
+            ExecutionCostingEntry::Keccak256Hash { size } => ft.keccak256_hash_cost(*size),
+            ExecutionCostingEntry::Sha256Hash { size } => ft.sha256_hash_cost(*size),
+            ExecutionCostingEntry::Ed25519Verify => ft.ed25519_verify_cost(),
+            ExecutionCostingEntry::Secp256k1Verify => ft.secp256k1_verify_cost(),
+            ExecutionCostingEntry::Secp256k1Recover => ft.secp256k1_recover_cost(),
+            ExecutionCostingEntry::Bls12381V1AggregateVerify { num_of_pairs, size } => {
+                ft.bls12381_v1_aggregate_verify_cost(*num_of_pairs, *size)
+            }
         }
     }
 }
@@ -13,6 +13,7 @@ use crate::system::module::SystemModule;
 use crate::system::system_callback::SystemConfig;
 use crate::system::system_callback_api::SystemCallbackObject;
 use crate::types::*;
+use crate::vm::wasm::{WasmExecutionProfile, WasmInvocation};
 use crate::{
     errors::{CanBeAbortion, RuntimeError, SystemModuleError},
     transaction::AbortReason,
@@ -84,6 +85,10 @@ pub struct CostingModule {
     pub execution_cost_breakdown: IndexMap<String, u32>,
     pub finalization_cost_breakdown: IndexMap<String, u32>,
     pub storage_cost_breakdown: IndexMap<StorageType, usize>,
+    pub enable_wasm_profiling: bool,
+    pub wasm_execution_profile: WasmExecutionProfile,
+    /// The wasm invocations in progress, outermost first, used to nest the profile frames
+    pub wasm_invocation_stack: Vec<WasmInvocation>,
 
     pub on_apply_cost: OnApplyCost,
 }
@@ -23,6 +23,11 @@ pub enum FeeReserveError {
     LoanRepaymentFailed {
         xrd_owed: Decimal,
     },
+    MaxFeeExceeded {
+        max_fee_in_xrd: Decimal,
+        committed: Decimal,
+        new: Decimal,
+    },
     Abort(AbortReason),
 }
 
@@ -109,6 +114,9 @@ pub struct SystemLoanFeeReserve {
 
     tip_percentage: u16,
 
+    /// The maximum total fee the fee payer accepts, if any
+    max_fee_in_xrd: Option<Decimal>,
+
     /// Whether to abort the transaction run when the loan is repaid.
     /// This is used when test-executing pending transactions.
     abort_when_loan_repaid: bool,
@@ -221,6 +229,9 @@ impl SystemLoanFeeReserve {
             // Tipping percentage
             tip_percentage: transaction_costing_parameters.tip_percentage,
 
+            // Max fee
+            max_fee_in_xrd: transaction_costing_parameters.max_fee_in_xrd,
+
             // Aborting support
             abort_when_loan_repaid,
 
@@ -308,6 +319,41 @@ impl SystemLoanFeeReserve {
         Ok(())
     }
 
+    /// The total of all fees committed so far, including tips.
+    fn total_cost_committed(&self) -> Decimal {
+        self.effective_execution_cost_unit_price
+            .checked_mul(self.execution_cost_units_committed)
+            .unwrap()
+            .checked_add(
+                self.effective_finalization_cost_unit_price
+                    .checked_mul(self.finalization_cost_units_committed)
+                    .unwrap(),
+            )
+            .unwrap()
+            .checked_add(self.royalty_cost_committed)
+            .unwrap()
+            .checked_add(self.storage_cost_committed)
+            .unwrap()
+    }
+
+    fn check_max_fee(&self, amount: Decimal) -> Result<(), FeeReserveError> {
+        if let Some(max_fee_in_xrd) = self.max_fee_in_xrd {
+            let committed = self.total_cost_committed();
+            if committed
+                .checked_add(amount)
+                .ok_or(FeeReserveError::Overflow)?
+                > max_fee_in_xrd
+            {
+                return Err(FeeReserveError::MaxFeeExceeded {
+                    max_fee_in_xrd,
+                    committed,
+                    new: amount,
+                });
+            }
+        }
+        Ok(())
+    }
+
     fn consume_execution_internal(&mut self, cost_units: u32) -> Result<(), FeeReserveError> {
         self.check_execution_cost_unit_limit(cost_units)?;
 
@@ -315,6 +361,7 @@ impl SystemLoanFeeReserve {
             .effective_execution_cost_unit_price
             .checked_mul(cost_units)
             .ok_or(FeeReserveError::Overflow)?;
+        self.check_max_fee(amount)?;
         if self.xrd_balance < amount {
             return Err(FeeReserveError::InsufficientBalance {
                 required: amount,
@@ -334,6 +381,7 @@ impl SystemLoanFeeReserve {
             .effective_finalization_cost_unit_price
             .checked_mul(cost_units)
             .ok_or(FeeReserveError::Overflow)?;
+        self.check_max_fee(amount)?;
         if self.xrd_balance < amount {
             return Err(FeeReserveError::InsufficientBalance {
                 required: amount,
@@ -358,6 +406,7 @@ impl SystemLoanFeeReserve {
                 .ok_or(FeeReserveError::Overflow)?,
             RoyaltyAmount::Free => Decimal::ZERO,
         };
+        self.check_max_fee(amount)?;
 
         if self.xrd_balance < amount {
             return Err(FeeReserveError::InsufficientBalance {
@@ -498,6 +547,7 @@ impl ExecutionFeeReserve for SystemLoanFeeReserve {
         }
         .checked_mul(size_increase)
         .ok_or(FeeReserveError::Overflow)?;
+        self.check_max_fee(amount)?;
 
         if self.xrd_balance < amount {
             return Err(FeeReserveError::InsufficientBalance {
@@ -746,4 +796,39 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn test_max_fee_exceeded() {
+        let mut costing_parameters = CostingParameters::default();
+        costing_parameters.execution_cost_unit_price = dec!(1);
+        costing_parameters.execution_cost_unit_limit = 1000;
+        costing_parameters.execution_cost_unit_loan = 50;
+        let mut transaction_costing_parameters = TransactionCostingParameters::default();
+        transaction_costing_parameters.tip_percentage = 0;
+        transaction_costing_parameters.max_fee_in_xrd = Some(dec!(100));
+        let mut fee_reserve =
+            SystemLoanFeeReserve::new(&costing_parameters, &transaction_costing_parameters, false);
+        fee_reserve.lock_fee(TEST_VAULT_ID, xrd(500), false);
+        fee_reserve.consume_execution(60).unwrap();
+        assert_eq!(
+            fee_reserve.consume_royalty(
+                RoyaltyAmount::Xrd(50.into()),
+                RoyaltyRecipient::Package(PACKAGE_PACKAGE, TEST_VAULT_ID),
+            ),
+            Err(FeeReserveError::MaxFeeExceeded {
+                max_fee_in_xrd: dec!("100"),
+                committed: dec!("60"),
+                new: dec!("50"),
+            }),
+        );
+        fee_reserve.consume_execution(40).unwrap();
+        assert_eq!(
+            fee_reserve.consume_execution(1),
+            Err(FeeReserveError::MaxFeeExceeded {
+                max_fee_in_xrd: dec!("100"),
+                committed: dec!("100"),
+                new: dec!("1"),
+            }),
+        );
+    }
 }
@@ -379,6 +379,52 @@ impl FeeTable {
         500 + Self::data_processing_cost(size)
     }
 
+    #[inline]
+    pub fn blake2b_256_hash_cost(&self, size: usize) -> u32 {
+        // Hashing is cheaper than SBOR decoding per byte, so this is conservative.
+        add(500, Self::data_processing_cost(size))
+    }
+
+    #[inline]
+    pub fn keccak256_hash_cost(&self, size: usize) -> u32 {
+        // Keccak256 is roughly twice as slow as Blake2b in software.
+        add(500, mul(cast(size), 4))
+    }
+
+    #[inline]
+    pub fn sha256_hash_cost(&self, size: usize) -> u32 {
+        add(500, Self::data_processing_cost(size))
+    }
+
+    #[inline]
+    pub fn ed25519_verify_cost(&self) -> u32 {
+        // Same as a transaction signature, see `verify_tx_signatures_cost`.
+        7_000
+    }
+
+    #[inline]
+    pub fn secp256k1_verify_cost(&self) -> u32 {
+        // Same as a transaction signature, see `verify_tx_signatures_cost`.
+        7_000
+    }
+
+    #[inline]
+    pub fn secp256k1_recover_cost(&self) -> u32 {
+        // Recovery costs about the same as verification, plus decompressing the public key.
+        8_000
+    }
+
+    #[inline]
+    pub fn bls12381_v1_aggregate_verify_cost(&self, num_of_pairs: usize, size: usize) -> u32 {
+        // Aggregate verification takes one pairing per public key, plus one for the signature.
+        // A pairing takes roughly 500 µs, i.e. 50,000 cost units, and hashing to the curve
+        // is priced like SBOR decoding.
+        add(
+            mul(add(cast(num_of_pairs), 1), 50_000),
+            Self::data_processing_cost(size),
+        )
+    }
+
     //======================
     // Finalization costs
     // This is primarily to account for the additional work on the Node side
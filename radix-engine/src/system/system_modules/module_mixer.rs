@@ -1,5 +1,6 @@
 use super::costing::{ExecutionCostingEntry, FinalizationCostingEntry, StorageType};
 use super::limits::TransactionLimitsError;
+use crate::blueprints::transaction_processor::TransactionProcessorChildIntent;
 use crate::errors::*;
 use crate::kernel::call_frame::CallFrameMessage;
 use crate::kernel::kernel_api::KernelInvocation;
@@ -31,7 +32,7 @@ use paste::paste;
 use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::crypto::Hash;
 use resources_tracker_macro::trace_resources;
-use transaction::model::AuthZoneParams;
+use transaction::model::{AuthZoneParams, ExecutableChildIntent};
 
 bitflags! {
     pub struct EnabledModules: u32 {
@@ -90,6 +91,9 @@ pub struct SystemModuleMixer {
     pub(crate) auth: AuthModule,
     pub(crate) transaction_runtime: TransactionRuntimeModule,
     pub(super) execution_trace: ExecutionTraceModule,
+    /// The index of the root manifest instruction being processed, kept regardless of the
+    /// enabled modules. Not part of the receipt; used by the system debugger.
+    root_instruction_index: Option<usize>,
 }
 
 // Macro generates default modules dispatches call based on passed function name and arguments.
@@ -147,10 +151,14 @@ impl SystemModuleMixer {
                 execution_cost_breakdown: index_map_new(),
                 finalization_cost_breakdown: index_map_new(),
                 storage_cost_breakdown: index_map_new(),
+                enable_wasm_profiling: execution_config.enable_wasm_profiling,
+                wasm_execution_profile: Default::default(),
+                wasm_invocation_stack: Vec::new(),
                 on_apply_cost: Default::default(),
             },
             auth: AuthModule {
                 params: auth_zone_params.clone(),
+                child_params: vec![],
             },
             limits: LimitsModule::new(TransactionLimitsConfig {
                 max_heap_substate_total_bytes: execution_config.max_heap_substate_total_bytes,
@@ -172,10 +180,33 @@ impl SystemModuleMixer {
                 logs: Vec::new(),
                 events: Vec::new(),
                 replacements: index_map_new(),
+                enable_wasm_backtraces: execution_config.enable_wasm_backtraces,
+                wasm_backtrace: Default::default(),
+                child_intents: Vec::new(),
             },
+            root_instruction_index: None,
         }
     }
 
+    /// Sets the child intents of the transaction, by child index.
+    pub fn with_child_intents(mut self, child_intents: &[ExecutableChildIntent]) -> Self {
+        self.auth.child_params = child_intents
+            .iter()
+            .map(|child_intent| child_intent.auth_zone_params.clone())
+            .collect();
+        self.transaction_runtime.child_intents = child_intents
+            .iter()
+            .map(|child_intent| {
+                Some(TransactionProcessorChildIntent {
+                    manifest_encoded_instructions: child_intent.encoded_instructions.to_vec(),
+                    references: child_intent.references.iter().cloned().collect(),
+                    blobs: child_intent.blobs.clone(),
+                })
+            })
+            .collect();
+        self
+    }
+
     pub fn unpack(
         self,
     ) -> (
@@ -442,6 +473,7 @@ impl SystemModuleMixer {
         api: &mut SystemService<Y, V>,
         blueprint_id: &BlueprintId,
         ident: &str,
+        args: &IndexedScryptoValue,
     ) -> Result<NodeId, RuntimeError>
     where
         V: SystemCallbackObject,
@@ -454,7 +486,7 @@ impl SystemModuleMixer {
             .enabled_modules
             .contains(EnabledModules::AUTH)
         {
-            AuthModule::on_call_function(api, blueprint_id, ident)?
+            AuthModule::on_call_function(api, blueprint_id, ident, args)?
         } else {
             AuthModule::on_call_fn_mock(api, None, btreeset!(), btreeset!())?
         };
@@ -604,6 +636,17 @@ impl SystemModuleMixer {
         }
     }
 
+    pub fn transaction_runtime_mut(&mut self) -> Option<&mut TransactionRuntimeModule> {
+        if self
+            .enabled_modules
+            .contains(EnabledModules::TRANSACTION_RUNTIME)
+        {
+            Some(&mut self.transaction_runtime)
+        } else {
+            None
+        }
+    }
+
     pub fn transaction_hash(&self) -> Option<Hash> {
         if self
             .enabled_modules
@@ -626,7 +669,13 @@ impl SystemModuleMixer {
         }
     }
 
-    pub fn update_instruction_index(&mut self, new_index: usize) {
+    /// Records the index of the manifest instruction being processed, by a transaction processor
+    /// running at the given call frame depth. The root manifest is processed at depth 1; deeper
+    /// ones process child intents.
+    pub fn update_instruction_index(&mut self, depth: usize, new_index: usize) {
+        if depth == 1 {
+            self.root_instruction_index = Some(new_index);
+        }
         if self
             .enabled_modules
             .contains(EnabledModules::EXECUTION_TRACE)
@@ -635,6 +684,10 @@ impl SystemModuleMixer {
         }
     }
 
+    pub fn root_instruction_index(&self) -> Option<usize> {
+        self.root_instruction_index
+    }
+
     pub fn apply_execution_cost(
         &mut self,
         costing_entry: ExecutionCostingEntry,
@@ -1,6 +1,8 @@
+use crate::blueprints::transaction_processor::TransactionProcessorChildIntent;
 use crate::kernel::kernel_callback_api::KernelCallbackObject;
 use crate::system::module::SystemModule;
 use crate::types::*;
+use crate::vm::wasm::{WasmBacktrace, WasmBacktraceFrame};
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::crypto::Hash;
@@ -29,6 +31,10 @@ pub struct TransactionRuntimeModule {
     pub logs: Vec<(Level, String)>,
     pub events: Vec<Event>,
     pub replacements: IndexMap<(NodeId, ModuleId), (NodeId, ModuleId)>,
+    pub enable_wasm_backtraces: bool,
+    pub wasm_backtrace: WasmBacktrace,
+    /// The child intents of the transaction, by child index, taken once each when run
+    pub child_intents: Vec<Option<TransactionProcessorChildIntent>>,
 }
 
 impl TransactionRuntimeModule {
@@ -50,6 +56,25 @@ impl TransactionRuntimeModule {
         self.events.push(event)
     }
 
+    pub fn add_wasm_backtrace_frame(&mut self, frame: WasmBacktraceFrame) {
+        self.wasm_backtrace.push(frame);
+    }
+
+    pub fn take_child_intent(
+        &mut self,
+        child_index: u32,
+    ) -> Option<TransactionProcessorChildIntent> {
+        self.child_intents
+            .get_mut(child_index as usize)
+            .and_then(|child_intent| child_intent.take())
+    }
+
+    pub fn first_child_intent_not_run(&self) -> Option<usize> {
+        self.child_intents
+            .iter()
+            .position(|child_intent| child_intent.is_some())
+    }
+
     pub fn add_replacement(&mut self, old: (NodeId, ModuleId), new: (NodeId, ModuleId)) {
         self.replacements.insert(old, new);
     }
@@ -110,6 +135,9 @@ mod tests {
             logs: Vec::new(),
             events: Vec::new(),
             replacements: index_map_new(),
+            enable_wasm_backtraces: false,
+            wasm_backtrace: Default::default(),
+            child_intents: Vec::new(),
         };
         assert_eq!(
             NonFungibleLocalId::ruid(id.generate_ruid()).to_string(),
@@ -123,6 +151,9 @@ mod tests {
             logs: Vec::new(),
             events: Vec::new(),
             replacements: index_map_new(),
+            enable_wasm_backtraces: false,
+            wasm_backtrace: Default::default(),
+            child_intents: Vec::new(),
         };
         assert_eq!(
             NonFungibleLocalId::ruid(id.generate_ruid()).to_string(),
@@ -136,6 +167,9 @@ mod tests {
             logs: Vec::new(),
             events: Vec::new(),
             replacements: index_map_new(),
+            enable_wasm_backtraces: false,
+            wasm_backtrace: Default::default(),
+            child_intents: Vec::new(),
         };
         assert_eq!(
             NonFungibleLocalId::ruid(id.generate_ruid()).to_string(),
@@ -84,6 +84,15 @@ pub trait CommitableSubstateStore {
         .unwrap()
     }
 
+    /// Reads a substate as currently seen by the transaction, without loading it into the store
+    /// nor reporting any IO access; meant for debugging only.
+    fn peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue>;
+
     fn get_substate<E, F: FnMut(IOAccess) -> Result<(), E>>(
         &mut self,
         node_id: &NodeId,
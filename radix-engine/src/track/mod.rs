@@ -1,6 +1,7 @@
 pub mod interface;
 pub mod legacy_state_updates;
 pub mod state_updates;
+pub mod substate_reads;
 pub mod track;
 pub mod utils;
 
@@ -10,4 +11,5 @@ mod test;
 pub use interface::*;
 pub use legacy_state_updates::*;
 pub use state_updates::*;
+pub use substate_reads::*;
 pub use track::*;
@@ -0,0 +1,63 @@
+use crate::track::state_updates::{ReadOnly, TrackedSubstateValue};
+use crate::types::*;
+
+/// A description of all Substates read during a transaction, to be (optionally) included as a part
+/// of a transaction receipt.
+/// Apart from the direct reads, this includes the Substates returned by any scan (or drain) of a
+/// Partition. Reads of Substates which do not exist are included too, since their outcome depends
+/// on the state just as well.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor, Default)]
+pub struct SubstateReads {
+    /// Indexed Substate reads, captured in the order of first read of a Substate.
+    pub by_substate: IndexMap<(NodeId, PartitionNumber, SubstateKey), SubstateReadSource>,
+}
+
+impl SubstateReads {
+    /// Records a read of the given Substate, unless it was already read before (i.e. only the
+    /// source of the first read is captured).
+    pub fn record(
+        &mut self,
+        node_id: &NodeId,
+        partition_number: PartitionNumber,
+        substate_key: &SubstateKey,
+        source: SubstateReadSource,
+    ) {
+        self.by_substate
+            .entry((*node_id, partition_number, substate_key.clone()))
+            .or_insert(source);
+    }
+
+    /// Returns the Substates whose value was read from the database (i.e. the ones which the
+    /// transaction's outcome depends on).
+    pub fn from_database(&self) -> impl Iterator<Item = &(NodeId, PartitionNumber, SubstateKey)> {
+        self.by_substate
+            .iter()
+            .filter(|(_, source)| **source == SubstateReadSource::Database)
+            .map(|(key, _)| key)
+    }
+}
+
+/// The origin of a value seen by a Substate read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ScryptoSbor)]
+pub enum SubstateReadSource {
+    /// The value (or its absence) came from the database, i.e. the state before the transaction.
+    Database,
+    /// The value was created (or written) by the transaction itself before it was read, or the
+    /// Substate is transient.
+    Transaction,
+}
+
+impl SubstateReadSource {
+    /// Returns the source of a read of a Substate which is already tracked with the given value.
+    pub fn of_tracked(tracked: &TrackedSubstateValue) -> Self {
+        match tracked {
+            TrackedSubstateValue::ReadOnly(ReadOnly::Existent(..))
+            | TrackedSubstateValue::ReadOnly(ReadOnly::NonExistent) => SubstateReadSource::Database,
+            TrackedSubstateValue::New(..)
+            | TrackedSubstateValue::ReadExistAndWrite(..)
+            | TrackedSubstateValue::ReadNonExistAndWrite(..)
+            | TrackedSubstateValue::WriteOnly(..)
+            | TrackedSubstateValue::Garbage => SubstateReadSource::Transaction,
+        }
+    }
+}
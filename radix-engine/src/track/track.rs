@@ -3,6 +3,7 @@ use crate::track::interface::{
     CommitableSubstateStore, IOAccess, NodeSubstates, TrackedSubstateInfo,
 };
 use crate::track::state_updates::*;
+use crate::track::substate_reads::{SubstateReadSource, SubstateReads};
 use crate::track::utils::OverlayingResultIterator;
 use crate::types::*;
 use radix_engine_interface::types::*;
@@ -35,6 +36,9 @@ pub struct Track<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> {
 
     transient_substates: TransientSubstates,
 
+    /// Captured only if enabled, see `enable_substate_read_tracking`
+    substate_reads: Option<SubstateReads>,
+
     phantom_data: PhantomData<M>,
 }
 
@@ -46,10 +50,62 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> Track<'s, S, M> {
             tracked_nodes: index_map_new(),
             deleted_partitions: index_set_new(),
             transient_substates: TransientSubstates::new(),
+            substate_reads: None,
             phantom_data: PhantomData::default(),
         }
     }
 
+    /// Starts capturing all substate reads (see [`SubstateReads`]).
+    pub fn enable_substate_read_tracking(&mut self) {
+        self.substate_reads = Some(SubstateReads::default());
+    }
+
+    /// Returns the substate reads captured so far, if enabled.
+    pub fn take_substate_reads(&mut self) -> Option<SubstateReads> {
+        self.substate_reads.take()
+    }
+
+    /// Records a read of the given substate (if enabled), based on its current tracked state.
+    /// Must be called before the read itself, since the read may load the substate into the track.
+    fn record_substate_read(
+        &mut self,
+        node_id: &NodeId,
+        partition_number: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) {
+        if self.substate_reads.is_none() {
+            return;
+        }
+        let tracked_node = self.tracked_nodes.get(node_id);
+        let tracked = tracked_node
+            .and_then(|n| n.tracked_partitions.get(&partition_number))
+            .and_then(|p| p.substates.get(&M::to_db_sort_key(substate_key)));
+        let source = match tracked {
+            Some(tracked) => SubstateReadSource::of_tracked(&tracked.substate_value),
+            None => {
+                let is_new = tracked_node.map(|n| n.is_new).unwrap_or(false);
+                if is_new
+                    || self.transient_substates.is_transient(
+                        node_id,
+                        partition_number,
+                        substate_key,
+                    )
+                {
+                    SubstateReadSource::Transaction
+                } else {
+                    SubstateReadSource::Database
+                }
+            }
+        };
+        record_read(
+            &mut self.substate_reads,
+            node_id,
+            partition_number,
+            substate_key,
+            source,
+        );
+    }
+
     // TODO cleanup interface to avoid redundant information
     fn get_substate_from_db<E, F: FnMut(IOAccess) -> Result<(), E>>(
         substate_db: &'s S,
@@ -402,6 +458,38 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         info
     }
 
+    fn peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        let tracked_node = self.tracked_nodes.get(node_id);
+        if let Some(tracked) = tracked_node
+            .and_then(|n| n.tracked_partitions.get(&partition_num))
+            .and_then(|p| p.substates.get(&M::to_db_sort_key(substate_key)))
+        {
+            return tracked.substate_value.get().cloned();
+        }
+
+        let is_new = tracked_node.map(|n| n.is_new).unwrap_or(false);
+        if is_new
+            || self.deleted_partitions.contains(&(*node_id, partition_num))
+            || self
+                .transient_substates
+                .is_transient(node_id, partition_num, substate_key)
+        {
+            return None;
+        }
+
+        self.substate_db
+            .get_substate(
+                &M::to_db_partition_key(node_id, partition_num),
+                &M::to_db_sort_key(substate_key),
+            )
+            .map(|e| IndexedScryptoValue::from_vec(e).expect("Failed to decode substate"))
+    }
+
     fn get_substate<E, F: FnMut(IOAccess) -> Result<(), E>>(
         &mut self,
         node_id: &NodeId,
@@ -409,6 +497,8 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         substate_key: &SubstateKey,
         on_io_access: &mut F,
     ) -> Result<Option<&IndexedScryptoValue>, E> {
+        self.record_substate_read(node_id, partition_num, substate_key);
+
         // Load the substate from state track
         let tracked =
             self.get_tracked_substate(node_id, partition_num, substate_key.clone(), on_io_access)?;
@@ -524,6 +614,8 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         substate_key: &SubstateKey,
         on_io_access: &mut F,
     ) -> Result<Option<IndexedScryptoValue>, E> {
+        self.record_substate_read(node_id, partition_number, substate_key);
+
         let tracked = self.get_tracked_substate(
             node_id,
             partition_number,
@@ -574,6 +666,13 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
 
                 // TODO: Check that substate is not write locked, before use outside of native blueprints
                 if let Some(_substate) = tracked_substate.substate_value.get() {
+                    record_read(
+                        &mut self.substate_reads,
+                        node_id,
+                        partition_number,
+                        &tracked_substate.substate_key,
+                        SubstateReadSource::of_tracked(&tracked_substate.substate_value),
+                    );
                     items.push(tracked_substate.substate_key.clone());
                 }
             }
@@ -611,6 +710,13 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
 
             // TODO: cache read substates in Track (and notify upper layer)
 
+            record_read(
+                &mut self.substate_reads,
+                node_id,
+                partition_number,
+                &substate_key,
+                SubstateReadSource::Database,
+            );
             items.push(substate_key);
         }
 
@@ -648,7 +754,15 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
                 }
 
                 let old_size = Some(tracked_substate.size());
+                let source = SubstateReadSource::of_tracked(&tracked_substate.substate_value);
                 if let Some(value) = tracked_substate.substate_value.take() {
+                    record_read(
+                        &mut self.substate_reads,
+                        node_id,
+                        partition_number,
+                        &tracked_substate.substate_key,
+                        source,
+                    );
                     items.push((tracked_substate.substate_key.clone(), value));
                 }
                 let new_size = Some(tracked_substate.size());
@@ -712,6 +826,13 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
                         ),
                     };
                     new_updates.push((db_sort_key, tracked));
+                    record_read(
+                        &mut self.substate_reads,
+                        node_id,
+                        partition_number,
+                        &substate_key,
+                        SubstateReadSource::Database,
+                    );
                     items.push((substate_key, substate_value));
                 }
                 new_updates
@@ -787,9 +908,18 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
                 },
             ))
         };
-        let db_read_entries = raw_db_entries.inspect(|_| {
-            db_values_count += 1;
-        });
+        let db_read_entries = raw_db_entries
+            .inspect(|_| {
+                db_values_count += 1;
+            })
+            .map(|result| {
+                result.map(|(db_sort_key, (substate_key, substate_value))| {
+                    (
+                        db_sort_key,
+                        (substate_key, substate_value, SubstateReadSource::Database),
+                    )
+                })
+            });
 
         // initialize the "from track" iterator
         let tracked_entry_changes =
@@ -801,7 +931,11 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
                     if let Some(value) = tracked_substate.substate_value.get() {
                         (
                             db_sort_key.clone(),
-                            Some((tracked_substate.substate_key.clone(), value.clone())),
+                            Some((
+                                tracked_substate.substate_key.clone(),
+                                value.clone(),
+                                SubstateReadSource::of_tracked(&tracked_substate.substate_value),
+                            )),
                         )
                     } else {
                         (db_sort_key.clone(), None)
@@ -813,7 +947,14 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         for result in
             OverlayingResultIterator::new(db_read_entries, tracked_entry_changes).take(limit)
         {
-            let (_db_sort_key, (substate_key, substate_value)) = result?;
+            let (_db_sort_key, (substate_key, substate_value, source)) = result?;
+            record_read(
+                &mut self.substate_reads,
+                node_id,
+                partition_number,
+                &substate_key,
+                source,
+            );
             let sorted_key = match substate_key {
                 SubstateKey::Sorted(sorted) => sorted,
                 _ => panic!("Should be a sorted key"),
@@ -931,3 +1072,15 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper + 'static> CommitableSubstate
         store_commit
     }
 }
+
+fn record_read(
+    substate_reads: &mut Option<SubstateReads>,
+    node_id: &NodeId,
+    partition_number: PartitionNumber,
+    substate_key: &SubstateKey,
+    source: SubstateReadSource,
+) {
+    if let Some(substate_reads) = substate_reads {
+        substate_reads.record(node_id, partition_number, substate_key, source);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::system::system_callback_api::SystemCallbackObject;
+use crate::transaction::*;
+use crate::types::*;
+use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
+use radix_engine_store_interface::interface::*;
+use radix_engine_store_interface::overlay::{DatabaseUpdatesOverlay, OverlaidSubstateDatabase};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use transaction::model::Executable;
+
+/// The database reads made during a transaction's execution (i.e. the reads which the `Track`
+/// could not serve from the substates it already loaded or created).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseReadSet {
+    /// The individual substates read (including the ones which turned out to be missing).
+    pub substates: BTreeSet<DbSubstateKey>,
+    /// The partitions listed (in any range).
+    pub listed_partitions: BTreeSet<DbPartitionKey>,
+}
+
+impl DatabaseReadSet {
+    /// Returns whether any of the reads could have had a different result if the given updates
+    /// were committed before.
+    pub fn conflicts_with(&self, updates: &DatabaseUpdatesOverlay) -> bool {
+        self.listed_partitions
+            .iter()
+            .any(|partition_key| updates.affects_partition(partition_key))
+            || self.substates.iter().any(|(partition_key, sort_key)| {
+                updates.get_substate(partition_key, sort_key).is_some()
+            })
+    }
+}
+
+/// A [`SubstateDatabase`] used for a single execution of a transaction within a batch executed by
+/// [`execute_in_parallel()`]: it reads the shared database (with the updates of the preceding
+/// transactions applied, if any), and records a [`DatabaseReadSet`].
+pub struct ReadRecordingSubstateDatabase<'a, S> {
+    database: OverlaidSubstateDatabase<'a, S>,
+    read_set: RefCell<DatabaseReadSet>,
+}
+
+impl<'a, S: SubstateDatabase> ReadRecordingSubstateDatabase<'a, S> {
+    pub fn new(database: &'a S, preceding_updates: &'a DatabaseUpdatesOverlay) -> Self {
+        Self {
+            database: OverlaidSubstateDatabase::new(database, preceding_updates),
+            read_set: RefCell::new(DatabaseReadSet::default()),
+        }
+    }
+
+    pub fn into_read_set(self) -> DatabaseReadSet {
+        self.read_set.into_inner()
+    }
+}
+
+impl<'a, S: SubstateDatabase> SubstateDatabase for ReadRecordingSubstateDatabase<'a, S> {
+    fn get_substate(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<DbSubstateValue> {
+        self.read_set
+            .borrow_mut()
+            .substates
+            .insert((partition_key.clone(), sort_key.clone()));
+        self.database.get_substate(partition_key, sort_key)
+    }
+
+    fn list_entries_from(
+        &self,
+        partition_key: &DbPartitionKey,
+        from_sort_key: Option<&DbSortKey>,
+    ) -> Box<dyn Iterator<Item = PartitionEntry> + '_> {
+        self.read_set
+            .borrow_mut()
+            .listed_partitions
+            .insert(partition_key.clone());
+        self.database
+            .list_entries_from(partition_key, from_sort_key)
+    }
+
+    fn prefetch(&self, substate_keys: &[DbSubstateKey]) {
+        self.database.prefetch(substate_keys)
+    }
+}
+
+/// A result of a single transaction of a batch executed by [`execute_in_parallel()`].
+pub struct ParallelExecutionOutcome<R> {
+    pub result: R,
+    /// The updates made by the transaction, to be committed after the ones of all the preceding
+    /// transactions of the batch.
+    pub database_updates: DatabaseUpdates,
+    /// Whether the transaction had to be executed again, since its first (speculative) execution
+    /// read a substate updated by some preceding transaction of the batch.
+    pub re_executed: bool,
+}
+
+/// Executes the given consecutive transactions on top of the given database state, using the given
+/// number of threads, with exactly the same outcomes as a sequential execution and commit of each
+/// of them.
+///
+/// All transactions are first executed speculatively and concurrently against the given state,
+/// recording their [`DatabaseReadSet`]s. Then, in the original order, each transaction's reads are
+/// checked against the updates of all the preceding transactions, and the transaction is executed
+/// again (against the state including these updates) in case of a conflict.
+/// Hence, the speedup depends on how independent the transactions are; note that the fee payments
+/// of all transactions update a few common system substates.
+///
+/// The given function must be deterministic (i.e. only depend on the database's state and the
+/// transaction), and return the transaction's result together with its updates.
+/// The given database is not modified: the caller is responsible for committing the returned
+/// updates, in order.
+pub fn execute_in_parallel<S, T, R, F>(
+    substate_db: &S,
+    transactions: &[T],
+    thread_count: usize,
+    execute: F,
+) -> Vec<ParallelExecutionOutcome<R>>
+where
+    S: SubstateDatabase + Sync,
+    T: Sync,
+    R: Send,
+    F: Fn(&ReadRecordingSubstateDatabase<'_, S>, &T) -> (R, DatabaseUpdates) + Sync,
+{
+    let no_updates = DatabaseUpdatesOverlay::default();
+    let next_index = AtomicUsize::new(0);
+    let mut speculative_outcomes = thread::scope(|scope| {
+        let workers = (0..thread_count.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = Vec::new();
+                    let mut index = next_index.fetch_add(1, Ordering::Relaxed);
+                    while let Some(transaction) = transactions.get(index) {
+                        let database = ReadRecordingSubstateDatabase::new(substate_db, &no_updates);
+                        let (result, database_updates) = execute(&database, transaction);
+                        outcomes.push((index, result, database_updates, database.into_read_set()));
+                        index = next_index.fetch_add(1, Ordering::Relaxed);
+                    }
+                    outcomes
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Transaction execution panicked"))
+            .collect::<Vec<_>>()
+    });
+    speculative_outcomes.sort_by_key(|(index, ..)| *index);
+
+    let mut preceding_updates = DatabaseUpdatesOverlay::default();
+    let mut outcomes = Vec::with_capacity(transactions.len());
+    for (index, result, database_updates, read_set) in speculative_outcomes {
+        let outcome = if read_set.conflicts_with(&preceding_updates) {
+            let database = ReadRecordingSubstateDatabase::new(substate_db, &preceding_updates);
+            let (result, database_updates) = execute(&database, &transactions[index]);
+            ParallelExecutionOutcome {
+                result,
+                database_updates,
+                re_executed: true,
+            }
+        } else {
+            ParallelExecutionOutcome {
+                result,
+                database_updates,
+                re_executed: false,
+            }
+        };
+        preceding_updates.apply(&outcome.database_updates);
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+/// Executes the given consecutive transactions in parallel (see [`execute_in_parallel()`]), with
+/// the same receipts as a sequential [`execute_and_commit_transaction()`] of each of them.
+pub fn execute_transactions_in_parallel<S, V>(
+    substate_db: &S,
+    vm: V,
+    costing_parameters: &CostingParameters,
+    execution_config: &ExecutionConfig,
+    transactions: &[Executable],
+    thread_count: usize,
+) -> Vec<ParallelExecutionOutcome<TransactionReceipt>>
+where
+    S: SubstateDatabase + Sync,
+    V: SystemCallbackObject + Clone + Sync,
+{
+    execute_in_parallel(
+        substate_db,
+        transactions,
+        thread_count,
+        |database, transaction| {
+            let receipt = execute_transaction(
+                database,
+                vm.clone(),
+                costing_parameters,
+                execution_config,
+                transaction,
+            );
+            let database_updates = match &receipt.result {
+                TransactionResult::Commit(commit) => commit
+                    .state_updates
+                    .create_database_updates::<SpreadPrefixKeyMapper>(),
+                TransactionResult::Reject(_) | TransactionResult::Abort(_) => {
+                    DatabaseUpdates::default()
+                }
+            };
+            (receipt, database_updates)
+        },
+    )
+}
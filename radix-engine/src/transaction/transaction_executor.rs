@@ -1,6 +1,6 @@
 use crate::blueprints::consensus_manager::{
-    ConsensusManagerField, ConsensusManagerStateFieldPayload,
-    ConsensusManagerValidatorRewardsFieldPayload,
+    ConsensusManagerField, ConsensusManagerProposerMilliTimestampFieldPayload,
+    ConsensusManagerStateFieldPayload, ConsensusManagerValidatorRewardsFieldPayload,
 };
 use crate::blueprints::models::FieldPayload;
 use crate::blueprints::resource::{
@@ -33,6 +33,7 @@ use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::blueprints::resource::LiquidFungibleResource;
 use radix_engine_interface::blueprints::transaction_processor::InstructionOutput;
 use radix_engine_store_interface::{db_key_mapper::SpreadPrefixKeyMapper, interface::*};
+use sbor::rust::mem;
 use transaction::model::*;
 
 /// Protocol-defined costing parameters
@@ -86,6 +87,9 @@ pub struct ExecutionConfig {
     pub enabled_modules: EnabledModules,
     pub abort_when_loan_repaid: bool,
     pub enable_cost_breakdown: bool,
+    pub enable_substate_reads: bool,
+    pub enable_wasm_profiling: bool,
+    pub enable_wasm_backtraces: bool,
     pub max_execution_trace_depth: usize,
     pub max_call_depth: usize,
     pub max_heap_substate_total_bytes: usize,
@@ -110,6 +114,9 @@ impl ExecutionConfig {
             enabled_modules: EnabledModules::for_notarized_transaction(),
             abort_when_loan_repaid: false,
             enable_cost_breakdown: false,
+            enable_substate_reads: false,
+            enable_wasm_profiling: false,
+            enable_wasm_backtraces: false,
             max_execution_trace_depth: MAX_EXECUTION_TRACE_DEPTH,
             max_call_depth: MAX_CALL_DEPTH,
             max_heap_substate_total_bytes: MAX_HEAP_SUBSTATE_TOTAL_BYTES,
@@ -181,6 +188,21 @@ impl ExecutionConfig {
         self
     }
 
+    pub fn with_substate_reads(mut self, enabled: bool) -> Self {
+        self.enable_substate_reads = enabled;
+        self
+    }
+
+    pub fn with_wasm_profiling(mut self, enabled: bool) -> Self {
+        self.enable_wasm_profiling = enabled;
+        self
+    }
+
+    pub fn with_wasm_backtraces(mut self, enabled: bool) -> Self {
+        self.enable_wasm_backtraces = enabled;
+        self
+    }
+
     pub fn up_to_loan_repayment(mut self, enabled: bool) -> Self {
         self.abort_when_loan_repaid = enabled;
         self
@@ -253,6 +275,9 @@ where
 
         // Create a track
         let mut track = Track::<_, SpreadPrefixKeyMapper>::new(self.substate_db);
+        if execution_config.enable_substate_reads {
+            track.enable_substate_read_tracking();
+        }
 
         // Perform runtime validation.
         // TODO: the following assumptions can be removed with better interface.
@@ -275,16 +300,62 @@ where
             } else {
                 Ok(())
             }
+            .and_then(|_| {
+                // Each child intent is committed along with the transaction, so is checked the same way
+                executable
+                    .child_intents()
+                    .iter()
+                    .try_for_each(|child_intent| match &child_intent.epoch_range {
+                        Some(range) => Self::validate_epoch_range(
+                            current_epoch,
+                            range.start_epoch_inclusive,
+                            range.end_epoch_exclusive,
+                        )
+                        .and_then(|_| {
+                            Self::validate_intent_hash(
+                                &mut track,
+                                child_intent.intent_hash.to_hash(),
+                                range.end_epoch_exclusive,
+                            )
+                        }),
+                        None => Ok(()),
+                    })
+            })
+            .and_then(|_| {
+                // The proposer timestamp is only read if there is a range to check it against
+                let proposer_timestamp_ranges: Vec<&ProposerTimestampRange> = executable
+                    .proposer_timestamp_range()
+                    .into_iter()
+                    .chain(
+                        executable
+                            .child_intents()
+                            .iter()
+                            .filter_map(|child_intent| {
+                                child_intent.proposer_timestamp_range.as_ref()
+                            }),
+                    )
+                    .collect();
+                if proposer_timestamp_ranges.is_empty() {
+                    return Ok(());
+                }
+                let current_time = Self::read_proposer_timestamp(&mut track)
+                    .ok_or(RejectionReason::TransactionProposerTimestampUnavailable)?;
+                proposer_timestamp_ranges.into_iter().try_for_each(|range| {
+                    Self::validate_proposer_timestamp_range(current_time, range)
+                })
+            })
         } else {
             Ok(())
         };
 
         // Run manifest
+        let mut wasm_execution_profile = None;
+        let mut wasm_backtrace = None;
         let (fee_summary, fee_details, result) = match validation_result {
             Ok(()) => {
                 let (
                     interpretation_result,
-                    (mut costing_module, runtime_module, execution_trace_module),
+                    (mut costing_module, mut runtime_module, execution_trace_module),
                 ) = self.interpret_manifest::<T>(
                     &mut track,
                     executable,
@@ -322,6 +393,17 @@ where
                     None
                 };
 
+                if execution_config.enable_wasm_profiling {
+                    wasm_execution_profile =
+                        Some(mem::take(&mut costing_module.wasm_execution_profile));
+                }
+
+                if execution_config.enable_wasm_backtraces
+                    && !runtime_module.wasm_backtrace.is_empty()
+                {
+                    wasm_backtrace = Some(mem::take(&mut runtime_module.wasm_backtrace));
+                }
+
                 // Panic if an error is encountered in the system layer or below. The following code
                 // is only enabled when compiling with the standard library since the panic catching
                 // machinery and `SystemPanic` errors are only implemented in `std`.
@@ -368,7 +450,12 @@ where
                             Self::update_transaction_tracker(
                                 &mut track,
                                 next_epoch,
-                                executable.intent_hash(),
+                                core::iter::once(executable.intent_hash()).chain(
+                                    executable
+                                        .child_intents()
+                                        .iter()
+                                        .map(|child_intent| &child_intent.intent_hash),
+                                ),
                                 is_success,
                             );
                         }
@@ -383,6 +470,7 @@ where
                             execution_trace_module.finalize(&paying_vaults, is_success);
 
                         // Finalize track
+                        let substate_reads = track.take_substate_reads();
                         let (tracked_nodes, deleted_partitions) = {
                             match track.finalize() {
                                 Ok(result) => result,
@@ -443,6 +531,7 @@ where
                                 } else {
                                     None
                                 },
+                                substate_reads,
                             }),
                         )
                     }
@@ -486,6 +575,8 @@ where
             fee_details,
             result,
             resources_usage,
+            wasm_execution_profile,
+            wasm_backtrace,
         };
 
         // Dump summary
@@ -517,6 +608,27 @@ where
         }
     }
 
+    fn read_proposer_timestamp(track: &mut Track<S, SpreadPrefixKeyMapper>) -> Option<Instant> {
+        match track.read_substate(
+            CONSENSUS_MANAGER.as_node_id(),
+            MAIN_BASE_PARTITION,
+            &ConsensusManagerField::ProposerMilliTimestamp.into(),
+        ) {
+            Some(x) => {
+                let substate: FieldSubstate<ConsensusManagerProposerMilliTimestampFieldPayload> =
+                    x.as_typed().unwrap();
+                Some(Instant::new(
+                    substate
+                        .into_payload()
+                        .into_latest()
+                        .epoch_milli
+                        .div_euclid(1000),
+                ))
+            }
+            None => None,
+        }
+    }
+
     fn validate_epoch_range(
         current_epoch: Epoch,
         start_epoch_inclusive: Epoch,
@@ -538,6 +650,30 @@ where
         Ok(())
     }
 
+    fn validate_proposer_timestamp_range(
+        current_time: Instant,
+        range: &ProposerTimestampRange,
+    ) -> Result<(), RejectionReason> {
+        if let Some(valid_from) = range.start_timestamp_inclusive {
+            if current_time.seconds_since_unix_epoch < valid_from.seconds_since_unix_epoch {
+                return Err(RejectionReason::TransactionProposerTimestampNotYetValid {
+                    valid_from,
+                    current_time,
+                });
+            }
+        }
+        if let Some(valid_until) = range.end_timestamp_exclusive {
+            if current_time.seconds_since_unix_epoch >= valid_until.seconds_since_unix_epoch {
+                return Err(RejectionReason::TransactionProposerTimestampNoLongerValid {
+                    valid_until,
+                    current_time,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     fn validate_intent_hash(
         track: &mut Track<S, SpreadPrefixKeyMapper>,
         intent_hash: Hash,
@@ -619,7 +755,8 @@ where
                 executable.payload_size(),
                 executable.num_of_signature_validations(),
                 execution_config,
-            ),
+            )
+            .with_child_intents(executable.child_intents()),
         };
 
         let mut wrapped_system = T::create(system, init);
@@ -983,10 +1120,10 @@ where
         (fee_reserve_finalization, fee_payments, events)
     }
 
-    fn update_transaction_tracker(
+    fn update_transaction_tracker<'a>(
         track: &mut Track<S, SpreadPrefixKeyMapper>,
         next_epoch: Epoch,
-        intent_hash: &TransactionIntentHash,
+        intent_hashes: impl IntoIterator<Item = &'a TransactionIntentHash>,
         is_success: bool,
     ) {
         // Read the intent hash store
@@ -1003,12 +1140,16 @@ where
 
         let mut transaction_tracker = transaction_tracker.into_v1();
 
-        // Update the status of the intent hash
-        if let TransactionIntentHash::ToCheck {
-            expiry_epoch,
-            intent_hash,
-        } = intent_hash
-        {
+        // Update the status of the intent hashes
+        for intent_hash in intent_hashes {
+            let (expiry_epoch, intent_hash) = match intent_hash {
+                TransactionIntentHash::ToCheck {
+                    expiry_epoch,
+                    intent_hash,
+                } => (expiry_epoch, intent_hash),
+                TransactionIntentHash::NotToCheck { .. } => continue,
+            };
+
             if let Some(partition_number) =
                 transaction_tracker.partition_for_expiry_epoch(*expiry_epoch)
             {
@@ -8,8 +8,11 @@ use crate::track::BatchPartitionStateUpdate;
 use crate::track::NodeStateUpdates;
 use crate::track::PartitionStateUpdates;
 use crate::track::StateUpdates;
+use crate::track::SubstateReads;
 use crate::transaction::SystemStructure;
+use crate::vm::wasm::{WasmBacktrace, WasmExecutionProfile, WasmFunctionNames};
 use colored::*;
+use radix_engine_interface::blueprints::package::CodeHash;
 use radix_engine_interface::blueprints::transaction_processor::InstructionOutput;
 use radix_engine_store_interface::interface::DatabaseUpdate;
 use sbor::representations::*;
@@ -40,6 +43,12 @@ pub struct TransactionReceiptV1 {
     /// Hardware resources usage report
     /// Available if `resources_usage` feature flag is enabled
     pub resources_usage: Option<ResourcesUsage>,
+    /// Wasm execution units consumed per function
+    /// Available if `ExecutionConfig::enable_wasm_profiling` is enabled
+    pub wasm_execution_profile: Option<WasmExecutionProfile>,
+    /// Wasm call stacks of the invocations which failed the transaction
+    /// Available if `ExecutionConfig::enable_wasm_backtraces` is enabled
+    pub wasm_backtrace: Option<WasmBacktrace>,
 }
 
 #[derive(Default, Debug, Clone, ScryptoSbor)]
@@ -98,6 +107,9 @@ pub struct CommitResult {
     /// Transaction execution traces
     /// Available if `ExecutionTrace` module is enabled
     pub execution_trace: Option<TransactionExecutionTrace>,
+    /// Substates read (from the database or not)
+    /// Available if `enable_substate_reads` is set in the `ExecutionConfig`
+    pub substate_reads: Option<SubstateReads>,
 }
 
 #[derive(Debug, Clone, Default, ScryptoSbor)]
@@ -186,6 +198,7 @@ impl CommitResult {
             application_logs: Default::default(),
             system_structure: Default::default(),
             execution_trace: Default::default(),
+            substate_reads: Default::default(),
         }
     }
 
@@ -364,6 +377,8 @@ impl TransactionReceipt {
             fee_details: Default::default(),
             result: TransactionResult::Commit(commit_result),
             resources_usage: Default::default(),
+            wasm_execution_profile: Default::default(),
+            wasm_backtrace: Default::default(),
         }
     }
 
@@ -582,6 +597,8 @@ pub struct TransactionReceiptDisplayContext<'a> {
     pub schema_lookup_callback: Option<
         Box<dyn Fn(&EventTypeIdentifier) -> Option<(LocalTypeId, VersionedScryptoSchema)> + 'a>,
     >,
+    pub wasm_function_names_callback:
+        Option<Box<dyn Fn(&PackageAddress, &CodeHash) -> Option<WasmFunctionNames> + 'a>>,
 }
 
 impl<'a> TransactionReceiptDisplayContext<'a> {
@@ -607,6 +624,20 @@ impl<'a> TransactionReceiptDisplayContext<'a> {
             None => None,
         }
     }
+
+    pub fn lookup_wasm_function_names(
+        &self,
+        package_address: &PackageAddress,
+        code_hash: &CodeHash,
+    ) -> Option<WasmFunctionNames> {
+        match self.wasm_function_names_callback {
+            Some(ref callback) => {
+                let callback = callback.as_ref();
+                callback(package_address, code_hash)
+            }
+            None => None,
+        }
+    }
 }
 
 impl<'a> From<&'a AddressBech32Encoder> for TransactionReceiptDisplayContext<'a> {
@@ -614,6 +645,7 @@ impl<'a> From<&'a AddressBech32Encoder> for TransactionReceiptDisplayContext<'a>
         Self {
             encoder: Some(encoder),
             schema_lookup_callback: None,
+            wasm_function_names_callback: None,
         }
     }
 }
@@ -623,6 +655,7 @@ impl<'a> From<Option<&'a AddressBech32Encoder>> for TransactionReceiptDisplayCon
         Self {
             encoder,
             schema_lookup_callback: None,
+            wasm_function_names_callback: None,
         }
     }
 }
@@ -634,6 +667,7 @@ impl<'a> TransactionReceiptDisplayContextBuilder<'a> {
         Self(TransactionReceiptDisplayContext {
             encoder: None,
             schema_lookup_callback: None,
+            wasm_function_names_callback: None,
         })
     }
 
@@ -650,6 +684,14 @@ impl<'a> TransactionReceiptDisplayContextBuilder<'a> {
         self
     }
 
+    pub fn wasm_function_names_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&PackageAddress, &CodeHash) -> Option<WasmFunctionNames> + 'a,
+    {
+        self.0.wasm_function_names_callback = Some(Box::new(callback));
+        self
+    }
+
     pub fn build(self) -> TransactionReceiptDisplayContext<'a> {
         self.0
     }
@@ -681,6 +723,34 @@ impl<'a> ContextualDisplay<TransactionReceiptDisplayContext<'a>> for Transaction
             },
         )?;
 
+        if let Some(wasm_backtrace) = &self.wasm_backtrace {
+            let mut names: IndexMap<(PackageAddress, CodeHash), Option<WasmFunctionNames>> =
+                index_map_new();
+            let functions: Vec<_> = wasm_backtrace.functions().collect();
+            write!(f, "\n{} {}", "Backtrace:".bold().green(), functions.len())?;
+            for (i, (frame, function_index)) in functions.iter().enumerate() {
+                let function_names = names
+                    .entry((frame.package_address, frame.code_hash))
+                    .or_insert_with(|| {
+                        context.lookup_wasm_function_names(&frame.package_address, &frame.code_hash)
+                    });
+                let function_name = match function_names {
+                    Some(function_names) => function_names.get(*function_index),
+                    None => format!("func[{}]", function_index),
+                };
+                write!(
+                    f,
+                    "\n{} {}: {}\n{}     at {}::{}",
+                    prefix!(i, functions),
+                    i,
+                    function_name.red(),
+                    if i == functions.len() - 1 { " " } else { "│" },
+                    frame.package_address.display(address_display_context),
+                    frame.export_name
+                )?;
+            }
+        }
+
         write!(
             f,
             "\n{} {} XRD",
@@ -0,0 +1,350 @@
+use super::native_blueprint_call_validator::{invocation_of, Invocation};
+use crate::blueprints::native_schema::*;
+use crate::blueprints::package::*;
+use crate::blueprints::pool::multi_resource_pool::*;
+use crate::blueprints::pool::one_resource_pool::*;
+use crate::blueprints::pool::two_resource_pool::*;
+use crate::system::system_db_reader::{SystemDatabaseReader, SystemReaderError};
+use crate::utils::{InstructionSchemaValidationError, LocatedInstructionSchemaValidationError};
+use radix_engine_common::data::manifest::*;
+use radix_engine_common::prelude::*;
+use radix_engine_interface::api::ModuleId;
+use radix_engine_interface::blueprints::access_controller::*;
+use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::blueprints::consensus_manager::*;
+use radix_engine_interface::blueprints::identity::*;
+use radix_engine_interface::blueprints::resource::*;
+use radix_engine_interface::schema::*;
+use radix_engine_store_interface::interface::SubstateDatabase;
+use transaction::prelude::*;
+
+/// The interface of a function (or method) which the arguments of its invocations are validated
+/// against.
+#[derive(Clone, Debug)]
+pub struct ResolvedFunctionSchema {
+    pub receiver: Option<ReceiverInfo>,
+    /// The schema of the function's input, or `None` if it cannot be resolved statically (i.e. is
+    /// generic).
+    pub input: Option<(Rc<VersionedScryptoSchema>, LocalTypeId)>,
+}
+
+/// A source of the blueprint interfaces used by [`validate_call_arguments()`].
+pub trait BlueprintInterfaceResolver {
+    /// Resolves the blueprint of the given module of the given object, or returns `None` if it
+    /// cannot be known (in which case the calls to the object are not validated).
+    fn resolve_blueprint_id(
+        &self,
+        node_id: &NodeId,
+        module_id: ModuleId,
+    ) -> Result<Option<BlueprintId>, InstructionSchemaValidationError>;
+
+    /// Resolves the interface of the given function of the given blueprint, or returns `None` if
+    /// the blueprint's package is not known.
+    fn resolve_function_schema(
+        &self,
+        blueprint_id: &BlueprintId,
+        function_name: &str,
+    ) -> Result<Option<ResolvedFunctionSchema>, InstructionSchemaValidationError>;
+}
+
+/// Validates the arguments of all the function and method calls in the given instructions against
+/// the input schemas of the called functions, as resolved by the given resolver.
+///
+/// Unlike [`super::validate_call_arguments_to_native_components()`], all errors are collected
+/// (rather than only the first one), and argument mismatches are reported with the path to the
+/// offending value (see [`InstructionSchemaValidationError::ArgumentsMismatch`]).
+pub fn validate_call_arguments<R: BlueprintInterfaceResolver>(
+    instructions: &[InstructionV1],
+    resolver: &R,
+) -> Result<(), Vec<LocatedInstructionSchemaValidationError>> {
+    let errors: Vec<_> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, instruction)| {
+            validate_instruction_call_arguments(instruction, resolver)
+                .err()
+                .map(|cause| LocatedInstructionSchemaValidationError {
+                    instruction_index: index,
+                    cause,
+                })
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_instruction_call_arguments<R: BlueprintInterfaceResolver>(
+    instruction: &InstructionV1,
+    resolver: &R,
+) -> Result<(), InstructionSchemaValidationError> {
+    let (invocation, args) = match invocation_of(instruction) {
+        Some(invocation) => invocation,
+        None => return Ok(()),
+    };
+
+    let blueprint_id = match &invocation {
+        Invocation::Function(package_address, blueprint_name, _) => {
+            Some(BlueprintId::new(package_address, blueprint_name))
+        }
+        Invocation::Method(address, module_id, _) => {
+            resolver.resolve_blueprint_id(address.as_node_id(), *module_id)?
+        }
+        Invocation::DirectMethod(address, _) => {
+            resolver.resolve_blueprint_id(address.as_node_id(), ModuleId::Main)?
+        }
+    };
+    let function_schema = match blueprint_id {
+        Some(blueprint_id) => {
+            resolver.resolve_function_schema(&blueprint_id, invocation.method())?
+        }
+        None => None,
+    };
+    let function_schema = match function_schema {
+        Some(function_schema) => function_schema,
+        None => return Ok(()),
+    };
+
+    if !invocation.matches_receiver(&function_schema.receiver) {
+        return Err(InstructionSchemaValidationError::InvalidReceiver);
+    }
+
+    if let Some((schema, local_type_id)) = function_schema.input {
+        let schema = schema.v1();
+        validate_payload_against_schema::<ManifestCustomExtension, _>(
+            &manifest_encode(args).unwrap(),
+            schema,
+            local_type_id,
+            &(),
+            MANIFEST_SBOR_V1_MAX_DEPTH,
+        )
+        .map_err(
+            |error| InstructionSchemaValidationError::ArgumentsMismatch {
+                // The arguments are encoded as a tuple, hence the index of the offending argument is
+                // the index of the root tuple's current child
+                argument_index: error
+                    .location
+                    .ancestor_path
+                    .first()
+                    .and_then(|(container_state, _)| container_state.current_child_index),
+                path: error.location.path_to_string(schema),
+                cause: error.error.to_string(),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Resolves the blueprint interfaces from the given package definitions, e.g. of the packages
+/// about to be published along with a manifest.
+///
+/// Since there is no ledger state to read, methods can only be resolved for objects of native
+/// blueprints (and the attached modules of any object), based on their entity types.
+#[derive(Clone, Debug, Default)]
+pub struct PackageDefinitionsResolver {
+    packages: IndexSet<PackageAddress>,
+    blueprints: IndexMap<BlueprintId, (Rc<VersionedScryptoSchema>, BlueprintFunctionsSchemaInit)>,
+}
+
+impl PackageDefinitionsResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver which knows the definitions of all native packages.
+    pub fn with_native_packages() -> Self {
+        let mut resolver = Self::new();
+        for (package_address, package_definition) in [
+            (PACKAGE_PACKAGE, &*PACKAGE_PACKAGE_DEFINITION),
+            (RESOURCE_PACKAGE, &*RESOURCE_PACKAGE_DEFINITION),
+            (ACCOUNT_PACKAGE, &*ACCOUNT_PACKAGE_DEFINITION),
+            (IDENTITY_PACKAGE, &*IDENTITY_PACKAGE_DEFINITION),
+            (
+                CONSENSUS_MANAGER_PACKAGE,
+                &*CONSENSUS_MANAGER_PACKAGE_DEFINITION,
+            ),
+            (
+                ACCESS_CONTROLLER_PACKAGE,
+                &*ACCESS_CONTROLLER_PACKAGE_DEFINITION,
+            ),
+            (POOL_PACKAGE, &*POOL_PACKAGE_DEFINITION),
+            (
+                TRANSACTION_PROCESSOR_PACKAGE,
+                &*TRANSACTION_PROCESSOR_PACKAGE_DEFINITION,
+            ),
+            (
+                TRANSACTION_TRACKER_PACKAGE,
+                &*TRANSACTION_TRACKER_PACKAGE_DEFINITION,
+            ),
+            (METADATA_MODULE_PACKAGE, &*METADATA_PACKAGE_DEFINITION),
+            (ROYALTY_MODULE_PACKAGE, &*ROYALTY_PACKAGE_DEFINITION),
+            (
+                ROLE_ASSIGNMENT_MODULE_PACKAGE,
+                &*ROLE_ASSIGNMENT_PACKAGE_DEFINITION,
+            ),
+        ] {
+            resolver.add_package(package_address, package_definition);
+        }
+        resolver
+    }
+
+    pub fn add_package(
+        &mut self,
+        package_address: PackageAddress,
+        package_definition: &PackageDefinition,
+    ) {
+        self.packages.insert(package_address);
+        for (blueprint_name, blueprint_definition) in &package_definition.blueprints {
+            self.blueprints.insert(
+                BlueprintId::new(&package_address, blueprint_name),
+                (
+                    Rc::new(blueprint_definition.schema.schema.clone()),
+                    blueprint_definition.schema.functions.clone(),
+                ),
+            );
+        }
+    }
+}
+
+impl BlueprintInterfaceResolver for PackageDefinitionsResolver {
+    fn resolve_blueprint_id(
+        &self,
+        node_id: &NodeId,
+        module_id: ModuleId,
+    ) -> Result<Option<BlueprintId>, InstructionSchemaValidationError> {
+        match module_id {
+            ModuleId::Main => Ok(node_id.entity_type().and_then(native_blueprint_id)),
+            module_id => Ok(module_id.static_blueprint()),
+        }
+    }
+
+    fn resolve_function_schema(
+        &self,
+        blueprint_id: &BlueprintId,
+        function_name: &str,
+    ) -> Result<Option<ResolvedFunctionSchema>, InstructionSchemaValidationError> {
+        if !self.packages.contains(&blueprint_id.package_address) {
+            return Ok(None);
+        }
+        let (schema, functions) = self.blueprints.get(blueprint_id).ok_or_else(|| {
+            InstructionSchemaValidationError::InvalidBlueprint(
+                blueprint_id.package_address,
+                blueprint_id.blueprint_name.clone(),
+            )
+        })?;
+        let function_schema = functions.functions.get(function_name).ok_or_else(|| {
+            InstructionSchemaValidationError::MethodNotFound(function_name.to_owned())
+        })?;
+        Ok(Some(ResolvedFunctionSchema {
+            receiver: function_schema.receiver.clone(),
+            input: match function_schema.input {
+                TypeRef::Static(local_type_id) => Some((schema.clone(), local_type_id)),
+                TypeRef::Generic(_) => None,
+            },
+        }))
+    }
+}
+
+/// Resolves the blueprint interfaces from the ledger state, i.e. the types of the called objects
+/// and the definitions of the packages they belong to.
+impl<'a, S: SubstateDatabase> BlueprintInterfaceResolver for SystemDatabaseReader<'a, S> {
+    fn resolve_blueprint_id(
+        &self,
+        node_id: &NodeId,
+        module_id: ModuleId,
+    ) -> Result<Option<BlueprintId>, InstructionSchemaValidationError> {
+        match self.get_blueprint_id(node_id, module_id) {
+            Ok(blueprint_id) => Ok(Some(blueprint_id)),
+            // A virtual account or identity may be called before it is instantiated
+            Err(SystemReaderError::NodeIdDoesNotExist)
+                if node_id
+                    .entity_type()
+                    .map(|entity_type| entity_type.is_global_virtual())
+                    .unwrap_or(false) =>
+            {
+                match module_id {
+                    ModuleId::Main => Ok(node_id.entity_type().and_then(native_blueprint_id)),
+                    module_id => Ok(module_id.static_blueprint()),
+                }
+            }
+            Err(SystemReaderError::ModuleDoesNotExist) => Err(
+                InstructionSchemaValidationError::ModuleNotFound(*node_id, module_id),
+            ),
+            Err(_) => Err(InstructionSchemaValidationError::ObjectNotFound(*node_id)),
+        }
+    }
+
+    fn resolve_function_schema(
+        &self,
+        blueprint_id: &BlueprintId,
+        function_name: &str,
+    ) -> Result<Option<ResolvedFunctionSchema>, InstructionSchemaValidationError> {
+        let blueprint_definition = self.get_blueprint_definition(blueprint_id).map_err(|_| {
+            InstructionSchemaValidationError::InvalidBlueprint(
+                blueprint_id.package_address,
+                blueprint_id.blueprint_name.clone(),
+            )
+        })?;
+        let function_schema = blueprint_definition
+            .interface
+            .functions
+            .get(function_name)
+            .ok_or_else(|| {
+                InstructionSchemaValidationError::MethodNotFound(function_name.to_owned())
+            })?;
+        let input = match function_schema.input {
+            BlueprintPayloadDef::Static(ScopedTypeId(schema_hash, local_type_id)) => self
+                .get_schema(blueprint_id.package_address.as_node_id(), &schema_hash)
+                .ok()
+                .map(|schema| (schema, local_type_id)),
+            BlueprintPayloadDef::Generic(_) => None,
+        };
+        Ok(Some(ResolvedFunctionSchema {
+            receiver: function_schema.receiver.clone(),
+            input,
+        }))
+    }
+}
+
+/// Returns the blueprint of the objects of the given entity type, if it is specific to a single
+/// native blueprint.
+fn native_blueprint_id(entity_type: EntityType) -> Option<BlueprintId> {
+    let (package_address, blueprint_name) = match entity_type {
+        EntityType::GlobalPackage => (PACKAGE_PACKAGE, PACKAGE_BLUEPRINT),
+        EntityType::GlobalConsensusManager => {
+            (CONSENSUS_MANAGER_PACKAGE, CONSENSUS_MANAGER_BLUEPRINT)
+        }
+        EntityType::GlobalValidator => (CONSENSUS_MANAGER_PACKAGE, VALIDATOR_BLUEPRINT),
+        EntityType::GlobalAccount
+        | EntityType::GlobalVirtualEd25519Account
+        | EntityType::GlobalVirtualSecp256k1Account => (ACCOUNT_PACKAGE, ACCOUNT_BLUEPRINT),
+        EntityType::GlobalIdentity
+        | EntityType::GlobalVirtualEd25519Identity
+        | EntityType::GlobalVirtualSecp256k1Identity => (IDENTITY_PACKAGE, IDENTITY_BLUEPRINT),
+        EntityType::GlobalAccessController => {
+            (ACCESS_CONTROLLER_PACKAGE, ACCESS_CONTROLLER_BLUEPRINT)
+        }
+        EntityType::GlobalOneResourcePool => (POOL_PACKAGE, ONE_RESOURCE_POOL_BLUEPRINT_IDENT),
+        EntityType::GlobalTwoResourcePool => (POOL_PACKAGE, TWO_RESOURCE_POOL_BLUEPRINT_IDENT),
+        EntityType::GlobalMultiResourcePool => (POOL_PACKAGE, MULTI_RESOURCE_POOL_BLUEPRINT_IDENT),
+        EntityType::GlobalTransactionTracker => {
+            (TRANSACTION_TRACKER_PACKAGE, TRANSACTION_TRACKER_BLUEPRINT)
+        }
+        EntityType::GlobalFungibleResourceManager => {
+            (RESOURCE_PACKAGE, FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT)
+        }
+        EntityType::GlobalNonFungibleResourceManager => {
+            (RESOURCE_PACKAGE, NON_FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT)
+        }
+        EntityType::InternalFungibleVault => (RESOURCE_PACKAGE, FUNGIBLE_VAULT_BLUEPRINT),
+        EntityType::InternalNonFungibleVault => (RESOURCE_PACKAGE, NON_FUNGIBLE_VAULT_BLUEPRINT),
+        EntityType::GlobalGenericComponent
+        | EntityType::InternalGenericComponent
+        | EntityType::InternalKeyValueStore => return None,
+    };
+    Some(BlueprintId::new(&package_address, blueprint_name))
+}
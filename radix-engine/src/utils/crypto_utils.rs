@@ -0,0 +1,115 @@
+use crate::types::*;
+use blst::min_pk::{PublicKey, Signature};
+use blst::BLST_ERROR;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// The domain separation tag of the `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_` ciphersuite,
+/// i.e. minimal-pubkey-size signatures with proof of possession.
+pub const BLS12381_V1_CIPHERSUITE: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+pub fn keccak256_hash<T: AsRef<[u8]>>(data: T) -> Hash {
+    Hash(Keccak256::digest(data).into())
+}
+
+pub fn sha256_hash<T: AsRef<[u8]>>(data: T) -> Hash {
+    Hash(Sha256::digest(data).into())
+}
+
+/// Verifies an aggregated signature, where each public key has signed its own message.
+///
+/// Public keys and the signature are group-checked, so invalid points are rejected rather than
+/// panicking. An empty list of public keys is never valid.
+pub fn verify_bls12381_v1_aggregate(
+    pub_keys_and_msgs: &[(Bls12381G1PublicKey, Vec<u8>)],
+    signature: &Bls12381G2Signature,
+) -> bool {
+    if pub_keys_and_msgs.is_empty() {
+        return false;
+    }
+
+    let signature = match Signature::from_bytes(&signature.0) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let mut pub_keys = Vec::with_capacity(pub_keys_and_msgs.len());
+    for (pub_key, _) in pub_keys_and_msgs {
+        match PublicKey::from_bytes(&pub_key.0) {
+            Ok(pub_key) => pub_keys.push(pub_key),
+            Err(_) => return false,
+        }
+    }
+    let pub_keys: Vec<&PublicKey> = pub_keys.iter().collect();
+    let msgs: Vec<&[u8]> = pub_keys_and_msgs
+        .iter()
+        .map(|(_, msg)| msg.as_slice())
+        .collect();
+
+    signature.aggregate_verify(true, &msgs, BLS12381_V1_CIPHERSUITE, &pub_keys, true)
+        == BLST_ERROR::BLST_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blst::min_pk::{AggregateSignature, SecretKey};
+    use sbor::rust::str::FromStr;
+
+    #[test]
+    fn test_keccak256_hash() {
+        assert_eq!(
+            keccak256_hash([]),
+            Hash::from_str("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sha256_hash() {
+        assert_eq!(
+            sha256_hash([]),
+            Hash::from_str("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_bls12381_v1_aggregate() {
+        let secret_keys: Vec<SecretKey> = (1u8..=3)
+            .map(|i| SecretKey::key_gen(&[i; 32], &[]).unwrap())
+            .collect();
+        let pub_keys_and_msgs: Vec<(Bls12381G1PublicKey, Vec<u8>)> = secret_keys
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| {
+                (
+                    Bls12381G1PublicKey(sk.sk_to_pk().compress()),
+                    format!("message {}", i).into_bytes(),
+                )
+            })
+            .collect();
+        let signatures: Vec<Signature> = secret_keys
+            .iter()
+            .zip(pub_keys_and_msgs.iter())
+            .map(|(sk, (_, msg))| sk.sign(msg, BLS12381_V1_CIPHERSUITE, &[]))
+            .collect();
+        let signature_refs: Vec<&Signature> = signatures.iter().collect();
+        let signature = Bls12381G2Signature(
+            AggregateSignature::aggregate(&signature_refs, true)
+                .unwrap()
+                .to_signature()
+                .compress(),
+        );
+
+        assert!(verify_bls12381_v1_aggregate(&pub_keys_and_msgs, &signature));
+
+        let mut tampered = pub_keys_and_msgs.clone();
+        tampered[0].1 = b"another message".to_vec();
+        assert!(!verify_bls12381_v1_aggregate(&tampered, &signature));
+        assert!(!verify_bls12381_v1_aggregate(
+            &pub_keys_and_msgs[1..],
+            &signature
+        ));
+        assert!(!verify_bls12381_v1_aggregate(&[], &signature));
+    }
+}
@@ -21,61 +21,9 @@ pub fn validate_call_arguments_to_native_components(
     instructions: &[InstructionV1],
 ) -> Result<(), LocatedInstructionSchemaValidationError> {
     for (index, instruction) in instructions.iter().enumerate() {
-        let (invocation, args) = match instruction {
-            InstructionV1::CallFunction {
-                package_address: DynamicPackageAddress::Static(address),
-                blueprint_name,
-                function_name,
-                args,
-            } => (
-                Invocation::Function(
-                    *address,
-                    blueprint_name.to_owned(),
-                    function_name.to_owned(),
-                ),
-                args,
-            ),
-            InstructionV1::CallMethod {
-                address: DynamicGlobalAddress::Static(address),
-                method_name,
-                args,
-            } => (
-                Invocation::Method(*address, ModuleId::Main, method_name.to_owned()),
-                args,
-            ),
-            InstructionV1::CallMetadataMethod {
-                address: DynamicGlobalAddress::Static(address),
-                method_name,
-                args,
-            } => (
-                Invocation::Method(*address, ModuleId::Metadata, method_name.to_owned()),
-                args,
-            ),
-            InstructionV1::CallRoyaltyMethod {
-                address: DynamicGlobalAddress::Static(address),
-                method_name,
-                args,
-            } => (
-                Invocation::Method(*address, ModuleId::Royalty, method_name.to_owned()),
-                args,
-            ),
-            InstructionV1::CallRoleAssignmentMethod {
-                address: DynamicGlobalAddress::Static(address),
-                method_name,
-                args,
-            } => (
-                Invocation::Method(*address, ModuleId::RoleAssignment, method_name.to_owned()),
-                args,
-            ),
-            InstructionV1::CallDirectVaultMethod {
-                address,
-                method_name,
-                args,
-            } => (
-                Invocation::DirectMethod(*address, method_name.to_owned()),
-                args,
-            ),
-            _ => continue,
+        let (invocation, args) = match invocation_of(instruction) {
+            Some(invocation) => invocation,
+            None => continue,
         };
 
         let schema = get_arguments_schema(invocation).map_err(|cause| {
@@ -108,6 +56,68 @@ pub fn validate_call_arguments_to_native_components(
     Ok(())
 }
 
+/// Returns the invocation made by the given instruction (if it is a call with a statically known
+/// target), together with its arguments.
+pub(crate) fn invocation_of(instruction: &InstructionV1) -> Option<(Invocation, &ManifestValue)> {
+    let invocation_and_args = match instruction {
+        InstructionV1::CallFunction {
+            package_address: DynamicPackageAddress::Static(address),
+            blueprint_name,
+            function_name,
+            args,
+        } => (
+            Invocation::Function(
+                *address,
+                blueprint_name.to_owned(),
+                function_name.to_owned(),
+            ),
+            args,
+        ),
+        InstructionV1::CallMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            args,
+        } => (
+            Invocation::Method(*address, ModuleId::Main, method_name.to_owned()),
+            args,
+        ),
+        InstructionV1::CallMetadataMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            args,
+        } => (
+            Invocation::Method(*address, ModuleId::Metadata, method_name.to_owned()),
+            args,
+        ),
+        InstructionV1::CallRoyaltyMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            args,
+        } => (
+            Invocation::Method(*address, ModuleId::Royalty, method_name.to_owned()),
+            args,
+        ),
+        InstructionV1::CallRoleAssignmentMethod {
+            address: DynamicGlobalAddress::Static(address),
+            method_name,
+            args,
+        } => (
+            Invocation::Method(*address, ModuleId::RoleAssignment, method_name.to_owned()),
+            args,
+        ),
+        InstructionV1::CallDirectVaultMethod {
+            address,
+            method_name,
+            args,
+        } => (
+            Invocation::DirectMethod(*address, method_name.to_owned()),
+            args,
+        ),
+        _ => return None,
+    };
+    Some(invocation_and_args)
+}
+
 fn get_blueprint_schema<'p>(
     package_definition: &'p PackageDefinition,
     package_address: PackageAddress,
@@ -272,11 +282,7 @@ fn get_arguments_schema<'s>(
             .functions
             .get(invocation.method())
         {
-            if is_self_or_mut_self_receiver(&function_schema.receiver) && invocation.is_method()
-                || is_direct_access_receiver(&function_schema.receiver)
-                    && invocation.is_direct_access_method()
-                || is_function_receiver(&function_schema.receiver) && invocation.is_function()
-            {
+            if invocation.matches_receiver(&function_schema.receiver) {
                 Ok(Some((
                     function_schema.input.clone(),
                     blueprint_schema.schema.schema.v1(),
@@ -321,14 +327,14 @@ fn is_function_receiver(receiver: &Option<ReceiverInfo>) -> bool {
 }
 
 #[derive(Clone, Debug)]
-enum Invocation {
+pub(crate) enum Invocation {
     DirectMethod(InternalAddress, String),
     Method(GlobalAddress, ModuleId, String),
     Function(PackageAddress, String, String),
 }
 
 impl Invocation {
-    fn method(&self) -> &str {
+    pub(crate) fn method(&self) -> &str {
         match self {
             Self::DirectMethod(_, method) => method,
             Self::Method(_, _, method) => method,
@@ -364,6 +370,13 @@ impl Invocation {
             Self::Function(..) | Self::Method(..) => false,
         }
     }
+
+    /// Checks whether the invoked function or method can be invoked like this, given its receiver.
+    pub(crate) fn matches_receiver(&self, receiver: &Option<ReceiverInfo>) -> bool {
+        is_self_or_mut_self_receiver(receiver) && self.is_method()
+            || is_direct_access_receiver(receiver) && self.is_direct_access_method()
+            || is_function_receiver(receiver) && self.is_function()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -380,4 +393,15 @@ pub enum InstructionSchemaValidationError {
     InvalidAddress(GlobalAddress),
     InvalidBlueprint(PackageAddress, String),
     InvalidReceiver,
+
+    ObjectNotFound(NodeId),
+    ModuleNotFound(NodeId, ModuleId),
+    ArgumentsMismatch {
+        /// The index of the offending argument, or `None` if the arguments as a whole mismatch
+        /// (e.g. their number is wrong).
+        argument_index: Option<usize>,
+        /// The path to the offending value (see `FullLocation::path_to_string()`).
+        path: String,
+        cause: String,
+    },
 }
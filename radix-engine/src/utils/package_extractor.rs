@@ -42,6 +42,7 @@ pub fn extract_definition(code: &[u8]) -> Result<PackageDefinition, ExtractSchem
         &TransactionCostingParameters {
             tip_percentage: 0,
             free_credit_in_xrd: Decimal::try_from(PREVIEW_CREDIT_IN_XRD).unwrap(),
+            max_fee_in_xrd: None,
         },
         false,
     );
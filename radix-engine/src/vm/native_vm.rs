@@ -15,10 +15,12 @@ use crate::system::attached_modules::role_assignment::RoleAssignmentNativePackag
 use crate::system::attached_modules::royalty::RoyaltyNativePackage;
 use crate::system::system_callback::SystemLockData;
 use crate::types::*;
+use crate::vm::wasm::InstrumentedCodeCache;
 use crate::vm::VmInvoke;
 use radix_engine_interface::api::ClientApi;
 use radix_engine_interface::blueprints::package::*;
 use resources_tracker_macro::trace_resources;
+use sbor::rust::sync::Arc;
 
 #[derive(Clone)]
 pub struct NativeVm<E: NativeVmExtension> {
@@ -34,6 +36,7 @@ impl<E: NativeVmExtension> NativeVm<E> {
         &self,
         package_address: &PackageAddress,
         code: &[u8],
+        instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
     ) -> Result<NativeVmInstance<E::Instance>, RuntimeError> {
         if let Some(custom_invoke) = self.extension.try_create_instance(code) {
             return Ok(NativeVmInstance::Extension(custom_invoke));
@@ -56,6 +59,7 @@ impl<E: NativeVmExtension> NativeVm<E> {
         let instance = NativeVmInstance::Native {
             package_address: *package_address,
             native_package_code_id,
+            instrumented_code_cache,
         };
 
         Ok(instance)
@@ -68,6 +72,8 @@ pub enum NativeVmInstance<I: VmInvoke> {
         #[allow(dead_code)]
         package_address: PackageAddress,
         native_package_code_id: u64,
+        // Used by the package blueprint when publishing WASM packages
+        instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
     },
     Extension(I),
 }
@@ -102,6 +108,7 @@ impl<I: VmInvoke> VmInvoke for NativeVmInstance<I> {
             NativeVmInstance::Native {
                 native_package_code_id,
                 package_address,
+                instrumented_code_cache,
             } => {
                 api.consume_cost_units(ClientCostingEntry::RunNativeCode {
                     package_address: package_address,
@@ -110,7 +117,12 @@ impl<I: VmInvoke> VmInvoke for NativeVmInstance<I> {
                 })?;
 
                 match *native_package_code_id {
-                    PACKAGE_CODE_ID => PackageNativePackage::invoke_export(export_name, input, api),
+                    PACKAGE_CODE_ID => PackageNativePackage::invoke_export(
+                        export_name,
+                        input,
+                        instrumented_code_cache.as_deref(),
+                        api,
+                    ),
                     RESOURCE_CODE_ID => {
                         ResourceNativePackage::invoke_export(export_name, input, api)
                     }
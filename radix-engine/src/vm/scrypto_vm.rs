@@ -6,10 +6,16 @@ use crate::vm::wasm_runtime::ScryptoRuntime;
 use radix_engine_interface::api::ClientApi;
 use radix_engine_interface::blueprints::package::CodeHash;
 use resources_tracker_macro::trace_resources;
+use sbor::rust::mem;
+use sbor::rust::sync::Arc;
 
 pub struct ScryptoVm<W: WasmEngine> {
     pub wasm_engine: W,
     pub wasm_validator_config: WasmValidatorConfigV1,
+    /// An optional cache of instrumented code, consulted when packages are published and when
+    /// instances are created with debugging aids. Unlike the module cache of the wasm engine,
+    /// this may outlive the process.
+    pub instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
 }
 
 impl<W: WasmEngine + Default> Default for ScryptoVm<W> {
@@ -17,6 +23,7 @@ impl<W: WasmEngine + Default> Default for ScryptoVm<W> {
         Self {
             wasm_engine: W::default(),
             wasm_validator_config: WasmValidatorConfigV1::new(),
+            instrumented_code_cache: None,
         }
     }
 }
@@ -27,10 +34,32 @@ impl<W: WasmEngine> ScryptoVm<W> {
         package_address: &PackageAddress,
         code_hash: CodeHash,
         instrumented_code: &[u8],
+        debug_options: WasmDebugOptions,
     ) -> ScryptoVmInstance<W::WasmInstance> {
+        let instance = if debug_options.is_enabled() {
+            let debug_code_cache = self.instrumented_code_cache.as_deref().map(|cache| {
+                let key = InstrumentedCodeKey {
+                    code_hash,
+                    validator_version: WASM_VALIDATOR_VERSION,
+                    config_version: self.wasm_validator_config.version(),
+                    debug_options,
+                };
+                (cache, key)
+            });
+            self.wasm_engine.instantiate_with_debugging(
+                code_hash,
+                instrumented_code,
+                debug_options,
+                debug_code_cache,
+            )
+        } else {
+            self.wasm_engine.instantiate(code_hash, instrumented_code)
+        };
         ScryptoVmInstance {
-            instance: self.wasm_engine.instantiate(code_hash, instrumented_code),
+            instance,
             package_address: *package_address,
+            wasm_execution_profile: index_map_new(),
+            wasm_backtrace: Vec::new(),
         }
     }
 }
@@ -38,6 +67,22 @@ impl<W: WasmEngine> ScryptoVm<W> {
 pub struct ScryptoVmInstance<I: WasmInstance> {
     instance: I,
     package_address: PackageAddress,
+    wasm_execution_profile: IndexMap<u32, u64>,
+    wasm_backtrace: Vec<u32>,
+}
+
+impl<I: WasmInstance> ScryptoVmInstance<I> {
+    /// Takes the wasm execution units consumed by the invocations so far, keyed by function
+    /// index. Always empty unless the instance was created with profiling enabled.
+    pub fn take_wasm_execution_profile(&mut self) -> IndexMap<u32, u64> {
+        mem::take(&mut self.wasm_execution_profile)
+    }
+
+    /// Takes the wasm call stack of the last failed invocation, innermost function first.
+    /// Always empty unless the instance was created with backtraces enabled.
+    pub fn take_wasm_backtrace(&mut self) -> Vec<u32> {
+        mem::take(&mut self.wasm_backtrace)
+    }
 }
 
 impl<I: WasmInstance> VmInvoke for ScryptoVmInstance<I> {
@@ -64,8 +109,20 @@ impl<I: WasmInstance> VmInvoke for ScryptoVmInstance<I> {
                     .allocate_buffer(args.as_slice().to_vec())
                     .expect("Failed to allocate buffer"),
             );
-            self.instance
-                .invoke_export(export_name, input, &mut runtime)?
+            let rtn = self
+                .instance
+                .invoke_export(export_name, input, &mut runtime);
+            // Kept on failure too, as the units have been consumed regardless
+            for (function_index, units) in runtime.take_wasm_execution_profile() {
+                *self
+                    .wasm_execution_profile
+                    .entry(function_index)
+                    .or_default() += units;
+            }
+            if rtn.is_err() {
+                self.wasm_backtrace = runtime.take_wasm_backtrace();
+            }
+            rtn?
         };
 
         let output = IndexedScryptoValue::from_vec(rtn).map_err(|e| {
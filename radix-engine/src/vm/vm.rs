@@ -5,7 +5,10 @@ use crate::system::system_callback::{SystemConfig, SystemLockData};
 use crate::system::system_callback_api::SystemCallbackObject;
 use crate::system::system_substates::KeyValueEntrySubstate;
 use crate::types::*;
-use crate::vm::wasm::{WasmEngine, WasmValidator};
+use crate::vm::wasm::{
+    InstrumentedCodeCache, WasmBacktraceFrame, WasmDebugOptions, WasmEngine, WasmInvocation,
+    WasmProfileFrame, WasmValidator,
+};
 use crate::vm::{NativeVm, NativeVmExtension, ScryptoVm};
 use radix_engine_interface::api::field_api::LockFlags;
 use radix_engine_interface::api::ClientApi;
@@ -94,11 +97,14 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                         .expect(&format!("Original code not found: {:?}", export))
                 };
 
-                let mut vm_instance = api
-                    .kernel_get_system()
-                    .callback_obj
-                    .native_vm
-                    .create_instance(address, &original_code.into_latest().code)?;
+                let mut vm_instance = {
+                    let callback_obj = &api.kernel_get_system().callback_obj;
+                    callback_obj.native_vm.create_instance(
+                        address,
+                        &original_code.into_latest().code,
+                        callback_obj.scrypto_vm.instrumented_code_cache.clone(),
+                    )?
+                };
                 let output = { vm_instance.invoke(export.export_name.as_str(), input, api)? };
 
                 output
@@ -128,6 +134,20 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                         .into_latest()
                 };
 
+                let debug_options = {
+                    let modules = &mut api.kernel_get_system().modules;
+                    WasmDebugOptions {
+                        profiling: modules
+                            .costing_mut()
+                            .map(|costing| costing.enable_wasm_profiling)
+                            .unwrap_or(false),
+                        backtraces: modules
+                            .transaction_runtime_mut()
+                            .map(|runtime| runtime.enable_wasm_backtraces)
+                            .unwrap_or(false),
+                    }
+                };
+
                 let mut scrypto_vm_instance = {
                     api.kernel_get_system()
                         .callback_obj
@@ -136,6 +156,7 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                             address,
                             export.code_hash,
                             &instrumented_code.instrumented_code,
+                            debug_options,
                         )
                 };
 
@@ -143,10 +164,48 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                     size: instrumented_code.instrumented_code.len(),
                 })?;
 
-                let output =
-                    { scrypto_vm_instance.invoke(export.export_name.as_str(), input, api)? };
+                if debug_options.profiling {
+                    if let Some(costing) = api.kernel_get_system().modules.costing_mut() {
+                        costing.wasm_invocation_stack.push(WasmInvocation {
+                            package_address: *address,
+                            code_hash: export.code_hash,
+                            export_name: export.export_name.clone(),
+                        });
+                    }
+                }
+
+                let output = scrypto_vm_instance.invoke(export.export_name.as_str(), input, api);
 
-                output
+                if debug_options.profiling {
+                    let wasm_execution_profile = scrypto_vm_instance.take_wasm_execution_profile();
+                    if let Some(costing) = api.kernel_get_system().modules.costing_mut() {
+                        for (function_index, wasm_execution_units) in wasm_execution_profile {
+                            costing.wasm_execution_profile.record(
+                                WasmProfileFrame {
+                                    invocation_stack: costing.wasm_invocation_stack.clone(),
+                                    function_index,
+                                },
+                                wasm_execution_units,
+                            );
+                        }
+                        costing.wasm_invocation_stack.pop();
+                    }
+                }
+
+                if debug_options.backtraces && output.is_err() {
+                    let function_indices = scrypto_vm_instance.take_wasm_backtrace();
+                    if let Some(runtime) = api.kernel_get_system().modules.transaction_runtime_mut()
+                    {
+                        runtime.add_wasm_backtrace_frame(WasmBacktraceFrame {
+                            package_address: *address,
+                            code_hash: export.code_hash,
+                            export_name: export.export_name.clone(),
+                            function_indices,
+                        });
+                    }
+                }
+
+                output?
             }
         };
 
@@ -173,19 +232,26 @@ impl VmPackageValidation {
         definition: &PackageDefinition,
         vm_type: VmType,
         code: &[u8],
+        instrumented_code_cache: Option<&dyn InstrumentedCodeCache>,
     ) -> Result<Option<Vec<u8>>, RuntimeError> {
         match vm_type {
             VmType::Native => Ok(None),
             VmType::ScryptoV1 => {
                 // Validate WASM
-                let instrumented_code = WasmValidator::default()
-                    .validate(&code, definition.blueprints.values())
-                    .map_err(|e| {
-                        RuntimeError::ApplicationError(ApplicationError::PackageError(
-                            PackageError::InvalidWasm(e),
-                        ))
-                    })?
-                    .0;
+                let validator = WasmValidator::default();
+                let instrumented_code = match instrumented_code_cache {
+                    Some(cache) => {
+                        validator.validate_with_cache(&code, definition.blueprints.values(), cache)
+                    }
+                    None => validator
+                        .validate(&code, definition.blueprints.values())
+                        .map(|(instrumented_code, _)| instrumented_code),
+                }
+                .map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::PackageError(
+                        PackageError::InvalidWasm(e),
+                    ))
+                })?;
 
                 for BlueprintDefinitionInit {
                     is_transient,
@@ -0,0 +1,392 @@
+use crate::types::*;
+use crate::vm::wasm::binary::*;
+use crate::vm::wasm::{constants::*, PrepareError};
+use radix_engine_interface::blueprints::package::CodeHash;
+use wasmparser::{CodeSectionReader, Operator};
+
+/// The wasm call stack of a failed invocation of a package.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct WasmBacktraceFrame {
+    pub package_address: PackageAddress,
+    pub code_hash: CodeHash,
+    /// The export being invoked, i.e. the blueprint function or method
+    pub export_name: String,
+    /// The indexes of the functions on the call stack within the instrumented code, innermost
+    /// first
+    pub function_indices: Vec<u32>,
+}
+
+/// The wasm call stacks of the invocations which failed a transaction.
+/// Available if `ExecutionConfig::enable_wasm_backtraces` is enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, ScryptoSbor)]
+pub struct WasmBacktrace {
+    /// The failed invocations, innermost first
+    pub frames: Vec<WasmBacktraceFrame>,
+}
+
+impl WasmBacktrace {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn push(&mut self, frame: WasmBacktraceFrame) {
+        self.frames.push(frame);
+    }
+
+    /// Iterates over the functions on the call stacks, innermost first.
+    pub fn functions(&self) -> impl Iterator<Item = (&WasmBacktraceFrame, u32)> {
+        self.frames.iter().flat_map(|frame| {
+            frame
+                .function_indices
+                .iter()
+                .map(move |function_index| (frame, *function_index))
+        })
+    }
+}
+
+/// Rewrites instrumented code so that every function reports entering and leaving it to the
+/// host, which can then tell the wasm call stack at the point of a trap.
+///
+/// This adds two function imports, shifting the indexes of all functions defined by the code;
+/// the reported indexes are the ones of the code before the rewrite. Only function calls to the
+/// host are added, so the code consumes the same wasm execution units.
+pub fn inject_backtraces(instrumented_code: &[u8]) -> Result<Vec<u8>, PrepareError> {
+    let sections = ModuleSections::parse(instrumented_code)?;
+    let section = |id: u8| {
+        sections
+            .section(id)
+            .map(|range| &instrumented_code[range])
+            .ok_or(PrepareError::ModuleInfoError(format!(
+                "Missing section with id {}",
+                id
+            )))
+    };
+
+    let imported_functions = sections.imported_functions()?.len() as u32;
+    let enter_function_index = imported_functions;
+    let exit_function_index = imported_functions + 1;
+    let shift = |function_index: u32| {
+        if function_index >= imported_functions {
+            function_index + 2
+        } else {
+            function_index
+        }
+    };
+
+    // Add the types of the host functions, and keep the results of every type
+    let (type_section, enter_type_index, exit_type_index, type_results) = {
+        let mut reader = LebReader::new(section(SECTION_ID_TYPE)?);
+        let count = reader.read_u32()?;
+        let mut type_results = Vec::new();
+        let mut content = encode_u32(count + 2);
+        let start = reader.remaining();
+        for _ in 0..count {
+            if reader.read_u8()? != 0x60 {
+                return Err(PrepareError::DeserializationError);
+            }
+            let params = reader.read_u32()? as usize;
+            if reader.remaining().len() < params {
+                return Err(PrepareError::DeserializationError);
+            }
+            reader.skip(params);
+            let results = reader.read_u32()? as usize;
+            if reader.remaining().len() < results {
+                return Err(PrepareError::DeserializationError);
+            }
+            type_results.push(reader.remaining()[..results].to_vec());
+            reader.skip(results);
+        }
+        content.extend_from_slice(&start[..start.len() - reader.remaining().len()]);
+        content.extend_from_slice(&[0x60, 0x01, 0x7f, 0x00]);
+        content.extend_from_slice(&[0x60, 0x00, 0x00]);
+        (content, count, count + 1, type_results)
+    };
+
+    // Import the host functions, after all other function imports
+    let import_section = {
+        let mut reader = LebReader::new(section(SECTION_ID_IMPORT)?);
+        let count = reader.read_u32()?;
+        let mut content = encode_u32(count + 2);
+        content.extend_from_slice(reader.remaining());
+        for (name, type_index) in [
+            (WASM_BACKTRACE_ENTER_FUNCTION_NAME, enter_type_index),
+            (WASM_BACKTRACE_EXIT_FUNCTION_NAME, exit_type_index),
+        ] {
+            content.extend(encode_u32(MODULE_ENV_NAME.len() as u32));
+            content.extend_from_slice(MODULE_ENV_NAME.as_bytes());
+            content.extend(encode_u32(name.len() as u32));
+            content.extend_from_slice(name.as_bytes());
+            content.push(0x00);
+            content.extend(encode_u32(type_index));
+        }
+        content
+    };
+
+    let function_types = {
+        let mut reader = LebReader::new(section(SECTION_ID_FUNCTION)?);
+        let count = reader.read_u32()?;
+        let mut function_types = Vec::new();
+        for _ in 0..count {
+            function_types.push(reader.read_u32()?);
+        }
+        function_types
+    };
+
+    let export_section = {
+        let mut reader = LebReader::new(section(SECTION_ID_EXPORT)?);
+        let count = reader.read_u32()?;
+        let mut content = encode_u32(count);
+        for _ in 0..count {
+            let name_len = reader.read_u32()? as usize;
+            if reader.remaining().len() < name_len {
+                return Err(PrepareError::DeserializationError);
+            }
+            content.extend(encode_u32(name_len as u32));
+            content.extend_from_slice(&reader.remaining()[..name_len]);
+            reader.skip(name_len);
+            let kind = reader.read_u8()?;
+            let index = reader.read_u32()?;
+            content.push(kind);
+            content.extend(encode_u32(if kind == 0x00 { shift(index) } else { index }));
+        }
+        content
+    };
+
+    let start_section = match sections.section(SECTION_ID_START) {
+        Some(range) => {
+            let mut reader = LebReader::new(&instrumented_code[range]);
+            Some(encode_u32(shift(reader.read_u32()?)))
+        }
+        None => None,
+    };
+
+    let element_section = match sections.section(SECTION_ID_ELEMENT) {
+        Some(range) => {
+            let mut reader = LebReader::new(&instrumented_code[range]);
+            let count = reader.read_u32()?;
+            let mut content = encode_u32(count);
+            for _ in 0..count {
+                // Only the MVP encoding of active segments for table 0 is supported
+                if reader.read_u32()? != 0 {
+                    return Err(PrepareError::ModuleInfoError(
+                        "Unsupported element segment".to_string(),
+                    ));
+                }
+                content.extend(encode_u32(0));
+                let offset_expr = reader.remaining();
+                match reader.read_u8()? {
+                    // i32.const and global.get, with a LEB128 immediate
+                    0x41 | 0x23 => reader.read_u32()?,
+                    _ => {
+                        return Err(PrepareError::ModuleInfoError(
+                            "Unsupported element segment offset".to_string(),
+                        ))
+                    }
+                };
+                if reader.read_u8()? != 0x0b {
+                    return Err(PrepareError::DeserializationError);
+                }
+                content.extend_from_slice(
+                    &offset_expr[..offset_expr.len() - reader.remaining().len()],
+                );
+                let functions = reader.read_u32()?;
+                content.extend(encode_u32(functions));
+                for _ in 0..functions {
+                    content.extend(encode_u32(shift(reader.read_u32()?)));
+                }
+            }
+            Some(content)
+        }
+        None => None,
+    };
+
+    // Wrap the body of every function in a block, so that it can report leaving the function
+    // on any path that doesn't trap
+    let code_section = {
+        let range = sections
+            .section(SECTION_ID_CODE)
+            .ok_or(PrepareError::ModuleInfoError(
+                "Missing code section".to_string(),
+            ))?;
+        let reader = CodeSectionReader::new(&instrumented_code[range.clone()], range.start)
+            .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+        let mut content = encode_u32(reader.count());
+        for (defined_index, body) in reader.into_iter().enumerate() {
+            let body = body.map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+            let function_index = imported_functions + defined_index as u32;
+
+            let block_type = match function_types
+                .get(defined_index)
+                .and_then(|type_index| type_results.get(*type_index as usize))
+                .map(|results| results.as_slice())
+            {
+                Some([]) => 0x40,
+                Some([result]) => *result,
+                _ => {
+                    return Err(PrepareError::ModuleInfoError(
+                        "Unsupported function type".to_string(),
+                    ))
+                }
+            };
+
+            let body_range = body.range();
+            let mut operators = body
+                .get_operators_reader()
+                .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+
+            // Locals
+            let mut new_body =
+                instrumented_code[body_range.start..operators.original_position()].to_vec();
+
+            // Prologue
+            new_body.push(0x41);
+            new_body.extend(encode_i32(function_index as i32));
+            new_body.push(0x10);
+            new_body.extend(encode_u32(enter_function_index));
+            new_body.push(0x02);
+            new_body.push(block_type);
+
+            let mut copied_up_to = operators.original_position();
+            let mut depth = 0u32;
+            while !operators.eof() {
+                let (operator, offset) = operators
+                    .read_with_offset()
+                    .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+                let replacement = match operator {
+                    Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => {
+                        depth += 1;
+                        None
+                    }
+                    Operator::End if depth > 0 => {
+                        depth -= 1;
+                        None
+                    }
+                    // The end of the function
+                    Operator::End => {
+                        let mut epilogue = vec![0x0b, 0x10];
+                        epilogue.extend(encode_u32(exit_function_index));
+                        epilogue.push(0x0b);
+                        Some(epilogue)
+                    }
+                    Operator::Call { function_index } => {
+                        let mut call = vec![0x10];
+                        call.extend(encode_u32(shift(function_index)));
+                        Some(call)
+                    }
+                    // Branch to the end of the wrapping block instead
+                    Operator::Return => {
+                        let mut br = vec![0x0c];
+                        br.extend(encode_u32(depth));
+                        Some(br)
+                    }
+                    _ => None,
+                };
+                if let Some(replacement) = replacement {
+                    new_body.extend_from_slice(&instrumented_code[copied_up_to..offset]);
+                    new_body.extend(replacement);
+                    copied_up_to = operators.original_position();
+                }
+            }
+            new_body.extend_from_slice(&instrumented_code[copied_up_to..body_range.end]);
+
+            content.extend(encode_u32(new_body.len() as u32));
+            content.extend(new_body);
+        }
+        content
+    };
+
+    let mut code = instrumented_code[..8].to_vec();
+    for (id, range) in &sections.sections {
+        let content = match *id {
+            SECTION_ID_TYPE => type_section.clone(),
+            SECTION_ID_IMPORT => import_section.clone(),
+            SECTION_ID_EXPORT => export_section.clone(),
+            SECTION_ID_START => start_section.clone().unwrap(),
+            SECTION_ID_ELEMENT => element_section.clone().unwrap(),
+            SECTION_ID_CODE => code_section.clone(),
+            _ => instrumented_code[range.clone()].to_vec(),
+        };
+        push_section(&mut code, *id, &content);
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::wasm::WasmiModule;
+    use wabt::{wat2wasm, wat2wasm_with_features, Features};
+
+    #[test]
+    fn test_inject_backtraces_produces_a_valid_module() {
+        let code = wat2wasm(
+            r#"
+            (module
+                (import "env" "gas" (func $gas (param i64)))
+                (memory $0 1)
+                (export "memory" (memory $0))
+                (table 1 funcref)
+                (elem (i32.const 0) $helper)
+                (func $helper (param $0 i32) (result i32)
+                    (call $gas (i64.const 3))
+                    (if (local.get $0)
+                        (then (return (i32.const 1)))
+                    )
+                    (i32.const 0)
+                )
+                (func $Test_f (param $0 i64) (result i64)
+                    (drop (call $helper (i32.const 1)))
+                    (drop (call_indirect (param i32) (result i32) (i32.const 0) (i32.const 0)))
+                    (i64.const 0)
+                )
+                (export "Test_f" (func $Test_f))
+            )
+            "#,
+        )
+        .unwrap();
+
+        let code = inject_backtraces(&code).unwrap();
+
+        assert!(WasmiModule::new(&code).is_ok());
+        let sections = ModuleSections::parse(&code).unwrap();
+        assert_eq!(
+            sections.imported_functions().unwrap(),
+            vec![
+                (MODULE_ENV_NAME.to_string(), "gas".to_string()),
+                (
+                    MODULE_ENV_NAME.to_string(),
+                    WASM_BACKTRACE_ENTER_FUNCTION_NAME.to_string()
+                ),
+                (
+                    MODULE_ENV_NAME.to_string(),
+                    WASM_BACKTRACE_EXIT_FUNCTION_NAME.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inject_backtraces_rejects_multi_value_functions() {
+        let mut features = Features::new();
+        features.enable_multi_value();
+        let code = wat2wasm_with_features(
+            r#"
+            (module
+                (import "env" "gas" (func $gas (param i64)))
+                (func (export "f") (result i32 i32)
+                    (i32.const 0)
+                    (i32.const 0)
+                )
+            )
+            "#,
+            features,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            inject_backtraces(&code),
+            Err(PrepareError::ModuleInfoError(..))
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::types::*;
+use crate::vm::wasm::PrepareError;
+use sbor::rust::ops::Range;
+use wasmparser::{ImportSectionReader, Name, NameSectionReader, TypeRef};
+
+pub(super) const SECTION_ID_CUSTOM: u8 = 0;
+pub(super) const SECTION_ID_TYPE: u8 = 1;
+pub(super) const SECTION_ID_IMPORT: u8 = 2;
+pub(super) const SECTION_ID_FUNCTION: u8 = 3;
+pub(super) const SECTION_ID_GLOBAL: u8 = 6;
+pub(super) const SECTION_ID_EXPORT: u8 = 7;
+pub(super) const SECTION_ID_START: u8 = 8;
+pub(super) const SECTION_ID_ELEMENT: u8 = 9;
+pub(super) const SECTION_ID_CODE: u8 = 10;
+
+pub(super) fn push_section(code: &mut Vec<u8>, id: u8, content: &[u8]) {
+    code.push(id);
+    code.extend(encode_u32(content.len() as u32));
+    code.extend_from_slice(content);
+}
+
+/// The top-level sections of a module, as (id, content range) pairs.
+pub(super) struct ModuleSections<'a> {
+    pub(super) code: &'a [u8],
+    pub(super) sections: Vec<(u8, Range<usize>)>,
+}
+
+impl<'a> ModuleSections<'a> {
+    pub(super) fn parse(code: &'a [u8]) -> Result<Self, PrepareError> {
+        if code.len() < 8 || &code[..4] != b"\0asm" {
+            return Err(PrepareError::DeserializationError);
+        }
+
+        let mut sections = Vec::new();
+        let mut reader = LebReader::new(&code[8..]);
+        while !reader.remaining().is_empty() {
+            let id = reader.read_u8()?;
+            let size = reader.read_u32()? as usize;
+            let start = code.len() - reader.remaining().len();
+            if start + size > code.len() {
+                return Err(PrepareError::DeserializationError);
+            }
+            reader.skip(size);
+            sections.push((id, start..start + size));
+        }
+
+        Ok(Self { code, sections })
+    }
+
+    pub(super) fn section(&self, id: u8) -> Option<Range<usize>> {
+        self.sections
+            .iter()
+            .find(|(section_id, _)| *section_id == id)
+            .map(|(_, range)| range.clone())
+    }
+
+    pub(super) fn imports(&self) -> Result<Vec<(String, String, TypeRef)>, PrepareError> {
+        let mut imports = Vec::new();
+        if let Some(range) = self.section(SECTION_ID_IMPORT) {
+            let reader = ImportSectionReader::new(&self.code[range.clone()], range.start)
+                .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+            for import in reader {
+                let import = import.map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+                imports.push((
+                    import.module.to_string(),
+                    import.name.to_string(),
+                    import.ty,
+                ));
+            }
+        }
+        Ok(imports)
+    }
+
+    pub(super) fn imported_functions(&self) -> Result<Vec<(String, String)>, PrepareError> {
+        Ok(self
+            .imports()?
+            .into_iter()
+            .filter_map(|(module, name, ty)| match ty {
+                TypeRef::Func(_) => Some((module, name)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    pub(super) fn imported_globals(&self) -> Result<u32, PrepareError> {
+        Ok(self
+            .imports()?
+            .into_iter()
+            .filter(|(_, _, ty)| matches!(ty, TypeRef::Global(_)))
+            .count() as u32)
+    }
+
+    pub(super) fn function_names(&self) -> Result<IndexMap<u32, String>, PrepareError> {
+        let mut names = index_map_new();
+        for (id, range) in &self.sections {
+            if *id != SECTION_ID_CUSTOM {
+                continue;
+            }
+            let mut reader = LebReader::new(&self.code[range.clone()]);
+            let name_len = reader.read_u32()? as usize;
+            if reader.remaining().len() < name_len {
+                return Err(PrepareError::DeserializationError);
+            }
+            if &reader.remaining()[..name_len] != b"name" {
+                continue;
+            }
+            reader.skip(name_len);
+            let data_offset = range.end - reader.remaining().len();
+
+            for subsection in NameSectionReader::new(reader.remaining(), data_offset) {
+                let subsection =
+                    subsection.map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+                if let Name::Function(map) = subsection {
+                    for naming in map {
+                        let naming =
+                            naming.map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+                        names.insert(naming.index, naming.name.to_string());
+                    }
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+pub(super) struct LebReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LebReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(super) fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub(super) fn skip(&mut self, n: usize) {
+        self.data = &self.data[n..];
+    }
+
+    pub(super) fn read_u8(&mut self) -> Result<u8, PrepareError> {
+        let byte = *self
+            .data
+            .first()
+            .ok_or(PrepareError::DeserializationError)?;
+        self.skip(1);
+        Ok(byte)
+    }
+
+    pub(super) fn read_u32(&mut self) -> Result<u32, PrepareError> {
+        let mut result = 0u32;
+        for shift in (0..35).step_by(7) {
+            let byte = self.read_u8()?;
+            result |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(PrepareError::DeserializationError)
+    }
+}
+
+pub(super) fn encode_u32(mut value: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+pub(super) fn encode_i32(mut value: i32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leb128_encoding() {
+        assert_eq!(encode_u32(0), vec![0x00]);
+        assert_eq!(encode_u32(624485), vec![0xe5, 0x8e, 0x26]);
+        assert_eq!(encode_i32(63), vec![0x3f]);
+        assert_eq!(encode_i32(64), vec![0xc0, 0x00]);
+        assert_eq!(
+            LebReader::new(&[0xe5, 0x8e, 0x26]).read_u32().unwrap(),
+            624485
+        );
+    }
+}
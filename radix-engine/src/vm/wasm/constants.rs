@@ -77,6 +77,18 @@ pub const SYS_GET_TRANSACTION_HASH_FUNCTION_NAME: &str = "sys_get_transaction_ha
 pub const SYS_GENERATE_RUID_FUNCTION_NAME: &str = "sys_generate_ruid";
 pub const SYS_PANIC_FUNCTION_NAME: &str = "sys_panic";
 
+//=================
+// Crypto Utils
+//=================
+pub const CRYPTO_UTILS_BLAKE2B_256_HASH_FUNCTION_NAME: &str = "crypto_utils_blake2b_256_hash";
+pub const CRYPTO_UTILS_KECCAK256_HASH_FUNCTION_NAME: &str = "crypto_utils_keccak256_hash";
+pub const CRYPTO_UTILS_SHA256_HASH_FUNCTION_NAME: &str = "crypto_utils_sha256_hash";
+pub const CRYPTO_UTILS_ED25519_VERIFY_FUNCTION_NAME: &str = "crypto_utils_ed25519_verify";
+pub const CRYPTO_UTILS_SECP256K1_VERIFY_FUNCTION_NAME: &str = "crypto_utils_secp256k1_verify";
+pub const CRYPTO_UTILS_SECP256K1_RECOVER_FUNCTION_NAME: &str = "crypto_utils_secp256k1_recover";
+pub const CRYPTO_UTILS_BLS12381_V1_AGGREGATE_VERIFY_FUNCTION_NAME: &str =
+    "crypto_utils_bls12381_v1_aggregate_verify";
+
 //=================
 // WASM Shim
 //=================
@@ -84,3 +96,17 @@ pub const BUFFER_CONSUME_FUNCTION_NAME: &str = "buffer_consume";
 
 pub const MODULE_ENV_NAME: &str = "env";
 pub const EXPORT_MEMORY: &str = "memory";
+
+//=================
+// Profiling
+//=================
+/// The global holding the index of the function being metered, exported by profiled code
+pub const WASM_PROFILING_FUNCTION_INDEX_EXPORT_NAME: &str = "__radix_profiling_function_index";
+
+//=================
+// Backtraces
+//=================
+/// Host functions imported by code instantiated with backtraces, reporting entering and leaving
+/// a function
+pub const WASM_BACKTRACE_ENTER_FUNCTION_NAME: &str = "__radix_backtrace_enter";
+pub const WASM_BACKTRACE_EXIT_FUNCTION_NAME: &str = "__radix_backtrace_exit";
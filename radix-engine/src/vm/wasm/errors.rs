@@ -153,6 +153,21 @@ pub enum WasmRuntimeError {
     InvalidPackageAddress,
 
     TooManyBuffers,
+
+    /// Invalid hash, e.g. a message hash of the wrong length
+    InvalidHash,
+
+    InvalidEd25519PublicKey,
+
+    InvalidEd25519Signature,
+
+    InvalidSecp256k1PublicKey,
+
+    InvalidSecp256k1Signature,
+
+    InvalidBls12381G2Signature,
+
+    InvalidBls12381PublicKeysAndMessages(DecodeError),
 }
 
 impl SelfError for WasmRuntimeError {
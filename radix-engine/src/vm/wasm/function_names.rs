@@ -0,0 +1,198 @@
+use crate::types::*;
+use crate::vm::wasm::binary::ModuleSections;
+
+/// The names of the functions of instrumented code, recovered from the `name` custom section
+/// of the original code (instrumentation drops custom sections).
+///
+/// Packages are usually published with their names stripped; the function names sidecar written
+/// by `scrypto build --function-names` has the same functions as the published code, and can be
+/// used as the original code instead.
+///
+/// Backtraces and profiles only record functions, so there is no file or line information.
+#[derive(Debug, Clone, Default)]
+pub struct WasmFunctionNames {
+    names: IndexMap<u32, String>,
+}
+
+impl WasmFunctionNames {
+    pub fn from_code(original_code: &[u8], instrumented_code: &[u8]) -> Self {
+        let mut names = index_map_new();
+
+        let original = match ModuleSections::parse(original_code) {
+            Ok(sections) => sections,
+            Err(_) => return Self { names },
+        };
+        let instrumented = match ModuleSections::parse(instrumented_code) {
+            Ok(sections) => sections,
+            Err(_) => return Self { names },
+        };
+        let (original_imports, original_names) =
+            match (original.imported_functions(), original.function_names()) {
+                (Ok(imports), Ok(names)) => (imports, names),
+                _ => return Self { names },
+            };
+        let instrumented_imports = match instrumented.imported_functions() {
+            Ok(imports) => imports,
+            Err(_) => return Self { names },
+        };
+
+        // Imported functions are matched by their import name, as instrumentation adds imports
+        for (index, import) in instrumented_imports.iter().enumerate() {
+            let name = match original_imports.iter().position(|i| i == import) {
+                Some(original_index) => original_names
+                    .get(&(original_index as u32))
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}::{}", import.0, import.1)),
+                None => format!("{}::{}", import.0, import.1),
+            };
+            names.insert(index as u32, name);
+        }
+
+        // Defined functions keep their relative order, with any functions added by the
+        // instrumentation (e.g. stack limiter thunks) appended at the end
+        let offset = instrumented_imports.len() as u32;
+        let original_offset = original_imports.len() as u32;
+        for (original_index, name) in original_names {
+            if original_index >= original_offset {
+                names.insert(original_index - original_offset + offset, demangle(&name));
+            }
+        }
+
+        Self { names }
+    }
+
+    pub fn get(&self, function_index: u32) -> String {
+        match self.names.get(&function_index) {
+            Some(name) => name.clone(),
+            None => format!("func[{}]", function_index),
+        }
+    }
+}
+
+/// Demangles a Rust symbol name, dropping the trailing hash.
+///
+/// Only the legacy mangling scheme is supported, which is what the `name` section is produced
+/// with; other names, or names which are already demangled, are returned without the hash.
+pub fn demangle(name: &str) -> String {
+    let mangled = name
+        .strip_prefix("_ZN")
+        .or_else(|| name.strip_prefix("ZN"))
+        .and_then(|name| name.strip_suffix('E'))
+        .filter(|name| name.is_ascii());
+
+    let mut segments = Vec::new();
+    match mangled {
+        Some(mut rest) => {
+            while !rest.is_empty() {
+                let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+                let len = match rest[..digits].parse::<usize>() {
+                    Ok(len) if len <= rest.len() - digits => len,
+                    _ => return name.to_string(),
+                };
+                segments.push(demangle_segment(&rest[digits..digits + len]));
+                rest = &rest[digits + len..];
+            }
+        }
+        None => segments.extend(name.split("::").map(ToString::to_string)),
+    }
+
+    let is_hash = |segment: &String| {
+        segment.len() == 17
+            && segment.starts_with('h')
+            && segment[1..].chars().all(|c| c.is_ascii_hexdigit())
+    };
+    if segments.len() > 1 && segments.last().map_or(false, is_hash) {
+        segments.pop();
+    }
+
+    segments.join("::")
+}
+
+fn demangle_segment(segment: &str) -> String {
+    const ESCAPES: [(&str, &str); 18] = [
+        ("$SP$", "@"),
+        ("$BP$", "*"),
+        ("$RF$", "&"),
+        ("$LT$", "<"),
+        ("$GT$", ">"),
+        ("$LP$", "("),
+        ("$RP$", ")"),
+        ("$C$", ","),
+        ("$u7e$", "~"),
+        ("$u20$", " "),
+        ("$u27$", "'"),
+        ("$u3d$", "="),
+        ("$u5b$", "["),
+        ("$u5d$", "]"),
+        ("$u7b$", "{"),
+        ("$u7d$", "}"),
+        ("$u3b$", ";"),
+        ("$u2b$", "+"),
+    ];
+
+    let mut segment = segment
+        .strip_prefix("_$")
+        .map_or(segment.to_string(), |s| format!("${}", s));
+    for (escape, replacement) in ESCAPES {
+        segment = segment.replace(escape, replacement);
+    }
+    segment.replace("..", "::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wabt::{wat2wasm, Wat2Wasm};
+
+    #[test]
+    fn test_function_names_are_resolved_from_original_code() {
+        let original_code = Wat2Wasm::new()
+            .write_debug_names(true)
+            .convert(
+                r#"
+                (module
+                    (func $first)
+                    (func $second)
+                )
+                "#,
+            )
+            .unwrap()
+            .as_ref()
+            .to_vec();
+        // Instrumentation adds an import, shifting the indexes of all defined functions
+        let instrumented_code = wat2wasm(
+            r#"
+            (module
+                (import "env" "gas" (func (param i64)))
+                (func)
+                (func)
+            )
+            "#,
+        )
+        .unwrap();
+
+        let names = WasmFunctionNames::from_code(&original_code, &instrumented_code);
+
+        assert_eq!(names.get(0), "env::gas");
+        assert_eq!(names.get(1), "first");
+        assert_eq!(names.get(2), "second");
+        assert_eq!(names.get(3), "func[3]");
+    }
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN4core9panicking5panic17h0123456789abcdefE"),
+            "core::panicking::panic"
+        );
+        assert_eq!(
+            demangle("_ZN60_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..Drop$GT$4drop17h0123456789abcdefE"),
+            "<alloc::vec::Vec<T> as core::ops::Drop>::drop"
+        );
+        assert_eq!(
+            demangle("hello::Hello::free_token::h0123456789abcdef"),
+            "hello::Hello::free_token"
+        );
+        assert_eq!(demangle("Hello_free_token"), "Hello_free_token");
+    }
+}
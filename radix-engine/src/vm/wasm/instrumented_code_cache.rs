@@ -0,0 +1,315 @@
+use crate::types::*;
+use crate::vm::wasm::WasmDebugOptions;
+use radix_engine_interface::blueprints::package::CodeHash;
+
+/// Identifies a piece of instrumented code: the hash of the original code, the
+/// versions of the validator and of the instrumentation config which produced it,
+/// and the debugging aids it was further instrumented with, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstrumentedCodeKey {
+    pub code_hash: CodeHash,
+    pub validator_version: u8,
+    pub config_version: u8,
+    pub debug_options: WasmDebugOptions,
+}
+
+/// A cache of instrumented WASM code, which may outlive the process.
+///
+/// It is used in two places:
+/// * When packages are published, for the output of the instrumentation. The validation
+///   passes are cheap and are always re-run on the original code (see
+///   [`WasmValidator::validate_with_cache`]), so a stale cache can never make the validator
+///   accept code it would reject.
+/// * When instantiating code with debugging aids (see [`WasmEngine::instantiate_with_debugging`]),
+///   for the code instrumented with those aids, as a second tier behind the module cache of the
+///   WASM engine.
+///
+/// Instantiating code without debugging aids doesn't consult it: the instrumented code is read
+/// from the ledger, and the compiled modules are kept in memory only.
+///
+/// The cache is best-effort: implementations are expected to swallow their own
+/// failures and simply report a miss, so that a broken cache can never fail a
+/// transaction.
+///
+/// [`WasmValidator::validate_with_cache`]: crate::vm::wasm::WasmValidator::validate_with_cache
+/// [`WasmEngine::instantiate_with_debugging`]: crate::vm::wasm::WasmEngine::instantiate_with_debugging
+pub trait InstrumentedCodeCache: Send + Sync {
+    fn get(&self, key: &InstrumentedCodeKey) -> Option<Vec<u8>>;
+
+    fn insert(&self, key: &InstrumentedCodeKey, instrumented_code: &[u8]);
+}
+
+#[cfg(not(feature = "alloc"))]
+pub use disk::*;
+
+#[cfg(not(feature = "alloc"))]
+mod disk {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    const ENTRY_MAGIC: [u8; 4] = *b"RICC";
+    const ENTRY_FORMAT_VERSION: u8 = 2;
+    const ENTRY_EXTENSION: &str = "wasm";
+    const ENTRY_HEADER_LENGTH: usize =
+        ENTRY_MAGIC.len() + 1 + 1 + 1 + 1 + Hash::LENGTH + Hash::LENGTH;
+
+    fn debug_flags(debug_options: &WasmDebugOptions) -> u8 {
+        (debug_options.profiling as u8) | ((debug_options.backtraces as u8) << 1)
+    }
+
+    /// An [`InstrumentedCodeCache`] persisted to a directory, so that it survives
+    /// process restarts.
+    ///
+    /// Every entry is a single file holding a header (magic, format version,
+    /// validator and config versions, debugging aids, code hash and a hash of the payload)
+    /// followed by the instrumented code. Entries failing the header or payload
+    /// checks are removed and treated as a miss.
+    ///
+    /// The payload hash only detects corruption. The instrumented code is used as
+    /// is, so the directory must not be writable by untrusted parties.
+    ///
+    /// The total size of the entries is bounded by `max_size_in_bytes`; once it is
+    /// exceeded, the least recently used entries are evicted.
+    pub struct WasmDiskCache {
+        directory: PathBuf,
+        max_size_in_bytes: u64,
+        // Serializes writes and evictions between threads of the same process.
+        // Entries are written to a temporary file and renamed into place, so
+        // concurrent processes sharing a directory never observe partial entries.
+        write_lock: Mutex<()>,
+    }
+
+    impl WasmDiskCache {
+        pub fn new<P: Into<PathBuf>>(
+            directory: P,
+            max_size_in_bytes: u64,
+        ) -> Result<Self, std::io::Error> {
+            let directory = directory.into();
+            fs::create_dir_all(&directory)?;
+            Ok(Self {
+                directory,
+                max_size_in_bytes,
+                write_lock: Mutex::new(()),
+            })
+        }
+
+        pub fn directory(&self) -> &Path {
+            &self.directory
+        }
+
+        fn entry_path(&self, key: &InstrumentedCodeKey) -> PathBuf {
+            self.directory.join(format!(
+                "{}-v{}.{}.{}.{}",
+                key.code_hash.0,
+                key.validator_version,
+                key.config_version,
+                debug_flags(&key.debug_options),
+                ENTRY_EXTENSION
+            ))
+        }
+
+        fn encode_entry(key: &InstrumentedCodeKey, instrumented_code: &[u8]) -> Vec<u8> {
+            let mut entry = Vec::with_capacity(ENTRY_HEADER_LENGTH + instrumented_code.len());
+            entry.extend_from_slice(&ENTRY_MAGIC);
+            entry.push(ENTRY_FORMAT_VERSION);
+            entry.push(key.validator_version);
+            entry.push(key.config_version);
+            entry.push(debug_flags(&key.debug_options));
+            entry.extend_from_slice(key.code_hash.0.as_slice());
+            entry.extend_from_slice(hash(instrumented_code).as_slice());
+            entry.extend_from_slice(instrumented_code);
+            entry
+        }
+
+        fn decode_entry(entry: &[u8], key: &InstrumentedCodeKey) -> Option<Vec<u8>> {
+            if entry.len() < ENTRY_HEADER_LENGTH {
+                return None;
+            }
+            let (header, payload) = entry.split_at(ENTRY_HEADER_LENGTH);
+            let (magic, header) = header.split_at(ENTRY_MAGIC.len());
+            let (versions, hashes) = header.split_at(4);
+            let (entry_code_hash, payload_hash) = hashes.split_at(Hash::LENGTH);
+
+            let expected_versions = [
+                ENTRY_FORMAT_VERSION,
+                key.validator_version,
+                key.config_version,
+                debug_flags(&key.debug_options),
+            ];
+            if magic != ENTRY_MAGIC
+                || versions != expected_versions
+                || entry_code_hash != key.code_hash.0.as_slice()
+                || payload_hash != hash(payload).as_slice()
+            {
+                return None;
+            }
+
+            Some(payload.to_vec())
+        }
+
+        /// Removes the least recently used entries until the total size of the
+        /// cache is within bounds.
+        fn evict(&self) {
+            let read_dir = match fs::read_dir(&self.directory) {
+                Ok(read_dir) => read_dir,
+                Err(_) => return,
+            };
+
+            let mut entries = Vec::new();
+            let mut total_size = 0u64;
+            for dir_entry in read_dir.flatten() {
+                let path = dir_entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+                    continue;
+                }
+                if let Ok(metadata) = dir_entry.metadata() {
+                    let last_used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    total_size += metadata.len();
+                    entries.push((last_used, metadata.len(), path));
+                }
+            }
+
+            entries.sort();
+            for (_, size, path) in entries {
+                if total_size <= self.max_size_in_bytes {
+                    break;
+                }
+                if fs::remove_file(&path).is_ok() {
+                    total_size -= size;
+                }
+            }
+        }
+    }
+
+    impl InstrumentedCodeCache for WasmDiskCache {
+        fn get(&self, key: &InstrumentedCodeKey) -> Option<Vec<u8>> {
+            let path = self.entry_path(key);
+            let entry = fs::read(&path).ok()?;
+
+            match Self::decode_entry(&entry, key) {
+                Some(instrumented_code) => {
+                    // Bump the modification time, which is what eviction orders by
+                    if let Ok(file) = fs::File::options().write(true).open(&path) {
+                        let _ = file.set_modified(SystemTime::now());
+                    }
+                    Some(instrumented_code)
+                }
+                None => {
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+            }
+        }
+
+        fn insert(&self, key: &InstrumentedCodeKey, instrumented_code: &[u8]) {
+            let entry = Self::encode_entry(key, instrumented_code);
+            if entry.len() as u64 > self.max_size_in_bytes {
+                return;
+            }
+
+            let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+            let path = self.entry_path(key);
+            let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+            if fs::write(&temp_path, &entry).is_err() || fs::rename(&temp_path, &path).is_err() {
+                let _ = fs::remove_file(&temp_path);
+                return;
+            }
+
+            self.evict();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn new_cache(name: &str, max_size_in_bytes: u64) -> WasmDiskCache {
+            let directory = std::env::temp_dir().join(format!(
+                "radix-engine-wasm-disk-cache-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&directory);
+            WasmDiskCache::new(directory, max_size_in_bytes).unwrap()
+        }
+
+        fn key(n: u8, config_version: u8) -> InstrumentedCodeKey {
+            InstrumentedCodeKey {
+                code_hash: CodeHash::from_hash(hash([n])),
+                validator_version: 1,
+                config_version,
+                debug_options: WasmDebugOptions::default(),
+            }
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let cache = new_cache("round-trip", 1024 * 1024);
+
+            assert_eq!(cache.get(&key(1, 1)), None);
+            cache.insert(&key(1, 1), &[1, 2, 3]);
+            assert_eq!(cache.get(&key(1, 1)), Some(vec![1, 2, 3]));
+            assert_eq!(cache.get(&key(1, 2)), None);
+            assert_eq!(
+                cache.get(&InstrumentedCodeKey {
+                    validator_version: 2,
+                    ..key(1, 1)
+                }),
+                None
+            );
+            assert_eq!(
+                cache.get(&InstrumentedCodeKey {
+                    debug_options: WasmDebugOptions {
+                        profiling: true,
+                        backtraces: false,
+                    },
+                    ..key(1, 1)
+                }),
+                None
+            );
+            assert_eq!(cache.get(&key(2, 1)), None);
+
+            fs::remove_dir_all(cache.directory()).unwrap();
+        }
+
+        #[test]
+        fn test_corrupted_entry_is_removed() {
+            let cache = new_cache("corrupted", 1024 * 1024);
+            cache.insert(&key(1, 1), &[1, 2, 3]);
+
+            let path = cache.entry_path(&key(1, 1));
+            let mut entry = fs::read(&path).unwrap();
+            *entry.last_mut().unwrap() ^= 0xff;
+            fs::write(&path, entry).unwrap();
+
+            assert_eq!(cache.get(&key(1, 1)), None);
+            assert!(!path.exists());
+
+            fs::remove_dir_all(cache.directory()).unwrap();
+        }
+
+        #[test]
+        fn test_least_recently_used_entries_are_evicted() {
+            let entry_size = (ENTRY_HEADER_LENGTH + 100) as u64;
+            let cache = new_cache("eviction", entry_size * 2);
+
+            cache.insert(&key(1, 1), &[1u8; 100]);
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            cache.insert(&key(2, 1), &[2u8; 100]);
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            assert!(cache.get(&key(1, 1)).is_some());
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            cache.insert(&key(3, 1), &[3u8; 100]);
+
+            assert!(cache.get(&key(1, 1)).is_some());
+            assert!(cache.get(&key(2, 1)).is_none());
+            assert!(cache.get(&key(3, 1)).is_some());
+
+            fs::remove_dir_all(cache.directory()).unwrap();
+        }
+    }
+}
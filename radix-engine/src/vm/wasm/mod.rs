@@ -1,6 +1,11 @@
+mod backtrace;
+mod binary;
 mod constants;
 mod errors;
+mod function_names;
+mod instrumented_code_cache;
 mod prepare;
+mod profiling;
 mod traits;
 mod wasm_validator;
 mod wasm_validator_config;
@@ -12,9 +17,13 @@ mod weights;
 #[cfg(feature = "wasmer")]
 pub use self::wasmer::*;
 pub use self::wasmi::*;
+pub use backtrace::*;
 pub use constants::*;
 pub use errors::*;
+pub use function_names::*;
+pub use instrumented_code_cache::*;
 pub use prepare::*;
+pub use profiling::*;
 pub use traits::*;
 pub use wasm_validator::*;
 pub use wasm_validator_config::*;
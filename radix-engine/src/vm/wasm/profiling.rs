@@ -0,0 +1,271 @@
+use crate::types::*;
+use crate::vm::wasm::binary::*;
+use crate::vm::wasm::function_names::WasmFunctionNames;
+use crate::vm::wasm::{constants::*, PrepareError};
+use radix_engine_interface::blueprints::package::CodeHash;
+use wasmparser::{CodeSectionReader, Operator};
+
+/// An invocation of an export of the code of a package.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, ScryptoSbor)]
+pub struct WasmInvocation {
+    pub package_address: PackageAddress,
+    pub code_hash: CodeHash,
+    /// The export being invoked, i.e. the blueprint function or method
+    pub export_name: String,
+}
+
+/// A function of the code of a package, as seen by the wasm execution profiler.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, ScryptoSbor)]
+pub struct WasmProfileFrame {
+    /// The wasm invocations leading to the function, outermost first. The last one is the
+    /// invocation running the function; the others are its (transitive) callers, across
+    /// blueprint and component calls.
+    pub invocation_stack: Vec<WasmInvocation>,
+    /// The index of the function within the instrumented code of the last invocation
+    pub function_index: u32,
+}
+
+impl WasmProfileFrame {
+    pub fn invocation(&self) -> &WasmInvocation {
+        self.invocation_stack
+            .last()
+            .expect("A profile frame has at least one invocation")
+    }
+}
+
+/// Wasm execution units consumed per function.
+/// Available if `ExecutionConfig::enable_wasm_profiling` is enabled.
+///
+/// The units of a frame are only those consumed by the function itself, so that the units
+/// of nested invocations are not counted twice.
+#[derive(Debug, Clone, Default, PartialEq, Eq, ScryptoSbor)]
+pub struct WasmExecutionProfile {
+    pub wasm_execution_units: BTreeMap<WasmProfileFrame, u64>,
+}
+
+impl WasmExecutionProfile {
+    pub fn is_empty(&self) -> bool {
+        self.wasm_execution_units.is_empty()
+    }
+
+    pub fn record(&mut self, frame: WasmProfileFrame, wasm_execution_units: u64) {
+        *self.wasm_execution_units.entry(frame).or_default() += wasm_execution_units;
+    }
+
+    /// Renders the profile in the folded-stack format consumed by flamegraph tools, i.e. one
+    /// `<package>::<export>;...;<package>::<export>;<function> <units>` line per function,
+    /// nested by the invocation stack.
+    ///
+    /// Calls between the functions of a single invocation are not tracked, so each function
+    /// is a direct child of the invocation running it.
+    ///
+    /// Function names are resolved through `resolve_names`, given the package address and the
+    /// code hash; functions that can't be resolved are rendered by index.
+    pub fn to_folded_stacks<F>(&self, mut resolve_names: F) -> String
+    where
+        F: FnMut(&PackageAddress, &CodeHash) -> Option<WasmFunctionNames>,
+    {
+        let mut names: IndexMap<(PackageAddress, CodeHash), Option<WasmFunctionNames>> =
+            index_map_new();
+        let mut folded = String::new();
+        for (frame, units) in &self.wasm_execution_units {
+            let invocation = frame.invocation();
+            let function_names = names
+                .entry((invocation.package_address, invocation.code_hash))
+                .or_insert_with(|| {
+                    resolve_names(&invocation.package_address, &invocation.code_hash)
+                });
+            let function_name = match function_names {
+                Some(function_names) => function_names.get(frame.function_index),
+                None => format!("func[{}]", frame.function_index),
+            };
+            for invocation in &frame.invocation_stack {
+                folded.push_str(&format!(
+                    "{}::{};",
+                    invocation.package_address.to_hex(),
+                    invocation.export_name
+                ));
+            }
+            folded.push_str(&format!("{} {}\n", function_name.replace(';', ":"), units));
+        }
+        folded
+    }
+}
+
+/// Rewrites instrumented code so that every call to the wasm execution metering function is
+/// preceded by storing the index of the calling function into an exported global, which lets
+/// the host attribute the consumed units to a function.
+///
+/// The metering itself is untouched, so profiled code consumes the same units.
+pub fn inject_profiling(instrumented_code: &[u8]) -> Result<Vec<u8>, PrepareError> {
+    let sections = ModuleSections::parse(instrumented_code)?;
+
+    let imported_functions = sections.imported_functions()?;
+    let metering_function_index = imported_functions
+        .iter()
+        .position(|(module, name)| {
+            module == MODULE_ENV_NAME && name == COSTING_CONSUME_WASM_EXECUTION_UNITS_FUNCTION_NAME
+        })
+        .ok_or(PrepareError::ModuleInfoError(
+            "Code is not instrumented for metering".to_string(),
+        ))? as u32;
+    let imported_globals = sections.imported_globals()?;
+
+    // Add the mutable i32 global holding the index of the function being metered
+    let (global_section, profiling_global_index) = match sections.section(SECTION_ID_GLOBAL) {
+        Some(range) => {
+            let mut reader = LebReader::new(&instrumented_code[range.clone()]);
+            let count = reader.read_u32()?;
+            let mut content = encode_u32(count + 1);
+            content.extend_from_slice(reader.remaining());
+            content.extend_from_slice(&[0x7f, 0x01, 0x41, 0x00, 0x0b]);
+            (content, imported_globals + count)
+        }
+        None => {
+            let mut content = encode_u32(1);
+            content.extend_from_slice(&[0x7f, 0x01, 0x41, 0x00, 0x0b]);
+            (content, imported_globals)
+        }
+    };
+
+    // Export it, so that host functions can read it
+    let export_section = {
+        let range = sections
+            .section(SECTION_ID_EXPORT)
+            .ok_or(PrepareError::NoExportSection)?;
+        let mut reader = LebReader::new(&instrumented_code[range.clone()]);
+        let count = reader.read_u32()?;
+        let mut content = encode_u32(count + 1);
+        content.extend_from_slice(reader.remaining());
+        content.extend(encode_u32(
+            WASM_PROFILING_FUNCTION_INDEX_EXPORT_NAME.len() as u32
+        ));
+        content.extend_from_slice(WASM_PROFILING_FUNCTION_INDEX_EXPORT_NAME.as_bytes());
+        content.push(0x03);
+        content.extend(encode_u32(profiling_global_index));
+        content
+    };
+
+    // Set the global before every call to the metering function
+    let code_section = {
+        let range = sections
+            .section(SECTION_ID_CODE)
+            .ok_or(PrepareError::ModuleInfoError(
+                "Missing code section".to_string(),
+            ))?;
+        let reader = CodeSectionReader::new(&instrumented_code[range.clone()], range.start)
+            .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+        let mut content = encode_u32(reader.count());
+        for (defined_index, body) in reader.into_iter().enumerate() {
+            let body = body.map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+            let function_index = (imported_functions.len() + defined_index) as u32;
+
+            let mut set_global = vec![0x41];
+            set_global.extend(encode_i32(function_index as i32));
+            set_global.push(0x24);
+            set_global.extend(encode_u32(profiling_global_index));
+
+            let body_range = body.range();
+            let mut new_body = Vec::with_capacity(body_range.len());
+            let mut copied_up_to = body_range.start;
+            let mut operators = body
+                .get_operators_reader()
+                .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+            while !operators.eof() {
+                let (operator, offset) = operators
+                    .read_with_offset()
+                    .map_err(|e| PrepareError::WasmParserError(e.to_string()))?;
+                if let Operator::Call { function_index } = operator {
+                    if function_index == metering_function_index {
+                        new_body.extend_from_slice(&instrumented_code[copied_up_to..offset]);
+                        new_body.extend_from_slice(&set_global);
+                        copied_up_to = offset;
+                    }
+                }
+            }
+            new_body.extend_from_slice(&instrumented_code[copied_up_to..body_range.end]);
+
+            content.extend(encode_u32(new_body.len() as u32));
+            content.extend(new_body);
+        }
+        content
+    };
+
+    // Reassemble the module, inserting the global section if there was none
+    let mut code = instrumented_code[..8].to_vec();
+    let mut global_section = Some(global_section);
+    for (id, range) in &sections.sections {
+        let content = match *id {
+            SECTION_ID_GLOBAL => global_section.take().unwrap(),
+            SECTION_ID_EXPORT => {
+                if let Some(global_section) = global_section.take() {
+                    push_section(&mut code, SECTION_ID_GLOBAL, &global_section);
+                }
+                export_section.clone()
+            }
+            SECTION_ID_CODE => code_section.clone(),
+            _ => instrumented_code[range.clone()].to_vec(),
+        };
+        push_section(&mut code, *id, &content);
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::wasm::WasmiModule;
+    use wabt::wat2wasm;
+    use wasmparser::{ExportSectionReader, ExternalKind};
+
+    const METERED_MODULE: &str = r#"
+        (module
+            (import "env" "gas" (func $gas (param i64)))
+            (memory $0 1)
+            (export "memory" (memory $0))
+            (global $g (mut i32) (i32.const 0))
+            (func $helper
+                (call $gas (i64.const 3))
+            )
+            (func $Test_f (param $0 i64) (result i64)
+                (call $gas (i64.const 5))
+                (call $helper)
+                (i64.const 0)
+            )
+            (export "Test_f" (func $Test_f))
+        )
+        "#;
+
+    #[test]
+    fn test_inject_profiling_produces_a_valid_module() {
+        let code = wat2wasm(METERED_MODULE).unwrap();
+
+        let profiled = inject_profiling(&code).unwrap();
+
+        assert!(WasmiModule::new(&profiled).is_ok());
+        let sections = ModuleSections::parse(&profiled).unwrap();
+        let range = sections.section(SECTION_ID_EXPORT).unwrap();
+        let exports: Vec<_> = ExportSectionReader::new(&profiled[range.clone()], range.start)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.unwrap())
+            .map(|e| (e.name.to_string(), e.kind, e.index))
+            .collect();
+        assert!(exports.contains(&(
+            WASM_PROFILING_FUNCTION_INDEX_EXPORT_NAME.to_string(),
+            ExternalKind::Global,
+            1
+        )));
+    }
+
+    #[test]
+    fn test_inject_profiling_rejects_unmetered_code() {
+        let code = wat2wasm("(module (func (export \"f\")))").unwrap();
+
+        assert!(matches!(
+            inject_profiling(&code),
+            Err(PrepareError::ModuleInfoError(..))
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::errors::InvokeError;
 use crate::types::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::{InstrumentedCodeCache, InstrumentedCodeKey};
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::ActorRefHandle;
 use radix_engine_interface::blueprints::package::CodeHash;
@@ -155,6 +156,29 @@ pub trait WasmRuntime {
     fn consume_wasm_execution_units(&mut self, n: u32)
         -> Result<(), InvokeError<WasmRuntimeError>>;
 
+    /// Attributes wasm execution units to the function with the given index, for profiling.
+    ///
+    /// This is only called for code instantiated with [`WasmDebugOptions::profiling`], right
+    /// before the units are consumed.
+    fn profile_wasm_execution_units(&mut self, function_index: u32, n: u32);
+
+    /// Takes the wasm execution units attributed so far, keyed by function index.
+    fn take_wasm_execution_profile(&mut self) -> IndexMap<u32, u64>;
+
+    /// Records entering the function with the given index, for backtraces.
+    ///
+    /// This and [`WasmRuntime::backtrace_exit`] are only called for code instantiated with
+    /// [`WasmDebugOptions::backtraces`].
+    fn backtrace_enter(&mut self, function_index: u32);
+
+    /// Records returning from the function entered last.
+    fn backtrace_exit(&mut self);
+
+    /// Takes the indices of the functions entered but not returned from, innermost first.
+    ///
+    /// After a trap, this is the wasm call stack at the time of the trap.
+    fn take_wasm_backtrace(&mut self) -> Vec<u32>;
+
     fn costing_get_execution_cost_unit_limit(
         &mut self,
     ) -> Result<u32, InvokeError<WasmRuntimeError>>;
@@ -200,6 +224,47 @@ pub trait WasmRuntime {
     fn sys_generate_ruid(&mut self) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
 
     fn sys_panic(&mut self, message: Vec<u8>) -> Result<(), InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_blake2b_256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_keccak256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_sha256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_ed25519_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_secp256k1_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_secp256k1_recover(
+        &mut self,
+        message_hash: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn crypto_utils_bls12381_v1_aggregate_verify(
+        &mut self,
+        pub_keys_and_msgs: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>>;
 }
 
 /// Represents an instantiated, invocable Scrypto module.
@@ -227,4 +292,39 @@ pub trait WasmEngine {
     ///
     /// The code must have been validated and instrumented!
     fn instantiate(&self, code_hash: CodeHash, instrumented_code: &[u8]) -> Self::WasmInstance;
+
+    /// Instantiate a Scrypto module, instrumented for debugging as per the given options.
+    ///
+    /// If given, the cache is consulted for (and populated with) the code instrumented for
+    /// debugging under the given key, whenever the module isn't in the engine's own cache.
+    ///
+    /// Engines not supporting debugging instantiate the module as usual.
+    fn instantiate_with_debugging(
+        &self,
+        code_hash: CodeHash,
+        instrumented_code: &[u8],
+        _options: WasmDebugOptions,
+        _debug_code_cache: Option<(&dyn InstrumentedCodeCache, InstrumentedCodeKey)>,
+    ) -> Self::WasmInstance {
+        self.instantiate(code_hash, instrumented_code)
+    }
+}
+
+/// Debugging aids to instrument Scrypto modules with, on top of the metering.
+///
+/// None of these change the units consumed, though they do slow down execution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WasmDebugOptions {
+    /// Attribute the consumed wasm execution units to the functions of the module, through
+    /// [`WasmRuntime::profile_wasm_execution_units`]
+    pub profiling: bool,
+    /// Track the wasm call stack, through [`WasmRuntime::backtrace_enter`] and
+    /// [`WasmRuntime::backtrace_exit`]
+    pub backtraces: bool,
+}
+
+impl WasmDebugOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.profiling || self.backtraces
+    }
 }
@@ -1,6 +1,6 @@
 use crate::types::*;
 use crate::vm::wasm::*;
-use radix_engine_interface::blueprints::package::BlueprintDefinitionInit;
+use radix_engine_interface::blueprints::package::{BlueprintDefinitionInit, CodeHash};
 
 pub struct WasmValidator {
     pub max_memory_size_in_pages: u32,
@@ -28,12 +28,59 @@ impl Default for WasmValidator {
     }
 }
 
+/// The version of the validation and instrumentation passes run by [`WasmValidator`],
+/// used to key the [`InstrumentedCodeCache`]. Bump it whenever the output of
+/// [`WasmValidator::validate`] changes other than through [`WasmValidatorConfigV1`],
+/// or the instrumentation of the debugging aids of [`WasmDebugOptions`] changes.
+pub const WASM_VALIDATOR_VERSION: u8 = 1;
+
 impl WasmValidator {
     pub fn validate<'a, I: Iterator<Item = &'a BlueprintDefinitionInit>>(
         &self,
         code: &[u8],
         blueprints: I,
     ) -> Result<(Vec<u8>, Vec<String>), PrepareError> {
+        self.enforce_constraints(code, blueprints)?
+            .inject_instruction_metering(&self.instrumenter_config)?
+            .inject_stack_metering(self.instrumenter_config.max_stack_size())?
+            .ensure_instantiatable()?
+            .ensure_compilable()?
+            .to_bytes()
+    }
+
+    /// Validates and instruments the given code, reusing previously instrumented code from
+    /// the cache when available.
+    ///
+    /// Only instrumentation is skipped on a cache hit: all the constraints checked by
+    /// [`Self::validate`] are still enforced on the original code.
+    pub fn validate_with_cache<'a, I: Iterator<Item = &'a BlueprintDefinitionInit>>(
+        &self,
+        code: &[u8],
+        blueprints: I,
+        cache: &dyn InstrumentedCodeCache,
+    ) -> Result<Vec<u8>, PrepareError> {
+        let key = InstrumentedCodeKey {
+            code_hash: CodeHash::from_hash(hash(code)),
+            validator_version: WASM_VALIDATOR_VERSION,
+            config_version: self.instrumenter_config.version(),
+            debug_options: WasmDebugOptions::default(),
+        };
+
+        if let Some(instrumented_code) = cache.get(&key) {
+            self.enforce_constraints(code, blueprints)?;
+            return Ok(instrumented_code);
+        }
+
+        let (instrumented_code, _) = self.validate(code, blueprints)?;
+        cache.insert(&key, &instrumented_code);
+        Ok(instrumented_code)
+    }
+
+    fn enforce_constraints<'a, I: Iterator<Item = &'a BlueprintDefinitionInit>>(
+        &self,
+        code: &[u8],
+        blueprints: I,
+    ) -> Result<WasmModule, PrepareError> {
         WasmModule::init(code)?
             .enforce_no_start_function()?
             .enforce_import_limit()?
@@ -47,12 +94,7 @@ impl WasmValidator {
                 self.max_number_of_function_locals,
             )?
             .enforce_global_limit(self.max_number_of_globals)?
-            .enforce_export_constraints(blueprints)?
-            .inject_instruction_metering(&self.instrumenter_config)?
-            .inject_stack_metering(self.instrumenter_config.max_stack_size())?
-            .ensure_instantiatable()?
-            .ensure_compilable()?
-            .to_bytes()
+            .enforce_export_constraints(blueprints)
     }
 }
 
@@ -61,7 +103,42 @@ mod tests {
     use radix_engine_interface::blueprints::package::PackageDefinition;
     use wabt::{wasm2wat, wat2wasm};
 
-    use super::WasmValidator;
+    use super::*;
+
+    /// A cache which returns the same code for every key.
+    struct PoisonedCache(Vec<u8>);
+
+    impl InstrumentedCodeCache for PoisonedCache {
+        fn get(&self, _key: &InstrumentedCodeKey) -> Option<Vec<u8>> {
+            Some(self.0.clone())
+        }
+
+        fn insert(&self, _key: &InstrumentedCodeKey, _instrumented_code: &[u8]) {}
+    }
+
+    #[test]
+    fn test_validate_with_cache_enforces_constraints_on_hit() {
+        let code = wat2wasm(
+            r#"
+            (module
+                (func $main)
+                (start $main)
+            )
+            "#,
+        )
+        .unwrap();
+        let cache = PoisonedCache(code.clone());
+
+        let result = WasmValidator::default().validate_with_cache(
+            &code,
+            PackageDefinition::new_single_function_test_definition("Test", "f")
+                .blueprints
+                .values(),
+            &cache,
+        );
+
+        assert_eq!(result, Err(PrepareError::StartFunctionNotAllowed));
+    }
 
     #[test]
     fn test_validate() {
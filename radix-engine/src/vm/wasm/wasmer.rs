@@ -674,6 +674,117 @@ impl WasmerModule {
             runtime.sys_generate_ruid().map(|buffer| buffer.0)
         }
 
+        fn crypto_utils_blake2b_256_hash(
+            env: &WasmerInstanceEnv,
+            data_ptr: u32,
+            data_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let data = read_memory(&instance, data_ptr, data_len)?;
+
+            runtime
+                .crypto_utils_blake2b_256_hash(data)
+                .map(|buffer| buffer.0)
+        }
+
+        fn crypto_utils_keccak256_hash(
+            env: &WasmerInstanceEnv,
+            data_ptr: u32,
+            data_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let data = read_memory(&instance, data_ptr, data_len)?;
+
+            runtime
+                .crypto_utils_keccak256_hash(data)
+                .map(|buffer| buffer.0)
+        }
+
+        fn crypto_utils_sha256_hash(
+            env: &WasmerInstanceEnv,
+            data_ptr: u32,
+            data_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let data = read_memory(&instance, data_ptr, data_len)?;
+
+            runtime
+                .crypto_utils_sha256_hash(data)
+                .map(|buffer| buffer.0)
+        }
+
+        fn crypto_utils_ed25519_verify(
+            env: &WasmerInstanceEnv,
+            message_hash_ptr: u32,
+            message_hash_len: u32,
+            public_key_ptr: u32,
+            public_key_len: u32,
+            signature_ptr: u32,
+            signature_len: u32,
+        ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let message_hash = read_memory(&instance, message_hash_ptr, message_hash_len)?;
+            let public_key = read_memory(&instance, public_key_ptr, public_key_len)?;
+            let signature = read_memory(&instance, signature_ptr, signature_len)?;
+
+            runtime.crypto_utils_ed25519_verify(message_hash, public_key, signature)
+        }
+
+        fn crypto_utils_secp256k1_verify(
+            env: &WasmerInstanceEnv,
+            message_hash_ptr: u32,
+            message_hash_len: u32,
+            public_key_ptr: u32,
+            public_key_len: u32,
+            signature_ptr: u32,
+            signature_len: u32,
+        ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let message_hash = read_memory(&instance, message_hash_ptr, message_hash_len)?;
+            let public_key = read_memory(&instance, public_key_ptr, public_key_len)?;
+            let signature = read_memory(&instance, signature_ptr, signature_len)?;
+
+            runtime.crypto_utils_secp256k1_verify(message_hash, public_key, signature)
+        }
+
+        fn crypto_utils_secp256k1_recover(
+            env: &WasmerInstanceEnv,
+            message_hash_ptr: u32,
+            message_hash_len: u32,
+            signature_ptr: u32,
+            signature_len: u32,
+        ) -> Result<u64, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let message_hash = read_memory(&instance, message_hash_ptr, message_hash_len)?;
+            let signature = read_memory(&instance, signature_ptr, signature_len)?;
+
+            runtime
+                .crypto_utils_secp256k1_recover(message_hash, signature)
+                .map(|buffer| buffer.0)
+        }
+
+        fn crypto_utils_bls12381_v1_aggregate_verify(
+            env: &WasmerInstanceEnv,
+            pub_keys_and_msgs_ptr: u32,
+            pub_keys_and_msgs_len: u32,
+            signature_ptr: u32,
+            signature_len: u32,
+        ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+            let (instance, runtime) = grab_runtime!(env);
+
+            let pub_keys_and_msgs =
+                read_memory(&instance, pub_keys_and_msgs_ptr, pub_keys_and_msgs_len)?;
+            let signature = read_memory(&instance, signature_ptr, signature_len)?;
+
+            runtime.crypto_utils_bls12381_v1_aggregate_verify(pub_keys_and_msgs, signature)
+        }
+
         #[cfg(feature = "radix_engine_tests")]
         pub fn host_read_memory(
             env: &WasmerInstanceEnv,
@@ -776,6 +887,13 @@ impl WasmerModule {
                 SYS_PANIC_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), sys_panic),
                 SYS_GET_TRANSACTION_HASH_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), sys_get_transaction_hash),
                 SYS_GENERATE_RUID_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), sys_generate_ruid),
+                CRYPTO_UTILS_BLAKE2B_256_HASH_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_blake2b_256_hash),
+                CRYPTO_UTILS_KECCAK256_HASH_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_keccak256_hash),
+                CRYPTO_UTILS_SHA256_HASH_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_sha256_hash),
+                CRYPTO_UTILS_ED25519_VERIFY_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_ed25519_verify),
+                CRYPTO_UTILS_SECP256K1_VERIFY_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_secp256k1_verify),
+                CRYPTO_UTILS_SECP256K1_RECOVER_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_secp256k1_recover),
+                CRYPTO_UTILS_BLS12381_V1_AGGREGATE_VERIFY_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), crypto_utils_bls12381_v1_aggregate_verify),
                 BUFFER_CONSUME_FUNCTION_NAME => Function::new_native_with_env(self.module.store(), env.clone(), buffer_consume),
                 #[cfg(feature = "radix_engine_tests")]
                 "test_host_read_memory" => Function::new_native_with_env(self.module.store(), env.clone(), host_read_memory),
@@ -11,10 +11,12 @@ use wasmi::*;
 
 use crate::errors::InvokeError;
 use crate::types::*;
+use crate::vm::wasm::backtrace::inject_backtraces;
 use crate::vm::wasm::constants::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::profiling::inject_profiling;
 use crate::vm::wasm::traits::*;
-use crate::vm::wasm::WasmEngine;
+use crate::vm::wasm::{InstrumentedCodeCache, InstrumentedCodeKey, WasmEngine};
 
 type FakeHostState = FakeWasmiInstanceEnv;
 type HostState = WasmiInstanceEnv;
@@ -54,12 +56,15 @@ pub struct WasmiInstance {
 pub struct FakeWasmiInstanceEnv {
     #[allow(dead_code)]
     runtime_ptr: MaybeUninit<*mut Box<dyn WasmRuntime>>,
+    #[allow(dead_code)]
+    profiling_global: Option<wasmi::Global>,
 }
 
 impl FakeWasmiInstanceEnv {
     pub fn new() -> Self {
         Self {
             runtime_ptr: MaybeUninit::uninit(),
+            profiling_global: None,
         }
     }
 }
@@ -70,12 +75,16 @@ unsafe impl Sync for FakeWasmiInstanceEnv {}
 /// This is to construct a real `Store<WasmiInstanceEnv>
 pub struct WasmiInstanceEnv {
     runtime_ptr: MaybeUninit<*mut Box<dyn WasmRuntime>>,
+    /// The global holding the index of the function being metered, if the code was
+    /// instantiated for profiling
+    profiling_global: Option<wasmi::Global>,
 }
 
 impl WasmiInstanceEnv {
     pub fn new() -> Self {
         Self {
             runtime_ptr: MaybeUninit::uninit(),
+            profiling_global: None,
         }
     }
 }
@@ -572,11 +581,34 @@ fn consume_wasm_execution_units(
 ) -> Result<(), InvokeError<WasmRuntimeError>> {
     let (_memory, runtime) = grab_runtime!(caller);
 
+    if let Some(profiling_global) = caller.data().profiling_global {
+        if let Value::I32(function_index) = profiling_global.get(&caller) {
+            runtime.profile_wasm_execution_units(function_index as u32, n as u32);
+        }
+    }
+
     // TODO: wasm-instrument uses u64 for cost units. We need to decide if we want to move from u32
     // to u64 as well.
     runtime.consume_wasm_execution_units(n as u32)
 }
 
+fn backtrace_enter(
+    caller: Caller<'_, HostState>,
+    function_index: u32,
+) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let (_memory, runtime) = grab_runtime!(caller);
+
+    runtime.backtrace_enter(function_index);
+    Ok(())
+}
+
+fn backtrace_exit(caller: Caller<'_, HostState>) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let (_memory, runtime) = grab_runtime!(caller);
+
+    runtime.backtrace_exit();
+    Ok(())
+}
+
 fn emit_event(
     mut caller: Caller<'_, HostState>,
     event_name_ptr: u32,
@@ -661,6 +693,166 @@ fn panic(
     runtime.sys_panic(message)
 }
 
+fn blake2b_256_hash(
+    mut caller: Caller<'_, HostState>,
+    data_ptr: u32,
+    data_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let data = read_memory(caller.as_context_mut(), memory, data_ptr, data_len)?;
+
+    runtime
+        .crypto_utils_blake2b_256_hash(data)
+        .map(|buffer| buffer.0)
+}
+
+fn keccak256_hash(
+    mut caller: Caller<'_, HostState>,
+    data_ptr: u32,
+    data_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let data = read_memory(caller.as_context_mut(), memory, data_ptr, data_len)?;
+
+    runtime
+        .crypto_utils_keccak256_hash(data)
+        .map(|buffer| buffer.0)
+}
+
+fn sha256_hash(
+    mut caller: Caller<'_, HostState>,
+    data_ptr: u32,
+    data_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let data = read_memory(caller.as_context_mut(), memory, data_ptr, data_len)?;
+
+    runtime
+        .crypto_utils_sha256_hash(data)
+        .map(|buffer| buffer.0)
+}
+
+fn ed25519_verify(
+    mut caller: Caller<'_, HostState>,
+    message_hash_ptr: u32,
+    message_hash_len: u32,
+    public_key_ptr: u32,
+    public_key_len: u32,
+    signature_ptr: u32,
+    signature_len: u32,
+) -> Result<u32, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let message_hash = read_memory(
+        caller.as_context_mut(),
+        memory,
+        message_hash_ptr,
+        message_hash_len,
+    )?;
+    let public_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        public_key_ptr,
+        public_key_len,
+    )?;
+    let signature = read_memory(
+        caller.as_context_mut(),
+        memory,
+        signature_ptr,
+        signature_len,
+    )?;
+
+    runtime.crypto_utils_ed25519_verify(message_hash, public_key, signature)
+}
+
+fn secp256k1_verify(
+    mut caller: Caller<'_, HostState>,
+    message_hash_ptr: u32,
+    message_hash_len: u32,
+    public_key_ptr: u32,
+    public_key_len: u32,
+    signature_ptr: u32,
+    signature_len: u32,
+) -> Result<u32, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let message_hash = read_memory(
+        caller.as_context_mut(),
+        memory,
+        message_hash_ptr,
+        message_hash_len,
+    )?;
+    let public_key = read_memory(
+        caller.as_context_mut(),
+        memory,
+        public_key_ptr,
+        public_key_len,
+    )?;
+    let signature = read_memory(
+        caller.as_context_mut(),
+        memory,
+        signature_ptr,
+        signature_len,
+    )?;
+
+    runtime.crypto_utils_secp256k1_verify(message_hash, public_key, signature)
+}
+
+fn secp256k1_recover(
+    mut caller: Caller<'_, HostState>,
+    message_hash_ptr: u32,
+    message_hash_len: u32,
+    signature_ptr: u32,
+    signature_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let message_hash = read_memory(
+        caller.as_context_mut(),
+        memory,
+        message_hash_ptr,
+        message_hash_len,
+    )?;
+    let signature = read_memory(
+        caller.as_context_mut(),
+        memory,
+        signature_ptr,
+        signature_len,
+    )?;
+
+    runtime
+        .crypto_utils_secp256k1_recover(message_hash, signature)
+        .map(|buffer| buffer.0)
+}
+
+fn bls12381_v1_aggregate_verify(
+    mut caller: Caller<'_, HostState>,
+    pub_keys_and_msgs_ptr: u32,
+    pub_keys_and_msgs_len: u32,
+    signature_ptr: u32,
+    signature_len: u32,
+) -> Result<u32, InvokeError<WasmRuntimeError>> {
+    let (memory, runtime) = grab_runtime!(caller);
+
+    let pub_keys_and_msgs = read_memory(
+        caller.as_context_mut(),
+        memory,
+        pub_keys_and_msgs_ptr,
+        pub_keys_and_msgs_len,
+    )?;
+    let signature = read_memory(
+        caller.as_context_mut(),
+        memory,
+        signature_ptr,
+        signature_len,
+    )?;
+
+    runtime.crypto_utils_bls12381_v1_aggregate_verify(pub_keys_and_msgs, signature)
+}
+
 #[cfg(feature = "radix_engine_tests")]
 fn test_host_read_memory(
     mut caller: Caller<'_, HostState>,
@@ -1156,6 +1348,20 @@ impl WasmiModule {
             },
         );
 
+        let host_backtrace_enter = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>, function_index: u32| -> Result<(), Trap> {
+                backtrace_enter(caller, function_index).map_err(|e| e.into())
+            },
+        );
+
+        let host_backtrace_exit = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>| -> Result<(), Trap> {
+                backtrace_exit(caller).map_err(|e| e.into())
+            },
+        );
+
         let host_emit_event = Func::wrap(
             store.as_context_mut(),
             |caller: Caller<'_, HostState>,
@@ -1224,6 +1430,111 @@ impl WasmiModule {
             },
         );
 
+        let host_blake2b_256_hash = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>, data_ptr: u32, data_len: u32| -> Result<u64, Trap> {
+                blake2b_256_hash(caller, data_ptr, data_len).map_err(|e| e.into())
+            },
+        );
+
+        let host_keccak256_hash = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>, data_ptr: u32, data_len: u32| -> Result<u64, Trap> {
+                keccak256_hash(caller, data_ptr, data_len).map_err(|e| e.into())
+            },
+        );
+
+        let host_sha256_hash = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>, data_ptr: u32, data_len: u32| -> Result<u64, Trap> {
+                sha256_hash(caller, data_ptr, data_len).map_err(|e| e.into())
+            },
+        );
+
+        let host_ed25519_verify = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             message_hash_ptr: u32,
+             message_hash_len: u32,
+             public_key_ptr: u32,
+             public_key_len: u32,
+             signature_ptr: u32,
+             signature_len: u32|
+             -> Result<u32, Trap> {
+                ed25519_verify(
+                    caller,
+                    message_hash_ptr,
+                    message_hash_len,
+                    public_key_ptr,
+                    public_key_len,
+                    signature_ptr,
+                    signature_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_secp256k1_verify = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             message_hash_ptr: u32,
+             message_hash_len: u32,
+             public_key_ptr: u32,
+             public_key_len: u32,
+             signature_ptr: u32,
+             signature_len: u32|
+             -> Result<u32, Trap> {
+                secp256k1_verify(
+                    caller,
+                    message_hash_ptr,
+                    message_hash_len,
+                    public_key_ptr,
+                    public_key_len,
+                    signature_ptr,
+                    signature_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_secp256k1_recover = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             message_hash_ptr: u32,
+             message_hash_len: u32,
+             signature_ptr: u32,
+             signature_len: u32|
+             -> Result<u64, Trap> {
+                secp256k1_recover(
+                    caller,
+                    message_hash_ptr,
+                    message_hash_len,
+                    signature_ptr,
+                    signature_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
+        let host_bls12381_v1_aggregate_verify = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             pub_keys_and_msgs_ptr: u32,
+             pub_keys_and_msgs_len: u32,
+             signature_ptr: u32,
+             signature_len: u32|
+             -> Result<u32, Trap> {
+                bls12381_v1_aggregate_verify(
+                    caller,
+                    pub_keys_and_msgs_ptr,
+                    pub_keys_and_msgs_len,
+                    signature_ptr,
+                    signature_len,
+                )
+                .map_err(|e| e.into())
+            },
+        );
+
         let mut linker = <Linker<HostState>>::new();
 
         linker_define!(linker, BUFFER_CONSUME_FUNCTION_NAME, host_consume_buffer);
@@ -1367,6 +1678,16 @@ impl WasmiModule {
             COSTING_CONSUME_WASM_EXECUTION_UNITS_FUNCTION_NAME,
             host_consume_wasm_execution_units
         );
+        linker_define!(
+            linker,
+            WASM_BACKTRACE_ENTER_FUNCTION_NAME,
+            host_backtrace_enter
+        );
+        linker_define!(
+            linker,
+            WASM_BACKTRACE_EXIT_FUNCTION_NAME,
+            host_backtrace_exit
+        );
         linker_define!(linker, ACTOR_EMIT_EVENT_FUNCTION_NAME, host_emit_event);
         linker_define!(linker, SYS_LOG_FUNCTION_NAME, host_emit_log);
         linker_define!(linker, SYS_PANIC_FUNCTION_NAME, host_panic);
@@ -1383,6 +1704,42 @@ impl WasmiModule {
 
         linker_define!(linker, SYS_GENERATE_RUID_FUNCTION_NAME, host_generate_ruid);
 
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_BLAKE2B_256_HASH_FUNCTION_NAME,
+            host_blake2b_256_hash
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_KECCAK256_HASH_FUNCTION_NAME,
+            host_keccak256_hash
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_SHA256_HASH_FUNCTION_NAME,
+            host_sha256_hash
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_ED25519_VERIFY_FUNCTION_NAME,
+            host_ed25519_verify
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_SECP256K1_VERIFY_FUNCTION_NAME,
+            host_secp256k1_verify
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_SECP256K1_RECOVER_FUNCTION_NAME,
+            host_secp256k1_recover
+        );
+        linker_define!(
+            linker,
+            CRYPTO_UTILS_BLS12381_V1_AGGREGATE_VERIFY_FUNCTION_NAME,
+            host_bls12381_v1_aggregate_verify
+        );
+
         #[cfg(feature = "radix_engine_tests")]
         {
             let host_read_memory = Func::wrap(
@@ -1428,10 +1785,20 @@ impl WasmiModule {
             Some(Extern::Memory(memory)) => memory,
             _ => panic!("Failed to find memory export"),
         };
+        let profiling_global = match instance.get_export(
+            store.as_context_mut(),
+            WASM_PROFILING_FUNCTION_INDEX_EXPORT_NAME,
+        ) {
+            Some(Extern::Global(global)) => Some(global),
+            _ => None,
+        };
+
+        let mut store: Store<HostState> = unsafe { transmute(store) };
+        store.data_mut().profiling_global = profiling_global;
 
         WasmiInstance {
             instance,
-            store: unsafe { transmute(store) },
+            store,
             memory,
         }
     }
@@ -1603,8 +1970,59 @@ impl WasmiEngine {
 impl WasmEngine for WasmiEngine {
     type WasmInstance = WasmiInstance;
 
-    #[allow(unused_variables)]
     fn instantiate(&self, code_hash: CodeHash, instrumented_code: &[u8]) -> WasmiInstance {
+        self.instantiate_cached(code_hash, || {
+            WasmiModule::new(instrumented_code).expect("Failed to instantiate module")
+        })
+    }
+
+    fn instantiate_with_debugging(
+        &self,
+        code_hash: CodeHash,
+        instrumented_code: &[u8],
+        options: WasmDebugOptions,
+        debug_code_cache: Option<(&dyn InstrumentedCodeCache, InstrumentedCodeKey)>,
+    ) -> WasmiInstance {
+        if !options.is_enabled() {
+            return self.instantiate(code_hash, instrumented_code);
+        }
+
+        // Debug modules are cached under their own key, so that they never get picked up by
+        // executions with different options
+        let debug_code_hash = CodeHash::from_hash(hash(
+            [
+                code_hash.0.as_slice(),
+                b"debugging",
+                &[options.profiling as u8, options.backtraces as u8],
+            ]
+            .concat(),
+        ));
+        self.instantiate_cached(debug_code_hash, || {
+            if let Some(debug_code) = debug_code_cache.and_then(|(cache, key)| cache.get(&key)) {
+                return WasmiModule::new(&debug_code).expect("Failed to instantiate module");
+            }
+            let mut debug_code = instrumented_code.to_vec();
+            // Profiling goes first, so that function indices refer to the instrumented code
+            if options.profiling {
+                debug_code = inject_profiling(&debug_code).expect("Failed to inject profiling");
+            }
+            if options.backtraces {
+                debug_code = inject_backtraces(&debug_code).expect("Failed to inject backtraces");
+            }
+            if let Some((cache, key)) = debug_code_cache {
+                cache.insert(&key, &debug_code);
+            }
+            WasmiModule::new(&debug_code).expect("Failed to instantiate module")
+        })
+    }
+}
+
+impl WasmiEngine {
+    #[allow(unused_variables)]
+    fn instantiate_cached<F>(&self, code_hash: CodeHash, create_module: F) -> WasmiInstance
+    where
+        F: FnOnce() -> WasmiModule,
+    {
         #[cfg(not(feature = "radix_engine_fuzzing"))]
         {
             #[cfg(not(feature = "moka"))]
@@ -1619,7 +2037,7 @@ impl WasmEngine for WasmiEngine {
             }
         }
 
-        let module = WasmiModule::new(instrumented_code).expect("Failed to instantiate module");
+        let module = create_module();
         let instance = module.instantiate();
 
         #[cfg(not(feature = "radix_engine_fuzzing"))]
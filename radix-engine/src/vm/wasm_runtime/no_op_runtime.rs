@@ -211,6 +211,20 @@ impl<'a> WasmRuntime for NoOpWasmRuntime<'a> {
             .map_err(|e| InvokeError::SelfError(WasmRuntimeError::FeeReserveError(e)))
     }
 
+    fn profile_wasm_execution_units(&mut self, _function_index: u32, _n: u32) {}
+
+    fn take_wasm_execution_profile(&mut self) -> IndexMap<u32, u64> {
+        index_map_new()
+    }
+
+    fn backtrace_enter(&mut self, _function_index: u32) {}
+
+    fn backtrace_exit(&mut self) {}
+
+    fn take_wasm_backtrace(&mut self) -> Vec<u32> {
+        Vec::new()
+    }
+
     fn instance_of(
         &mut self,
         component_id: Vec<u8>,
@@ -305,4 +319,59 @@ impl<'a> WasmRuntime for NoOpWasmRuntime<'a> {
     fn costing_get_fee_balance(&mut self) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
         Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
     }
+
+    fn crypto_utils_blake2b_256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_keccak256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_sha256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_ed25519_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_secp256k1_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_secp256k1_recover(
+        &mut self,
+        message_hash: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn crypto_utils_bls12381_v1_aggregate_verify(
+        &mut self,
+        pub_keys_and_msgs: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
 }
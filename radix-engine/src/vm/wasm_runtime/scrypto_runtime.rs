@@ -8,6 +8,7 @@ use radix_engine_interface::api::key_value_store_api::KeyValueStoreDataSchema;
 use radix_engine_interface::api::{ActorRefHandle, AttachedModuleId, ClientApi, FieldValue};
 use radix_engine_interface::types::ClientCostingEntry;
 use radix_engine_interface::types::Level;
+use sbor::rust::mem;
 use sbor::rust::vec::Vec;
 
 /// A shim between ClientApi and WASM, with buffer capability.
@@ -21,6 +22,8 @@ where
     package_address: PackageAddress,
     export_name: String,
     wasm_execution_units_buffer: u32,
+    wasm_execution_profile: IndexMap<u32, u64>,
+    wasm_backtrace: Vec<u32>,
     max_number_of_buffers: usize,
 }
 
@@ -36,6 +39,8 @@ where
             package_address,
             export_name,
             wasm_execution_units_buffer: 0,
+            wasm_execution_profile: index_map_new(),
+            wasm_backtrace: Vec::new(),
             max_number_of_buffers: MAX_NUMBER_OF_BUFFERS,
         }
     }
@@ -397,6 +402,31 @@ where
         Ok(())
     }
 
+    fn profile_wasm_execution_units(&mut self, function_index: u32, n: u32) {
+        *self
+            .wasm_execution_profile
+            .entry(function_index)
+            .or_default() += n as u64;
+    }
+
+    fn take_wasm_execution_profile(&mut self) -> IndexMap<u32, u64> {
+        mem::take(&mut self.wasm_execution_profile)
+    }
+
+    fn backtrace_enter(&mut self, function_index: u32) {
+        self.wasm_backtrace.push(function_index);
+    }
+
+    fn backtrace_exit(&mut self) {
+        self.wasm_backtrace.pop();
+    }
+
+    fn take_wasm_backtrace(&mut self) -> Vec<u32> {
+        let mut wasm_backtrace = mem::take(&mut self.wasm_backtrace);
+        wasm_backtrace.reverse();
+        wasm_backtrace
+    }
+
     fn instance_of(
         &mut self,
         object_id: Vec<u8>,
@@ -560,4 +590,104 @@ where
 
         self.allocate_buffer(scrypto_encode(&fee_balance).expect("Failed to encode fee_balance"))
     }
+
+    fn crypto_utils_blake2b_256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let hash = self.api.blake2b_256_hash(data)?;
+
+        self.allocate_buffer(hash.to_vec())
+    }
+
+    fn crypto_utils_keccak256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let hash = self.api.keccak256_hash(data)?;
+
+        self.allocate_buffer(hash.to_vec())
+    }
+
+    fn crypto_utils_sha256_hash(
+        &mut self,
+        data: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let hash = self.api.sha256_hash(data)?;
+
+        self.allocate_buffer(hash.to_vec())
+    }
+
+    fn crypto_utils_ed25519_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        let message_hash =
+            Hash::try_from(message_hash.as_slice()).map_err(|_| WasmRuntimeError::InvalidHash)?;
+        let public_key = Ed25519PublicKey::try_from(public_key.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidEd25519PublicKey)?;
+        let signature = Ed25519Signature::try_from(signature.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidEd25519Signature)?;
+
+        let verified = self
+            .api
+            .ed25519_verify(&message_hash, &public_key, &signature)?;
+
+        Ok(verified.into())
+    }
+
+    fn crypto_utils_secp256k1_verify(
+        &mut self,
+        message_hash: Vec<u8>,
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        let message_hash =
+            Hash::try_from(message_hash.as_slice()).map_err(|_| WasmRuntimeError::InvalidHash)?;
+        let public_key = Secp256k1PublicKey::try_from(public_key.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidSecp256k1PublicKey)?;
+        let signature = Secp256k1Signature::try_from(signature.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidSecp256k1Signature)?;
+
+        let verified = self
+            .api
+            .secp256k1_verify(&message_hash, &public_key, &signature)?;
+
+        Ok(verified.into())
+    }
+
+    fn crypto_utils_secp256k1_recover(
+        &mut self,
+        message_hash: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let message_hash =
+            Hash::try_from(message_hash.as_slice()).map_err(|_| WasmRuntimeError::InvalidHash)?;
+        let signature = Secp256k1Signature::try_from(signature.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidSecp256k1Signature)?;
+
+        let public_key = self.api.secp256k1_recover(&message_hash, &signature)?;
+
+        self.allocate_buffer(scrypto_encode(&public_key).expect("Failed to encode public_key"))
+    }
+
+    fn crypto_utils_bls12381_v1_aggregate_verify(
+        &mut self,
+        pub_keys_and_msgs: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Result<u32, InvokeError<WasmRuntimeError>> {
+        let pub_keys_and_msgs =
+            scrypto_decode::<Vec<(Bls12381G1PublicKey, Vec<u8>)>>(&pub_keys_and_msgs)
+                .map_err(WasmRuntimeError::InvalidBls12381PublicKeysAndMessages)?;
+        let signature = Bls12381G2Signature::try_from(signature.as_slice())
+            .map_err(|_| WasmRuntimeError::InvalidBls12381G2Signature)?;
+
+        let verified = self
+            .api
+            .bls12381_v1_aggregate_verify(pub_keys_and_msgs, &signature)?;
+
+        Ok(verified.into())
+    }
 }
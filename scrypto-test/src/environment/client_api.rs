@@ -258,6 +258,33 @@ implement_client_api! {
         emit_log: (&mut self, level: Level, message: String) -> Result<(), RuntimeError>,
         panic: (&mut self, message: String) -> Result<(), RuntimeError>,
     },
+    ClientCryptoUtilsApi: {
+        blake2b_256_hash: (&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError>,
+        keccak256_hash: (&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError>,
+        sha256_hash: (&mut self, data: Vec<u8>) -> Result<Hash, RuntimeError>,
+        ed25519_verify: (
+            &mut self,
+            message_hash: &Hash,
+            public_key: &Ed25519PublicKey,
+            signature: &Ed25519Signature,
+        ) -> Result<bool, RuntimeError>,
+        secp256k1_verify: (
+            &mut self,
+            message_hash: &Hash,
+            public_key: &Secp256k1PublicKey,
+            signature: &Secp256k1Signature,
+        ) -> Result<bool, RuntimeError>,
+        secp256k1_recover: (
+            &mut self,
+            message_hash: &Hash,
+            signature: &Secp256k1Signature,
+        ) -> Result<Option<Secp256k1PublicKey>, RuntimeError>,
+        bls12381_v1_aggregate_verify: (
+            &mut self,
+            pub_keys_and_msgs: Vec<(Bls12381G1PublicKey, Vec<u8>)>,
+            signature: &Bls12381G2Signature,
+        ) -> Result<bool, RuntimeError>,
+    },
     ClientCostingApi: {
         start_lock_fee: (&mut self, amount: Decimal) -> Result<bool, RuntimeError>,
         lock_fee: (
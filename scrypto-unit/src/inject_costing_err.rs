@@ -501,6 +501,16 @@ impl<'a, M: KernelCallbackObject, Y: KernelApi<InjectCostingError<M>>>
         self.api
             .kernel_drain_substates::<K>(node_id, partition_num, count)
     }
+
+    fn kernel_peek_substate(
+        &self,
+        node_id: &NodeId,
+        partition_num: PartitionNumber,
+        substate_key: &SubstateKey,
+    ) -> Option<IndexedScryptoValue> {
+        self.api
+            .kernel_peek_substate(node_id, partition_num, substate_key)
+    }
 }
 
 impl<'a, M: KernelCallbackObject + 'a, K: KernelApi<InjectCostingError<M>>>
@@ -541,6 +551,14 @@ impl<'a, M: KernelCallbackObject, K: KernelApi<InjectCostingError<M>>> KernelInt
     fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
         self.api.kernel_read_proof(proof_id)
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&M::CallFrameData> {
+        self.api.kernel_get_call_frame_stack()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
 }
 
 impl<'a, M: KernelCallbackObject, K: KernelApi<InjectCostingError<M>>> KernelApi<M>
@@ -584,4 +602,12 @@ impl<'a, M: KernelCallbackObject, K: KernelInternalApi<InjectCostingError<M>>> K
     fn kernel_read_proof(&mut self, proof_id: &NodeId) -> Option<ProofSnapshot> {
         self.api.kernel_read_proof(proof_id)
     }
+
+    fn kernel_get_call_frame_stack(&self) -> Vec<&M::CallFrameData> {
+        self.api.kernel_get_call_frame_stack()
+    }
+
+    fn kernel_get_owned_nodes(&self, depth: usize) -> Vec<NodeId> {
+        self.api.kernel_get_owned_nodes(depth)
+    }
 }
@@ -2,6 +2,7 @@ use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 
 use radix_engine::blueprints::consensus_manager::*;
 use radix_engine::blueprints::models::FieldPayload;
@@ -17,12 +18,15 @@ use radix_engine::system::system_substates::FieldSubstate;
 use radix_engine::system::type_info::TypeInfoSubstate;
 use radix_engine::transaction::{
     execute_preview, execute_transaction_with_system, BalanceChange, CommitResult,
-    CostingParameters, ExecutionConfig, PreviewError, TransactionReceipt, TransactionResult,
-    WrappedSystem,
+    CostingParameters, ExecutionConfig, PreviewError, TransactionReceipt,
+    TransactionReceiptDisplayContextBuilder, TransactionResult, WrappedSystem,
 };
 use radix_engine::types::*;
 use radix_engine::utils::*;
-use radix_engine::vm::wasm::{DefaultWasmEngine, WasmValidatorConfigV1};
+use radix_engine::vm::wasm::{
+    DefaultWasmEngine, InstrumentedCodeCache, WasmExecutionProfile, WasmFunctionNames,
+    WasmValidatorConfigV1,
+};
 use radix_engine::vm::{NativeVm, NativeVmExtension, NoExtension, ScryptoVm, Vm};
 use radix_engine_interface::api::node_modules::auth::ToRoleEntry;
 use radix_engine_interface::api::node_modules::auth::*;
@@ -305,6 +309,9 @@ pub struct TestRunnerBuilder<E, D> {
     custom_database: D,
     trace: bool,
     skip_receipt_check: bool,
+    instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
+    wasm_profiling: bool,
+    wasm_backtraces: bool,
 }
 
 impl TestRunnerBuilder<NoExtension, InMemorySubstateDatabase> {
@@ -315,6 +322,9 @@ impl TestRunnerBuilder<NoExtension, InMemorySubstateDatabase> {
             custom_database: InMemorySubstateDatabase::standard(),
             trace: true,
             skip_receipt_check: false,
+            instrumented_code_cache: None,
+            wasm_profiling: false,
+            wasm_backtraces: false,
         }
     }
 }
@@ -332,9 +342,33 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             custom_database: HashTreeUpdatingDatabase::new(self.custom_database),
             trace: self.trace,
             skip_receipt_check: false,
+            instrumented_code_cache: self.instrumented_code_cache,
+            wasm_profiling: self.wasm_profiling,
+            wasm_backtraces: self.wasm_backtraces,
         }
     }
 
+    /// Reuses instrumented code across test runners, and across processes when the cache
+    /// is backed by disk (see [`radix_engine::vm::wasm::WasmDiskCache`]).
+    pub fn with_instrumented_code_cache(mut self, cache: Arc<dyn InstrumentedCodeCache>) -> Self {
+        self.instrumented_code_cache = Some(cache);
+        self
+    }
+
+    /// Attributes the wasm execution units of every transaction to the functions of the
+    /// packages, see [`TestRunner::wasm_execution_profile_to_folded_stacks`].
+    pub fn with_wasm_profiling(mut self) -> Self {
+        self.wasm_profiling = true;
+        self
+    }
+
+    /// Captures the wasm call stack of blueprints failing a transaction, which is then shown by
+    /// [`TestRunner::display_receipt`].
+    pub fn with_wasm_backtraces(mut self) -> Self {
+        self.wasm_backtraces = true;
+        self
+    }
+
     pub fn with_custom_genesis(mut self, genesis: CustomGenesis) -> Self {
         self.custom_genesis = Some(genesis);
         self
@@ -355,6 +389,9 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             custom_database: self.custom_database,
             trace: self.trace,
             skip_receipt_check: self.skip_receipt_check,
+            instrumented_code_cache: self.instrumented_code_cache,
+            wasm_profiling: self.wasm_profiling,
+            wasm_backtraces: self.wasm_backtraces,
         }
     }
 
@@ -365,6 +402,9 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             custom_database: database,
             trace: self.trace,
             skip_receipt_check: self.skip_receipt_check,
+            instrumented_code_cache: self.instrumented_code_cache,
+            wasm_profiling: self.wasm_profiling,
+            wasm_backtraces: self.wasm_backtraces,
         }
     }
 
@@ -389,6 +429,9 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             collected_events: snapshot.collected_events,
             xrd_free_credits_used: snapshot.xrd_free_credits_used,
             skip_receipt_check: snapshot.skip_receipt_check,
+            wasm_profiling: self.wasm_profiling,
+            wasm_backtraces: self.wasm_backtraces,
+            wasm_function_names_sidecars: index_map_new(),
         }
     }
 
@@ -405,6 +448,7 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
         let scrypto_vm = ScryptoVm {
             wasm_engine: DefaultWasmEngine::default(),
             wasm_validator_config: WasmValidatorConfigV1::new(),
+            instrumented_code_cache: self.instrumented_code_cache,
         };
         let native_vm = NativeVm::new_with_extension(self.custom_extension);
         let vm = Vm::new(&scrypto_vm, native_vm.clone());
@@ -467,6 +511,9 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunnerBuilder<E, D> {
             collected_events: events,
             xrd_free_credits_used: false,
             skip_receipt_check: self.skip_receipt_check,
+            wasm_profiling: self.wasm_profiling,
+            wasm_backtraces: self.wasm_backtraces,
+            wasm_function_names_sidecars: index_map_new(),
         };
 
         let next_epoch = wrap_up_receipt
@@ -491,6 +538,9 @@ pub struct TestRunner<E: NativeVmExtension, D: TestDatabase> {
     collected_events: Vec<Vec<(EventTypeIdentifier, Vec<u8>)>>,
     xrd_free_credits_used: bool,
     skip_receipt_check: bool,
+    wasm_profiling: bool,
+    wasm_backtraces: bool,
+    wasm_function_names_sidecars: IndexMap<CodeHash, Vec<u8>>,
 }
 
 #[cfg(feature = "post_run_db_check")]
@@ -642,6 +692,94 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunner<E, D> {
         balance.amount()
     }
 
+    /// Registers the function names sidecar of some code (see `scrypto build --function-names`),
+    /// which is then used to resolve function names of the packages published with that code.
+    pub fn add_wasm_function_names_sidecar(&mut self, code: &[u8], sidecar_code: Vec<u8>) {
+        self.wasm_function_names_sidecars
+            .insert(CodeHash::from_hash(hash(code)), sidecar_code);
+    }
+
+    /// Resolves the names of the functions of the code of a package, preferring the registered
+    /// function names sidecar over the original code.
+    pub fn wasm_function_names(
+        &self,
+        package_address: &PackageAddress,
+        code_hash: &CodeHash,
+    ) -> Option<WasmFunctionNames> {
+        let key = SubstateKey::Map(scrypto_encode(code_hash).unwrap());
+        let instrumented_code = self
+            .substate_db()
+            .get_mapped::<SpreadPrefixKeyMapper, PackageCodeInstrumentedCodeEntrySubstate>(
+                package_address.as_node_id(),
+                PackagePartitionOffset::CodeInstrumentedCodeKeyValue.as_main_partition(),
+                &key,
+            )?
+            .into_value()?
+            .into_latest()
+            .instrumented_code;
+        if let Some(sidecar_code) = self.wasm_function_names_sidecars.get(code_hash) {
+            return Some(WasmFunctionNames::from_code(
+                sidecar_code,
+                &instrumented_code,
+            ));
+        }
+        let original_code = self
+            .substate_db()
+            .get_mapped::<SpreadPrefixKeyMapper, PackageCodeOriginalCodeEntrySubstate>(
+                package_address.as_node_id(),
+                PackagePartitionOffset::CodeOriginalCodeKeyValue.as_main_partition(),
+                &key,
+            )?
+            .into_value()?
+            .into_latest()
+            .code;
+        Some(WasmFunctionNames::from_code(
+            &original_code,
+            &instrumented_code,
+        ))
+    }
+
+    /// Renders a receipt, with the wasm backtrace (if any) symbolized through
+    /// [`TestRunner::wasm_function_names`].
+    pub fn display_receipt(&self, receipt: &TransactionReceipt) -> String {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let context = TransactionReceiptDisplayContextBuilder::new()
+            .encoder(&encoder)
+            .wasm_function_names_callback(|package_address, code_hash| {
+                self.wasm_function_names(package_address, code_hash)
+            })
+            .build();
+        format!("{}", receipt.display(context))
+    }
+
+    /// Renders a wasm execution profile in the folded-stack format, with function names
+    /// resolved through [`TestRunner::wasm_function_names`].
+    pub fn wasm_execution_profile_to_folded_stacks(
+        &self,
+        wasm_execution_profile: &WasmExecutionProfile,
+    ) -> String {
+        wasm_execution_profile.to_folded_stacks(|package_address, code_hash| {
+            self.wasm_function_names(package_address, code_hash)
+        })
+    }
+
+    /// Writes the wasm execution profile of a receipt as a folded-stack file, which can be
+    /// rendered by flamegraph tools (e.g. `inferno-flamegraph` or `flamegraph.pl`).
+    pub fn write_wasm_execution_profile<P: AsRef<Path>>(
+        &self,
+        receipt: &TransactionReceipt,
+        path: P,
+    ) -> std::io::Result<()> {
+        let wasm_execution_profile = receipt
+            .wasm_execution_profile
+            .as_ref()
+            .expect("Wasm profiling is not enabled");
+        fs::write(
+            path,
+            self.wasm_execution_profile_to_folded_stacks(wasm_execution_profile),
+        )
+    }
+
     pub fn inspect_package_royalty(&mut self, package_address: PackageAddress) -> Option<Decimal> {
         let reader = SystemDatabaseReader::new(self.substate_db());
         let accumulator = reader
@@ -1531,6 +1669,12 @@ impl<E: NativeVmExtension, D: TestDatabase> TestRunner<E, D> {
     ) -> TransactionReceipt {
         // Override the kernel trace config
         execution_config = execution_config.with_kernel_trace(self.trace);
+        if self.wasm_profiling {
+            execution_config = execution_config.with_wasm_profiling(true);
+        }
+        if self.wasm_backtraces {
+            execution_config = execution_config.with_wasm_backtraces(true);
+        }
 
         if executable
             .costing_parameters()
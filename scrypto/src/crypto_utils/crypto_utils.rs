@@ -0,0 +1,72 @@
+use crate::engine::scrypto_env::ScryptoVmV1Api;
+use radix_engine_interface::crypto::*;
+use sbor::rust::prelude::*;
+
+/// Cryptographic utilities, executed natively by the engine and charged as execution costs.
+///
+/// Signatures are verified against a message hash rather than the message itself, as is the
+/// case for transaction signatures; use one of the hash functions to obtain it first.
+#[derive(Debug)]
+pub struct CryptoUtils {}
+
+impl CryptoUtils {
+    /// Calculates the Blake2b-256 hash of the given data.
+    pub fn blake2b_256_hash<T: AsRef<[u8]>>(data: T) -> Hash {
+        ScryptoVmV1Api::crypto_utils_blake2b_256_hash(data.as_ref())
+    }
+
+    /// Calculates the Keccak-256 hash of the given data, as used by Ethereum.
+    pub fn keccak256_hash<T: AsRef<[u8]>>(data: T) -> Hash {
+        ScryptoVmV1Api::crypto_utils_keccak256_hash(data.as_ref())
+    }
+
+    /// Calculates the SHA-256 hash of the given data.
+    pub fn sha256_hash<T: AsRef<[u8]>>(data: T) -> Hash {
+        ScryptoVmV1Api::crypto_utils_sha256_hash(data.as_ref())
+    }
+
+    /// Verifies an Ed25519 signature of the given message hash.
+    pub fn ed25519_verify(
+        message_hash: &Hash,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> bool {
+        ScryptoVmV1Api::crypto_utils_ed25519_verify(message_hash, public_key, signature)
+    }
+
+    /// Verifies an ECDSA Secp256k1 signature of the given message hash.
+    pub fn secp256k1_verify(
+        message_hash: &Hash,
+        public_key: &Secp256k1PublicKey,
+        signature: &Secp256k1Signature,
+    ) -> bool {
+        ScryptoVmV1Api::crypto_utils_secp256k1_verify(message_hash, public_key, signature)
+    }
+
+    /// Recovers the public key which produced an ECDSA Secp256k1 signature of the given message
+    /// hash, if the signature is valid.
+    pub fn secp256k1_recover(
+        message_hash: &Hash,
+        signature: &Secp256k1Signature,
+    ) -> Option<Secp256k1PublicKey> {
+        ScryptoVmV1Api::crypto_utils_secp256k1_recover(message_hash, signature)
+    }
+
+    /// Verifies a BLS12-381 signature of the given message.
+    pub fn bls12381_v1_verify(
+        message: &[u8],
+        public_key: &Bls12381G1PublicKey,
+        signature: &Bls12381G2Signature,
+    ) -> bool {
+        Self::bls12381_v1_aggregate_verify(&[(*public_key, message.to_vec())], signature)
+    }
+
+    /// Verifies a BLS12-381 signature aggregated from signatures of each public key over its
+    /// own message.
+    pub fn bls12381_v1_aggregate_verify(
+        pub_keys_and_msgs: &[(Bls12381G1PublicKey, Vec<u8>)],
+        signature: &Bls12381G2Signature,
+    ) -> bool {
+        ScryptoVmV1Api::crypto_utils_bls12381_v1_aggregate_verify(pub_keys_and_msgs, signature)
+    }
+}
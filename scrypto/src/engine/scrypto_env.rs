@@ -5,7 +5,7 @@ use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::key_value_entry_api::KeyValueEntryHandle;
 use radix_engine_interface::api::{ActorRefHandle, FieldValue};
 use radix_engine_interface::api::{AttachedModuleId, FieldIndex, LockFlags};
-use radix_engine_interface::crypto::Hash;
+use radix_engine_interface::crypto::*;
 use radix_engine_interface::data::scrypto::*;
 use radix_engine_interface::types::PackageAddress;
 use radix_engine_interface::types::{BlueprintId, GlobalAddress};
@@ -320,4 +320,99 @@ impl ScryptoVmV1Api {
             system::sys_panic(message.as_ptr(), message.len());
         };
     }
+
+    pub fn crypto_utils_blake2b_256_hash(data: &[u8]) -> Hash {
+        let hash = copy_buffer(unsafe {
+            crypto_utils::crypto_utils_blake2b_256_hash(data.as_ptr(), data.len())
+        });
+
+        Hash(hash.try_into().unwrap())
+    }
+
+    pub fn crypto_utils_keccak256_hash(data: &[u8]) -> Hash {
+        let hash = copy_buffer(unsafe {
+            crypto_utils::crypto_utils_keccak256_hash(data.as_ptr(), data.len())
+        });
+
+        Hash(hash.try_into().unwrap())
+    }
+
+    pub fn crypto_utils_sha256_hash(data: &[u8]) -> Hash {
+        let hash = copy_buffer(unsafe {
+            crypto_utils::crypto_utils_sha256_hash(data.as_ptr(), data.len())
+        });
+
+        Hash(hash.try_into().unwrap())
+    }
+
+    pub fn crypto_utils_ed25519_verify(
+        message_hash: &Hash,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> bool {
+        let verified = unsafe {
+            crypto_utils::crypto_utils_ed25519_verify(
+                message_hash.0.as_ptr(),
+                message_hash.0.len(),
+                public_key.0.as_ptr(),
+                public_key.0.len(),
+                signature.0.as_ptr(),
+                signature.0.len(),
+            )
+        };
+
+        verified != 0
+    }
+
+    pub fn crypto_utils_secp256k1_verify(
+        message_hash: &Hash,
+        public_key: &Secp256k1PublicKey,
+        signature: &Secp256k1Signature,
+    ) -> bool {
+        let verified = unsafe {
+            crypto_utils::crypto_utils_secp256k1_verify(
+                message_hash.0.as_ptr(),
+                message_hash.0.len(),
+                public_key.0.as_ptr(),
+                public_key.0.len(),
+                signature.0.as_ptr(),
+                signature.0.len(),
+            )
+        };
+
+        verified != 0
+    }
+
+    pub fn crypto_utils_secp256k1_recover(
+        message_hash: &Hash,
+        signature: &Secp256k1Signature,
+    ) -> Option<Secp256k1PublicKey> {
+        let public_key = copy_buffer(unsafe {
+            crypto_utils::crypto_utils_secp256k1_recover(
+                message_hash.0.as_ptr(),
+                message_hash.0.len(),
+                signature.0.as_ptr(),
+                signature.0.len(),
+            )
+        });
+
+        scrypto_decode(&public_key).unwrap()
+    }
+
+    pub fn crypto_utils_bls12381_v1_aggregate_verify(
+        pub_keys_and_msgs: &[(Bls12381G1PublicKey, Vec<u8>)],
+        signature: &Bls12381G2Signature,
+    ) -> bool {
+        let pub_keys_and_msgs = scrypto_encode(pub_keys_and_msgs).unwrap();
+        let verified = unsafe {
+            crypto_utils::crypto_utils_bls12381_v1_aggregate_verify(
+                pub_keys_and_msgs.as_ptr(),
+                pub_keys_and_msgs.len(),
+                signature.0.as_ptr(),
+                signature.0.len(),
+            )
+        };
+
+        verified != 0
+    }
 }
@@ -284,6 +284,58 @@ pub mod system {
     }
 }
 
+/// Metered cryptographic utilities
+pub mod crypto_utils {
+    pub use radix_engine_interface::types::{Buffer, BufferId, Slice};
+
+    super::wasm_extern_c! {
+        /// Calculates the Blake2b-256 hash of the given data
+        pub fn crypto_utils_blake2b_256_hash(data_ptr: *const u8, data_len: usize) -> Buffer;
+
+        /// Calculates the Keccak-256 hash of the given data
+        pub fn crypto_utils_keccak256_hash(data_ptr: *const u8, data_len: usize) -> Buffer;
+
+        /// Calculates the SHA-256 hash of the given data
+        pub fn crypto_utils_sha256_hash(data_ptr: *const u8, data_len: usize) -> Buffer;
+
+        /// Verifies an Ed25519 signature of a message hash, returning 1 if valid and 0 otherwise
+        pub fn crypto_utils_ed25519_verify(
+            message_hash_ptr: *const u8,
+            message_hash_len: usize,
+            public_key_ptr: *const u8,
+            public_key_len: usize,
+            signature_ptr: *const u8,
+            signature_len: usize,
+        ) -> u32;
+
+        /// Verifies a Secp256k1 signature of a message hash, returning 1 if valid and 0 otherwise
+        pub fn crypto_utils_secp256k1_verify(
+            message_hash_ptr: *const u8,
+            message_hash_len: usize,
+            public_key_ptr: *const u8,
+            public_key_len: usize,
+            signature_ptr: *const u8,
+            signature_len: usize,
+        ) -> u32;
+
+        /// Recovers the public key of a Secp256k1 signature of a message hash
+        pub fn crypto_utils_secp256k1_recover(
+            message_hash_ptr: *const u8,
+            message_hash_len: usize,
+            signature_ptr: *const u8,
+            signature_len: usize,
+        ) -> Buffer;
+
+        /// Verifies a BLS12-381 aggregated signature, returning 1 if valid and 0 otherwise
+        pub fn crypto_utils_bls12381_v1_aggregate_verify(
+            pub_keys_and_msgs_ptr: *const u8,
+            pub_keys_and_msgs_len: usize,
+            signature_ptr: *const u8,
+            signature_len: usize,
+        ) -> u32;
+    }
+}
+
 pub mod buffer {
     pub use radix_engine_interface::types::{Buffer, BufferId, Slice};
 
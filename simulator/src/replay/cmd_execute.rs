@@ -1,6 +1,8 @@
-use super::ledger_transaction_execution::execute_ledger_transaction;
+use super::ledger_transaction_execution::{
+    execute_prepared_ledger_transaction, prepare_ledger_transaction,
+};
 use super::txn_reader::TxnReader;
-use super::Error;
+use super::{open_wasm_cache, Error};
 use clap::Parser;
 use flate2::read::GzDecoder;
 use flume;
@@ -9,7 +11,6 @@ use radix_engine::vm::wasm::*;
 use radix_engine::vm::ScryptoVm;
 use radix_engine_interface::prelude::NetworkDefinition;
 use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
-use radix_engine_store_interface::interface::CommittableSubstateDatabase;
 use radix_engine_stores::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
 use std::fs::File;
 use std::path::PathBuf;
@@ -31,6 +32,16 @@ pub struct TxnExecute {
     /// The max version to execute
     #[clap(short, long)]
     pub max_version: Option<u64>,
+    /// Whether to prefetch the substates which each transaction is likely to touch (in a single
+    /// batch read) before executing it
+    #[clap(long)]
+    pub prefetch: bool,
+    /// A folder for caching, across runs, the WASM code instrumented when packages are published
+    #[clap(long)]
+    pub wasm_cache_dir: Option<PathBuf>,
+    /// The max size of the WASM cache, in megabytes
+    #[clap(long, default_value = "512")]
+    pub wasm_cache_size_mb: u64,
 }
 
 impl TxnExecute {
@@ -40,13 +51,19 @@ impl TxnExecute {
             None => NetworkDefinition::mainnet(),
         };
 
-        let cur_version = {
+        let (cur_version, last_transaction_hash) = {
             let database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
             let cur_version = database.get_current_version();
             if cur_version >= self.max_version.unwrap_or(u64::MAX) {
                 return Ok(());
             }
-            cur_version
+            (cur_version, database.get_last_transaction_hash())
+        };
+        // if the last committed transaction is known, re-read it to verify that we resume from
+        // the same ledger
+        let from_version = match last_transaction_hash {
+            Some(_) => cur_version - 1,
+            None => cur_version,
         };
         let to_version = self.max_version.clone();
 
@@ -65,19 +82,48 @@ impl TxnExecute {
             return Err(Error::InvalidTransactionSource);
         };
         let txn_read_thread_handle =
-            thread::spawn(move || txn_reader.read(cur_version, to_version, tx));
+            thread::spawn(move || txn_reader.read(from_version, to_version, tx));
 
         // txn executor
         let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let prefetch = self.prefetch;
+        let instrumented_code_cache =
+            open_wasm_cache(self.wasm_cache_dir.as_ref(), self.wasm_cache_size_mb)?;
         let txn_write_thread_handle = thread::spawn(move || {
-            let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
-            let iter = rx.iter();
+            let scrypto_vm = ScryptoVm::<DefaultWasmEngine> {
+                instrumented_code_cache,
+                ..Default::default()
+            };
+            let mut iter = rx.iter();
+            if let Some(last_transaction_hash) = last_transaction_hash {
+                let tx_payload = iter.next().expect("Missing the last committed transaction");
+                let transaction_hash = prepare_ledger_transaction(&tx_payload)
+                    .create_identifiers()
+                    .ledger_transaction_hash
+                    .0;
+                if transaction_hash != last_transaction_hash {
+                    panic!(
+                        "Transaction hash mismatch at version {}. Expected {} Actual {}",
+                        cur_version, last_transaction_hash, transaction_hash
+                    );
+                }
+            }
             for tx_payload in iter {
-                let state_updates =
-                    execute_ledger_transaction(&database, &scrypto_vm, &network, &tx_payload);
+                let prepared = prepare_ledger_transaction(&tx_payload);
+                let state_updates = execute_prepared_ledger_transaction(
+                    &database,
+                    &scrypto_vm,
+                    &network,
+                    &prepared,
+                    prefetch,
+                )
+                .into_state_updates();
                 let database_updates =
                     state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
-                database.commit(&database_updates);
+                database.commit_transaction(
+                    &database_updates,
+                    prepared.create_identifiers().ledger_transaction_hash.0,
+                );
 
                 let new_state_root_hash = database.get_current_root_hash();
                 let new_version = database.get_current_version();
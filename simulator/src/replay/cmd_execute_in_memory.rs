@@ -1,15 +1,17 @@
 use super::ledger_transaction_execution::execute_ledger_transaction;
 use super::txn_reader::TxnReader;
-use super::Error;
+use super::{open_wasm_cache, Error};
 use clap::Parser;
 use flate2::read::GzDecoder;
 use flume;
+use radix_engine::transaction::execute_in_parallel;
 use radix_engine::types::*;
 use radix_engine::vm::wasm::*;
 use radix_engine::vm::ScryptoVm;
 use radix_engine_interface::prelude::NetworkDefinition;
 use radix_engine_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
-use radix_engine_store_interface::interface::CommittableSubstateDatabase;
+use radix_engine_store_interface::interface::{DatabaseUpdates, SubstateDatabase};
+use radix_engine_store_interface::overlay::{DatabaseUpdatesOverlay, OverlaidSubstateDatabase};
 use radix_engine_stores::hash_tree_support::HashTreeUpdatingDatabase;
 use radix_engine_stores::memory_db::InMemorySubstateDatabase;
 use std::fs::File;
@@ -34,8 +36,29 @@ pub struct TxnExecuteInMemory {
     /// State hash breakpoints, in format of comma separated `<version>:<hash>`
     #[clap(short, long)]
     pub breakpoints: Option<String>,
+
+    /// The number of consecutive transactions to apply to the state hash tree at once
+    #[clap(long, default_value = "100")]
+    pub hash_batch_size: usize,
+
+    /// The number of threads to execute the transactions with (optimistically, i.e. re-executing
+    /// the ones which conflict with the preceding ones)
+    #[clap(long)]
+    pub parallel: Option<usize>,
+    /// Whether to verify the results of the parallel execution against a sequential execution
+    #[clap(long)]
+    pub verify_parallel: bool,
+    /// A folder for caching, across runs, the WASM code instrumented when packages are published
+    #[clap(long)]
+    pub wasm_cache_dir: Option<PathBuf>,
+    /// The max size of the WASM cache, in megabytes
+    #[clap(long, default_value = "512")]
+    pub wasm_cache_size_mb: u64,
 }
 
+/// The number of transactions executed in parallel at once, per thread.
+const PARALLEL_BATCH_SIZE_PER_THREAD: usize = 16;
+
 impl TxnExecuteInMemory {
     pub fn run(&self) -> Result<(), Error> {
         let network = match &self.network {
@@ -58,6 +81,10 @@ impl TxnExecuteInMemory {
             }
         }
 
+        if self.hash_batch_size == 0 || self.parallel == Some(0) {
+            return Err(Error::InvalidBatchSize);
+        }
+
         let cur_version = 0;
         let to_version = self.max_version.clone();
 
@@ -81,37 +108,93 @@ impl TxnExecuteInMemory {
         // txn executor
         let substate_database = InMemorySubstateDatabase::standard();
         let mut database = HashTreeUpdatingDatabase::new(substate_database);
+        let hash_batch_size = self.hash_batch_size;
+        let parallel = self.parallel;
+        let verify_parallel = self.verify_parallel;
+        let instrumented_code_cache =
+            open_wasm_cache(self.wasm_cache_dir.as_ref(), self.wasm_cache_size_mb)?;
         let txn_write_thread_handle = thread::spawn(move || {
-            let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
-            let iter = rx.iter();
-            for tx_payload in iter {
-                let state_updates =
-                    execute_ledger_transaction(&database, &scrypto_vm, &network, &tx_payload);
-                let database_updates =
-                    state_updates.create_database_updates::<SpreadPrefixKeyMapper>();
-                database.commit(&database_updates);
-
-                let new_state_root_hash = database.get_current_root_hash();
-                let new_version = database.get_current_version();
-
-                if let Some(expected) = breakpoints.get(&new_version) {
-                    if new_state_root_hash != *expected {
-                        panic!(
-                            "Unexpected state hash at version {}: expected = {}, actual = {}",
-                            new_version, expected, new_state_root_hash
-                        )
-                    }
+            let scrypto_vm = ScryptoVm::<DefaultWasmEngine> {
+                instrumented_code_cache,
+                ..Default::default()
+            };
+            let execution_batch_size = parallel.map_or(1, |thread_count| {
+                thread_count * PARALLEL_BATCH_SIZE_PER_THREAD
+            });
+            let mut re_executed_count = 0;
+            let mut iter = rx.iter();
+            loop {
+                let tx_payloads = iter.by_ref().take(execution_batch_size).collect::<Vec<_>>();
+                if tx_payloads.is_empty() {
+                    break;
                 }
+                let database_updates_batch = match parallel {
+                    Some(thread_count) => {
+                        let outcomes = execute_in_parallel(
+                            &database,
+                            &tx_payloads,
+                            thread_count,
+                            |database, tx_payload| {
+                                let database_updates = execute_ledger_transaction(
+                                    database,
+                                    &scrypto_vm,
+                                    &network,
+                                    tx_payload,
+                                )
+                                .create_database_updates::<SpreadPrefixKeyMapper>();
+                                ((), database_updates)
+                            },
+                        );
+                        re_executed_count += outcomes
+                            .iter()
+                            .filter(|outcome| outcome.re_executed)
+                            .count();
+                        let database_updates_batch = outcomes
+                            .into_iter()
+                            .map(|outcome| outcome.database_updates)
+                            .collect::<Vec<_>>();
+                        if verify_parallel {
+                            verify_against_sequential_execution(
+                                &database,
+                                &scrypto_vm,
+                                &network,
+                                &tx_payloads,
+                                &database_updates_batch,
+                            );
+                        }
+                        database_updates_batch
+                    }
+                    None => tx_payloads
+                        .iter()
+                        .map(|tx_payload| {
+                            execute_ledger_transaction(&database, &scrypto_vm, &network, tx_payload)
+                                .create_database_updates::<SpreadPrefixKeyMapper>()
+                        })
+                        .collect(),
+                };
+
+                for database_updates in database_updates_batch {
+                    database.commit_without_hashing(&database_updates);
 
-                if new_version < 1000 || new_version % 1000 == 0 {
-                    print_progress(start.elapsed(), new_version, new_state_root_hash);
+                    // the root hash is needed at every breakpoint, so a batch may need to end early
+                    let pending_version =
+                        database.get_current_version() + database.count_pending_updates() as u64;
+                    if database.count_pending_updates() >= hash_batch_size
+                        || breakpoints.contains_key(&pending_version)
+                    {
+                        update_hash_tree(&mut database, &breakpoints, start);
+                    }
                 }
             }
+            update_hash_tree(&mut database, &breakpoints, start);
 
             let duration = start.elapsed();
             println!("Time elapsed: {:?}", duration);
             println!("State version: {}", database.get_current_version());
             println!("State root hash: {}", database.get_current_root_hash());
+            if parallel.is_some() {
+                println!("Re-executed transactions: {}", re_executed_count);
+            }
         });
 
         txn_read_thread_handle.join().unwrap()?;
@@ -121,6 +204,61 @@ impl TxnExecuteInMemory {
     }
 }
 
+/// Executes the given transactions sequentially (without committing them), and panics if any of
+/// them results in different updates than the given ones.
+fn verify_against_sequential_execution<S: SubstateDatabase>(
+    database: &S,
+    scrypto_vm: &ScryptoVm<DefaultWasmEngine>,
+    network: &NetworkDefinition,
+    tx_payloads: &[Vec<u8>],
+    database_updates_batch: &[DatabaseUpdates],
+) {
+    let mut preceding_updates = DatabaseUpdatesOverlay::default();
+    for (index, (tx_payload, database_updates)) in
+        tx_payloads.iter().zip(database_updates_batch).enumerate()
+    {
+        let expected = execute_ledger_transaction(
+            &OverlaidSubstateDatabase::new(database, &preceding_updates),
+            scrypto_vm,
+            network,
+            tx_payload,
+        )
+        .create_database_updates::<SpreadPrefixKeyMapper>();
+        if *database_updates != expected {
+            panic!(
+                "Parallel execution diverged from the sequential one at transaction {} of the batch",
+                index
+            );
+        }
+        preceding_updates.apply(&expected);
+    }
+}
+
+/// Applies the pending updates to the state hash tree, checking the resulting root hashes against
+/// the breakpoints.
+fn update_hash_tree(
+    database: &mut HashTreeUpdatingDatabase<InMemorySubstateDatabase>,
+    breakpoints: &BTreeMap<u64, Hash>,
+    start: std::time::Instant,
+) {
+    let first_new_version = database.get_current_version() + 1;
+    for (new_version, new_state_root_hash) in (first_new_version..).zip(database.update_hash_tree())
+    {
+        if let Some(expected) = breakpoints.get(&new_version) {
+            if new_state_root_hash != *expected {
+                panic!(
+                    "Unexpected state hash at version {}: expected = {}, actual = {}",
+                    new_version, expected, new_state_root_hash
+                )
+            }
+        }
+
+        if new_version < 1000 || new_version % 1000 == 0 {
+            print_progress(start.elapsed(), new_version, new_state_root_hash);
+        }
+    }
+}
+
 fn print_progress(duration: Duration, new_version: u64, new_root: Hash) {
     let seconds = duration.as_secs() % 60;
     let minutes = (duration.as_secs() / 60) % 60;
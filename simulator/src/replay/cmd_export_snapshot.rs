@@ -0,0 +1,37 @@
+use super::Error;
+use clap::Parser;
+use radix_engine_stores::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use radix_engine_stores::snapshot::{export_snapshot, SnapshotHeader};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+/// Export the state of a database to a snapshot file
+#[derive(Parser, Debug)]
+pub struct ExportSnapshot {
+    /// Path to a folder with the stored state
+    pub database_dir: PathBuf,
+    /// The output snapshot file
+    pub output: PathBuf,
+}
+
+impl ExportSnapshot {
+    pub fn run(&self) -> Result<(), Error> {
+        let database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let header = SnapshotHeader {
+            state_version: Some(database.get_current_version()),
+            state_root_hash: Some(database.get_current_root_hash()),
+        };
+        let file = File::create(&self.output).map_err(Error::IOError)?;
+        let summary = export_snapshot(&database, header, BufWriter::new(file))
+            .map_err(Error::SnapshotError)?;
+
+        println!("State version: {}", database.get_current_version());
+        println!("State root hash: {}", database.get_current_root_hash());
+        println!(
+            "Exported {} substates of {} partitions (in {} chunks)",
+            summary.substate_count, summary.partition_count, summary.chunk_count
+        );
+        Ok(())
+    }
+}
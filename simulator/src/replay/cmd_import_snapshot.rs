@@ -0,0 +1,34 @@
+use super::Error;
+use clap::Parser;
+use radix_engine_stores::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+/// Import the state of a database from a snapshot file (into an empty database), so that the
+/// transactions can be executed from the snapshot's state version onwards
+#[derive(Parser, Debug)]
+pub struct ImportSnapshot {
+    /// The input snapshot file
+    pub input: PathBuf,
+    /// Path to a folder for storing state
+    pub database_dir: PathBuf,
+}
+
+impl ImportSnapshot {
+    pub fn run(&self) -> Result<(), Error> {
+        let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let file = File::open(&self.input).map_err(Error::IOError)?;
+        let summary = database
+            .import_snapshot(BufReader::new(file))
+            .map_err(Error::SnapshotError)?;
+
+        println!("State version: {}", database.get_current_version());
+        println!("State root hash: {}", database.get_current_root_hash());
+        println!(
+            "Imported {} substates of {} partitions (in {} chunks)",
+            summary.substate_count, summary.partition_count, summary.chunk_count
+        );
+        Ok(())
+    }
+}
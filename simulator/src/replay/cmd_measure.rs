@@ -38,6 +38,15 @@ pub struct TxnMeasure {
     /// The max version to execute
     #[clap(short, long)]
     pub max_version: Option<u64>,
+    /// The number of consecutive commits to buffer in memory and write at once (note: the
+    /// processing times then exclude the writes of the buffered commits, except the last one of
+    /// each batch)
+    #[clap(long, default_value = "1")]
+    pub commit_batch_size: usize,
+    /// Whether to prefetch the substates which each transaction is likely to touch (in a single
+    /// batch read) before executing it
+    #[clap(long)]
+    pub prefetch: bool,
 }
 
 impl TxnMeasure {
@@ -56,6 +65,9 @@ impl TxnMeasure {
             cur_version
         };
         let to_version = self.max_version.clone();
+        if self.commit_batch_size == 0 {
+            return Err(Error::InvalidBatchSize);
+        }
 
         let start = std::time::Instant::now();
         let (tx, rx) = flume::bounded(10);
@@ -76,6 +88,9 @@ impl TxnMeasure {
 
         // txn executor
         let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        if self.commit_batch_size > 1 {
+            database = database.with_commit_batching(self.commit_batch_size);
+        }
         let exists = self.output_file.exists();
         let mut output = OpenOptions::new()
             .write(true)
@@ -91,8 +106,10 @@ impl TxnMeasure {
             .map_err(Error::IOError)?;
         }
 
+        let prefetch = self.prefetch;
         let txn_write_thread_handle = thread::spawn(move || {
             let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+            let mut last_version = cur_version;
             let iter = rx.iter();
             for tx_payload in iter {
                 let tx_start_time = std::time::Instant::now();
@@ -102,6 +119,7 @@ impl TxnMeasure {
                     &scrypto_vm,
                     &network,
                     &prepared,
+                    prefetch,
                 );
                 let execution_cost_units = receipt
                     .fee_summary()
@@ -128,13 +146,17 @@ impl TxnMeasure {
                     .map_err(Error::IOError)?;
                 }
 
-                let new_state_root_hash = database.get_current_root_hash();
+                // (with commit batching, the version only advances when a batch gets written)
                 let new_version = database.get_current_version();
-
-                if new_version < 1000 || new_version % 1000 == 0 {
+                if new_version != last_version
+                    && (new_version < 1000 || new_version / 1000 != last_version / 1000)
+                {
+                    let new_state_root_hash = database.get_current_root_hash();
                     print_progress(start.elapsed(), new_version, new_state_root_hash);
                 }
+                last_version = new_version;
             }
+            database.flush_pending_commits();
 
             let duration = start.elapsed();
             println!("Time elapsed: {:?}", duration);
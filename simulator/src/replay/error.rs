@@ -1,4 +1,5 @@
 use radix_engine_interface::prelude::ParseNetworkError;
+use radix_engine_stores::snapshot::SnapshotError;
 
 #[derive(Debug)]
 pub enum Error {
@@ -8,4 +9,6 @@ pub enum Error {
     InvalidTransactionArchive,
     InvalidTransactionSource,
     InvalidBreakpoints(String),
+    InvalidBatchSize,
+    SnapshotError(SnapshotError),
 }
@@ -107,6 +107,7 @@ impl PreparedRoundUpdateTransactionV1 {
                     intent_hash: self.summary.hash,
                 },
                 epoch_range: None,
+                proposer_timestamp_range: None,
                 payload_size: 0,
                 num_of_signature_validations: 0,
                 auth_zone_params: AuthZoneParams {
@@ -116,6 +117,7 @@ impl PreparedRoundUpdateTransactionV1 {
                 costing_parameters: TransactionCostingParameters {
                     tip_percentage: 0,
                     free_credit_in_xrd: Decimal::ZERO,
+                    max_fee_in_xrd: None,
                 },
                 pre_allocated_addresses: vec![],
             },
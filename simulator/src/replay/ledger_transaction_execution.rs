@@ -10,7 +10,9 @@ use radix_engine::vm::wasm::*;
 use radix_engine::vm::{DefaultNativeVm, ScryptoVm, Vm};
 use radix_engine_interface::prelude::node_modules::auth::AuthAddresses;
 use radix_engine_interface::prelude::NetworkDefinition;
+use radix_engine_queries::query::prefetch_for_transaction;
 use radix_engine_store_interface::interface::SubstateDatabase;
+use transaction::model::Executable;
 use transaction::validation::{
     NotarizedTransactionValidator, TransactionValidator, ValidationConfig,
 };
@@ -45,7 +47,7 @@ pub fn execute_ledger_transaction<S: SubstateDatabase>(
     tx_payload: &[u8],
 ) -> StateUpdates {
     let prepared = prepare_ledger_transaction(tx_payload);
-    execute_prepared_ledger_transaction(database, scrypto_vm, network, &prepared)
+    execute_prepared_ledger_transaction(database, scrypto_vm, network, &prepared, false)
         .into_state_updates()
 }
 
@@ -58,11 +60,14 @@ pub fn prepare_ledger_transaction(tx_payload: &[u8]) -> PreparedLedgerTransactio
     prepared
 }
 
+/// Executes the given transaction, optionally prefetching the substates it is likely to touch
+/// first (see [`prefetch_for_transaction()`]).
 pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
     database: &S,
     scrypto_vm: &ScryptoVm<DefaultWasmEngine>,
     network: &NetworkDefinition,
     prepared: &PreparedLedgerTransaction,
+    prefetch: bool,
 ) -> LedgerTransactionReceipt {
     match &prepared.inner {
         PreparedLedgerTransactionInner::Genesis(prepared_genesis_tx) => {
@@ -72,48 +77,62 @@ pub fn execute_prepared_ledger_transaction<S: SubstateDatabase>(
                     LedgerTransactionReceipt::Flash(receipt)
                 }
                 PreparedGenesisTransaction::Transaction(tx) => {
-                    let receipt = execute_transaction(
+                    let receipt = execute(
                         database,
-                        Vm {
-                            scrypto_vm,
-                            native_vm: DefaultNativeVm::new(),
-                        },
-                        &CostingParameters::default(),
+                        scrypto_vm,
                         &ExecutionConfig::for_genesis_transaction(network.clone()),
                         &tx.get_executable(btreeset!(AuthAddresses::system_role())),
+                        prefetch,
                     );
                     LedgerTransactionReceipt::Standard(receipt)
                 }
             }
         }
         PreparedLedgerTransactionInner::UserV1(tx) => {
-            let receipt = execute_transaction(
+            let validated =
+                NotarizedTransactionValidator::new(ValidationConfig::default(network.id))
+                    .validate(tx.as_ref().clone())
+                    .expect("Transaction validation failure");
+            let receipt = execute(
                 database,
-                Vm {
-                    scrypto_vm,
-                    native_vm: DefaultNativeVm::new(),
-                },
-                &CostingParameters::default(),
+                scrypto_vm,
                 &ExecutionConfig::for_notarized_transaction(network.clone()),
-                &NotarizedTransactionValidator::new(ValidationConfig::default(network.id))
-                    .validate(tx.as_ref().clone())
-                    .expect("Transaction validation failure")
-                    .get_executable(),
+                &validated.get_executable(),
+                prefetch,
             );
             LedgerTransactionReceipt::Standard(receipt)
         }
         PreparedLedgerTransactionInner::RoundUpdateV1(tx) => {
-            let receipt = execute_transaction(
+            let receipt = execute(
                 database,
-                Vm {
-                    scrypto_vm,
-                    native_vm: DefaultNativeVm::new(),
-                },
-                &CostingParameters::default(),
+                scrypto_vm,
                 &ExecutionConfig::for_system_transaction(network.clone()),
                 &tx.get_executable(),
+                prefetch,
             );
             LedgerTransactionReceipt::Standard(receipt)
         }
     }
 }
+
+fn execute<S: SubstateDatabase>(
+    database: &S,
+    scrypto_vm: &ScryptoVm<DefaultWasmEngine>,
+    execution_config: &ExecutionConfig,
+    executable: &Executable,
+    prefetch: bool,
+) -> TransactionReceipt {
+    if prefetch {
+        prefetch_for_transaction(database, executable);
+    }
+    execute_transaction(
+        database,
+        Vm {
+            scrypto_vm,
+            native_vm: DefaultNativeVm::new(),
+        },
+        &CostingParameters::default(),
+        execution_config,
+        executable,
+    )
+}
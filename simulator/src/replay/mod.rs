@@ -4,6 +4,8 @@ pub mod txn_reader;
 
 mod cmd_execute;
 mod cmd_execute_in_memory;
+mod cmd_export_snapshot;
+mod cmd_import_snapshot;
 mod cmd_measure;
 mod cmd_prepare;
 mod cmd_sync;
@@ -11,12 +13,17 @@ mod error;
 
 pub use cmd_execute::*;
 pub use cmd_execute_in_memory::*;
+pub use cmd_export_snapshot::*;
+pub use cmd_import_snapshot::*;
 pub use cmd_measure::*;
 pub use cmd_prepare::*;
 pub use cmd_sync::*;
 pub use error::*;
 
 use clap::{Parser, Subcommand};
+use radix_engine::vm::wasm::{InstrumentedCodeCache, WasmDiskCache};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Transaction replay toolkit
 #[derive(Parser, Debug)]
@@ -33,6 +40,8 @@ pub enum Command {
     ExecuteInMemory(TxnExecuteInMemory),
     Sync(TxnSync),
     Measure(TxnMeasure),
+    ExportSnapshot(ExportSnapshot),
+    ImportSnapshot(ImportSnapshot),
 }
 
 pub fn run() -> Result<(), Error> {
@@ -44,5 +53,22 @@ pub fn run() -> Result<(), Error> {
         Command::ExecuteInMemory(cmd) => cmd.run(),
         Command::Sync(cmd) => cmd.sync(),
         Command::Measure(cmd) => cmd.run(),
+        Command::ExportSnapshot(cmd) => cmd.run(),
+        Command::ImportSnapshot(cmd) => cmd.run(),
+    }
+}
+
+/// Opens the on-disk cache of instrumented WASM code, if a directory is given.
+pub fn open_wasm_cache(
+    wasm_cache_dir: Option<&PathBuf>,
+    wasm_cache_size_mb: u64,
+) -> Result<Option<Arc<dyn InstrumentedCodeCache>>, Error> {
+    match wasm_cache_dir {
+        Some(dir) => {
+            let cache = WasmDiskCache::new(dir, wasm_cache_size_mb * 1024 * 1024)
+                .map_err(Error::IOError)?;
+            Ok(Some(Arc::new(cache)))
+        }
+        None => Ok(None),
     }
 }
@@ -32,6 +32,14 @@ pub struct CallFunction {
     #[clap(short, long)]
     pub manifest: Option<PathBuf>,
 
+    /// Output an unsigned transaction intent without execution, to be signed with `resim sign`
+    #[clap(long)]
+    pub unsigned_out: Option<PathBuf>,
+
+    /// The public key of the notary of the unsigned transaction intent, defaulting to the default account's key
+    #[clap(long)]
+    pub notary_public_key: Option<String>,
+
     /// The private keys used for signing, separated by comma
     #[clap(short, long)]
     pub signing_keys: Option<String>,
@@ -71,6 +79,16 @@ impl CallFunction {
             )?
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
+        if let Some(path) = &self.unsigned_out {
+            return export_unsigned_intent(
+                manifest,
+                &self.network,
+                &self.notary_public_key,
+                path,
+                out,
+            );
+        }
+
         handle_manifest(
             manifest,
             &self.signing_keys,
@@ -0,0 +1,400 @@
+use clap::Parser;
+use radix_engine::system::actor::{Actor, BlueprintHookActor, FunctionActor};
+use radix_engine::system::system_debugger::*;
+use radix_engine::system::system_modules::execution_trace::BucketSnapshot;
+use radix_engine::transaction::execute_transaction_with_system;
+use radix_engine::utils::validate_call_arguments_to_native_components;
+use sbor::representations::*;
+use std::io::BufRead;
+use std::path::PathBuf;
+use transaction::manifest::{compile_error_diagnostics, BlobProvider};
+
+use crate::resim::*;
+
+/// Runs a transaction manifest in a debugger, without committing it
+#[derive(Parser, Debug)]
+pub struct DebugManifest {
+    /// The path to a transaction manifest file
+    pub path: PathBuf,
+
+    /// The paths to blobs
+    #[clap(short, long, multiple = true)]
+    pub blobs: Option<Vec<String>>,
+
+    /// The private keys used for signing, separated by comma
+    #[clap(short, long)]
+    pub signing_keys: Option<String>,
+
+    /// Break before invoking a blueprint, given as <package_address>:<blueprint_name>,
+    /// optionally followed by :<function_or_method_name>
+    #[clap(long, multiple = true)]
+    pub break_invocation: Option<Vec<String>>,
+
+    /// Break before processing the manifest instructions at the given indices
+    #[clap(long, multiple = true)]
+    pub break_instruction: Option<Vec<usize>>,
+
+    /// Break after an event with the given name is emitted
+    #[clap(long, multiple = true)]
+    pub break_event: Option<Vec<String>>,
+}
+
+impl DebugManifest {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        self.run_with_input(&mut std::io::stdin().lock(), out)
+    }
+
+    /// Runs the debugger, reading the REPL commands from the given input.
+    pub fn run_with_input<I: BufRead, O: std::io::Write>(
+        &self,
+        input: &mut I,
+        out: &mut O,
+    ) -> Result<(), Error> {
+        let manifest = std::fs::read_to_string(&self.path).map_err(Error::IOError)?;
+        let pre_processed_manifest = Run::pre_process_manifest(&manifest);
+        let mut blobs = Vec::new();
+        if let Some(paths) = &self.blobs {
+            for path in paths {
+                blobs.push(std::fs::read(path).map_err(Error::IOError)?);
+            }
+        }
+        let compiled_manifest = match transaction::manifest::compile(
+            &pre_processed_manifest,
+            &NetworkDefinition::simulator(),
+            BlobProvider::new_with_blobs(blobs),
+        ) {
+            Ok(compiled_manifest) => compiled_manifest,
+            Err(err) => {
+                writeln!(
+                    out,
+                    "{}",
+                    compile_error_diagnostics(&pre_processed_manifest, &err)
+                )
+                .map_err(Error::IOError)?;
+                return Err(Error::CompileError(err));
+            }
+        };
+
+        validate_call_arguments_to_native_components(&compiled_manifest.instructions)
+            .map_err(Error::InstructionSchemaValidationError)?;
+
+        let mut breakpoints = Vec::new();
+        for invocation in self.break_invocation.iter().flatten() {
+            breakpoints.push(parse_invocation_breakpoint(invocation)?);
+        }
+        for index in self.break_instruction.iter().flatten() {
+            breakpoints.push(Breakpoint::Instruction(*index));
+        }
+        for event_name in self.break_event.iter().flatten() {
+            breakpoints.push(Breakpoint::Event(event_name.clone()));
+        }
+        if breakpoints.is_empty() {
+            breakpoints.push(Breakpoint::Instruction(0));
+        }
+
+        let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+        let native_vm = DefaultNativeVm::new();
+        let vm = Vm::new(&scrypto_vm, native_vm);
+        let mut substate_db = RocksdbSubstateStore::standard(get_data_dir()?);
+        Bootstrapper::new(
+            NetworkDefinition::simulator(),
+            &mut substate_db,
+            vm.clone(),
+            false,
+        )
+        .bootstrap_test_default();
+
+        let initial_proofs = get_signing_keys(&self.signing_keys)?
+            .into_iter()
+            .map(|e| NonFungibleGlobalId::from_public_key(&e.public_key()))
+            .collect::<BTreeSet<NonFungibleGlobalId>>();
+        let transaction = TestTransaction::new_from_nonce(compiled_manifest, get_nonce()?);
+        let prepared = transaction
+            .prepare()
+            .map_err(Error::TransactionPrepareError)?;
+
+        let receipt = execute_transaction_with_system::<_, _, SystemDebugger<'_, _>>(
+            &substate_db,
+            vm,
+            &CostingParameters::default(),
+            &ExecutionConfig::for_test_transaction(),
+            &prepared.get_executable(initial_proofs),
+            SystemDebuggerInit {
+                breakpoints,
+                handler: Box::new(DebuggerRepl {
+                    input: &mut *input,
+                    out: &mut *out,
+                    encoder: AddressBech32Encoder::for_simulator(),
+                }),
+            },
+        );
+
+        let encoder = AddressBech32Encoder::for_simulator();
+        let display_context = TransactionReceiptDisplayContextBuilder::new()
+            .encoder(&encoder)
+            .schema_lookup_callback(|event_type_identifier: &EventTypeIdentifier| {
+                get_event_schema(&substate_db, event_type_identifier)
+            })
+            .build();
+        writeln!(out, "{}", receipt.display(display_context)).map_err(Error::IOError)?;
+        writeln!(out, "The transaction has not been committed.").map_err(Error::IOError)?;
+
+        Ok(())
+    }
+}
+
+fn parse_invocation_breakpoint(breakpoint: &str) -> Result<Breakpoint, Error> {
+    let parts: Vec<&str> = breakpoint.split(':').collect();
+    let (package_address, blueprint_name, ident) = match parts.as_slice() {
+        [package_address, blueprint_name] => (package_address, blueprint_name, None),
+        [package_address, blueprint_name, ident] => {
+            (package_address, blueprint_name, Some(ident.to_string()))
+        }
+        _ => return Err(Error::InvalidBreakpoint(breakpoint.to_string())),
+    };
+    let package_address = SimulatorPackageAddress::from_str(package_address)
+        .map_err(|_| Error::InvalidBreakpoint(breakpoint.to_string()))?;
+
+    Ok(Breakpoint::Invocation {
+        blueprint_id: BlueprintId::new(&package_address.0, blueprint_name),
+        ident,
+    })
+}
+
+const DEBUGGER_HELP: &str = "Commands:
+  actors                             Shows the call frame stack
+  owned [<depth>]                    Shows the nodes owned by a call frame, the current one by default
+  substate <node> <partition> <key>  Shows a substate; the key is field:<index> or map:<hex>
+  worktop                            Shows the content of the worktop
+  fees                               Shows the state of the fee reserve
+  events                             Shows the events emitted so far
+  logs                               Shows the logs emitted so far
+  step                               Runs until the next invocation
+  continue                           Runs until the next breakpoint";
+
+struct DebuggerRepl<'a, I: BufRead, O: std::io::Write> {
+    input: &'a mut I,
+    out: &'a mut O,
+    encoder: AddressBech32Encoder,
+}
+
+impl<'a, I: BufRead, O: std::io::Write> DebuggerHandler for DebuggerRepl<'a, I, O> {
+    fn on_break(
+        &mut self,
+        reason: &BreakReason,
+        inspector: &mut dyn DebuggerInspector,
+    ) -> DebuggerAction {
+        // Failing to write is not worth aborting the transaction for
+        self.repl(reason, inspector)
+            .unwrap_or(DebuggerAction::Continue)
+    }
+}
+
+impl<'a, I: BufRead, O: std::io::Write> DebuggerRepl<'a, I, O> {
+    fn repl(
+        &mut self,
+        reason: &BreakReason,
+        inspector: &mut dyn DebuggerInspector,
+    ) -> std::io::Result<DebuggerAction> {
+        match reason {
+            BreakReason::Invocation(actor) => {
+                let actor = self.display_actor(actor);
+                writeln!(self.out, "Break before invoking {}", actor)?
+            }
+            BreakReason::Instruction(index) => {
+                writeln!(self.out, "Break at instruction {}", index)?
+            }
+            BreakReason::Event(EventTypeIdentifier(emitter, event_name)) => writeln!(
+                self.out,
+                "Break after event {} emitted by {}",
+                event_name,
+                emitter.display(&self.encoder)
+            )?,
+        }
+
+        loop {
+            write!(self.out, "(debug) ")?;
+            self.out.flush()?;
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(..) => return Ok(DebuggerAction::Continue),
+                Ok(_) => {}
+            }
+
+            let args: Vec<&str> = line.split_whitespace().collect();
+            match args.as_slice() {
+                [] => {}
+                ["actors"] => {
+                    for (depth, actor) in inspector.actors().iter().enumerate() {
+                        let actor = self.display_actor(actor);
+                        writeln!(self.out, "#{} {}", depth, actor)?;
+                    }
+                }
+                ["owned"] | ["owned", _] => {
+                    let depth = match args.get(1) {
+                        Some(depth) => depth.parse().ok(),
+                        None => inspector.actors().len().checked_sub(1),
+                    };
+                    match depth {
+                        Some(depth) => {
+                            for node_id in inspector.owned_nodes(depth) {
+                                writeln!(self.out, "{}", node_id.display(&self.encoder))?;
+                            }
+                        }
+                        None => writeln!(self.out, "Invalid depth")?,
+                    }
+                }
+                ["substate", node_id, partition_num, substate_key] => {
+                    let substate = parse_node_id(node_id)
+                        .zip(partition_num.parse::<u8>().ok())
+                        .zip(parse_substate_key(substate_key));
+                    match substate {
+                        Some(((node_id, partition_num), substate_key)) => match inspector
+                            .peek_substate(&node_id, PartitionNumber(partition_num), &substate_key)
+                        {
+                            Some(value) => {
+                                let value = self.display_value(&value);
+                                writeln!(self.out, "{}", value)?
+                            }
+                            None => writeln!(self.out, "Substate not found")?,
+                        },
+                        None => writeln!(self.out, "Invalid substate")?,
+                    }
+                }
+                ["worktop"] => match inspector.worktop() {
+                    Some(buckets) => {
+                        for bucket in buckets {
+                            self.print_bucket(&bucket)?;
+                        }
+                    }
+                    None => writeln!(self.out, "No worktop")?,
+                },
+                ["fees"] => match inspector.fee_reserve() {
+                    Some(fee_reserve) => {
+                        writeln!(self.out, "Fee balance: {} XRD", fee_reserve.fee_balance())?;
+                        writeln!(
+                            self.out,
+                            "Execution cost unit limit: {}",
+                            fee_reserve.execution_cost_unit_limit()
+                        )?;
+                        writeln!(
+                            self.out,
+                            "Execution cost unit price: {} XRD",
+                            fee_reserve.execution_cost_unit_price()
+                        )?;
+                        writeln!(
+                            self.out,
+                            "Finalization cost unit limit: {}",
+                            fee_reserve.finalization_cost_unit_limit()
+                        )?;
+                        writeln!(
+                            self.out,
+                            "Finalization cost unit price: {} XRD",
+                            fee_reserve.finalization_cost_unit_price()
+                        )?;
+                    }
+                    None => writeln!(self.out, "Costing is disabled")?,
+                },
+                ["events"] => {
+                    for event in inspector.events() {
+                        let EventTypeIdentifier(emitter, event_name) = &event.type_identifier;
+                        writeln!(
+                            self.out,
+                            "{} emitted by {}",
+                            event_name,
+                            emitter.display(&self.encoder)
+                        )?;
+                    }
+                }
+                ["logs"] => {
+                    for (level, message) in inspector.logs() {
+                        writeln!(self.out, "[{}] {}", level, message)?;
+                    }
+                }
+                ["step"] | ["s"] => return Ok(DebuggerAction::Step),
+                ["continue"] | ["c"] => return Ok(DebuggerAction::Continue),
+                _ => writeln!(self.out, "{}", DEBUGGER_HELP)?,
+            }
+        }
+    }
+
+    fn display_actor(&self, actor: &Actor) -> String {
+        match actor {
+            Actor::Root => "root".to_string(),
+            Actor::Method(method_actor) => format!(
+                "{}::{} on {}",
+                method_actor.get_blueprint_id().blueprint_name,
+                method_actor.ident,
+                method_actor.node_id.display(&self.encoder)
+            ),
+            Actor::Function(FunctionActor {
+                blueprint_id,
+                ident,
+                ..
+            }) => format!(
+                "{}::{}::{}",
+                blueprint_id.package_address.display(&self.encoder),
+                blueprint_id.blueprint_name,
+                ident
+            ),
+            Actor::BlueprintHook(BlueprintHookActor {
+                blueprint_id, hook, ..
+            }) => format!("{} {:?} hook", blueprint_id.blueprint_name, hook),
+        }
+    }
+
+    fn display_value(&self, value: &IndexedScryptoValue) -> String {
+        value.to_string(ValueDisplayParameters::Schemaless {
+            display_mode: DisplayMode::RustLike,
+            print_mode: PrintMode::MultiLine {
+                indent_size: 2,
+                base_indent: 0,
+                first_line_indent: 0,
+            },
+            custom_context: ScryptoValueDisplayContext::with_optional_bech32(Some(&self.encoder)),
+            depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+        })
+    }
+
+    fn print_bucket(&mut self, bucket: &BucketSnapshot) -> std::io::Result<()> {
+        match bucket {
+            BucketSnapshot::Fungible {
+                resource_address,
+                liquid,
+            } => writeln!(
+                self.out,
+                "{} of {}",
+                liquid,
+                resource_address.display(&self.encoder)
+            ),
+            BucketSnapshot::NonFungible {
+                resource_address,
+                liquid,
+            } => writeln!(
+                self.out,
+                "{:?} of {}",
+                liquid,
+                resource_address.display(&self.encoder)
+            ),
+        }
+    }
+}
+
+fn parse_node_id(node_id: &str) -> Option<NodeId> {
+    let bytes = hex::decode(node_id).ok().or_else(|| {
+        AddressBech32Decoder::for_simulator()
+            .validate_and_decode(node_id)
+            .ok()
+            .map(|(_, bytes)| bytes)
+    })?;
+    bytes.try_into().ok().map(NodeId)
+}
+
+fn parse_substate_key(substate_key: &str) -> Option<SubstateKey> {
+    match substate_key.split_once(':')? {
+        ("field", field) => field.parse().ok().map(SubstateKey::Field),
+        ("map", key) => hex::decode(key).ok().map(SubstateKey::Map),
+        _ => None,
+    }
+}
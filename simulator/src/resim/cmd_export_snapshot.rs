@@ -0,0 +1,38 @@
+use clap::Parser;
+use radix_engine_stores::snapshot::{compute_state_root_hash, export_snapshot, SnapshotHeader};
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::resim::*;
+
+/// Export the simulator's ledger state to a snapshot file
+#[derive(Parser, Debug)]
+pub struct ExportSnapshot {
+    /// The output snapshot file
+    pub output: PathBuf,
+}
+
+impl ExportSnapshot {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let substate_db = RocksdbSubstateStore::standard(get_data_dir()?);
+        // the simulator's store does not track a state tree, so the root hash is computed here
+        // (which allows the snapshot to be verified on import)
+        let header = SnapshotHeader {
+            state_version: Some(substate_db.get_current_version()).filter(|v| *v > 0),
+            state_root_hash: Some(compute_state_root_hash(&substate_db)),
+        };
+        let file = File::create(&self.output)
+            .map_err(|err| Error::IOErrorAtPath(err, self.output.clone()))?;
+        let summary = export_snapshot(&substate_db, header, BufWriter::new(file))
+            .map_err(Error::SnapshotError)?;
+        writeln!(
+            out,
+            "Exported {} substates of {} partitions to {}",
+            summary.substate_count,
+            summary.partition_count,
+            self.output.display()
+        )
+        .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
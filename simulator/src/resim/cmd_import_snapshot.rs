@@ -0,0 +1,32 @@
+use clap::Parser;
+use radix_engine_stores::snapshot::import_snapshot;
+use std::fs::File;
+use std::io::BufReader;
+
+use crate::resim::*;
+
+/// Import the simulator's ledger state from a snapshot file (requires a reset simulator)
+#[derive(Parser, Debug)]
+pub struct ImportSnapshot {
+    /// The input snapshot file
+    pub input: PathBuf,
+}
+
+impl ImportSnapshot {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let mut substate_db = RocksdbSubstateStore::standard(get_data_dir()?);
+        let file =
+            File::open(&self.input).map_err(|err| Error::IOErrorAtPath(err, self.input.clone()))?;
+        let summary = import_snapshot(&mut substate_db, BufReader::new(file))
+            .map_err(Error::SnapshotError)?;
+        writeln!(
+            out,
+            "Imported {} substates of {} partitions from {}",
+            summary.substate_count,
+            summary.partition_count,
+            self.input.display()
+        )
+        .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
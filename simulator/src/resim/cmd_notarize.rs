@@ -0,0 +1,64 @@
+use clap::Parser;
+use radix_engine::types::*;
+use std::path::PathBuf;
+use transaction::prelude::*;
+
+use crate::resim::*;
+
+/// Notarize a signed transaction intent
+#[derive(Parser, Debug)]
+pub struct Notarize {
+    /// The path to a signed intent, as written by `resim sign`
+    pub path: PathBuf,
+
+    /// The notary private key, matching the notary public key of the intent, defaulting to the default account's key
+    #[clap(short, long)]
+    pub key: Option<String>,
+
+    /// The path to write the notarized transaction to, defaulting to the input path
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Notarize {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let payload = std::fs::read(&self.path).map_err(Error::IOError)?;
+        let signed_intent =
+            SignedIntentV1::from_payload_bytes(&payload).map_err(Error::SborDecodeError)?;
+
+        let private_key = match &self.key {
+            Some(key) => parse_private_key_from_str(key)?,
+            None => get_default_private_key()?,
+        };
+        if PublicKey::from(private_key.public_key())
+            != signed_intent.intent.header.notary_public_key
+        {
+            return Err(Error::NotaryKeyMismatch);
+        }
+        let signed_intent_hash = signed_intent
+            .prepare()
+            .map_err(Error::TransactionPrepareError)?
+            .signed_intent_hash();
+        let transaction = NotarizedTransactionV1 {
+            signed_intent,
+            notary_signature: NotarySignatureV1(
+                private_key
+                    .sign_with_public_key(&signed_intent_hash)
+                    .signature(),
+            ),
+        };
+
+        let output = self.output.as_ref().unwrap_or(&self.path);
+        std::fs::write(
+            output,
+            transaction
+                .to_payload_bytes()
+                .map_err(Error::SborEncodeError)?,
+        )
+        .map_err(Error::IOError)?;
+
+        writeln!(out, "Notarized transaction written to {}", output.display())
+            .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
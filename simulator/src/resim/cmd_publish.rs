@@ -65,6 +65,7 @@ impl Publish {
                 false,
                 false,
                 self.disable_wasm_opt,
+                false,
                 self.log_level.unwrap_or(Level::default()),
             )
             .map_err(Error::BuildError)?
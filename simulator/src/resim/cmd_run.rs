@@ -3,7 +3,7 @@ use radix_engine::utils::validate_call_arguments_to_native_components;
 use regex::{Captures, Regex};
 use std::env;
 use std::path::PathBuf;
-use transaction::manifest::BlobProvider;
+use transaction::manifest::{compile_error_diagnostics, BlobProvider};
 
 use crate::resim::*;
 
@@ -17,6 +17,14 @@ pub struct Run {
     #[clap(short, long)]
     pub network: Option<String>,
 
+    /// Output an unsigned transaction intent without execution, to be signed with `resim sign`
+    #[clap(long)]
+    pub unsigned_out: Option<PathBuf>,
+
+    /// The public key of the notary of the unsigned transaction intent, defaulting to the default account's key
+    #[clap(long)]
+    pub notary_public_key: Option<String>,
+
     /// The paths to blobs
     #[clap(short, long, multiple = true)]
     pub blobs: Option<Vec<String>>,
@@ -52,16 +60,36 @@ impl Run {
                 blobs.push(std::fs::read(path).map_err(Error::IOError)?);
             }
         }
-        let compiled_manifest = transaction::manifest::compile(
+        let compiled_manifest = match transaction::manifest::compile(
             &pre_processed_manifest,
             &network,
             BlobProvider::new_with_blobs(blobs),
-        )
-        .map_err(Error::CompileError)?;
+        ) {
+            Ok(compiled_manifest) => compiled_manifest,
+            Err(err) => {
+                writeln!(
+                    out,
+                    "{}",
+                    compile_error_diagnostics(&pre_processed_manifest, &err)
+                )
+                .map_err(Error::IOError)?;
+                return Err(Error::CompileError(err));
+            }
+        };
 
         validate_call_arguments_to_native_components(&compiled_manifest.instructions)
             .map_err(Error::InstructionSchemaValidationError)?;
 
+        if let Some(path) = &self.unsigned_out {
+            return export_unsigned_intent(
+                compiled_manifest,
+                &self.network,
+                &self.notary_public_key,
+                path,
+                out,
+            );
+        }
+
         handle_manifest(
             compiled_manifest,
             &self.signing_keys,
@@ -0,0 +1,80 @@
+use clap::Parser;
+use radix_engine::types::*;
+use std::path::PathBuf;
+use transaction::prelude::*;
+use transaction::validation::recover;
+
+use crate::resim::*;
+
+/// Sign an unsigned or partially signed transaction intent
+#[derive(Parser, Debug)]
+pub struct Sign {
+    /// The path to an intent, as written by `--unsigned-out`, or a signed intent
+    pub path: PathBuf,
+
+    /// The private key used for signing, defaulting to the default account's key
+    #[clap(short, long)]
+    pub key: Option<String>,
+
+    /// The path to write the signed intent to, defaulting to the input path
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Sign {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let payload = std::fs::read(&self.path).map_err(Error::IOError)?;
+        let mut signed_intent = match SignedIntentV1::from_payload_bytes(&payload) {
+            Ok(signed_intent) => signed_intent,
+            Err(_) => SignedIntentV1 {
+                intent: IntentV1::from_payload_bytes(&payload).map_err(Error::SborDecodeError)?,
+                intent_signatures: IntentSignaturesV1 { signatures: vec![] },
+            },
+        };
+
+        let private_key = match &self.key {
+            Some(key) => parse_private_key_from_str(key)?,
+            None => get_default_private_key()?,
+        };
+        let intent_hash = signed_intent
+            .intent
+            .prepare()
+            .map_err(Error::TransactionPrepareError)?
+            .intent_hash();
+        if signed_intent
+            .intent_signatures
+            .signatures
+            .iter()
+            .any(|signature| {
+                recover(intent_hash.as_hash(), &signature.0)
+                    == Some(private_key.public_key().into())
+            })
+        {
+            return Err(Error::DuplicateSigner(private_key.public_key()));
+        }
+        signed_intent
+            .intent_signatures
+            .signatures
+            .push(IntentSignatureV1(
+                private_key.sign_with_public_key(&intent_hash),
+            ));
+
+        let output = self.output.as_ref().unwrap_or(&self.path);
+        std::fs::write(
+            output,
+            signed_intent
+                .to_payload_bytes()
+                .map_err(Error::SborEncodeError)?,
+        )
+        .map_err(Error::IOError)?;
+
+        writeln!(
+            out,
+            "Signed intent with {} signature(s) written to {}",
+            signed_intent.intent_signatures.signatures.len(),
+            output.display()
+        )
+        .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use clap::Parser;
+use radix_engine::system::bootstrap::Bootstrapper;
+use radix_engine::transaction::{
+    execute_and_commit_transaction, CostingParameters, ExecutionConfig,
+    TransactionReceiptDisplayContextBuilder,
+};
+use radix_engine::types::*;
+use radix_engine::vm::wasm::*;
+use radix_engine::vm::{DefaultNativeVm, ScryptoVm, Vm};
+use radix_engine_stores::rocks_db::RocksdbSubstateStore;
+use std::path::PathBuf;
+use transaction::validation::{
+    NotarizedTransactionValidator, TransactionValidator, ValidationConfig,
+};
+use utils::ContextualDisplay;
+
+use crate::resim::*;
+
+/// Validate and run a notarized transaction
+#[derive(Parser, Debug)]
+pub struct Submit {
+    /// The path to a notarized transaction, as written by `resim notarize`
+    pub path: PathBuf,
+
+    /// Turn on tracing
+    #[clap(short, long)]
+    pub trace: bool,
+}
+
+impl Submit {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let payload = std::fs::read(&self.path).map_err(Error::IOError)?;
+        let network = NetworkDefinition::simulator();
+        // Validate the transaction as the simulator network does
+        let validated = NotarizedTransactionValidator::new(ValidationConfig::simulator())
+            .validate_from_payload_bytes(&payload)
+            .map_err(Error::TransactionValidationError)?;
+
+        let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+        let native_vm = DefaultNativeVm::new();
+        let vm = Vm::new(&scrypto_vm, native_vm);
+        let mut substate_db = RocksdbSubstateStore::standard(get_data_dir()?);
+        Bootstrapper::new(network.clone(), &mut substate_db, vm.clone(), false)
+            .bootstrap_test_default();
+
+        let receipt = execute_and_commit_transaction(
+            &mut substate_db,
+            vm,
+            &CostingParameters::default(),
+            &ExecutionConfig::for_notarized_transaction(network).with_kernel_trace(self.trace),
+            &validated.get_executable(),
+        );
+
+        let encoder = AddressBech32Encoder::for_simulator();
+        let display_context = TransactionReceiptDisplayContextBuilder::new()
+            .encoder(&encoder)
+            .schema_lookup_callback(|event_type_identifier: &EventTypeIdentifier| {
+                get_event_schema(&substate_db, event_type_identifier)
+            })
+            .build();
+        writeln!(out, "{}", receipt.display(display_context)).map_err(Error::IOError)?;
+        drop(substate_db);
+
+        process_receipt(receipt).map(|_| ())
+    }
+}
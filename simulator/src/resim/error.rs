@@ -3,12 +3,13 @@ use std::path::PathBuf;
 
 use radix_engine::errors::{RejectionReason, RuntimeError};
 use radix_engine::transaction::AbortReason;
-use radix_engine::types::{ComponentAddress, NodeId, PackageAddress};
+use radix_engine::types::{ComponentAddress, NodeId, PackageAddress, Secp256k1PublicKey};
 use radix_engine::utils::ExtractSchemaError;
 use radix_engine::vm::wasm::PrepareError;
 use radix_engine_interface::blueprints::resource::ParseNonFungibleGlobalIdError;
 use radix_engine_interface::network::ParseNetworkError;
 use radix_engine_interface::types::SchemaHash;
+use radix_engine_stores::snapshot::SnapshotError;
 use sbor::*;
 use transaction::errors::*;
 use transaction::model::PrepareError as TransactionPrepareError;
@@ -59,6 +60,8 @@ pub enum Error {
 
     LedgerDumpError(EntityDumpError),
 
+    SnapshotError(SnapshotError),
+
     CompileError(transaction::manifest::CompileError),
 
     DecompileError(transaction::manifest::DecompileError),
@@ -67,6 +70,14 @@ pub enum Error {
 
     InvalidPrivateKey,
 
+    InvalidPublicKey,
+
+    /// The notary key doesn't match the notary public key in the transaction header.
+    NotaryKeyMismatch,
+
+    /// The intent was already signed with the key.
+    DuplicateSigner(Secp256k1PublicKey),
+
     /// e.g. if you accidentally pass in a public key in `set_default_account` command.
     GotPublicKeyExpectedPrivateKey,
 
@@ -82,5 +93,7 @@ pub enum Error {
 
     InvalidResourceSpecifier(String),
 
+    InvalidBreakpoint(String),
+
     RemoteGenericSubstitutionNotSupported,
 }
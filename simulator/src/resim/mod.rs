@@ -1,8 +1,11 @@
 mod addressing;
 mod cmd_call_function;
 mod cmd_call_method;
+mod cmd_debug;
 mod cmd_export_package_definition;
+mod cmd_export_snapshot;
 mod cmd_generate_key_pair;
+mod cmd_import_snapshot;
 mod cmd_mint;
 mod cmd_new_account;
 mod cmd_new_badge_fixed;
@@ -10,6 +13,7 @@ mod cmd_new_badge_mutable;
 mod cmd_new_simple_badge;
 mod cmd_new_token_fixed;
 mod cmd_new_token_mutable;
+mod cmd_notarize;
 mod cmd_publish;
 mod cmd_reset;
 mod cmd_run;
@@ -19,6 +23,8 @@ mod cmd_set_default_account;
 mod cmd_show;
 mod cmd_show_configs;
 mod cmd_show_ledger;
+mod cmd_sign;
+mod cmd_submit;
 mod cmd_transfer;
 mod config;
 mod dumper;
@@ -27,8 +33,11 @@ mod error;
 pub use addressing::*;
 pub use cmd_call_function::*;
 pub use cmd_call_method::*;
+pub use cmd_debug::*;
 pub use cmd_export_package_definition::*;
+pub use cmd_export_snapshot::*;
 pub use cmd_generate_key_pair::*;
+pub use cmd_import_snapshot::*;
 pub use cmd_mint::*;
 pub use cmd_new_account::*;
 pub use cmd_new_badge_fixed::*;
@@ -36,6 +45,7 @@ pub use cmd_new_badge_mutable::*;
 pub use cmd_new_simple_badge::*;
 pub use cmd_new_token_fixed::*;
 pub use cmd_new_token_mutable::*;
+pub use cmd_notarize::*;
 pub use cmd_publish::*;
 pub use cmd_reset::*;
 pub use cmd_run::*;
@@ -45,6 +55,8 @@ pub use cmd_set_default_account::*;
 pub use cmd_show::*;
 pub use cmd_show_configs::*;
 pub use cmd_show_ledger::*;
+pub use cmd_sign::*;
+pub use cmd_submit::*;
 pub use cmd_transfer::*;
 pub use config::*;
 pub use dumper::*;
@@ -111,8 +123,11 @@ impl ResimCli {
 pub enum Command {
     CallFunction(CallFunction),
     CallMethod(CallMethod),
+    Debug(DebugManifest),
     ExportPackageDefinition(ExportPackageDefinition),
+    ExportSnapshot(ExportSnapshot),
     GenerateKeyPair(GenerateKeyPair),
+    ImportSnapshot(ImportSnapshot),
     Mint(crate::resim::cmd_mint::Mint),
     NewAccount(NewAccount),
     NewSimpleBadge(NewSimpleBadge),
@@ -120,6 +135,7 @@ pub enum Command {
     NewBadgeMutable(NewBadgeMutable),
     NewTokenFixed(NewTokenFixed),
     NewTokenMutable(NewTokenMutable),
+    Notarize(Notarize),
     Publish(Publish),
     Reset(Reset),
     Run(Run),
@@ -129,6 +145,8 @@ pub enum Command {
     ShowConfigs(ShowConfigs),
     ShowLedger(ShowLedger),
     Show(Show),
+    Sign(Sign),
+    Submit(Submit),
     Transfer(Transfer),
 }
 
@@ -140,8 +158,11 @@ pub fn run() -> Result<(), Error> {
     match cli.command {
         Command::CallFunction(cmd) => cmd.run(&mut out),
         Command::CallMethod(cmd) => cmd.run(&mut out),
+        Command::Debug(cmd) => cmd.run(&mut out),
         Command::ExportPackageDefinition(cmd) => cmd.run(&mut out),
+        Command::ExportSnapshot(cmd) => cmd.run(&mut out),
         Command::GenerateKeyPair(cmd) => cmd.run(&mut out),
+        Command::ImportSnapshot(cmd) => cmd.run(&mut out),
         Command::Mint(cmd) => cmd.run(&mut out),
         Command::NewAccount(cmd) => cmd.run(&mut out),
         Command::NewSimpleBadge(cmd) => cmd.run(&mut out).map(|_| ()),
@@ -149,6 +170,7 @@ pub fn run() -> Result<(), Error> {
         Command::NewBadgeMutable(cmd) => cmd.run(&mut out),
         Command::NewTokenFixed(cmd) => cmd.run(&mut out),
         Command::NewTokenMutable(cmd) => cmd.run(&mut out),
+        Command::Notarize(cmd) => cmd.run(&mut out),
         Command::Publish(cmd) => cmd.run(&mut out),
         Command::Reset(cmd) => cmd.run(&mut out),
         Command::Run(cmd) => cmd.run(&mut out),
@@ -158,6 +180,8 @@ pub fn run() -> Result<(), Error> {
         Command::ShowConfigs(cmd) => cmd.run(&mut out),
         Command::ShowLedger(cmd) => cmd.run(&mut out),
         Command::Show(cmd) => cmd.run(&mut out),
+        Command::Sign(cmd) => cmd.run(&mut out),
+        Command::Submit(cmd) => cmd.run(&mut out),
         Command::Transfer(cmd) => cmd.run(&mut out),
     }
 }
@@ -299,6 +323,65 @@ pub fn handle_manifest<O: std::io::Write>(
     }
 }
 
+pub fn export_unsigned_intent<O: std::io::Write>(
+    manifest: TransactionManifestV1,
+    network: &Option<String>,
+    notary_public_key: &Option<String>,
+    path: &PathBuf,
+    out: &mut O,
+) -> Result<(), Error> {
+    let network = match network {
+        Some(n) => NetworkDefinition::from_str(&n).map_err(Error::ParseNetworkError)?,
+        None => NetworkDefinition::simulator(),
+    };
+    let current_epoch = db_read_epoch()?;
+    let notary_public_key = match notary_public_key {
+        Some(key) => Secp256k1PublicKey::from_str(key).map_err(|_| Error::InvalidPublicKey)?,
+        None => get_default_private_key()?.public_key(),
+    };
+    let nonce = get_nonce()?;
+
+    let intent = TransactionBuilder::new()
+        .header(TransactionHeaderV1 {
+            network_id: network.id,
+            start_epoch_inclusive: current_epoch,
+            end_epoch_exclusive: current_epoch
+                .after(MAX_EPOCH_RANGE)
+                .unwrap_or(Epoch::of(u64::MAX)),
+            nonce,
+            notary_public_key: notary_public_key.into(),
+            notary_is_signatory: false,
+            tip_percentage: 0,
+        })
+        .manifest(manifest)
+        .build_intent();
+    let intent_hash = intent
+        .prepare()
+        .map_err(Error::TransactionPrepareError)?
+        .intent_hash();
+    fs::write(
+        path,
+        intent.to_payload_bytes().map_err(Error::SborEncodeError)?,
+    )
+    .map_err(Error::IOError)?;
+
+    // Each exported intent needs a distinct nonce, as it won't be committed by `process_receipt`
+    let mut configs = get_configs()?;
+    configs.nonce = nonce + 1;
+    set_configs(&configs)?;
+
+    writeln!(
+        out,
+        "Unsigned intent {} written to {}",
+        TransactionHashBech32Encoder::new(&network)
+            .encode(&intent_hash)
+            .unwrap(),
+        path.display()
+    )
+    .map_err(Error::IOError)?;
+    Ok(())
+}
+
 pub fn process_receipt(receipt: TransactionReceipt) -> Result<TransactionReceipt, Error> {
     match &receipt.result {
         TransactionResult::Commit(commit) => {
@@ -512,6 +595,28 @@ pub fn db_upsert_timestamps(
     Ok(())
 }
 
+pub fn db_read_epoch() -> Result<Epoch, Error> {
+    let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
+    let native_vm = DefaultNativeVm::new();
+    let vm = Vm::new(&scrypto_vm, native_vm);
+    let mut substate_db = RocksdbSubstateStore::standard(get_data_dir()?);
+    Bootstrapper::new(NetworkDefinition::simulator(), &mut substate_db, vm, false)
+        .bootstrap_test_default();
+
+    let reader = SystemDatabaseReader::new(&substate_db);
+
+    let epoch = reader
+        .read_typed_object_field::<ConsensusManagerStateFieldPayload>(
+            CONSENSUS_MANAGER.as_node_id(),
+            ModuleId::Main,
+            ConsensusManagerField::State.field_index(),
+        )
+        .map(|state| state.into_latest().epoch)
+        .unwrap_or(Epoch::zero());
+
+    Ok(epoch)
+}
+
 pub fn db_upsert_epoch(epoch: Epoch) -> Result<(), Error> {
     let scrypto_vm = ScryptoVm::<DefaultWasmEngine>::default();
     let native_vm = DefaultNativeVm::new();
@@ -619,9 +724,66 @@ mod tests {
         assert!(make_cmd(public_key.to_string()).run(&mut out).is_err());
     }
 
+    fn test_debug_manifest_reads_commands_from_input() {
+        let manifest_dir = tempfile::tempdir().unwrap();
+        let path = manifest_dir.path().join("free_xrd.rtm");
+        std::fs::write(
+            &path,
+            r#"CALL_METHOD Address("${faucet}") "lock_fee" Decimal("10");
+CALL_METHOD Address("${faucet}") "free";
+"#
+            .replace(
+                "${faucet}",
+                &FAUCET.to_string(&AddressBech32Encoder::for_simulator()),
+            ),
+        )
+        .unwrap();
+        let cmd = DebugManifest {
+            path,
+            blobs: None,
+            signing_keys: Some(String::new()),
+            break_invocation: None,
+            break_instruction: Some(vec![1]),
+            break_event: None,
+        };
+
+        let mut out = Vec::new();
+        let result = cmd.run_with_input(&mut "fees\ncontinue\n".as_bytes(), &mut out);
+
+        assert!(result.is_ok());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Break at instruction 1\n(debug) Fee balance: "));
+        assert!(out.ends_with("The transaction has not been committed.\n"));
+    }
+
+    #[test]
+    fn test_run_writes_compile_error_diagnostics_to_out() {
+        let manifest_dir = tempfile::tempdir().unwrap();
+        let path = manifest_dir.path().join("invalid.rtm");
+        std::fs::write(&path, "DROP_ALL_PROOFS;\nDROP_ALL_PROOFS\nDROP_ALL_PROOFS;").unwrap();
+        let cmd = Run {
+            path,
+            network: None,
+            unsigned_out: None,
+            notary_public_key: None,
+            blobs: None,
+            signing_keys: None,
+            trace: false,
+        };
+
+        let mut out = Vec::new();
+        let result = cmd.run(&mut out);
+
+        assert!(matches!(result, Err(Error::CompileError(..))));
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("error: expected `;`, found `DROP_ALL_PROOFS`"));
+    }
+
     #[test]
     fn serial_resim_command_tests() {
         test_no_value();
+        test_debug_manifest_reads_commands_from_input();
         test_pre_process_manifest();
         test_set_default_account_validation();
     }
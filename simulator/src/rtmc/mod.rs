@@ -1,28 +1,79 @@
-use clap::Parser;
+use crate::utils::{ManifestFormat, ParseManifestFormatError};
+use clap::{Parser, Subcommand};
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
 use radix_engine::{types::*, utils::*};
+use radix_engine_stores::rocks_db::RocksdbSubstateStore;
 use std::path::PathBuf;
 use std::str::FromStr;
-use transaction::manifest::{compile, BlobProvider};
+use transaction::manifest::diagnostics::{
+    manifest_warning_diagnostics, render_diagnostic, CompileErrorDiagnostic,
+};
+use transaction::manifest::{
+    analyze_manifest, compile_error_diagnostics, compile_with_source_map, format_manifest_source,
+    json, BlobProvider, ManifestSourceMap,
+};
 
 /// Radix transaction manifest compiler
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None, name = "rtmc")]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    name = "rtmc",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Path to the output file
-    #[clap(short, long)]
-    output: PathBuf,
+    #[clap(short, long, required = true)]
+    output: Option<PathBuf>,
 
     /// Network to Use [Simulator | Alphanet | Mainnet]
     #[clap(short, long)]
     network: Option<String>,
 
+    /// Format of the output file [Sbor | Json], defaults to Sbor
+    #[clap(short, long)]
+    format: Option<String>,
+
     /// The paths to blobs
     #[clap(short, long, multiple = true)]
     blobs: Option<Vec<String>>,
 
+    /// Path to a ledger database (e.g. the `resim` data directory), to check the arguments of
+    /// all function and method calls against the schemas of the called blueprints
+    #[clap(short, long)]
+    ledger: Option<PathBuf>,
+
+    /// Whether to statically analyze the manifest and report likely mistakes (e.g. buckets which
+    /// are never consumed, or resources left on the worktop)
+    #[clap(long, action)]
+    lint: bool,
+
     /// Input file
     #[clap(required = true)]
-    input: PathBuf,
+    input: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Formats manifest files in place, keeping their comments
+    Fmt(FmtArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct FmtArgs {
+    /// Only check that the files are formatted, failing if any is not
+    #[clap(long, action)]
+    check: bool,
+
+    /// The manifest files to format
+    #[clap(required = true)]
+    inputs: Vec<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -32,31 +83,191 @@ pub enum Error {
     CompileError(transaction::manifest::CompileError),
     ParseNetworkError(ParseNetworkError),
     InstructionSchemaValidationError(radix_engine::utils::LocatedInstructionSchemaValidationError),
+    InstructionSchemaValidationErrors(
+        Vec<radix_engine::utils::LocatedInstructionSchemaValidationError>,
+    ),
+    NotFormatted(Vec<PathBuf>),
+    ParseManifestFormatError(ParseManifestFormatError),
+    ManifestJsonError(transaction::manifest::ManifestJsonError),
 }
 
 pub fn run() -> Result<(), Error> {
     let args = Args::parse();
+    if let Some(Command::Fmt(fmt_args)) = args.command {
+        return run_fmt(fmt_args);
+    }
+    let input = args.input.expect("Required unless a subcommand is given");
+    let output = args.output.expect("Required unless a subcommand is given");
 
-    let content = std::fs::read_to_string(&args.input).map_err(Error::IoError)?;
+    let content = std::fs::read_to_string(&input).map_err(Error::IoError)?;
     let network = match args.network {
         Some(n) => NetworkDefinition::from_str(&n).map_err(Error::ParseNetworkError)?,
         None => NetworkDefinition::simulator(),
     };
+    let format = match args.format {
+        Some(f) => ManifestFormat::from_str(&f).map_err(Error::ParseManifestFormatError)?,
+        None => ManifestFormat::Sbor,
+    };
     let mut blobs = Vec::new();
     if let Some(paths) = args.blobs {
         for path in paths {
             blobs.push(std::fs::read(path).map_err(Error::IoError)?);
         }
     }
-    let transaction = compile(&content, &network, BlobProvider::new_with_blobs(blobs))
-        .map_err(Error::CompileError)?;
-    validate_call_arguments_to_native_components(&transaction.instructions)
-        .map_err(Error::InstructionSchemaValidationError)?;
-    std::fs::write(
-        args.output,
-        manifest_encode(&transaction).map_err(Error::EncodeError)?,
-    )
-    .map_err(Error::IoError)?;
+    let (transaction, source_map) =
+        compile_with_source_map(&content, &network, BlobProvider::new_with_blobs(blobs)).map_err(
+            |err| {
+                eprintln!("{}", compile_error_diagnostics(&content, &err));
+                Error::CompileError(err)
+            },
+        )?;
+    if args.lint {
+        for warning in analyze_manifest(&transaction.instructions) {
+            eprintln!(
+                "{}",
+                manifest_warning_diagnostics(&content, &source_map, &warning)
+            );
+        }
+    }
+    match args.ledger {
+        Some(ledger) => {
+            if !ledger.is_dir() {
+                return Err(Error::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Ledger directory not found: {}", ledger.display()),
+                )));
+            }
+            let substate_db = RocksdbSubstateStore::standard(ledger);
+            validate_call_arguments(
+                &transaction.instructions,
+                &SystemDatabaseReader::new(&substate_db),
+            )
+            .map_err(|errors| {
+                for error in &errors {
+                    eprintln!(
+                        "{}",
+                        validation_error_diagnostics(&content, &source_map, error)
+                    );
+                }
+                Error::InstructionSchemaValidationErrors(errors)
+            })?;
+        }
+        None => {
+            validate_call_arguments_to_native_components(&transaction.instructions).map_err(
+                |error| {
+                    eprintln!(
+                        "{}",
+                        validation_error_diagnostics(&content, &source_map, &error)
+                    );
+                    Error::InstructionSchemaValidationError(error)
+                },
+            )?;
+        }
+    }
+    let output_bytes = match format {
+        ManifestFormat::Sbor => manifest_encode(&transaction).map_err(Error::EncodeError)?,
+        ManifestFormat::Json => json::to_json(&transaction, &network)
+            .map_err(Error::ManifestJsonError)?
+            .into_bytes(),
+    };
+    std::fs::write(output, output_bytes).map_err(Error::IoError)?;
 
     Ok(())
 }
+
+fn run_fmt(args: FmtArgs) -> Result<(), Error> {
+    let mut not_formatted = Vec::new();
+    for input in args.inputs {
+        let content = std::fs::read_to_string(&input).map_err(Error::IoError)?;
+        let formatted = format_manifest_source(&content).map_err(|err| {
+            eprintln!("{}", compile_error_diagnostics(&content, &err));
+            Error::CompileError(err)
+        })?;
+        if formatted == content {
+            continue;
+        }
+        if args.check {
+            eprintln!("Not formatted: {}", input.display());
+            not_formatted.push(input);
+        } else {
+            std::fs::write(&input, formatted).map_err(Error::IoError)?;
+        }
+    }
+    if not_formatted.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::NotFormatted(not_formatted))
+    }
+}
+
+/// Renders the given error of validating the compiled manifest as an excerpt of its source (see
+/// [`compile_error_diagnostics()`]).
+fn validation_error_diagnostics(
+    s: &str,
+    source_map: &ManifestSourceMap,
+    error: &LocatedInstructionSchemaValidationError,
+) -> String {
+    let instruction_span = source_map
+        .instruction_span(error.instruction_index)
+        .expect("Every compiled instruction has a source span");
+    let (span, title, label) = match &error.cause {
+        InstructionSchemaValidationError::MethodNotFound(name) => (
+            instruction_span,
+            format!("function or method `{}` not found", name),
+            "not defined by the called blueprint".to_string(),
+        ),
+        InstructionSchemaValidationError::SchemaValidationError(cause) => (
+            instruction_span,
+            format!("arguments do not match the input schema: {}", cause),
+            "invalid arguments".to_string(),
+        ),
+        InstructionSchemaValidationError::InvalidAddress(address) => (
+            instruction_span,
+            format!("invalid address {:?}", address),
+            "invalid address".to_string(),
+        ),
+        InstructionSchemaValidationError::InvalidBlueprint(_, blueprint_name) => (
+            instruction_span,
+            format!("blueprint `{}` not found in the package", blueprint_name),
+            "unknown blueprint".to_string(),
+        ),
+        InstructionSchemaValidationError::InvalidReceiver => (
+            instruction_span,
+            "invalid receiver".to_string(),
+            "a function must not be called as a method, nor a method as a function".to_string(),
+        ),
+        InstructionSchemaValidationError::ObjectNotFound(node_id) => (
+            instruction_span,
+            format!(
+                "object {} not found in the ledger",
+                hex::encode(node_id.as_bytes())
+            ),
+            "called object does not exist".to_string(),
+        ),
+        InstructionSchemaValidationError::ModuleNotFound(_, module_id) => (
+            instruction_span,
+            format!("object has no {:?} module attached", module_id),
+            "called module does not exist".to_string(),
+        ),
+        InstructionSchemaValidationError::ArgumentsMismatch {
+            argument_index,
+            path,
+            cause,
+        } => (
+            argument_index
+                .and_then(|index| source_map.argument_span(error.instruction_index, index))
+                .unwrap_or(instruction_span),
+            format!("argument does not match the input schema: {}", cause),
+            format!("mismatch at {}", path),
+        ),
+    };
+    render_diagnostic(
+        s,
+        &CompileErrorDiagnostic {
+            span,
+            title,
+            label,
+            hint: None,
+        },
+    )
+}
@@ -1,3 +1,4 @@
+use crate::utils::{ManifestFormat, ParseManifestFormatError};
 use clap::Parser;
 use radix_engine::types::*;
 use radix_engine::utils::validate_call_arguments_to_native_components;
@@ -5,7 +6,7 @@ use radix_engine_interface::crypto::hash;
 use radix_engine_interface::data::manifest::manifest_decode;
 use std::path::PathBuf;
 use std::str::FromStr;
-use transaction::manifest::decompile;
+use transaction::manifest::{decompile, json};
 use transaction::prelude::*;
 
 /// Radix transaction manifest decompiler
@@ -20,6 +21,10 @@ pub struct Args {
     #[clap(short, long)]
     network: Option<String>,
 
+    /// Format of the input file [Sbor | Json], defaults to Sbor
+    #[clap(short, long)]
+    format: Option<String>,
+
     /// Whether to export blobs
     #[clap(short, long, action)]
     export_blobs: bool,
@@ -36,6 +41,8 @@ pub enum Error {
     DecompileError(transaction::manifest::DecompileError),
     ParseNetworkError(ParseNetworkError),
     InstructionSchemaValidationError(radix_engine::utils::LocatedInstructionSchemaValidationError),
+    ParseManifestFormatError(ParseManifestFormatError),
+    ManifestJsonError(transaction::manifest::ManifestJsonError),
 }
 
 pub fn run() -> Result<(), Error> {
@@ -46,8 +53,21 @@ pub fn run() -> Result<(), Error> {
         Some(n) => NetworkDefinition::from_str(&n).map_err(Error::ParseNetworkError)?,
         None => NetworkDefinition::simulator(),
     };
-    let manifest =
-        manifest_decode::<TransactionManifestV1>(&content).map_err(Error::DecodeError)?;
+    let format = match args.format {
+        Some(f) => ManifestFormat::from_str(&f).map_err(Error::ParseManifestFormatError)?,
+        None => ManifestFormat::Sbor,
+    };
+    let manifest = match format {
+        ManifestFormat::Sbor => {
+            manifest_decode::<TransactionManifestV1>(&content).map_err(Error::DecodeError)?
+        }
+        ManifestFormat::Json => {
+            let content = String::from_utf8(content).map_err(|err| {
+                Error::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            })?;
+            json::from_json(&content, &network).map_err(Error::ManifestJsonError)?
+        }
+    };
     validate_call_arguments_to_native_components(&manifest.instructions)
         .map_err(Error::InstructionSchemaValidationError)?;
 
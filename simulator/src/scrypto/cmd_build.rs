@@ -20,6 +20,12 @@ pub struct Build {
     #[clap(long)]
     disable_wasm_opt: bool,
 
+    /// When passed, the function names of the built wasm are preserved in a `.names.wasm`
+    /// sidecar file, which can be used to name the functions of wasm backtraces and profiles.
+    /// The published wasm is stripped regardless.
+    #[clap(long)]
+    function_names: bool,
+
     /// The max log level, such as ERROR, WARN, INFO, DEBUG and TRACE.
     /// The default is INFO.
     #[clap(long)]
@@ -33,6 +39,7 @@ impl Build {
             self.trace,
             false,
             self.disable_wasm_opt,
+            self.function_names,
             self.log_level.unwrap_or(Level::default()),
         )
         .map(|_| ())
@@ -59,6 +59,7 @@ fn run_cargo_build(
     target_path: impl AsRef<OsStr>,
     trace: bool,
     no_schema: bool,
+    function_names: bool,
     log_level: Level,
 ) -> Result<(), BuildError> {
     let mut features = String::new();
@@ -84,7 +85,13 @@ fn run_cargo_build(
         features.push_str(",scrypto/log-trace");
     }
 
-    let status = Command::new("cargo")
+    let mut command = Command::new("cargo");
+    if function_names {
+        // Overrides the `strip = true` release profile of Scrypto packages, keeping the symbols
+        // (i.e. the name section) but not the DWARF sections
+        command.env("CARGO_PROFILE_RELEASE_STRIP", "debuginfo");
+    }
+    let status = command
         .arg("build")
         .arg("--target")
         .arg("wasm32-unknown-unknown")
@@ -141,6 +148,7 @@ pub fn build_package<P: AsRef<Path>>(
     trace: bool,
     force_local_target: bool,
     disable_wasm_opt: bool,
+    function_names: bool,
     log_level: Level,
 ) -> Result<(PathBuf, PathBuf), BuildError> {
     let base_path = base_path.as_ref().to_owned();
@@ -168,7 +176,7 @@ pub fn build_package<P: AsRef<Path>>(
     out_path.push("release");
 
     // Build with SCHEMA
-    run_cargo_build(&manifest_path, &target_path, trace, false, log_level)?;
+    run_cargo_build(&manifest_path, &target_path, trace, false, false, log_level)?;
 
     // Find the binary paths
     let manifest = Manifest::from_path(&manifest_path)
@@ -199,12 +207,19 @@ pub fn build_package<P: AsRef<Path>>(
     .map_err(|err| BuildError::IOErrorAtPath(err, definition_path.clone()))?;
 
     // Build without SCHEMA
-    run_cargo_build(&manifest_path, &target_path, trace, true, log_level)?;
+    run_cargo_build(
+        &manifest_path,
+        &target_path,
+        trace,
+        true,
+        function_names,
+        log_level,
+    )?;
 
     // Optimizes the built wasm using Binaryen's wasm-opt tool. The code that follows is equivalent
     // to running the following commands in the CLI:
     // wasm-opt -0z --strip-debug --strip-dwarf --strip-procedures $some_path $some_path
-    if !disable_wasm_opt {
+    if !disable_wasm_opt && !function_names {
         wasm_opt::OptimizationOptions::new_optimize_for_size_aggressively()
             .add_pass(wasm_opt::Pass::StripDebug)
             .add_pass(wasm_opt::Pass::StripDwarf)
@@ -213,6 +228,28 @@ pub fn build_package<P: AsRef<Path>>(
             .map_err(BuildError::OptimizationError)?;
     }
 
+    // With function names, the name section of the built wasm is kept in a sidecar file, next
+    // to the stripped wasm. Stripping leaves the functions untouched, so the names of the
+    // sidecar can be used for the functions of the published package.
+    if function_names {
+        let names_wasm_path = bin_path.with_extension("names.wasm");
+        if disable_wasm_opt {
+            fs::copy(&wasm_path, &names_wasm_path)
+                .map_err(|err| BuildError::IOErrorAtPath(err, names_wasm_path.clone()))?;
+        } else {
+            wasm_opt::OptimizationOptions::new_optimize_for_size_aggressively()
+                .debug_info(true)
+                .run(&wasm_path, &names_wasm_path)
+                .map_err(BuildError::OptimizationError)?;
+        }
+        wasm_opt::OptimizationOptions::new_opt_level_0()
+            .add_default_passes(false)
+            .add_pass(wasm_opt::Pass::StripDebug)
+            .add_pass(wasm_opt::Pass::StripProducers)
+            .run(&names_wasm_path, &wasm_path)
+            .map_err(BuildError::OptimizationError)?;
+    }
+
     Ok((wasm_path, definition_path))
 }
 
@@ -222,7 +259,8 @@ where
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
 {
-    build_package(&path, false, false, false, Level::Trace).map_err(TestError::BuildError)?;
+    build_package(&path, false, false, false, false, Level::Trace)
+        .map_err(TestError::BuildError)?;
 
     let mut cargo = path.as_ref().to_owned();
     cargo.push("Cargo.toml");
@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+/// The format of a compiled manifest, as written by `rtmc` and read by `rtmd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// The SBOR encoding of the manifest
+    Sbor,
+    /// The JSON representation of the manifest (see `transaction::manifest::json`)
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseManifestFormatError(pub String);
+
+impl FromStr for ManifestFormat {
+    type Err = ParseManifestFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sbor" => Ok(Self::Sbor),
+            "json" => Ok(Self::Json),
+            _ => Err(ParseManifestFormatError(s.to_string())),
+        }
+    }
+}
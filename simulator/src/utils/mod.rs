@@ -2,10 +2,12 @@ mod cargo;
 mod common_instructions;
 mod display;
 mod iter;
+mod manifest_format;
 mod resource_specifier;
 
 pub use cargo::*;
 pub use common_instructions::*;
 pub use display::list_item_prefix;
 pub use iter::{IdentifyLast, Iter};
+pub use manifest_format::*;
 pub use resource_specifier::*;
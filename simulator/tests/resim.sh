@@ -60,6 +60,29 @@ $resim transfer "$non_fungible_global_id" $account2
 $resim mint 777 $token_address --proofs $minter_badge:1
 $resim transfer $token_address:111 $account2
 
+# Test - offline signing
+$resim transfer $token_address:111 $account2 --unsigned-out target/temp.intent
+$resim sign target/temp.intent
+$resim notarize target/temp.intent
+$resim submit target/temp.intent
+
+# Test - offline signing, notarized by other than the default account
+notary=`$resim new-account`
+notary_public_key=`echo "$notary" | awk '/Public key:/ {print $NF}'`
+notary_private_key=`echo "$notary" | awk '/Private key:/ {print $NF}'`
+$resim transfer $token_address:111 $account2 --unsigned-out target/temp.intent --notary-public-key $notary_public_key
+$resim sign target/temp.intent
+if $resim sign target/temp.intent; then
+    echo "Intent signed twice by the same key!"
+    exit 1
+fi
+if $resim notarize target/temp.intent; then
+    echo "Intent notarized with a key other than its notary's!"
+    exit 1
+fi
+$resim notarize target/temp.intent --key $notary_private_key
+$resim submit target/temp.intent
+
 # Test - publish, call-function and call-method and non-fungibles
 owner_badge=`$resim new-simple-badge --name 'OwnerBadge' | awk '/NonFungibleGlobalId:/ {print $NF}'`
 package=`$resim publish ../examples/hello-world --owner-badge $owner_badge | awk '/Package:/ {print $NF}'`
@@ -125,3 +148,9 @@ token_address=`$resim new-token-mutable $non_fungible_global_id | awk '/Resource
 
 # Test - mint and transfer (Mintable that requires a `NonFungibleGlobalId`)
 $resim mint 777 $token_address --proofs "$non_fungible_global_id"
+
+# Test - export and import ledger snapshot
+$resim export-snapshot target/temp.snapshot
+$resim reset
+$resim import-snapshot target/temp.snapshot
+$resim show $token_address
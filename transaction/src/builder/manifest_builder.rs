@@ -484,6 +484,28 @@ impl ManifestBuilder {
         })
     }
 
+    /// Runs the child intent at the given index of the transaction, passing it the given
+    /// arguments (which may only contain buckets). The buckets it yields back are put on
+    /// the worktop.
+    ///
+    /// Only permitted in the root intent of a V2 transaction.
+    pub fn yield_to_child(self, child_index: u32, arguments: impl ResolvableArguments) -> Self {
+        self.add_instruction(InstructionV1::YieldToChild {
+            child_index,
+            args: arguments.resolve(),
+        })
+    }
+
+    /// Ends a child intent, passing the given arguments (which may only contain buckets) back
+    /// to its parent.
+    ///
+    /// Only permitted as the last instruction of a child intent.
+    pub fn yield_to_parent(self, arguments: impl ResolvableArguments) -> Self {
+        self.add_instruction(InstructionV1::YieldToParent {
+            args: arguments.resolve(),
+        })
+    }
+
     /// Drops a proof.
     pub fn drop_proof(self, proof: impl ExistingManifestProof) -> Self {
         let proof = proof.mark_consumed(&self.registrar);
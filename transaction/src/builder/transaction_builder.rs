@@ -76,6 +76,11 @@ impl TransactionBuilder {
         self
     }
 
+    /// Builds the unsigned intent, e.g. to have it signed offline.
+    pub fn build_intent(&self) -> IntentV1 {
+        self.transaction_intent()
+    }
+
     pub fn build(&self) -> NotarizedTransactionV1 {
         NotarizedTransactionV1 {
             signed_intent: self.signed_transaction_intent(),
@@ -115,6 +120,218 @@ impl TransactionBuilder {
     }
 }
 
+pub struct ChildIntentBuilder {
+    manifest: Option<TransactionManifestV1>,
+    header: Option<ChildIntentHeaderV2>,
+    message: Option<MessageV1>,
+    intent_signatures: Vec<SignatureWithPublicKeyV1>,
+}
+
+impl ChildIntentBuilder {
+    pub fn new() -> Self {
+        Self {
+            manifest: None,
+            header: None,
+            message: None,
+            intent_signatures: vec![],
+        }
+    }
+
+    pub fn manifest(mut self, manifest: TransactionManifestV1) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    pub fn header(mut self, header: ChildIntentHeaderV2) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn message(mut self, message: MessageV1) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn sign<S: Signer>(mut self, signer: &S) -> Self {
+        let intent = self.child_intent();
+        let prepared = intent.prepare().expect("Child intent could be prepared");
+        self.intent_signatures
+            .push(signer.sign_with_public_key(&prepared.child_intent_hash()));
+        self
+    }
+
+    pub fn multi_sign<S: Signer>(mut self, signers: &[&S]) -> Self {
+        let intent = self.child_intent();
+        let prepared = intent.prepare().expect("Child intent could be prepared");
+        for signer in signers {
+            self.intent_signatures
+                .push(signer.sign_with_public_key(&prepared.child_intent_hash()));
+        }
+        self
+    }
+
+    pub fn signer_signatures(mut self, sigs: Vec<SignatureWithPublicKeyV1>) -> Self {
+        self.intent_signatures.extend(sigs);
+        self
+    }
+
+    pub fn build(&self) -> SignedChildIntentV2 {
+        SignedChildIntentV2 {
+            intent: self.child_intent(),
+            intent_signatures: IntentSignaturesV1 {
+                signatures: self
+                    .intent_signatures
+                    .clone()
+                    .into_iter()
+                    .map(|sig| IntentSignatureV1(sig))
+                    .collect(),
+            },
+        }
+    }
+
+    fn child_intent(&self) -> ChildIntentV2 {
+        let (instructions, blobs) = self
+            .manifest
+            .clone()
+            .expect("Manifest not specified")
+            .for_intent();
+        ChildIntentV2 {
+            header: self.header.clone().expect("Header not specified"),
+            instructions,
+            blobs,
+            message: self.message.clone().unwrap_or(MessageV1::None),
+        }
+    }
+}
+
+pub struct TransactionV2Builder {
+    manifest: Option<TransactionManifestV1>,
+    header: Option<TransactionHeaderV2>,
+    message: Option<MessageV1>,
+    child_intents: Vec<SignedChildIntentV2>,
+    intent_signatures: Vec<SignatureWithPublicKeyV1>,
+    notary_signature: Option<SignatureV1>,
+}
+
+impl TransactionV2Builder {
+    pub fn new() -> Self {
+        Self {
+            manifest: None,
+            header: None,
+            message: None,
+            child_intents: vec![],
+            intent_signatures: vec![],
+            notary_signature: None,
+        }
+    }
+
+    pub fn manifest(mut self, manifest: TransactionManifestV1) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    pub fn header(mut self, header: TransactionHeaderV2) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn message(mut self, message: MessageV1) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Adds a signed child intent, which the manifest can run with `YIELD_TO_CHILD` and the
+    /// index at which it was added.
+    ///
+    /// As the intent hash commits to the child intents, they must be added before signing.
+    pub fn add_child_intent(mut self, child_intent: SignedChildIntentV2) -> Self {
+        self.child_intents.push(child_intent);
+        self
+    }
+
+    pub fn sign<S: Signer>(mut self, signer: &S) -> Self {
+        let intent = self.transaction_intent();
+        let prepared = intent.prepare().expect("Intent could be prepared");
+        self.intent_signatures
+            .push(signer.sign_with_public_key(&prepared.intent_hash()));
+        self
+    }
+
+    pub fn multi_sign<S: Signer>(mut self, signers: &[&S]) -> Self {
+        let intent = self.transaction_intent();
+        let prepared = intent.prepare().expect("Intent could be prepared");
+        for signer in signers {
+            self.intent_signatures
+                .push(signer.sign_with_public_key(&prepared.intent_hash()));
+        }
+        self
+    }
+
+    pub fn signer_signatures(mut self, sigs: Vec<SignatureWithPublicKeyV1>) -> Self {
+        self.intent_signatures.extend(sigs);
+        self
+    }
+
+    pub fn notarize<S: Signer>(mut self, signer: &S) -> Self {
+        let signed_intent = self.signed_transaction_intent();
+        let prepared = signed_intent
+            .prepare()
+            .expect("Signed intent could be prepared");
+        self.notary_signature = Some(
+            signer
+                .sign_with_public_key(&prepared.signed_intent_hash())
+                .signature(),
+        );
+        self
+    }
+
+    pub fn notary_signature(mut self, signature: SignatureV1) -> Self {
+        self.notary_signature = Some(signature);
+        self
+    }
+
+    pub fn build(&self) -> NotarizedTransactionV2 {
+        NotarizedTransactionV2 {
+            signed_intent: self.signed_transaction_intent(),
+            notary_signature: NotarySignatureV1(
+                self.notary_signature.clone().expect("Not notarized"),
+            ),
+        }
+    }
+
+    fn transaction_intent(&self) -> IntentV2 {
+        let (instructions, blobs) = self
+            .manifest
+            .clone()
+            .expect("Manifest not specified")
+            .for_intent();
+        IntentV2 {
+            header: self.header.clone().expect("Header not specified"),
+            instructions,
+            blobs,
+            message: self.message.clone().unwrap_or(MessageV1::None),
+            child_intents: ChildIntentsV2 {
+                children: self.child_intents.clone(),
+            },
+        }
+    }
+
+    fn signed_transaction_intent(&self) -> SignedIntentV2 {
+        let intent = self.transaction_intent();
+        SignedIntentV2 {
+            intent,
+            intent_signatures: IntentSignaturesV1 {
+                signatures: self
+                    .intent_signatures
+                    .clone()
+                    .into_iter()
+                    .map(|sig| IntentSignatureV1(sig))
+                    .collect(),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use radix_engine_common::types::Epoch;
@@ -8,6 +8,8 @@ pub enum HeaderValidationError {
     InvalidNetwork,
     InvalidCostUnitLimit,
     InvalidTipPercentage,
+    InvalidProposerTimestampRange,
+    InvalidMaxFee,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,6 +42,22 @@ pub enum CallDataValidationError {
     IdValidationError(ManifestIdValidationError),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildIntentValidationError {
+    DuplicateChildIntent(ChildIntentHash),
+    ChildIntentNotFound(u32),
+    ChildIntentYieldedMoreThanOnce(u32),
+    ChildIntentNotYielded(usize),
+    YieldToChildNotPermitted,
+    YieldToParentNotPermitted,
+    MissingYieldToParent,
+    InvalidYieldArgs,
+    InvalidChildIntent {
+        child_index: usize,
+        error: Box<TransactionValidationError>,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransactionValidationError {
     TransactionTooLarge,
@@ -50,6 +68,7 @@ pub enum TransactionValidationError {
     IdValidationError(ManifestIdValidationError),
     CallDataValidationError(CallDataValidationError),
     InvalidMessage(InvalidMessageError),
+    ChildIntentValidationError(ChildIntentValidationError),
 }
 
 impl From<PrepareError> for TransactionValidationError {
@@ -64,6 +83,12 @@ impl From<EncodeError> for TransactionValidationError {
     }
 }
 
+impl From<ChildIntentValidationError> for TransactionValidationError {
+    fn from(value: ChildIntentValidationError) -> Self {
+        Self::ChildIntentValidationError(value)
+    }
+}
+
 impl From<InvalidMessageError> for TransactionValidationError {
     fn from(value: InvalidMessageError) -> Self {
         Self::InvalidMessage(value)
@@ -0,0 +1,639 @@
+use crate::internal_prelude::*;
+use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::blueprints::resource::*;
+
+/// A likely mistake in a manifest, found by [`analyze_manifest()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestWarning {
+    /// The index of the instruction which the warning is about (e.g. the one creating a bucket
+    /// which is never consumed).
+    pub instruction_index: usize,
+    pub warning_kind: ManifestWarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestWarningKind {
+    /// The bucket is still held at the end of the manifest.
+    BucketNeverConsumed(ManifestBucket),
+    /// The proof is dropped (explicitly or at the end of the manifest) without ever being passed
+    /// to a call, pushed to the auth zone or cloned.
+    ProofNeverUsed(ManifestProof),
+    /// The worktop is known not to contain enough of the resource for the assertion to pass.
+    AssertionCanNeverPass(ResourceAddress),
+    /// The worktop is known not to contain enough of the resource for the take to succeed.
+    TakeCanNeverSucceed(ResourceAddress),
+    /// The resource put on the worktop by the instruction is still there at the end of the
+    /// manifest, i.e. it is neither deposited nor otherwise taken from the worktop.
+    ResourceLeftOnWorktop(ResourceAddress),
+}
+
+/// Statically analyzes the flow of resources through the worktop, buckets and proofs of the given
+/// manifest instructions, and returns the warnings about likely mistakes, ordered by instruction.
+///
+/// The analysis abstractly interprets the instructions. The effects of the calls are only known
+/// for some native methods (e.g. an account's `withdraw` and `deposit` family of methods); any
+/// other call may put unknown resources on the worktop, after which no warning about the
+/// worktop's contents is reported until it is emptied again (e.g. by passing
+/// `Expression("ENTIRE_WORKTOP")` to a call).
+pub fn analyze_manifest(instructions: &[InstructionV1]) -> Vec<ManifestWarning> {
+    let mut analyzer = ManifestAnalyzer::default();
+    for (index, instruction) in instructions.iter().enumerate() {
+        analyzer.process_instruction(index, instruction);
+    }
+    analyzer.finish()
+}
+
+/// The statically known amount of a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Amount {
+    Known(Decimal),
+    Unknown,
+}
+
+impl Amount {
+    fn add(self, other: Amount) -> Amount {
+        match (self, other) {
+            (Amount::Known(a), Amount::Known(b)) => a
+                .checked_add(b)
+                .map(Amount::Known)
+                .unwrap_or(Amount::Unknown),
+            _ => Amount::Unknown,
+        }
+    }
+
+    fn is_known_positive(&self) -> bool {
+        matches!(self, Amount::Known(amount) if amount.is_positive())
+    }
+}
+
+struct WorktopResource {
+    amount: Amount,
+    /// The index of the last instruction which put the resource on the worktop.
+    added_at: usize,
+}
+
+struct BucketInfo {
+    resource_address: ResourceAddress,
+    amount: Amount,
+    created_at: usize,
+}
+
+struct ProofInfo {
+    created_at: usize,
+    used: bool,
+}
+
+#[derive(Default)]
+struct ManifestAnalyzer {
+    id_allocator: ManifestIdAllocator,
+    /// The resources known to be on the worktop. The amounts are exact, unless
+    /// `worktop_may_contain_more` is set, in which case they are lower bounds.
+    worktop: IndexMap<ResourceAddress, WorktopResource>,
+    /// Whether a call with unknown effects may have put (further) resources on the worktop since
+    /// it was last emptied.
+    worktop_may_contain_more: bool,
+    buckets: IndexMap<ManifestBucket, BucketInfo>,
+    proofs: IndexMap<ManifestProof, ProofInfo>,
+    warnings: Vec<ManifestWarning>,
+}
+
+impl ManifestAnalyzer {
+    fn process_instruction(&mut self, index: usize, instruction: &InstructionV1) {
+        match instruction {
+            InstructionV1::TakeAllFromWorktop { resource_address } => {
+                let amount = match self.worktop.swap_remove(resource_address) {
+                    Some(resource) if !self.worktop_may_contain_more => resource.amount,
+                    None if !self.worktop_may_contain_more => Amount::Known(Decimal::ZERO),
+                    _ => Amount::Unknown,
+                };
+                self.new_bucket(index, *resource_address, amount);
+            }
+            InstructionV1::TakeFromWorktop {
+                resource_address,
+                amount,
+            } => {
+                self.take_from_worktop(index, *resource_address, *amount);
+                self.new_bucket(index, *resource_address, Amount::Known(*amount));
+            }
+            InstructionV1::TakeNonFungiblesFromWorktop {
+                resource_address,
+                ids,
+            } => {
+                let amount = Decimal::from(ids.len());
+                self.take_from_worktop(index, *resource_address, amount);
+                self.new_bucket(index, *resource_address, Amount::Known(amount));
+            }
+            InstructionV1::ReturnToWorktop { bucket_id } => {
+                if let Some(bucket) = self.buckets.swap_remove(bucket_id) {
+                    self.put_on_worktop(index, bucket.resource_address, bucket.amount);
+                }
+            }
+            InstructionV1::AssertWorktopContainsAny { resource_address } => {
+                if !self.worktop_may_contain_more && !self.worktop.contains_key(resource_address) {
+                    self.warn(
+                        index,
+                        ManifestWarningKind::AssertionCanNeverPass(*resource_address),
+                    );
+                }
+            }
+            InstructionV1::AssertWorktopContains {
+                resource_address,
+                amount,
+            } => {
+                if !self.worktop_may_contain(resource_address, *amount) {
+                    self.warn(
+                        index,
+                        ManifestWarningKind::AssertionCanNeverPass(*resource_address),
+                    );
+                }
+            }
+            InstructionV1::AssertWorktopContainsNonFungibles {
+                resource_address,
+                ids,
+            } => {
+                if !self.worktop_may_contain(resource_address, Decimal::from(ids.len())) {
+                    self.warn(
+                        index,
+                        ManifestWarningKind::AssertionCanNeverPass(*resource_address),
+                    );
+                }
+            }
+            InstructionV1::PopFromAuthZone
+            | InstructionV1::CreateProofFromAuthZoneOfAmount { .. }
+            | InstructionV1::CreateProofFromAuthZoneOfNonFungibles { .. }
+            | InstructionV1::CreateProofFromAuthZoneOfAll { .. }
+            | InstructionV1::CreateProofFromBucketOfAmount { .. }
+            | InstructionV1::CreateProofFromBucketOfNonFungibles { .. }
+            | InstructionV1::CreateProofFromBucketOfAll { .. } => {
+                self.new_proof(index);
+            }
+            InstructionV1::PushToAuthZone { proof_id } => {
+                self.use_proof(proof_id);
+                self.drop_proof(proof_id);
+            }
+            InstructionV1::CloneProof { proof_id } => {
+                self.use_proof(proof_id);
+                self.new_proof(index);
+            }
+            InstructionV1::DropProof { proof_id } => {
+                self.drop_proof(proof_id);
+            }
+            InstructionV1::DropNamedProofs | InstructionV1::DropAllProofs => {
+                let proof_ids: Vec<_> = self.proofs.keys().copied().collect();
+                for proof_id in proof_ids {
+                    self.drop_proof(&proof_id);
+                }
+            }
+            InstructionV1::BurnResource { bucket_id } => {
+                self.buckets.swap_remove(bucket_id);
+            }
+            InstructionV1::CallFunction { args, .. }
+            | InstructionV1::CallRoyaltyMethod { args, .. }
+            | InstructionV1::CallMetadataMethod { args, .. }
+            | InstructionV1::CallRoleAssignmentMethod { args, .. }
+            | InstructionV1::CallDirectVaultMethod { args, .. }
+            | InstructionV1::YieldToChild { args, .. } => {
+                self.pass_args(args);
+                self.worktop_may_contain_more = true;
+            }
+            InstructionV1::YieldToParent { args } => {
+                self.pass_args(args);
+            }
+            InstructionV1::CallMethod {
+                address,
+                method_name,
+                args,
+            } => {
+                self.pass_args(args);
+                match address {
+                    DynamicGlobalAddress::Static(address) => {
+                        self.apply_method_returns(index, address, method_name, args)
+                    }
+                    DynamicGlobalAddress::Named(_) => self.worktop_may_contain_more = true,
+                }
+            }
+            InstructionV1::DropAuthZoneProofs
+            | InstructionV1::DropAuthZoneRegularProofs
+            | InstructionV1::DropAuthZoneSignatureProofs
+            | InstructionV1::AllocateGlobalAddress { .. } => {}
+        }
+    }
+
+    fn finish(mut self) -> Vec<ManifestWarning> {
+        for (resource_address, resource) in self.worktop.drain(..) {
+            if resource.amount.is_known_positive() {
+                self.warnings.push(ManifestWarning {
+                    instruction_index: resource.added_at,
+                    warning_kind: ManifestWarningKind::ResourceLeftOnWorktop(resource_address),
+                });
+            }
+        }
+        for (bucket_id, bucket) in self.buckets.drain(..) {
+            self.warnings.push(ManifestWarning {
+                instruction_index: bucket.created_at,
+                warning_kind: ManifestWarningKind::BucketNeverConsumed(bucket_id),
+            });
+        }
+        for (proof_id, proof) in self.proofs.drain(..) {
+            if !proof.used {
+                self.warnings.push(ManifestWarning {
+                    instruction_index: proof.created_at,
+                    warning_kind: ManifestWarningKind::ProofNeverUsed(proof_id),
+                });
+            }
+        }
+        self.warnings
+            .sort_by_key(|warning| warning.instruction_index);
+        self.warnings
+    }
+
+    fn warn(&mut self, instruction_index: usize, warning_kind: ManifestWarningKind) {
+        self.warnings.push(ManifestWarning {
+            instruction_index,
+            warning_kind,
+        });
+    }
+
+    /// Checks whether the worktop may contain at least the given amount of the resource.
+    fn worktop_may_contain(&self, resource_address: &ResourceAddress, amount: Decimal) -> bool {
+        if self.worktop_may_contain_more || !amount.is_positive() {
+            return true;
+        }
+        match self.worktop.get(resource_address) {
+            Some(WorktopResource {
+                amount: Amount::Known(available),
+                ..
+            }) => *available >= amount,
+            Some(WorktopResource {
+                amount: Amount::Unknown,
+                ..
+            }) => true,
+            None => false,
+        }
+    }
+
+    fn take_from_worktop(
+        &mut self,
+        index: usize,
+        resource_address: ResourceAddress,
+        amount: Decimal,
+    ) {
+        if !self.worktop_may_contain(&resource_address, amount) {
+            self.warn(
+                index,
+                ManifestWarningKind::TakeCanNeverSucceed(resource_address),
+            );
+        }
+        if let Some(resource) = self.worktop.get_mut(&resource_address) {
+            if let Amount::Known(available) = resource.amount {
+                match available.checked_sub(amount) {
+                    Some(remaining) if remaining.is_positive() => {
+                        resource.amount = Amount::Known(remaining)
+                    }
+                    _ => {
+                        self.worktop.swap_remove(&resource_address);
+                    }
+                }
+            }
+        }
+    }
+
+    fn put_on_worktop(&mut self, index: usize, resource_address: ResourceAddress, amount: Amount) {
+        if amount == Amount::Known(Decimal::ZERO) {
+            return;
+        }
+        let resource = self
+            .worktop
+            .entry(resource_address)
+            .or_insert(WorktopResource {
+                amount: Amount::Known(Decimal::ZERO),
+                added_at: index,
+            });
+        resource.amount = resource.amount.add(amount);
+        resource.added_at = index;
+    }
+
+    fn new_bucket(&mut self, index: usize, resource_address: ResourceAddress, amount: Amount) {
+        let bucket_id = self.id_allocator.new_bucket_id();
+        self.buckets.insert(
+            bucket_id,
+            BucketInfo {
+                resource_address,
+                amount,
+                created_at: index,
+            },
+        );
+    }
+
+    fn new_proof(&mut self, index: usize) {
+        let proof_id = self.id_allocator.new_proof_id();
+        self.proofs.insert(
+            proof_id,
+            ProofInfo {
+                created_at: index,
+                used: false,
+            },
+        );
+    }
+
+    fn use_proof(&mut self, proof_id: &ManifestProof) {
+        if let Some(proof) = self.proofs.get_mut(proof_id) {
+            proof.used = true;
+        }
+    }
+
+    fn drop_proof(&mut self, proof_id: &ManifestProof) {
+        if let Some(proof) = self.proofs.swap_remove(proof_id) {
+            if !proof.used {
+                self.warn(
+                    proof.created_at,
+                    ManifestWarningKind::ProofNeverUsed(*proof_id),
+                );
+            }
+        }
+    }
+
+    /// Moves the buckets and proofs passed as the arguments of a call, and the whole worktop if
+    /// `Expression("ENTIRE_WORKTOP")` is passed.
+    fn pass_args(&mut self, value: &ManifestValue) {
+        match value {
+            Value::Custom {
+                value: ManifestCustomValue::Bucket(bucket_id),
+            } => {
+                self.buckets.swap_remove(bucket_id);
+            }
+            Value::Custom {
+                value: ManifestCustomValue::Proof(proof_id),
+            } => {
+                self.use_proof(proof_id);
+                self.proofs.swap_remove(proof_id);
+            }
+            Value::Custom {
+                value: ManifestCustomValue::Expression(ManifestExpression::EntireWorktop),
+            } => {
+                self.worktop.clear();
+                self.worktop_may_contain_more = false;
+            }
+            Value::Enum { fields, .. } | Value::Tuple { fields } => {
+                for field in fields {
+                    self.pass_args(field);
+                }
+            }
+            Value::Array { elements, .. } => {
+                for element in elements {
+                    self.pass_args(element);
+                }
+            }
+            Value::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.pass_args(key);
+                    self.pass_args(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies the effect of the method's return value on the worktop, i.e. puts the returned
+    /// resources on it. The effects are only known for some native methods; any other method may
+    /// return any resources.
+    fn apply_method_returns(
+        &mut self,
+        index: usize,
+        address: &GlobalAddress,
+        method_name: &str,
+        args: &ManifestValue,
+    ) {
+        let returned = match address.as_node_id().entity_type() {
+            Some(
+                EntityType::GlobalAccount
+                | EntityType::GlobalVirtualSecp256k1Account
+                | EntityType::GlobalVirtualEd25519Account,
+            ) => account_method_returns(method_name, args),
+            Some(EntityType::GlobalFungibleResourceManager)
+                if method_name == FUNGIBLE_RESOURCE_MANAGER_MINT_IDENT =>
+            {
+                decode_args::<FungibleResourceManagerMintInput>(args)
+                    .map(|input| vec![(ResourceAddress::try_from(*address).unwrap(), input.amount)])
+            }
+            Some(EntityType::GlobalNonFungibleResourceManager)
+                if method_name == NON_FUNGIBLE_RESOURCE_MANAGER_MINT_IDENT =>
+            {
+                decode_args::<NonFungibleResourceManagerMintManifestInput>(args).map(|input| {
+                    vec![(
+                        ResourceAddress::try_from(*address).unwrap(),
+                        Decimal::from(input.entries.len()),
+                    )]
+                })
+            }
+            Some(EntityType::GlobalNonFungibleResourceManager)
+                if method_name == NON_FUNGIBLE_RESOURCE_MANAGER_MINT_RUID_IDENT =>
+            {
+                decode_args::<NonFungibleResourceManagerMintRuidManifestInput>(args).map(|input| {
+                    vec![(
+                        ResourceAddress::try_from(*address).unwrap(),
+                        Decimal::from(input.entries.len()),
+                    )]
+                })
+            }
+            _ => None,
+        };
+        match returned {
+            Some(returned) => {
+                for (resource_address, amount) in returned {
+                    self.put_on_worktop(index, resource_address, Amount::Known(amount));
+                }
+            }
+            None => self.worktop_may_contain_more = true,
+        }
+    }
+}
+
+/// Returns the resources returned by the given account method, or `None` if they are not known.
+fn account_method_returns(
+    method_name: &str,
+    args: &ManifestValue,
+) -> Option<Vec<(ResourceAddress, Decimal)>> {
+    match method_name {
+        ACCOUNT_WITHDRAW_IDENT => decode_args::<AccountWithdrawInput>(args)
+            .map(|input| vec![(input.resource_address, input.amount)]),
+        ACCOUNT_WITHDRAW_NON_FUNGIBLES_IDENT => {
+            decode_args::<AccountWithdrawNonFungiblesInput>(args)
+                .map(|input| vec![(input.resource_address, Decimal::from(input.ids.len()))])
+        }
+        ACCOUNT_LOCK_FEE_AND_WITHDRAW_IDENT => decode_args::<AccountLockFeeAndWithdrawInput>(args)
+            .map(|input| vec![(input.resource_address, input.amount)]),
+        ACCOUNT_LOCK_FEE_AND_WITHDRAW_NON_FUNGIBLES_IDENT => {
+            decode_args::<AccountLockFeeAndWithdrawNonFungiblesInput>(args)
+                .map(|input| vec![(input.resource_address, Decimal::from(input.ids.len()))])
+        }
+        // Note: the refunding deposits only return the resources if the deposit is rejected, in
+        // which case the worktop is most likely expected to be non-empty
+        ACCOUNT_LOCK_FEE_IDENT
+        | ACCOUNT_LOCK_CONTINGENT_FEE_IDENT
+        | ACCOUNT_DEPOSIT_IDENT
+        | ACCOUNT_DEPOSIT_BATCH_IDENT
+        | ACCOUNT_TRY_DEPOSIT_OR_ABORT_IDENT
+        | ACCOUNT_TRY_DEPOSIT_BATCH_OR_ABORT_IDENT
+        | ACCOUNT_TRY_DEPOSIT_OR_REFUND_IDENT
+        | ACCOUNT_TRY_DEPOSIT_BATCH_OR_REFUND_IDENT
+        | ACCOUNT_CREATE_PROOF_OF_AMOUNT_IDENT
+        | ACCOUNT_CREATE_PROOF_OF_NON_FUNGIBLES_IDENT
+        | ACCOUNT_SET_DEFAULT_DEPOSIT_RULE_IDENT
+        | ACCOUNT_SET_RESOURCE_PREFERENCE_IDENT
+        | ACCOUNT_REMOVE_RESOURCE_PREFERENCE_IDENT
+        | ACCOUNT_BURN_IDENT
+        | ACCOUNT_BURN_NON_FUNGIBLES_IDENT => Some(vec![]),
+        _ => None,
+    }
+}
+
+pub(super) fn decode_args<T: ManifestDecode>(args: &ManifestValue) -> Option<T> {
+    manifest_encode(args)
+        .ok()
+        .and_then(|payload| manifest_decode::<T>(&payload).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_engine_interface::network::NetworkDefinition;
+
+    fn account() -> ComponentAddress {
+        ComponentAddress::virtual_account_from_public_key(
+            &Secp256k1PrivateKey::from_u64(1).unwrap().public_key(),
+        )
+    }
+
+    fn warning_kinds(manifest: TransactionManifestV1) -> Vec<(usize, ManifestWarningKind)> {
+        analyze_manifest(&manifest.instructions)
+            .into_iter()
+            .map(|warning| (warning.instruction_index, warning.warning_kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_transfer_has_no_warnings() {
+        let manifest = ManifestBuilder::new()
+            .lock_fee(account(), 10)
+            .withdraw_from_account(account(), XRD, 10)
+            .take_from_worktop(XRD, 5, "bucket")
+            .try_deposit_or_abort(account(), None, "bucket")
+            .try_deposit_entire_worktop_or_abort(account(), None)
+            .build();
+
+        assert_eq!(warning_kinds(manifest), vec![]);
+    }
+
+    #[test]
+    fn test_withdrawn_resource_left_on_worktop() {
+        let manifest = ManifestBuilder::new()
+            .withdraw_from_account(account(), XRD, 10)
+            .take_from_worktop(XRD, 5, "bucket")
+            .try_deposit_or_abort(account(), None, "bucket")
+            .build();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![(0, ManifestWarningKind::ResourceLeftOnWorktop(XRD))]
+        );
+    }
+
+    #[test]
+    fn test_bucket_never_consumed() {
+        let manifest = ManifestBuilder::new()
+            .withdraw_from_account(account(), XRD, 10)
+            .take_all_from_worktop(XRD, "bucket")
+            .build();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![(
+                1,
+                ManifestWarningKind::BucketNeverConsumed(ManifestBucket(0))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_assertion_and_take_which_can_never_pass() {
+        let manifest = ManifestBuilder::new()
+            .withdraw_from_account(account(), XRD, 10)
+            .assert_worktop_contains(XRD, 20)
+            .assert_worktop_contains_any(ACCOUNT_OWNER_BADGE)
+            .take_from_worktop(XRD, 11, "bucket")
+            .try_deposit_or_abort(account(), None, "bucket")
+            .build();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![
+                (1, ManifestWarningKind::AssertionCanNeverPass(XRD)),
+                (
+                    2,
+                    ManifestWarningKind::AssertionCanNeverPass(ACCOUNT_OWNER_BADGE)
+                ),
+                (3, ManifestWarningKind::TakeCanNeverSucceed(XRD)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_call_disables_worktop_warnings_until_worktop_is_emptied() {
+        let manifest = ManifestBuilder::new()
+            .call_method(FAUCET, "free", manifest_args!())
+            .assert_worktop_contains(XRD, 10000)
+            .try_deposit_entire_worktop_or_abort(account(), None)
+            .assert_worktop_contains(XRD, 1)
+            .build();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![(3, ManifestWarningKind::AssertionCanNeverPass(XRD))]
+        );
+    }
+
+    #[test]
+    fn test_proof_never_used() {
+        let manifest = ManifestBuilder::new()
+            .create_proof_from_account_of_amount(account(), XRD, 1)
+            .pop_from_auth_zone("unused")
+            .pop_from_auth_zone("used")
+            .push_to_auth_zone("used")
+            .drop_all_proofs()
+            .build();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![(1, ManifestWarningKind::ProofNeverUsed(ManifestProof(0)))]
+        );
+    }
+
+    #[test]
+    fn test_compiled_manifest_is_analyzed() {
+        let manifest = compile(
+            r#"
+CALL_METHOD
+    Address("account_sim1cyvgx33089ukm2pl97pv4max0x40ruvfy4lt60yvya744cve475w0q")
+    "withdraw"
+    Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+    Decimal("10");
+TAKE_FROM_WORKTOP
+    Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+    Decimal("10")
+    Bucket("bucket");
+"#,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            warning_kinds(manifest),
+            vec![(
+                1,
+                ManifestWarningKind::BucketNeverConsumed(ManifestBucket(0))
+            )]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::internal_prelude::*;
+use crate::manifest::lexer::Span;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CompileError {
@@ -7,11 +8,78 @@ pub enum CompileError {
     GeneratorError(generator::GeneratorError),
 }
 
+/// The source spans of a compiled manifest's instructions, for locating the problems found in the
+/// compiled manifest (e.g. by a schema check of its invocations' arguments) in its source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestSourceMap {
+    /// The spans of the instructions, in the order of the compiled manifest's instructions.
+    pub instructions: Vec<InstructionSourceSpans>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionSourceSpans {
+    /// The span of the whole instruction.
+    pub span: Span,
+    /// The spans of the instruction's arguments, if it is an invocation (see
+    /// [`ast::Instruction::args()`]).
+    pub argument_spans: Vec<Span>,
+}
+
+impl ManifestSourceMap {
+    pub fn from_instructions(instructions: &[ast::InstructionWithSpan]) -> Self {
+        Self {
+            instructions: instructions
+                .iter()
+                .map(|instruction| InstructionSourceSpans {
+                    span: instruction.span,
+                    argument_spans: instruction
+                        .instruction
+                        .args()
+                        .iter()
+                        .map(|arg| arg.span)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn instruction_span(&self, instruction_index: usize) -> Option<Span> {
+        self.instructions
+            .get(instruction_index)
+            .map(|instruction| instruction.span)
+    }
+
+    /// Returns the span of the given argument of the given instruction, falling back to the span
+    /// of the whole instruction if there is no such argument.
+    pub fn argument_span(&self, instruction_index: usize, argument_index: usize) -> Option<Span> {
+        self.instructions.get(instruction_index).map(|instruction| {
+            instruction
+                .argument_spans
+                .get(argument_index)
+                .copied()
+                .unwrap_or(instruction.span)
+        })
+    }
+}
+
 pub fn compile<B>(
     s: &str,
     network: &NetworkDefinition,
     blobs: B,
 ) -> Result<TransactionManifestV1, CompileError>
+where
+    B: IsBlobProvider,
+{
+    compile_with_source_map(s, network, blobs).map(|(manifest, _)| manifest)
+}
+
+/// Compiles the given manifest source like [`compile()`], additionally returning the source spans
+/// of the compiled instructions.
+pub fn compile_with_source_map<B>(
+    s: &str,
+    network: &NetworkDefinition,
+    blobs: B,
+) -> Result<(TransactionManifestV1, ManifestSourceMap), CompileError>
 where
     B: IsBlobProvider,
 {
@@ -21,6 +89,50 @@ where
     let instructions = parser::Parser::new(tokens, parser::PARSER_MAX_DEPTH)
         .parse_manifest()
         .map_err(CompileError::ParserError)?;
-    generator::generate_manifest(&instructions, &address_bech32_decoder, blobs)
-        .map_err(CompileError::GeneratorError)
+    let manifest = generator::generate_manifest(&instructions, &address_bech32_decoder, blobs)
+        .map_err(CompileError::GeneratorError)?;
+    Ok((
+        manifest,
+        ManifestSourceMap::from_instructions(&instructions),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map_locates_instructions_and_their_arguments() {
+        let manifest = r#"DROP_ALL_PROOFS;
+CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee"
+    Decimal("5")
+    5u8;
+"#;
+        let (compiled, source_map) = compile_with_source_map(
+            manifest,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+        .unwrap();
+
+        assert_eq!(source_map.instructions.len(), compiled.instructions.len());
+        let span_text = |span: Span| &manifest[span.start.full_index..span.end.full_index];
+        assert_eq!(
+            span_text(source_map.instruction_span(0).unwrap()),
+            "DROP_ALL_PROOFS;"
+        );
+        assert!(source_map.instructions[0].argument_spans.is_empty());
+        assert_eq!(
+            span_text(source_map.argument_span(1, 0).unwrap()),
+            "Decimal(\"5\")"
+        );
+        assert_eq!(span_text(source_map.argument_span(1, 1).unwrap()), "5u8");
+        assert_eq!(
+            source_map.argument_span(1, 2),
+            source_map.instruction_span(1)
+        );
+        assert_eq!(source_map.argument_span(2, 0), None);
+    }
 }
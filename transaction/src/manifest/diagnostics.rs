@@ -0,0 +1,817 @@
+use crate::internal_prelude::*;
+use crate::manifest::ast;
+use crate::manifest::generator::{GeneratorErrorKind, NameResolverError};
+use crate::manifest::lexer::{LexerError, Position, Span, TokenKind};
+use crate::manifest::parser::{ParserErrorKind, TokenType};
+use sbor::rust::fmt::Write;
+
+/// A human-readable description of a [`CompileError`], locating the offending part of the
+/// manifest source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileErrorDiagnostic {
+    /// The span of the offending part of the manifest source.
+    pub span: Span,
+    /// A one-line summary of the error.
+    pub title: String,
+    /// A short description of the offending part, printed next to its underline.
+    pub label: String,
+    /// An optional suggestion on how to fix the error.
+    pub hint: Option<String>,
+}
+
+/// Renders the given error of compiling the given manifest source as an excerpt of the source,
+/// with the offending part underlined by carets, e.g.:
+/// ```text
+/// error: invalid decimal "1.2.3"
+///  --> line 3, column 13
+///   |
+/// 3 |     Decimal("1.2.3")
+///   |             ^^^^^^^ not a valid decimal number
+///   |
+///   = hint: decimals are written like `Decimal("1.5")`, with at most 18 decimal places
+/// ```
+pub fn compile_error_diagnostics(s: &str, err: &CompileError) -> String {
+    render_diagnostic(s, &describe_compile_error(s, err))
+}
+
+/// Describes the given error of compiling the given manifest source.
+pub fn describe_compile_error(s: &str, err: &CompileError) -> CompileErrorDiagnostic {
+    match err {
+        CompileError::LexerError(err) => describe_lexer_error(s, err),
+        CompileError::ParserError(err) => {
+            let (title, label, hint) = describe_parser_error_kind(&err.error_kind);
+            CompileErrorDiagnostic {
+                span: err.span,
+                title,
+                label,
+                hint,
+            }
+        }
+        CompileError::GeneratorError(err) => {
+            let (title, label, hint) = describe_generator_error_kind(&err.error_kind);
+            CompileErrorDiagnostic {
+                span: err.span,
+                title,
+                label,
+                hint,
+            }
+        }
+    }
+}
+
+/// Renders the given diagnostic as an excerpt of the given manifest source (see
+/// [`compile_error_diagnostics()`]).
+pub fn render_diagnostic(s: &str, diagnostic: &CompileErrorDiagnostic) -> String {
+    render_diagnostic_with_severity(s, "error", diagnostic)
+}
+
+/// Renders the given warning about the manifest compiled from the given source (see
+/// [`analyze_manifest()`]) as an excerpt of the source, like [`render_diagnostic()`].
+pub fn manifest_warning_diagnostics(
+    s: &str,
+    source_map: &ManifestSourceMap,
+    warning: &ManifestWarning,
+) -> String {
+    let span = source_map
+        .instruction_span(warning.instruction_index)
+        .expect("Every compiled instruction has a source span");
+    let (title, label, hint) = describe_manifest_warning_kind(&warning.warning_kind);
+    render_diagnostic_with_severity(
+        s,
+        "warning",
+        &CompileErrorDiagnostic {
+            span,
+            title,
+            label,
+            hint,
+        },
+    )
+}
+
+fn render_diagnostic_with_severity(
+    s: &str,
+    severity: &str,
+    diagnostic: &CompileErrorDiagnostic,
+) -> String {
+    let lines: Vec<&str> = s
+        .split('\n')
+        .map(|line| line.trim_end_matches('\r'))
+        .collect();
+    let start = diagnostic.span.start;
+    let mut end = diagnostic.span.end;
+    // A span ending at the very beginning of a line does not include anything of it
+    if end.line_number > start.line_number && end.line_char_index == 0 {
+        let line_number = end.line_number - 1;
+        end = Position {
+            full_index: end.full_index - 1,
+            line_number,
+            line_char_index: lines
+                .get(line_number - 1)
+                .map(|line| line.chars().count())
+                .unwrap_or(0),
+        };
+    }
+    let gutter_width = end.line_number.to_string().len();
+    let empty_gutter = " ".repeat(gutter_width);
+
+    let mut output = String::new();
+    writeln!(output, "{}: {}", severity, diagnostic.title).unwrap();
+    writeln!(
+        output,
+        "{}--> line {}, column {}",
+        empty_gutter,
+        start.line_number,
+        start.line_char_index + 1
+    )
+    .unwrap();
+    writeln!(output, "{} |", empty_gutter).unwrap();
+    for line_number in start.line_number..=end.line_number {
+        let line: Vec<char> = lines
+            .get(line_number - 1)
+            .map(|line| {
+                line.chars()
+                    .map(|c| if c == '\t' { ' ' } else { c })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let underline_start = if line_number == start.line_number {
+            start.line_char_index
+        } else {
+            line.iter().take_while(|c| c.is_whitespace()).count()
+        };
+        let underline_end = if line_number == end.line_number {
+            end.line_char_index
+        } else {
+            line.len()
+        };
+        let underline_width = underline_end.saturating_sub(underline_start).max(1);
+        writeln!(
+            output,
+            "{:>width$} | {}",
+            line_number,
+            line.iter().collect::<String>(),
+            width = gutter_width
+        )
+        .unwrap();
+        write!(
+            output,
+            "{} | {}{}",
+            empty_gutter,
+            " ".repeat(underline_start),
+            "^".repeat(underline_width)
+        )
+        .unwrap();
+        if line_number == end.line_number {
+            write!(output, " {}", diagnostic.label).unwrap();
+        }
+        writeln!(output).unwrap();
+    }
+    if let Some(hint) = &diagnostic.hint {
+        writeln!(output, "{} |", empty_gutter).unwrap();
+        writeln!(output, "{} = hint: {}", empty_gutter, hint).unwrap();
+    }
+    output
+}
+
+fn describe_lexer_error(s: &str, err: &LexerError) -> CompileErrorDiagnostic {
+    // Note: the lexer reports the position at which it stopped, i.e. right after an invalid
+    // integer or unicode escape sequence
+    let (span, title, label, hint) = match err {
+        LexerError::UnexpectedEof => {
+            let end = end_position(s);
+            (
+                Span { start: end, end },
+                "unexpected end of manifest".to_string(),
+                "the manifest ends here".to_string(),
+                Some("check that every string literal is closed with a `\"`".to_string()),
+            )
+        }
+        LexerError::UnexpectedChar(c, position) => (
+            span_of_chars_from(*position, 1),
+            format!("unexpected character {:?}", c),
+            "unexpected character".to_string(),
+            None,
+        ),
+        LexerError::InvalidInteger(int, position) => (
+            span_of_chars_before(*position, int.chars().count()),
+            format!("invalid integer `{}`", int),
+            "not a valid integer of this type".to_string(),
+            Some(
+                "an integer literal needs a type suffix (e.g. `1u8` or `-5i64`) and must fit \
+                into the range of that type"
+                    .to_string(),
+            ),
+        ),
+        LexerError::InvalidUnicode(unicode, position) => (
+            span_of_chars_before(*position, 1),
+            format!("invalid unicode code point `{:#x}`", unicode),
+            "not a valid unicode escape sequence".to_string(),
+            None,
+        ),
+        LexerError::UnknownIdentifier(ident, position) => (
+            span_of_chars_from(*position, ident.chars().count()),
+            format!("unknown identifier `{}`", ident),
+            "unknown identifier".to_string(),
+            None,
+        ),
+    };
+    CompileErrorDiagnostic {
+        span,
+        title,
+        label,
+        hint,
+    }
+}
+
+fn describe_parser_error_kind(error_kind: &ParserErrorKind) -> (String, String, Option<String>) {
+    match error_kind {
+        ParserErrorKind::UnexpectedEof => (
+            "unexpected end of manifest".to_string(),
+            "the manifest ends here".to_string(),
+            Some(
+                "check that every instruction ends with a `;` and that all brackets are closed"
+                    .to_string(),
+            ),
+        ),
+        ParserErrorKind::UnexpectedToken { expected, actual } => {
+            let hint = match expected {
+                TokenType::Instruction => Some(
+                    "an instruction starts with its upper-case name, e.g. `CALL_METHOD`"
+                        .to_string(),
+                ),
+                TokenType::Value => Some(
+                    "a value is either a literal (e.g. `1u32`, `\"text\"` or `true`) or a \
+                    constructor (e.g. `Decimal(\"1\")` or `Tuple()`)"
+                        .to_string(),
+                ),
+                TokenType::ValueKind => Some(
+                    "a value kind is named like its values' constructor, e.g. `U8`, `String` or \
+                    `Address`"
+                        .to_string(),
+                ),
+                TokenType::EnumDiscriminator => Some(
+                    "an enum discriminator is either a `u8` (e.g. `Enum<0u8>()`) or a known \
+                    variant name (e.g. `Enum<PublicKey::Secp256k1>()`)"
+                        .to_string(),
+                ),
+                TokenType::Parameter => Some(
+                    "a template parameter is a `$` followed by its name, e.g. `$amount`"
+                        .to_string(),
+                ),
+                TokenType::Exact(_) => None,
+            };
+            (
+                format!(
+                    "expected {}, found {}",
+                    describe_token_type(expected),
+                    describe_token_kind(&actual.kind)
+                ),
+                format!("expected {}", describe_token_type(expected)),
+                hint,
+            )
+        }
+        ParserErrorKind::InvalidNumberOfValues { expected, actual } => (
+            format!("expected {} value(s), found {}", expected, actual),
+            format!("expected {} value(s) here", expected),
+            None,
+        ),
+        ParserErrorKind::InvalidNumberOfTypes { expected, actual } => (
+            format!("expected {} value kind(s), found {}", expected, actual),
+            format!("expected {} value kind(s) here", expected),
+            None,
+        ),
+        ParserErrorKind::InvalidHex(hex) => (
+            format!("invalid hex string \"{}\"", hex),
+            "not a valid hex string".to_string(),
+            None,
+        ),
+        ParserErrorKind::UnknownEnumDiscriminator(discriminator) => (
+            format!("unknown enum discriminator `{}`", discriminator),
+            "unknown enum discriminator".to_string(),
+            Some(
+                "an enum discriminator is either a `u8` (e.g. `Enum<0u8>()`) or a known variant \
+                name (e.g. `Enum<PublicKey::Secp256k1>()`)"
+                    .to_string(),
+            ),
+        ),
+        ParserErrorKind::MaxDepthExceeded(max_depth) => (
+            format!(
+                "value is nested too deeply (the maximum depth is {})",
+                max_depth
+            ),
+            "too deeply nested value".to_string(),
+            None,
+        ),
+    }
+}
+
+fn describe_generator_error_kind(
+    error_kind: &GeneratorErrorKind,
+) -> (String, String, Option<String>) {
+    let address_hint = Some(
+        "addresses must be Bech32m-encoded for the network which the manifest is compiled for \
+        (e.g. `resource_sim1...` for the simulator)"
+            .to_string(),
+    );
+    match error_kind {
+        GeneratorErrorKind::InvalidAstType {
+            expected_type,
+            actual,
+        } => (
+            format!(
+                "expected value kind `{:?}`, found `{:?}`",
+                expected_type, actual
+            ),
+            format!("expected `{:?}`", expected_type),
+            None,
+        ),
+        GeneratorErrorKind::InvalidAstValue {
+            expected_type,
+            actual,
+        } => {
+            let expected = expected_type
+                .iter()
+                .map(|value_kind| format!("`{:?}`", value_kind))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            (
+                format!(
+                    "expected a value of kind {}, found `{}`",
+                    expected,
+                    value_kind_name(actual)
+                ),
+                format!("expected {}", expected),
+                None,
+            )
+        }
+        GeneratorErrorKind::UnexpectedValue {
+            expected_type,
+            actual,
+        } => (
+            format!(
+                "expected a value of kind `{}`, found `{}`",
+                manifest_value_kind_name(expected_type),
+                value_kind_name(actual)
+            ),
+            format!("expected `{}`", manifest_value_kind_name(expected_type)),
+            Some(
+                "the elements of an `Array` (and the keys and values of a `Map`) must be of its \
+                declared value kinds"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidPackageAddress(address) => (
+            format!("invalid package address \"{}\"", address),
+            "not a valid package address".to_string(),
+            address_hint,
+        ),
+        GeneratorErrorKind::InvalidComponentAddress(address) => (
+            format!("invalid component address \"{}\"", address),
+            "not a valid component address".to_string(),
+            address_hint,
+        ),
+        GeneratorErrorKind::InvalidResourceAddress(address) => (
+            format!("invalid resource address \"{}\"", address),
+            "not a valid resource address".to_string(),
+            address_hint,
+        ),
+        GeneratorErrorKind::InvalidGlobalAddress(address) => (
+            format!("invalid global address \"{}\"", address),
+            "not a valid address of this kind".to_string(),
+            address_hint,
+        ),
+        GeneratorErrorKind::InvalidInternalAddress(address) => (
+            format!("invalid internal address \"{}\"", address),
+            "not a valid internal address".to_string(),
+            address_hint,
+        ),
+        GeneratorErrorKind::InvalidDecimal(decimal) => (
+            format!("invalid decimal \"{}\"", decimal),
+            "not a valid decimal number".to_string(),
+            Some(
+                "decimals are written like `Decimal(\"1.5\")`, with at most 18 decimal places"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidPreciseDecimal(decimal) => (
+            format!("invalid precise decimal \"{}\"", decimal),
+            "not a valid precise decimal number".to_string(),
+            Some(
+                "precise decimals are written like `PreciseDecimal(\"1.5\")`, with at most 36 \
+                decimal places"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidNonFungibleLocalId(local_id) => (
+            format!("invalid non-fungible local id \"{}\"", local_id),
+            "not a valid non-fungible local id".to_string(),
+            Some(
+                "non-fungible local ids are written like `#1#` (integer), `<name>` (string), \
+                `[c0ffee]` (bytes) or `{...}` (RUID)"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidNonFungibleGlobalId => (
+            "invalid non-fungible global id".to_string(),
+            "not a valid non-fungible global id".to_string(),
+            Some(
+                "non-fungible global ids are written like \
+                `NonFungibleGlobalId(\"<resource address>:<local id>\")`"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidExpression(expression) => (
+            format!("invalid expression \"{}\"", expression),
+            "unknown expression".to_string(),
+            Some(
+                "an expression is either `Expression(\"ENTIRE_WORKTOP\")` or \
+                `Expression(\"ENTIRE_AUTH_ZONE\")`"
+                    .to_string(),
+            ),
+        ),
+        GeneratorErrorKind::InvalidBlobHash(hash) => (
+            format!("invalid blob hash \"{}\"", hash),
+            "not a valid blob hash".to_string(),
+            Some("a blob is referenced by the hex-encoded hash of its content".to_string()),
+        ),
+        GeneratorErrorKind::BlobNotFound(hash) => (
+            format!("blob with hash \"{}\" not found", hash),
+            "unknown blob".to_string(),
+            Some("the referenced blob must be provided along with the manifest".to_string()),
+        ),
+        GeneratorErrorKind::InvalidBytesHex(hex) => (
+            format!("invalid hex string \"{}\"", hex),
+            "not a valid hex string".to_string(),
+            None,
+        ),
+        GeneratorErrorKind::NameResolverError(err) => describe_name_resolver_error(err),
+        GeneratorErrorKind::IdValidationError(err) => describe_id_validation_error(err),
+        error_kind => (
+            format!("{:?}", error_kind),
+            "invalid value".to_string(),
+            None,
+        ),
+    }
+}
+
+fn describe_name_resolver_error(err: &NameResolverError) -> (String, String, Option<String>) {
+    match err {
+        NameResolverError::UndefinedBucket(name) => (
+            format!("undefined bucket \"{}\"", name),
+            "no bucket of this name".to_string(),
+            Some(
+                "a bucket must be created (e.g. by `TAKE_ALL_FROM_WORKTOP`) before it is used"
+                    .to_string(),
+            ),
+        ),
+        NameResolverError::UndefinedProof(name) => (
+            format!("undefined proof \"{}\"", name),
+            "no proof of this name".to_string(),
+            Some(
+                "a proof must be created (e.g. by `CREATE_PROOF_FROM_AUTH_ZONE_OF_ALL`) before it \
+                is used"
+                    .to_string(),
+            ),
+        ),
+        NameResolverError::UndefinedAddressReservation(name) => (
+            format!("undefined address reservation \"{}\"", name),
+            "no address reservation of this name".to_string(),
+            Some(
+                "an address reservation must be created by `ALLOCATE_GLOBAL_ADDRESS` before it \
+                is used"
+                    .to_string(),
+            ),
+        ),
+        NameResolverError::UndefinedNamedAddress(name) => (
+            format!("undefined named address \"{}\"", name),
+            "no named address of this name".to_string(),
+            Some(
+                "a named address must be created by `ALLOCATE_GLOBAL_ADDRESS` before it is used"
+                    .to_string(),
+            ),
+        ),
+        NameResolverError::NamedAlreadyDefined(name) => (
+            format!("name \"{}\" is already defined", name),
+            "name already in use".to_string(),
+            Some("buckets, proofs and address reservations need unique names".to_string()),
+        ),
+    }
+}
+
+fn describe_id_validation_error(
+    err: &ManifestIdValidationError,
+) -> (String, String, Option<String>) {
+    match err {
+        ManifestIdValidationError::BucketNotFound(_) => (
+            "bucket was already consumed".to_string(),
+            "no longer exists".to_string(),
+            Some("a bucket can only be used once (e.g. deposited or burned)".to_string()),
+        ),
+        ManifestIdValidationError::ProofNotFound(_) => (
+            "proof was already consumed".to_string(),
+            "no longer exists".to_string(),
+            Some("a proof can only be used once (e.g. pushed or dropped)".to_string()),
+        ),
+        ManifestIdValidationError::BucketLocked(_) => (
+            "bucket is locked by a proof".to_string(),
+            "locked bucket".to_string(),
+            Some("drop all proofs created from the bucket before consuming it".to_string()),
+        ),
+        ManifestIdValidationError::AddressReservationNotFound(_) => (
+            "address reservation was already consumed".to_string(),
+            "no longer exists".to_string(),
+            None,
+        ),
+        ManifestIdValidationError::AddressNotFound(_) => (
+            "named address not found".to_string(),
+            "no such named address".to_string(),
+            None,
+        ),
+    }
+}
+
+fn describe_manifest_warning_kind(
+    warning_kind: &ManifestWarningKind,
+) -> (String, String, Option<String>) {
+    match warning_kind {
+        ManifestWarningKind::BucketNeverConsumed(_) => (
+            "bucket created but never consumed".to_string(),
+            "this bucket is still held at the end of the manifest".to_string(),
+            Some(
+                "deposit the bucket (e.g. with `try_deposit_or_abort`), burn it, or return it to \
+                the worktop"
+                    .to_string(),
+            ),
+        ),
+        ManifestWarningKind::ProofNeverUsed(_) => (
+            "proof created but never used".to_string(),
+            "this proof is never passed to a call, pushed to the auth zone or cloned".to_string(),
+            None,
+        ),
+        ManifestWarningKind::AssertionCanNeverPass(_) => (
+            "assertion can never pass".to_string(),
+            "the worktop does not contain enough of this resource here".to_string(),
+            None,
+        ),
+        ManifestWarningKind::TakeCanNeverSucceed(_) => (
+            "take from worktop can never succeed".to_string(),
+            "the worktop does not contain enough of this resource here".to_string(),
+            None,
+        ),
+        ManifestWarningKind::ResourceLeftOnWorktop(_) => (
+            "resources left on worktop without deposit".to_string(),
+            "the resources put on the worktop here are never taken from it".to_string(),
+            Some(
+                "deposit the remaining resources, e.g. by passing `Expression(\"ENTIRE_WORKTOP\")` \
+                to an account's `deposit_batch` method"
+                    .to_string(),
+            ),
+        ),
+    }
+}
+
+fn describe_token_type(token_type: &TokenType) -> String {
+    match token_type {
+        TokenType::Instruction => "an instruction".to_string(),
+        TokenType::Value => "a value".to_string(),
+        TokenType::ValueKind => "a value kind".to_string(),
+        TokenType::EnumDiscriminator => "an enum discriminator".to_string(),
+        TokenType::Parameter => "a template parameter".to_string(),
+        TokenType::Exact(kind) => describe_token_kind(kind),
+    }
+}
+
+fn describe_token_kind(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::BoolLiteral(value) => format!("`{}`", value),
+        TokenKind::I8Literal(value) => format!("`{}i8`", value),
+        TokenKind::I16Literal(value) => format!("`{}i16`", value),
+        TokenKind::I32Literal(value) => format!("`{}i32`", value),
+        TokenKind::I64Literal(value) => format!("`{}i64`", value),
+        TokenKind::I128Literal(value) => format!("`{}i128`", value),
+        TokenKind::U8Literal(value) => format!("`{}u8`", value),
+        TokenKind::U16Literal(value) => format!("`{}u16`", value),
+        TokenKind::U32Literal(value) => format!("`{}u32`", value),
+        TokenKind::U64Literal(value) => format!("`{}u64`", value),
+        TokenKind::U128Literal(value) => format!("`{}u128`", value),
+        TokenKind::StringLiteral(value) => format!("string {:?}", value),
+        TokenKind::Ident(value) => format!("`{}`", value),
+        TokenKind::Parameter(name) => format!("`${}`", name),
+        TokenKind::OpenParenthesis => "`(`".to_string(),
+        TokenKind::CloseParenthesis => "`)`".to_string(),
+        TokenKind::LessThan => "`<`".to_string(),
+        TokenKind::GreaterThan => "`>`".to_string(),
+        TokenKind::Comma => "`,`".to_string(),
+        TokenKind::Semicolon => "`;`".to_string(),
+        TokenKind::FatArrow => "`=>`".to_string(),
+    }
+}
+
+/// Returns the name of the given value's kind, as written in a manifest.
+fn value_kind_name(value: &ast::Value) -> &'static str {
+    match value {
+        ast::Value::Bool(_) => "Bool",
+        ast::Value::I8(_) => "I8",
+        ast::Value::I16(_) => "I16",
+        ast::Value::I32(_) => "I32",
+        ast::Value::I64(_) => "I64",
+        ast::Value::I128(_) => "I128",
+        ast::Value::U8(_) => "U8",
+        ast::Value::U16(_) => "U16",
+        ast::Value::U32(_) => "U32",
+        ast::Value::U64(_) => "U64",
+        ast::Value::U128(_) => "U128",
+        ast::Value::String(_) => "String",
+        ast::Value::Enum(..) => "Enum",
+        ast::Value::Array(..) => "Array",
+        ast::Value::Tuple(_) => "Tuple",
+        ast::Value::Map(..) => "Map",
+        ast::Value::Some(_) => "Some",
+        ast::Value::None => "None",
+        ast::Value::Ok(_) => "Ok",
+        ast::Value::Err(_) => "Err",
+        ast::Value::Bytes(_) => "Bytes",
+        ast::Value::NonFungibleGlobalId(_) => "NonFungibleGlobalId",
+        ast::Value::Address(_) => "Address",
+        ast::Value::NamedAddress(_) => "NamedAddress",
+        ast::Value::Bucket(_) => "Bucket",
+        ast::Value::Proof(_) => "Proof",
+        ast::Value::Expression(_) => "Expression",
+        ast::Value::Blob(_) => "Blob",
+        ast::Value::Decimal(_) => "Decimal",
+        ast::Value::PreciseDecimal(_) => "PreciseDecimal",
+        ast::Value::NonFungibleLocalId(_) => "NonFungibleLocalId",
+        ast::Value::AddressReservation(_) => "AddressReservation",
+    }
+}
+
+fn manifest_value_kind_name(value_kind: &ManifestValueKind) -> String {
+    match value_kind {
+        ManifestValueKind::Custom(custom_value_kind) => format!("{:?}", custom_value_kind),
+        value_kind => format!("{:?}", value_kind),
+    }
+}
+
+fn end_position(s: &str) -> Position {
+    let mut position = Position {
+        full_index: 0,
+        line_number: 1,
+        line_char_index: 0,
+    };
+    for c in s.chars() {
+        position.full_index += 1;
+        if c == '\n' {
+            position.line_number += 1;
+            position.line_char_index = 0;
+        } else {
+            position.line_char_index += 1;
+        }
+    }
+    position
+}
+
+/// Returns the span of the given number of chars (within a single line) starting at the position.
+fn span_of_chars_from(start: Position, count: usize) -> Span {
+    Span {
+        start,
+        end: Position {
+            full_index: start.full_index + count,
+            line_number: start.line_number,
+            line_char_index: start.line_char_index + count,
+        },
+    }
+}
+
+/// Returns the span of the given number of chars (within a single line) ending at the position.
+fn span_of_chars_before(end: Position, count: usize) -> Span {
+    let count = count.min(end.line_char_index);
+    Span {
+        start: Position {
+            full_index: end.full_index - count,
+            line_number: end.line_number,
+            line_char_index: end.line_char_index - count,
+        },
+        end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_engine_interface::network::NetworkDefinition;
+
+    fn compile_error_of(s: &str) -> CompileError {
+        compile(s, &NetworkDefinition::simulator(), BlobProvider::default()).unwrap_err()
+    }
+
+    #[test]
+    fn test_generator_error_is_rendered_with_underlined_value() {
+        let manifest = r#"CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee"
+    Decimal("1.2.3");
+"#;
+        let err = compile_error_of(manifest);
+
+        assert_eq!(
+            compile_error_diagnostics(manifest, &err),
+            r#"error: invalid decimal "1.2.3"
+ --> line 4, column 13
+  |
+4 |     Decimal("1.2.3");
+  |             ^^^^^^^ not a valid decimal number
+  |
+  = hint: decimals are written like `Decimal("1.5")`, with at most 18 decimal places
+"#
+        );
+    }
+
+    #[test]
+    fn test_parser_error_is_rendered_with_underlined_token() {
+        let manifest = "DROP_ALL_PROOFS;\nDROP_ALL_PROOFS\nDROP_ALL_PROOFS;";
+        let err = compile_error_of(manifest);
+
+        assert_eq!(
+            compile_error_diagnostics(manifest, &err),
+            r#"error: expected `;`, found `DROP_ALL_PROOFS`
+ --> line 3, column 1
+  |
+3 | DROP_ALL_PROOFS;
+  | ^^^^^^^^^^^^^^^ expected `;`
+"#
+        );
+    }
+
+    #[test]
+    fn test_multi_line_span_is_rendered_line_by_line() {
+        let manifest =
+            "CALL_METHOD\n    Address(\n        \"abc\",\n        \"def\"\n    )\n    \"free\";";
+        let err = compile_error_of(manifest);
+
+        assert_eq!(
+            compile_error_diagnostics(manifest, &err),
+            r#"error: expected 1 value(s), found 2
+ --> line 2, column 12
+  |
+2 |     Address(
+  |            ^
+3 |         "abc",
+  |         ^^^^^^
+4 |         "def"
+  |         ^^^^^
+5 |     )
+  |     ^ expected 1 value(s) here
+"#
+        );
+    }
+
+    #[test]
+    fn test_lexer_error_is_rendered_at_its_position() {
+        let manifest = "CALL_METHOD\n    Address(\"abc\")\n    \"free\"\n    300u8;";
+        let err = compile_error_of(manifest);
+
+        assert_eq!(
+            compile_error_diagnostics(manifest, &err),
+            r#"error: invalid integer `300u8`
+ --> line 4, column 5
+  |
+4 |     300u8;
+  |     ^^^^^ not a valid integer of this type
+  |
+  = hint: an integer literal needs a type suffix (e.g. `1u8` or `-5i64`) and must fit into the range of that type
+"#
+        );
+    }
+
+    #[test]
+    fn test_manifest_warning_is_rendered_with_underlined_instruction() {
+        let manifest = r#"TAKE_ALL_FROM_WORKTOP
+    Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+    Bucket("xrd");
+"#;
+        let (compiled, source_map) = compile_with_source_map(
+            manifest,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+        .unwrap();
+        let warnings = analyze_manifest(&compiled.instructions);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            manifest_warning_diagnostics(manifest, &source_map, &warnings[0]),
+            r#"warning: bucket created but never consumed
+ --> line 1, column 1
+  |
+1 | TAKE_ALL_FROM_WORKTOP
+  | ^^^^^^^^^^^^^^^^^^^^^
+2 |     Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+  |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+3 |     Bucket("xrd");
+  |     ^^^^^^^^^^^^^^ this bucket is still held at the end of the manifest
+  |
+  = hint: deposit the bucket (e.g. with `try_deposit_or_abort`), burn it, or return it to the worktop
+"#
+        );
+    }
+}
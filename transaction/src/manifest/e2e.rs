@@ -1549,6 +1549,31 @@ CALL_METHOD
         let re_recompiled_binary =
             manifest_encode(&re_recompiled).expect("Re-recompiled manifest could not be encoded");
 
+        // Check that formatting keeps the manifest, and that canonical manifests are formatted
+        let formatted = format_manifest_source(original_string)
+            .expect("Manifest string could not be formatted");
+        let formatted_compiled = compile(&formatted, network, blob_provider.clone())
+            .expect("Formatted manifest could not be compiled");
+        assert_eq!(
+            manifest_encode(&formatted_compiled).unwrap(),
+            original_binary
+        );
+        assert_eq!(format_manifest_source(&formatted).unwrap(), formatted);
+        assert_eq!(
+            format_manifest_source(&original_decompiled).unwrap(),
+            original_decompiled
+        );
+
+        // Check that the JSON representation keeps the manifest
+        #[cfg(feature = "serde")]
+        {
+            let json = json::to_json(&original_compiled, network)
+                .expect("Manifest could not be converted to JSON");
+            let from_json =
+                json::from_json(&json, network).expect("Manifest could not be converted from JSON");
+            assert_eq!(manifest_encode(&from_json).unwrap(), original_binary);
+        }
+
         // If you use the following output for test cases, make sure you've checked the diff
         println!("{}", recompiled_decompiled);
 
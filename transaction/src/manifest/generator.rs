@@ -3,6 +3,7 @@ use crate::data::*;
 use crate::errors::*;
 use crate::internal_prelude::TransactionManifestV1;
 use crate::manifest::ast;
+use crate::manifest::lexer::Span;
 use crate::model::*;
 use crate::validation::*;
 use radix_engine_common::constants::PACKAGE_PACKAGE;
@@ -57,14 +58,21 @@ use radix_engine_interface::types::GlobalAddress;
 use radix_engine_interface::types::InternalAddress;
 use radix_engine_interface::types::ResourceAddress;
 use radix_engine_interface::*;
-use sbor::rust::borrow::Borrow;
 use sbor::rust::collections::IndexMap;
 use sbor::rust::str::FromStr;
 use sbor::rust::vec;
 use sbor::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum GeneratorError {
+pub struct GeneratorError {
+    pub error_kind: GeneratorErrorKind,
+    /// The span of the offending value (or of the whole instruction, if no single value is at
+    /// fault).
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorErrorKind {
     InvalidAstType {
         expected_type: ast::ValueKind,
         actual: ast::ValueKind,
@@ -222,7 +230,7 @@ impl NameResolver {
 }
 
 pub fn generate_manifest<B>(
-    instructions: &[ast::Instruction],
+    instructions: &[ast::InstructionWithSpan],
     address_bech32_decoder: &AddressBech32Decoder,
     blobs: B,
 ) -> Result<TransactionManifestV1, GeneratorError>
@@ -250,7 +258,7 @@ where
 }
 
 pub fn generate_instruction<B>(
-    instruction: &ast::Instruction,
+    instruction: &ast::InstructionWithSpan,
     id_validator: &mut ManifestValidator,
     resolver: &mut NameResolver,
     address_bech32_decoder: &AddressBech32Decoder,
@@ -259,7 +267,7 @@ pub fn generate_instruction<B>(
 where
     B: IsBlobProvider,
 {
-    Ok(match instruction {
+    Ok(match &instruction.instruction {
         ast::Instruction::TakeFromWorktop {
             resource_address,
             amount,
@@ -310,7 +318,10 @@ where
             let bucket_id = generate_bucket(bucket, resolver)?;
             id_validator
                 .drop_bucket(&bucket_id)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: bucket.span,
+                })?;
             InstructionV1::ReturnToWorktop { bucket_id }
         }
         ast::Instruction::AssertWorktopContains {
@@ -338,7 +349,10 @@ where
         ast::Instruction::PopFromAuthZone { new_proof } => {
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::PopFromAuthZone
@@ -347,7 +361,10 @@ where
             let proof_id = generate_proof(proof, resolver)?;
             id_validator
                 .drop_proof(&proof_id)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: proof.span,
+                })?;
             InstructionV1::PushToAuthZone { proof_id }
         }
         ast::Instruction::DropAuthZoneProofs => InstructionV1::DropAuthZoneProofs,
@@ -364,7 +381,10 @@ where
             let amount = generate_decimal(amount)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromAuthZoneOfAmount {
@@ -382,7 +402,10 @@ where
             let ids = generate_non_fungible_local_ids(ids)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromAuthZoneOfNonFungibles {
@@ -398,7 +421,10 @@ where
                 generate_resource_address(resource_address, address_bech32_decoder)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromAuthZoneOfAll { resource_address }
@@ -408,7 +434,10 @@ where
             let bucket_id = generate_bucket(bucket, resolver)?;
             id_validator
                 .drop_bucket(&bucket_id)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: bucket.span,
+                })?;
             InstructionV1::BurnResource { bucket_id }
         }
 
@@ -421,7 +450,10 @@ where
             let amount = generate_decimal(amount)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::BucketProof(bucket_id.clone()))
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: bucket.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromBucketOfAmount { bucket_id, amount }
@@ -435,7 +467,10 @@ where
             let ids = generate_non_fungible_local_ids(ids)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::BucketProof(bucket_id.clone()))
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: bucket.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromBucketOfNonFungibles { bucket_id, ids }
@@ -444,7 +479,10 @@ where
             let bucket_id = generate_bucket(bucket, resolver)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::BucketProof(bucket_id.clone()))
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: bucket.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id)?;
 
             InstructionV1::CreateProofFromBucketOfAll { bucket_id }
@@ -454,7 +492,10 @@ where
             let proof_id = generate_proof(proof, resolver)?;
             let proof_id2 = id_validator
                 .clone_proof(&proof_id)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: proof.span,
+                })?;
             declare_proof(new_proof, resolver, proof_id2)?;
 
             InstructionV1::CloneProof { proof_id }
@@ -463,7 +504,10 @@ where
             let proof_id = generate_proof(proof, resolver)?;
             id_validator
                 .drop_proof(&proof_id)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: proof.span,
+                })?;
             InstructionV1::DropProof { proof_id }
         }
 
@@ -483,7 +527,10 @@ where
             let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
             id_validator
                 .process_call_data(&args)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
 
             InstructionV1::CallFunction {
                 package_address,
@@ -503,7 +550,10 @@ where
             let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
             id_validator
                 .process_call_data(&args)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::CallMethod {
                 address,
                 method_name,
@@ -521,7 +571,10 @@ where
             let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
             id_validator
                 .process_call_data(&args)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::CallRoyaltyMethod {
                 address,
                 method_name,
@@ -539,7 +592,10 @@ where
             let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
             id_validator
                 .process_call_data(&args)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::CallMetadataMethod {
                 address,
                 method_name,
@@ -557,7 +613,10 @@ where
             let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
             id_validator
                 .process_call_data(&args)
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::CallRoleAssignmentMethod {
                 address,
                 method_name,
@@ -568,14 +627,20 @@ where
         ast::Instruction::DropNamedProofs => {
             id_validator
                 .drop_all_named_proofs()
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::DropNamedProofs
         }
 
         ast::Instruction::DropAllProofs => {
             id_validator
                 .drop_all_named_proofs()
-                .map_err(GeneratorError::IdValidationError)?;
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
             InstructionV1::DropAllProofs
         }
 
@@ -597,6 +662,28 @@ where
             }
         }
 
+        ast::Instruction::YieldToChild { child_index, args } => {
+            let child_index = generate_u32(child_index)?;
+            let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
+            id_validator
+                .process_call_data(&args)
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
+            InstructionV1::YieldToChild { child_index, args }
+        }
+        ast::Instruction::YieldToParent { args } => {
+            let args = generate_args(args, resolver, address_bech32_decoder, blobs)?;
+            id_validator
+                .process_call_data(&args)
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::IdValidationError(err),
+                    span: instruction.span,
+                })?;
+            InstructionV1::YieldToParent { args }
+        }
+
         /* direct vault method aliases */
         ast::Instruction::RecallFromVault { vault_id, args } => {
             InstructionV1::CallDirectVaultMethod {
@@ -808,15 +895,18 @@ where
 #[macro_export]
 macro_rules! invalid_type {
     ( $v:expr, $($exp:expr),+ ) => {
-        Err(GeneratorError::InvalidAstValue {
-            expected_type: vec!($($exp),+),
-            actual: $v.clone(),
+        Err(GeneratorError {
+            error_kind: GeneratorErrorKind::InvalidAstValue {
+                expected_type: vec!($($exp),+),
+                actual: $v.value.clone(),
+            },
+            span: $v.span,
         })
     };
 }
 
 fn generate_args<B>(
-    values: &Vec<ast::Value>,
+    values: &Vec<ast::ValueWithSpan>,
     resolver: &mut NameResolver,
     address_bech32_decoder: &AddressBech32Decoder,
     blobs: &B,
@@ -838,104 +928,128 @@ where
     Ok(ManifestValue::Tuple { fields })
 }
 
-fn generate_string(value: &ast::Value) -> Result<String, GeneratorError> {
-    match value {
+fn generate_string(value: &ast::ValueWithSpan) -> Result<String, GeneratorError> {
+    match &value.value {
         ast::Value::String(s) => Ok(s.into()),
-        v => invalid_type!(v, ast::ValueKind::String),
+        _ => invalid_type!(value, ast::ValueKind::String),
     }
 }
 
-fn generate_decimal(value: &ast::Value) -> Result<Decimal, GeneratorError> {
-    match value {
-        ast::Value::Decimal(inner) => match &**inner {
-            ast::Value::String(s) => {
-                Decimal::from_str(s).map_err(|_| GeneratorError::InvalidDecimal(s.into()))
-            }
-            v => invalid_type!(v, ast::ValueKind::String),
+fn generate_u32(value: &ast::ValueWithSpan) -> Result<u32, GeneratorError> {
+    match &value.value {
+        ast::Value::U32(v) => Ok(*v),
+        _ => invalid_type!(value, ast::ValueKind::U32),
+    }
+}
+
+fn generate_decimal(value: &ast::ValueWithSpan) -> Result<Decimal, GeneratorError> {
+    match &value.value {
+        ast::Value::Decimal(inner) => match &inner.value {
+            ast::Value::String(s) => Decimal::from_str(s).map_err(|_| GeneratorError {
+                error_kind: GeneratorErrorKind::InvalidDecimal(s.into()),
+                span: inner.span,
+            }),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Decimal),
+        _ => invalid_type!(value, ast::ValueKind::Decimal),
     }
 }
 
-fn generate_precise_decimal(value: &ast::Value) -> Result<PreciseDecimal, GeneratorError> {
-    match value {
-        ast::Value::PreciseDecimal(inner) => match &**inner {
-            ast::Value::String(s) => PreciseDecimal::from_str(s)
-                .map_err(|_| GeneratorError::InvalidPreciseDecimal(s.into())),
+fn generate_precise_decimal(value: &ast::ValueWithSpan) -> Result<PreciseDecimal, GeneratorError> {
+    match &value.value {
+        ast::Value::PreciseDecimal(inner) => match &inner.value {
+            ast::Value::String(s) => PreciseDecimal::from_str(s).map_err(|_| GeneratorError {
+                error_kind: GeneratorErrorKind::InvalidPreciseDecimal(s.into()),
+                span: inner.span,
+            }),
 
-            v => invalid_type!(v, ast::ValueKind::String),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Decimal),
+        _ => invalid_type!(value, ast::ValueKind::Decimal),
     }
 }
 
 fn generate_package_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
 ) -> Result<PackageAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(inner) => match inner.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
                     if let Ok(address) = PackageAddress::try_from(full_data.as_ref()) {
                         return Ok(address);
                     }
                 }
-                return Err(GeneratorError::InvalidGlobalAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidGlobalAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => invalid_type!(v, ast::ValueKind::String),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::PackageAddress),
+        _ => invalid_type!(value, ast::ValueKind::PackageAddress),
     }
 }
 
 fn generate_resource_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
 ) -> Result<ResourceAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(inner) => match inner.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
                     if let Ok(address) = ResourceAddress::try_from(full_data.as_ref()) {
                         return Ok(address);
                     }
                 }
-                return Err(GeneratorError::InvalidGlobalAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidGlobalAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => invalid_type!(v, ast::ValueKind::String),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::ResourceAddress),
+        _ => invalid_type!(value, ast::ValueKind::ResourceAddress),
     }
 }
 
 fn generate_dynamic_global_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
     resolver: &mut NameResolver,
 ) -> Result<DynamicGlobalAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(value) => match value.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
                     if let Ok(address) = GlobalAddress::try_from(full_data.as_ref()) {
                         return Ok(DynamicGlobalAddress::Static(address));
                     }
                 }
-                return Err(GeneratorError::InvalidGlobalAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidGlobalAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => return invalid_type!(v, ast::ValueKind::String),
+            _ => return invalid_type!(inner, ast::ValueKind::String),
         },
-        ast::Value::NamedAddress(inner) => match &**inner {
-            ast::Value::U32(n) => Ok(DynamicGlobalAddress::Named(*n)),
-            ast::Value::String(s) => resolver
-                .resolve_named_address(&s)
-                .map(Into::into)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
-        },
-        v => invalid_type!(
-            v,
+        ast::Value::NamedAddress(inner) => {
+            match &inner.value {
+                ast::Value::U32(n) => Ok(DynamicGlobalAddress::Named(*n)),
+                ast::Value::String(s) => resolver
+                    .resolve_named_address(&s)
+                    .map(Into::into)
+                    .map_err(|err| GeneratorError {
+                        error_kind: GeneratorErrorKind::NameResolverError(err),
+                        span: inner.span,
+                    }),
+                _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
+            }
+        }
+        _ => invalid_type!(
+            value,
             ast::ValueKind::Address,
             ast::ValueKind::PackageAddress,
             ast::ValueKind::ResourceAddress,
@@ -946,32 +1060,40 @@ fn generate_dynamic_global_address(
 }
 
 fn generate_dynamic_package_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
     resolver: &mut NameResolver,
 ) -> Result<DynamicPackageAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(value) => match value.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
                     if let Ok(address) = PackageAddress::try_from(full_data.as_ref()) {
                         return Ok(DynamicPackageAddress::Static(address));
                     }
                 }
-                return Err(GeneratorError::InvalidPackageAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidPackageAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => return invalid_type!(v, ast::ValueKind::String),
-        },
-        ast::Value::NamedAddress(inner) => match &**inner {
-            ast::Value::U32(n) => Ok(DynamicPackageAddress::Named(*n)),
-            ast::Value::String(s) => resolver
-                .resolve_named_address(&s)
-                .map(Into::into)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
+            _ => return invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(
-            v,
+        ast::Value::NamedAddress(inner) => {
+            match &inner.value {
+                ast::Value::U32(n) => Ok(DynamicPackageAddress::Named(*n)),
+                ast::Value::String(s) => resolver
+                    .resolve_named_address(&s)
+                    .map(Into::into)
+                    .map_err(|err| GeneratorError {
+                        error_kind: GeneratorErrorKind::NameResolverError(err),
+                        span: inner.span,
+                    }),
+                _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
+            }
+        }
+        _ => invalid_type!(
+            value,
             ast::ValueKind::PackageAddress,
             ast::ValueKind::NamedAddress
         ),
@@ -979,23 +1101,26 @@ fn generate_dynamic_package_address(
 }
 
 fn generate_local_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
 ) -> Result<InternalAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(value) => match value.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
                     if let Ok(address) = InternalAddress::try_from(full_data.as_ref()) {
                         return Ok(address);
                     }
                 }
-                return Err(GeneratorError::InvalidInternalAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidInternalAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => return invalid_type!(v, ast::ValueKind::String),
+            _ => return invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(
-            v,
+        _ => invalid_type!(
+            value,
             ast::ValueKind::Address,
             ast::ValueKind::PackageAddress,
             ast::ValueKind::ResourceAddress,
@@ -1005,123 +1130,144 @@ fn generate_local_address(
 }
 
 fn declare_bucket(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
     bucket_id: ManifestBucket,
 ) -> Result<(), GeneratorError> {
-    match value {
-        ast::Value::Bucket(inner) => match &**inner {
+    match &value.value {
+        ast::Value::Bucket(inner) => match &inner.value {
             ast::Value::String(name) => resolver
                 .insert_bucket(name.to_string(), bucket_id)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::String),
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::NameResolverError(err),
+                    span: inner.span,
+                }),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Bucket),
+        _ => invalid_type!(value, ast::ValueKind::Bucket),
     }
 }
 
 fn generate_bucket(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
 ) -> Result<ManifestBucket, GeneratorError> {
-    match value {
-        ast::Value::Bucket(inner) => match &**inner {
+    match &value.value {
+        ast::Value::Bucket(inner) => match &inner.value {
             ast::Value::U32(n) => Ok(ManifestBucket(*n)),
-            ast::Value::String(s) => resolver
-                .resolve_bucket(&s)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
+            ast::Value::String(s) => resolver.resolve_bucket(&s).map_err(|err| GeneratorError {
+                error_kind: GeneratorErrorKind::NameResolverError(err),
+                span: inner.span,
+            }),
+            _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Bucket),
+        _ => invalid_type!(value, ast::ValueKind::Bucket),
     }
 }
 
 fn declare_proof(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
     proof_id: ManifestProof,
 ) -> Result<(), GeneratorError> {
-    match value {
-        ast::Value::Proof(inner) => match &**inner {
-            ast::Value::String(name) => resolver
-                .insert_proof(name.to_string(), proof_id)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::String),
-        },
-        v => invalid_type!(v, ast::ValueKind::Proof),
+    match &value.value {
+        ast::Value::Proof(inner) => {
+            match &inner.value {
+                ast::Value::String(name) => resolver
+                    .insert_proof(name.to_string(), proof_id)
+                    .map_err(|err| GeneratorError {
+                        error_kind: GeneratorErrorKind::NameResolverError(err),
+                        span: inner.span,
+                    }),
+                _ => invalid_type!(inner, ast::ValueKind::String),
+            }
+        }
+        _ => invalid_type!(value, ast::ValueKind::Proof),
     }
 }
 
 fn declare_address_reservation(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
     address_reservation_id: ManifestAddressReservation,
 ) -> Result<(), GeneratorError> {
-    match value {
-        ast::Value::AddressReservation(inner) => match &**inner {
+    match &value.value {
+        ast::Value::AddressReservation(inner) => match &inner.value {
             ast::Value::String(name) => resolver
                 .insert_address_reservation(name.to_string(), address_reservation_id)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::String),
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::NameResolverError(err),
+                    span: inner.span,
+                }),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::AddressReservation),
+        _ => invalid_type!(value, ast::ValueKind::AddressReservation),
     }
 }
 
 fn declare_named_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
     address_id: u32,
 ) -> Result<(), GeneratorError> {
-    match value {
-        ast::Value::NamedAddress(inner) => match &**inner {
+    match &value.value {
+        ast::Value::NamedAddress(inner) => match &inner.value {
             ast::Value::String(name) => resolver
                 .insert_named_address(name.to_string(), address_id)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::String),
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::NameResolverError(err),
+                    span: inner.span,
+                }),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::NamedAddress),
+        _ => invalid_type!(value, ast::ValueKind::NamedAddress),
     }
 }
 
 fn generate_proof(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
 ) -> Result<ManifestProof, GeneratorError> {
-    match value {
-        ast::Value::Proof(inner) => match &**inner {
+    match &value.value {
+        ast::Value::Proof(inner) => match &inner.value {
             ast::Value::U32(n) => Ok(ManifestProof(*n)),
-            ast::Value::String(s) => resolver
-                .resolve_proof(&s)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
+            ast::Value::String(s) => resolver.resolve_proof(&s).map_err(|err| GeneratorError {
+                error_kind: GeneratorErrorKind::NameResolverError(err),
+                span: inner.span,
+            }),
+            _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Proof),
+        _ => invalid_type!(value, ast::ValueKind::Proof),
     }
 }
 
 fn generate_address_reservation(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
 ) -> Result<ManifestAddressReservation, GeneratorError> {
-    match value {
-        ast::Value::AddressReservation(inner) => match &**inner {
+    match &value.value {
+        ast::Value::AddressReservation(inner) => match &inner.value {
             ast::Value::U32(n) => Ok(ManifestAddressReservation(*n)),
-            ast::Value::String(s) => resolver
-                .resolve_address_reservation(&s)
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
+            ast::Value::String(s) => {
+                resolver
+                    .resolve_address_reservation(&s)
+                    .map_err(|err| GeneratorError {
+                        error_kind: GeneratorErrorKind::NameResolverError(err),
+                        span: inner.span,
+                    })
+            }
+            _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::AddressReservation),
+        _ => invalid_type!(value, ast::ValueKind::AddressReservation),
     }
 }
 
 fn generate_static_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     address_bech32_decoder: &AddressBech32Decoder,
 ) -> Result<ManifestAddress, GeneratorError> {
-    match value {
-        ast::Value::Address(value) => match value.borrow() {
+    match &value.value {
+        ast::Value::Address(inner) => match &inner.value {
             ast::Value::String(s) => {
                 // Check bech32 && entity type
                 if let Ok((_, full_data)) = address_bech32_decoder.validate_and_decode(&s) {
@@ -1132,12 +1278,15 @@ fn generate_static_address(
                         )));
                     }
                 }
-                return Err(GeneratorError::InvalidGlobalAddress(s.into()));
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidGlobalAddress(s.into()),
+                    span: inner.span,
+                });
             }
-            v => return invalid_type!(v, ast::ValueKind::String),
+            _ => return invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(
-            v,
+        _ => invalid_type!(
+            value,
             ast::ValueKind::Address,
             ast::ValueKind::PackageAddress,
             ast::ValueKind::ResourceAddress,
@@ -1147,78 +1296,97 @@ fn generate_static_address(
 }
 
 fn generate_named_address(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     resolver: &mut NameResolver,
 ) -> Result<ManifestAddress, GeneratorError> {
-    match value {
-        ast::Value::NamedAddress(inner) => match &**inner {
+    match &value.value {
+        ast::Value::NamedAddress(inner) => match &inner.value {
             ast::Value::U32(n) => Ok(ManifestAddress::Named(*n)),
             ast::Value::String(s) => resolver
                 .resolve_named_address(&s)
                 .map(|x| ManifestAddress::Named(x))
-                .map_err(GeneratorError::NameResolverError),
-            v => invalid_type!(v, ast::ValueKind::U32, ast::ValueKind::String),
+                .map_err(|err| GeneratorError {
+                    error_kind: GeneratorErrorKind::NameResolverError(err),
+                    span: inner.span,
+                }),
+            _ => invalid_type!(inner, ast::ValueKind::U32, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::NamedAddress),
+        _ => invalid_type!(value, ast::ValueKind::NamedAddress),
     }
 }
 
 fn generate_non_fungible_local_id(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
 ) -> Result<NonFungibleLocalId, GeneratorError> {
-    match value {
-        ast::Value::NonFungibleLocalId(inner) => match inner.as_ref() {
-            ast::Value::String(s) => NonFungibleLocalId::from_str(s.as_str())
-                .map_err(|_| GeneratorError::InvalidNonFungibleLocalId(s.clone())),
-            v => invalid_type!(v, ast::ValueKind::String)?,
+    match &value.value {
+        ast::Value::NonFungibleLocalId(inner) => match &inner.value {
+            ast::Value::String(s) => {
+                NonFungibleLocalId::from_str(s.as_str()).map_err(|_| GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidNonFungibleLocalId(s.clone()),
+                    span: inner.span,
+                })
+            }
+            _ => invalid_type!(inner, ast::ValueKind::String)?,
         },
-        v => invalid_type!(v, ast::ValueKind::NonFungibleLocalId),
+        _ => invalid_type!(value, ast::ValueKind::NonFungibleLocalId),
     }
 }
 
-fn generate_expression(value: &ast::Value) -> Result<ManifestExpression, GeneratorError> {
-    match value {
-        ast::Value::Expression(inner) => match &**inner {
+fn generate_expression(value: &ast::ValueWithSpan) -> Result<ManifestExpression, GeneratorError> {
+    match &value.value {
+        ast::Value::Expression(inner) => match &inner.value {
             ast::Value::String(s) => match s.as_str() {
                 "ENTIRE_WORKTOP" => Ok(ManifestExpression::EntireWorktop),
                 "ENTIRE_AUTH_ZONE" => Ok(ManifestExpression::EntireAuthZone),
-                _ => Err(GeneratorError::InvalidExpression(s.into())),
+                _ => Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidExpression(s.into()),
+                    span: inner.span,
+                }),
             },
-            v => invalid_type!(v, ast::ValueKind::String),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Expression),
+        _ => invalid_type!(value, ast::ValueKind::Expression),
     }
 }
 
-fn generate_blob<B>(value: &ast::Value, blobs: &B) -> Result<ManifestBlobRef, GeneratorError>
+fn generate_blob<B>(
+    value: &ast::ValueWithSpan,
+    blobs: &B,
+) -> Result<ManifestBlobRef, GeneratorError>
 where
     B: IsBlobProvider,
 {
-    match value {
-        ast::Value::Blob(inner) => match &**inner {
+    match &value.value {
+        ast::Value::Blob(inner) => match &inner.value {
             ast::Value::String(s) => {
-                let hash = Hash::from_str(s)
-                    .map_err(|_| GeneratorError::InvalidBlobHash(s.to_string()))?;
-                blobs
-                    .get_blob(&hash)
-                    .ok_or(GeneratorError::BlobNotFound(s.clone()))?;
+                let hash = Hash::from_str(s).map_err(|_| GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidBlobHash(s.to_string()),
+                    span: inner.span,
+                })?;
+                blobs.get_blob(&hash).ok_or_else(|| GeneratorError {
+                    error_kind: GeneratorErrorKind::BlobNotFound(s.clone()),
+                    span: inner.span,
+                })?;
                 Ok(ManifestBlobRef(hash.0))
             }
-            v => invalid_type!(v, ast::ValueKind::String),
+            _ => invalid_type!(inner, ast::ValueKind::String),
         },
-        v => invalid_type!(v, ast::ValueKind::Blob),
+        _ => invalid_type!(value, ast::ValueKind::Blob),
     }
 }
 
 fn generate_non_fungible_local_ids(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
 ) -> Result<Vec<NonFungibleLocalId>, GeneratorError> {
-    match value {
+    match &value.value {
         ast::Value::Array(kind, values) => {
             if kind != &ast::ValueKind::NonFungibleLocalId {
-                return Err(GeneratorError::InvalidAstType {
-                    expected_type: ast::ValueKind::String,
-                    actual: kind.clone(),
+                return Err(GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidAstType {
+                        expected_type: ast::ValueKind::String,
+                        actual: kind.clone(),
+                    },
+                    span: value.span,
                 });
             }
 
@@ -1227,22 +1395,23 @@ fn generate_non_fungible_local_ids(
                 .map(|v| generate_non_fungible_local_id(v))
                 .collect()
         }
-        v => invalid_type!(v, ast::ValueKind::Array),
+        _ => invalid_type!(value, ast::ValueKind::Array),
     }
 }
 
-fn generate_byte_vec_from_hex(value: &ast::Value) -> Result<Vec<u8>, GeneratorError> {
-    let bytes = match value {
-        ast::Value::String(s) => {
-            hex::decode(s).map_err(|_| GeneratorError::InvalidBytesHex(s.to_owned()))?
-        }
-        v => invalid_type!(v, ast::ValueKind::String)?,
+fn generate_byte_vec_from_hex(value: &ast::ValueWithSpan) -> Result<Vec<u8>, GeneratorError> {
+    let bytes = match &value.value {
+        ast::Value::String(s) => hex::decode(s).map_err(|_| GeneratorError {
+            error_kind: GeneratorErrorKind::InvalidBytesHex(s.to_owned()),
+            span: value.span,
+        })?,
+        _ => invalid_type!(value, ast::ValueKind::String)?,
     };
     Ok(bytes)
 }
 
 pub fn generate_value<B>(
-    value: &ast::Value,
+    value: &ast::ValueWithSpan,
     expected_type: Option<ManifestValueKind>,
     resolver: &mut NameResolver,
     address_bech32_decoder: &AddressBech32Decoder,
@@ -1253,14 +1422,17 @@ where
 {
     if let Some(ty) = expected_type {
         if ty != value.value_kind() {
-            return Err(GeneratorError::UnexpectedValue {
-                expected_type: ty,
-                actual: value.clone(),
+            return Err(GeneratorError {
+                error_kind: GeneratorErrorKind::UnexpectedValue {
+                    expected_type: ty,
+                    actual: value.value.clone(),
+                },
+                span: value.span,
             });
         }
     }
 
-    match value {
+    match &value.value {
         // ==============
         // Basic types
         // ==============
@@ -1359,13 +1531,16 @@ where
             })
         }
         ast::Value::NonFungibleGlobalId(value) => {
-            let global_id = match value.as_ref() {
+            let global_id = match &value.value {
                 ast::Value::String(s) => NonFungibleGlobalId::try_from_canonical_string(
                     address_bech32_decoder,
                     s.as_str(),
                 )
-                .map_err(|_| GeneratorError::InvalidNonFungibleGlobalId),
-                v => invalid_type!(v, ast::ValueKind::String)?,
+                .map_err(|_| GeneratorError {
+                    error_kind: GeneratorErrorKind::InvalidNonFungibleGlobalId,
+                    span: value.span,
+                }),
+                _ => invalid_type!(value, ast::ValueKind::String)?,
             }?;
             Ok(Value::Tuple {
                 fields: vec![
@@ -1427,7 +1602,7 @@ where
 }
 
 fn generate_singletons<B>(
-    elements: &Vec<ast::Value>,
+    elements: &Vec<ast::ValueWithSpan>,
     expected_value_kind: Option<ManifestValueKind>,
     resolver: &mut NameResolver,
     address_bech32_decoder: &AddressBech32Decoder,
@@ -1450,7 +1625,7 @@ where
 }
 
 fn generate_kv_entries<B>(
-    entries: &[(ast::Value, ast::Value)],
+    entries: &[(ast::ValueWithSpan, ast::ValueWithSpan)],
     key_value_kind: ManifestValueKind,
     value_value_kind: ManifestValueKind,
     resolver: &mut NameResolver,
@@ -1485,8 +1660,9 @@ where
 mod tests {
     use super::*;
     use crate::manifest::lexer::tokenize;
-    use crate::manifest::parser::{Parser, ParserError, PARSER_MAX_DEPTH};
+    use crate::manifest::parser::{Parser, ParserError, ParserErrorKind, PARSER_MAX_DEPTH};
     use crate::signing::secp256k1::Secp256k1PrivateKey;
+    use crate::span;
     use radix_engine_common::constants::CONSENSUS_MANAGER;
     use radix_engine_common::manifest_args;
     use radix_engine_common::types::{ComponentAddress, PackageAddress};
@@ -1641,18 +1817,38 @@ mod tests {
     fn test_failures() {
         generate_value_error!(
             r#"Address(100u32)"#,
-            GeneratorError::InvalidAstValue {
-                expected_type: vec![ast::ValueKind::String],
-                actual: ast::Value::U32(100),
+            GeneratorError {
+                error_kind: GeneratorErrorKind::InvalidAstValue {
+                    expected_type: vec![ast::ValueKind::String],
+                    actual: ast::Value::U32(100),
+                },
+                span: span!(start = (8, 1, 8), end = (14, 1, 14)),
             }
         );
         generate_value_error!(
             r#"Address("invalid_package_address")"#,
-            GeneratorError::InvalidGlobalAddress("invalid_package_address".into())
+            GeneratorError {
+                error_kind: GeneratorErrorKind::InvalidGlobalAddress(
+                    "invalid_package_address".into()
+                ),
+                span: span!(start = (8, 1, 8), end = (33, 1, 33)),
+            }
         );
         generate_value_error!(
             r#"Decimal("invalid_decimal")"#,
-            GeneratorError::InvalidDecimal("invalid_decimal".into())
+            GeneratorError {
+                error_kind: GeneratorErrorKind::InvalidDecimal("invalid_decimal".into()),
+                span: span!(start = (8, 1, 8), end = (25, 1, 25)),
+            }
+        );
+        generate_value_error!(
+            r#"Tuple(Decimal("1"), Bucket("missing"))"#,
+            GeneratorError {
+                error_kind: GeneratorErrorKind::NameResolverError(
+                    NameResolverError::UndefinedBucket("missing".into())
+                ),
+                span: span!(start = (27, 1, 27), end = (36, 1, 36)),
+            }
         );
     }
 
@@ -2165,7 +2361,12 @@ mod tests {
             &NetworkDefinition::simulator(),
             BlobProvider::default(),
         );
-        let expected = CompileError::ParserError(ParserError::MaxDepthExceeded(PARSER_MAX_DEPTH));
+        // (pointing at the first value which is too deep)
+        let start = manifest.find("Tuple(").unwrap() + "Tuple(".len() * PARSER_MAX_DEPTH;
+        let expected = CompileError::ParserError(ParserError {
+            error_kind: ParserErrorKind::MaxDepthExceeded(PARSER_MAX_DEPTH),
+            span: span!(start = (start, 1, start), end = (start + 5, 1, start + 5)),
+        });
 
         match result {
             Ok(_) => {
@@ -0,0 +1,879 @@
+//! A JSON representation of [`TransactionManifestV1`], for building manifests outside of Rust.
+//!
+//! A manifest is represented as an object of the form:
+//! ```json
+//! {
+//!     "instructions": [
+//!         {
+//!             "instruction": "CALL_METHOD",
+//!             "address": { "kind": "Address", "value": "component_sim1..." },
+//!             "method_name": { "kind": "String", "value": "withdraw" },
+//!             "args": {
+//!                 "kind": "Tuple",
+//!                 "fields": [
+//!                     { "kind": "Address", "value": "resource_sim1..." },
+//!                     { "kind": "Decimal", "value": "10" }
+//!                 ]
+//!             }
+//!         }
+//!     ],
+//!     "blobs": [
+//!         { "hash": "<hex>", "content": "<hex>" }
+//!     ]
+//! }
+//! ```
+//!
+//! Each instruction is named as in manifest sources, and has a field for each field of its
+//! [`InstructionV1`] variant, holding the value in the programmatic SBOR JSON (see
+//! [`SerializationMode::Programmatic`]). Static addresses are Bech32m-encoded for the network (or
+//! hex-encoded if they have no Bech32m encoding). Named addresses, buckets, proofs and address
+//! reservations are referred to by their ids, which the instructions creating them allocate in
+//! order, starting from 0.
+//!
+//! The representation is lossless: [`from_json`] of the output of [`to_json`] gives a manifest with
+//! the same SBOR encoding.
+
+use crate::internal_prelude::*;
+use radix_engine_common::data::manifest::converter::*;
+use radix_engine_interface::address::{AddressBech32Decoder, AddressBech32Encoder};
+use sbor::representations::{SerializationMode, SerializationParameters};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use utils::ContextualSerialize;
+
+/// The instructions of the JSON representation, with their discriminators, names and fields in
+/// the order of their [`InstructionV1`] variants.
+const INSTRUCTIONS: &[(u8, &str, &[&str])] = &[
+    (
+        INSTRUCTION_TAKE_ALL_FROM_WORKTOP_DISCRIMINATOR,
+        "TAKE_ALL_FROM_WORKTOP",
+        &["resource_address"],
+    ),
+    (
+        INSTRUCTION_TAKE_FROM_WORKTOP_DISCRIMINATOR,
+        "TAKE_FROM_WORKTOP",
+        &["resource_address", "amount"],
+    ),
+    (
+        INSTRUCTION_TAKE_NON_FUNGIBLES_FROM_WORKTOP_DISCRIMINATOR,
+        "TAKE_NON_FUNGIBLES_FROM_WORKTOP",
+        &["resource_address", "ids"],
+    ),
+    (
+        INSTRUCTION_RETURN_TO_WORKTOP_DISCRIMINATOR,
+        "RETURN_TO_WORKTOP",
+        &["bucket_id"],
+    ),
+    (
+        INSTRUCTION_ASSERT_WORKTOP_CONTAINS_ANY_DISCRIMINATOR,
+        "ASSERT_WORKTOP_CONTAINS_ANY",
+        &["resource_address"],
+    ),
+    (
+        INSTRUCTION_ASSERT_WORKTOP_CONTAINS_DISCRIMINATOR,
+        "ASSERT_WORKTOP_CONTAINS",
+        &["resource_address", "amount"],
+    ),
+    (
+        INSTRUCTION_ASSERT_WORKTOP_CONTAINS_NON_FUNGIBLES_DISCRIMINATOR,
+        "ASSERT_WORKTOP_CONTAINS_NON_FUNGIBLES",
+        &["resource_address", "ids"],
+    ),
+    (
+        INSTRUCTION_POP_FROM_AUTH_ZONE_DISCRIMINATOR,
+        "POP_FROM_AUTH_ZONE",
+        &[],
+    ),
+    (
+        INSTRUCTION_PUSH_TO_AUTH_ZONE_DISCRIMINATOR,
+        "PUSH_TO_AUTH_ZONE",
+        &["proof_id"],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_AUTH_ZONE_OF_AMOUNT_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_AUTH_ZONE_OF_AMOUNT",
+        &["resource_address", "amount"],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_AUTH_ZONE_OF_NON_FUNGIBLES_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_AUTH_ZONE_OF_NON_FUNGIBLES",
+        &["resource_address", "ids"],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_AUTH_ZONE_OF_ALL_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_AUTH_ZONE_OF_ALL",
+        &["resource_address"],
+    ),
+    (
+        INSTRUCTION_DROP_AUTH_ZONE_PROOFS_DISCRIMINATOR,
+        "DROP_AUTH_ZONE_PROOFS",
+        &[],
+    ),
+    (
+        INSTRUCTION_DROP_AUTH_ZONE_REGULAR_PROOFS_DISCRIMINATOR,
+        "DROP_AUTH_ZONE_REGULAR_PROOFS",
+        &[],
+    ),
+    (
+        INSTRUCTION_DROP_AUTH_ZONE_SIGNATURE_PROOFS_DISCRIMINATOR,
+        "DROP_AUTH_ZONE_SIGNATURE_PROOFS",
+        &[],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_BUCKET_OF_AMOUNT_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_BUCKET_OF_AMOUNT",
+        &["bucket_id", "amount"],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_BUCKET_OF_NON_FUNGIBLES_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_BUCKET_OF_NON_FUNGIBLES",
+        &["bucket_id", "ids"],
+    ),
+    (
+        INSTRUCTION_CREATE_PROOF_FROM_BUCKET_OF_ALL_DISCRIMINATOR,
+        "CREATE_PROOF_FROM_BUCKET_OF_ALL",
+        &["bucket_id"],
+    ),
+    (
+        INSTRUCTION_BURN_RESOURCE_DISCRIMINATOR,
+        "BURN_RESOURCE",
+        &["bucket_id"],
+    ),
+    (
+        INSTRUCTION_CLONE_PROOF_DISCRIMINATOR,
+        "CLONE_PROOF",
+        &["proof_id"],
+    ),
+    (
+        INSTRUCTION_DROP_PROOF_DISCRIMINATOR,
+        "DROP_PROOF",
+        &["proof_id"],
+    ),
+    (
+        INSTRUCTION_CALL_FUNCTION_DISCRIMINATOR,
+        "CALL_FUNCTION",
+        &["package_address", "blueprint_name", "function_name", "args"],
+    ),
+    (
+        INSTRUCTION_CALL_METHOD_DISCRIMINATOR,
+        "CALL_METHOD",
+        &["address", "method_name", "args"],
+    ),
+    (
+        INSTRUCTION_CALL_ROYALTY_METHOD_DISCRIMINATOR,
+        "CALL_ROYALTY_METHOD",
+        &["address", "method_name", "args"],
+    ),
+    (
+        INSTRUCTION_CALL_METADATA_METHOD_DISCRIMINATOR,
+        "CALL_METADATA_METHOD",
+        &["address", "method_name", "args"],
+    ),
+    (
+        INSTRUCTION_CALL_ROLE_ASSIGNMENT_METHOD_DISCRIMINATOR,
+        "CALL_ROLE_ASSIGNMENT_METHOD",
+        &["address", "method_name", "args"],
+    ),
+    (
+        INSTRUCTION_CALL_DIRECT_VAULT_METHOD_DISCRIMINATOR,
+        "CALL_DIRECT_VAULT_METHOD",
+        &["address", "method_name", "args"],
+    ),
+    (
+        INSTRUCTION_DROP_NAMED_PROOFS_DISCRIMINATOR,
+        "DROP_NAMED_PROOFS",
+        &[],
+    ),
+    (
+        INSTRUCTION_DROP_ALL_PROOFS_DISCRIMINATOR,
+        "DROP_ALL_PROOFS",
+        &[],
+    ),
+    (
+        INSTRUCTION_ALLOCATE_GLOBAL_ADDRESS_DISCRIMINATOR,
+        "ALLOCATE_GLOBAL_ADDRESS",
+        &["package_address", "blueprint_name"],
+    ),
+    (
+        INSTRUCTION_YIELD_TO_CHILD_DISCRIMINATOR,
+        "YIELD_TO_CHILD",
+        &["child_index", "args"],
+    ),
+    (
+        INSTRUCTION_YIELD_TO_PARENT_DISCRIMINATOR,
+        "YIELD_TO_PARENT",
+        &["args"],
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestJsonError {
+    EncodeError(EncodeError),
+    DecodeError(DecodeError),
+    SerializationError(String),
+    InvalidJson(String),
+    MissingField {
+        path: String,
+        field: String,
+    },
+    UnknownField {
+        path: String,
+        field: String,
+    },
+    InvalidValue {
+        path: String,
+        expected: String,
+    },
+    UnknownInstruction {
+        path: String,
+        instruction: String,
+    },
+    UnknownValueKind {
+        path: String,
+        kind: String,
+    },
+    MismatchedValueKind {
+        path: String,
+        expected: ManifestValueKind,
+        actual: ManifestValueKind,
+    },
+    InvalidInstruction {
+        path: String,
+        error: ManifestToRustValueError,
+    },
+}
+
+/// Converts the given manifest into its JSON representation (see the [module docs](self)).
+pub fn to_json(
+    manifest: &TransactionManifestV1,
+    network: &NetworkDefinition,
+) -> Result<String, ManifestJsonError> {
+    let address_bech32_encoder = AddressBech32Encoder::new(network);
+
+    let mut instructions = Vec::new();
+    for instruction in &manifest.instructions {
+        let (discriminator, fields) = match to_manifest_value(instruction) {
+            Ok(ManifestValue::Enum {
+                discriminator,
+                fields,
+            }) => (discriminator, fields),
+            Ok(_) => panic!("Instructions are encoded as enum variants"),
+            Err(RustToManifestValueError::EncodeError(error)) => {
+                return Err(ManifestJsonError::EncodeError(error))
+            }
+            Err(RustToManifestValueError::DecodeError(error)) => {
+                return Err(ManifestJsonError::DecodeError(error))
+            }
+        };
+        let (_, name, field_names) = INSTRUCTIONS
+            .iter()
+            .find(|(d, _, _)| *d == discriminator)
+            .expect("Every instruction has a JSON representation");
+
+        let mut object = JsonMap::new();
+        object.insert("instruction".to_string(), json!(name));
+        for (field_name, field) in field_names.iter().zip(fields) {
+            object.insert(
+                field_name.to_string(),
+                value_to_json(&field, &address_bech32_encoder)?,
+            );
+        }
+        instructions.push(JsonValue::Object(object));
+    }
+
+    let blobs: Vec<JsonValue> = manifest
+        .blobs
+        .iter()
+        .map(|(hash, content)| {
+            json!({
+                "hash": hash.to_string(),
+                "content": hex::encode(content),
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({
+        "instructions": instructions,
+        "blobs": blobs,
+    }))
+    .map_err(|error| ManifestJsonError::SerializationError(error.to_string()))
+}
+
+/// Converts the JSON representation of a manifest (see the [module docs](self)) into the manifest.
+pub fn from_json(
+    s: &str,
+    network: &NetworkDefinition,
+) -> Result<TransactionManifestV1, ManifestJsonError> {
+    let address_bech32_decoder = AddressBech32Decoder::new(network);
+    let json: JsonValue = serde_json::from_str(s)
+        .map_err(|error| ManifestJsonError::InvalidJson(error.to_string()))?;
+    let object = as_object(&json, "$")?;
+    check_fields(object, &["instructions", "blobs"], "$")?;
+
+    let mut instructions = Vec::new();
+    for (i, instruction) in as_array(get_field(object, "instructions", "$")?, "$.instructions")?
+        .iter()
+        .enumerate()
+    {
+        let path = format!("$.instructions[{}]", i);
+        let object = as_object(instruction, &path)?;
+        let name = as_str(
+            get_field(object, "instruction", &path)?,
+            &format!("{}.instruction", path),
+        )?;
+        let (discriminator, _, field_names) = INSTRUCTIONS
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .ok_or_else(|| ManifestJsonError::UnknownInstruction {
+                path: path.clone(),
+                instruction: name.to_string(),
+            })?;
+        check_fields(object, &[&["instruction"], *field_names].concat(), &path)?;
+
+        let mut fields = Vec::new();
+        for field_name in field_names.iter() {
+            fields.push(value_from_json(
+                get_field(object, field_name, &path)?,
+                &format!("{}.{}", path, field_name),
+                &address_bech32_decoder,
+            )?);
+        }
+        let instruction = from_manifest_value(&ManifestValue::Enum {
+            discriminator: *discriminator,
+            fields,
+        })
+        .map_err(|error| ManifestJsonError::InvalidInstruction { path, error })?;
+        instructions.push(instruction);
+    }
+
+    let mut blobs = BTreeMap::new();
+    for (i, blob) in as_array(get_field(object, "blobs", "$")?, "$.blobs")?
+        .iter()
+        .enumerate()
+    {
+        let path = format!("$.blobs[{}]", i);
+        let object = as_object(blob, &path)?;
+        check_fields(object, &["hash", "content"], &path)?;
+        let hash_path = format!("{}.hash", path);
+        let hash = Hash::from_str(as_str(get_field(object, "hash", &path)?, &hash_path)?)
+            .map_err(|_| invalid_value(&hash_path, "a hex-encoded hash"))?;
+        let content_path = format!("{}.content", path);
+        let content = hex::decode(as_str(get_field(object, "content", &path)?, &content_path)?)
+            .map_err(|_| invalid_value(&content_path, "hex-encoded bytes"))?;
+        blobs.insert(hash, content);
+    }
+
+    Ok(TransactionManifestV1 {
+        instructions,
+        blobs,
+    })
+}
+
+fn value_to_json(
+    value: &ManifestValue,
+    address_bech32_encoder: &AddressBech32Encoder,
+) -> Result<JsonValue, ManifestJsonError> {
+    let payload = manifest_encode(value).map_err(ManifestJsonError::EncodeError)?;
+    serde_json::to_value(
+        ManifestRawPayload::new_from_valid_slice(&payload).serializable(
+            SerializationParameters::Schemaless {
+                mode: SerializationMode::Programmatic,
+                custom_context: ManifestValueDisplayContext::with_optional_bech32(Some(
+                    address_bech32_encoder,
+                )),
+                depth_limit: MANIFEST_SBOR_V1_MAX_DEPTH,
+            },
+        ),
+    )
+    .map_err(|error| ManifestJsonError::SerializationError(error.to_string()))
+}
+
+/// Parses a value in the programmatic SBOR JSON. Annotations from a schema (e.g. `type_name` or
+/// `field_name`) are allowed, but ignored.
+fn value_from_json(
+    json: &JsonValue,
+    path: &str,
+    address_bech32_decoder: &AddressBech32Decoder,
+) -> Result<ManifestValue, ManifestJsonError> {
+    let object = as_object(json, path)?;
+    let kind = as_str(get_field(object, "kind", path)?, &format!("{}.kind", path))?;
+    let value = match kind {
+        "Bool" => {
+            let value_path = format!("{}.value", path);
+            ManifestValue::Bool {
+                value: get_field(object, "value", path)?
+                    .as_bool()
+                    .ok_or_else(|| invalid_value(&value_path, "a boolean"))?,
+            }
+        }
+        "I8" => ManifestValue::I8 {
+            value: number_field(object, "value", path)?,
+        },
+        "I16" => ManifestValue::I16 {
+            value: number_field(object, "value", path)?,
+        },
+        "I32" => ManifestValue::I32 {
+            value: number_field(object, "value", path)?,
+        },
+        "I64" => ManifestValue::I64 {
+            value: number_field(object, "value", path)?,
+        },
+        "I128" => ManifestValue::I128 {
+            value: number_field(object, "value", path)?,
+        },
+        "U8" => ManifestValue::U8 {
+            value: number_field(object, "value", path)?,
+        },
+        "U16" => ManifestValue::U16 {
+            value: number_field(object, "value", path)?,
+        },
+        "U32" => ManifestValue::U32 {
+            value: number_field(object, "value", path)?,
+        },
+        "U64" => ManifestValue::U64 {
+            value: number_field(object, "value", path)?,
+        },
+        "U128" => ManifestValue::U128 {
+            value: number_field(object, "value", path)?,
+        },
+        "String" => ManifestValue::String {
+            value: as_str(
+                get_field(object, "value", path)?,
+                &format!("{}.value", path),
+            )?
+            .to_string(),
+        },
+        "Enum" => ManifestValue::Enum {
+            discriminator: number_field(object, "variant_id", path)?,
+            fields: values_from_json(object, "fields", path, address_bech32_decoder)?,
+        },
+        "Tuple" => ManifestValue::Tuple {
+            fields: values_from_json(object, "fields", path, address_bech32_decoder)?,
+        },
+        "Bytes" => {
+            let hex_path = format!("{}.hex", path);
+            let bytes = hex::decode(as_str(get_field(object, "hex", path)?, &hex_path)?)
+                .map_err(|_| invalid_value(&hex_path, "hex-encoded bytes"))?;
+            ManifestValue::Array {
+                element_value_kind: ManifestValueKind::U8,
+                elements: bytes
+                    .into_iter()
+                    .map(|value| ManifestValue::U8 { value })
+                    .collect(),
+            }
+        }
+        "Array" => {
+            let element_value_kind = value_kind_field(object, "element_kind", path)?;
+            let elements = values_from_json(object, "elements", path, address_bech32_decoder)?;
+            for (i, element) in elements.iter().enumerate() {
+                check_value_kind(
+                    element,
+                    element_value_kind,
+                    &format!("{}.elements[{}]", path, i),
+                )?;
+            }
+            ManifestValue::Array {
+                element_value_kind,
+                elements,
+            }
+        }
+        "Map" => {
+            let key_value_kind = value_kind_field(object, "key_kind", path)?;
+            let value_value_kind = value_kind_field(object, "value_kind", path)?;
+            let mut entries = Vec::new();
+            for (i, entry) in as_array(
+                get_field(object, "entries", path)?,
+                &format!("{}.entries", path),
+            )?
+            .iter()
+            .enumerate()
+            {
+                let entry_path = format!("{}.entries[{}]", path, i);
+                let entry = as_object(entry, &entry_path)?;
+                let key_path = format!("{}.key", entry_path);
+                let key = value_from_json(
+                    get_field(entry, "key", &entry_path)?,
+                    &key_path,
+                    address_bech32_decoder,
+                )?;
+                check_value_kind(&key, key_value_kind, &key_path)?;
+                let value_path = format!("{}.value", entry_path);
+                let value = value_from_json(
+                    get_field(entry, "value", &entry_path)?,
+                    &value_path,
+                    address_bech32_decoder,
+                )?;
+                check_value_kind(&value, value_value_kind, &value_path)?;
+                entries.push((key, value));
+            }
+            ManifestValue::Map {
+                key_value_kind,
+                value_value_kind,
+                entries,
+            }
+        }
+        _ => {
+            let value_path = format!("{}.value", path);
+            let text = as_str(get_field(object, "value", path)?, &value_path)?;
+            ManifestValue::Custom {
+                value: custom_value_from_json(kind, text, path, address_bech32_decoder)?,
+            }
+        }
+    };
+    Ok(value)
+}
+
+fn custom_value_from_json(
+    kind: &str,
+    text: &str,
+    path: &str,
+    address_bech32_decoder: &AddressBech32Decoder,
+) -> Result<ManifestCustomValue, ManifestJsonError> {
+    let value_path = format!("{}.value", path);
+    let value = match kind {
+        "Address" => {
+            let address = match address_bech32_decoder.validate_and_decode(text) {
+                Ok((_, bytes)) => bytes
+                    .try_into()
+                    .map(|bytes| ManifestAddress::Static(NodeId(bytes)))
+                    .map_err(|_| invalid_value(&value_path, "an address"))?,
+                Err(_) => match text.parse::<u32>() {
+                    Ok(id) => ManifestAddress::Named(id),
+                    Err(_) => hex::decode(text)
+                        .ok()
+                        .and_then(|bytes| bytes.try_into().ok())
+                        .map(|bytes| ManifestAddress::Static(NodeId(bytes)))
+                        .ok_or_else(|| {
+                            invalid_value(
+                                &value_path,
+                                "a Bech32m-encoded or hex-encoded address, or a named address id",
+                            )
+                        })?,
+                },
+            };
+            ManifestCustomValue::Address(address)
+        }
+        "Bucket" => ManifestCustomValue::Bucket(ManifestBucket(parse_id(text, &value_path)?)),
+        "Proof" => ManifestCustomValue::Proof(ManifestProof(parse_id(text, &value_path)?)),
+        "AddressReservation" => ManifestCustomValue::AddressReservation(
+            ManifestAddressReservation(parse_id(text, &value_path)?),
+        ),
+        "Expression" => ManifestCustomValue::Expression(match text {
+            "ENTIRE_WORKTOP" => ManifestExpression::EntireWorktop,
+            "ENTIRE_AUTH_ZONE" => ManifestExpression::EntireAuthZone,
+            _ => {
+                return Err(invalid_value(
+                    &value_path,
+                    "ENTIRE_WORKTOP or ENTIRE_AUTH_ZONE",
+                ))
+            }
+        }),
+        "Blob" => ManifestCustomValue::Blob(ManifestBlobRef(
+            hex::decode(text)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| invalid_value(&value_path, "a hex-encoded blob hash"))?,
+        )),
+        "Decimal" => ManifestCustomValue::Decimal(from_decimal(
+            &Decimal::from_str(text).map_err(|_| invalid_value(&value_path, "a decimal"))?,
+        )),
+        "PreciseDecimal" => ManifestCustomValue::PreciseDecimal(from_precise_decimal(
+            &PreciseDecimal::from_str(text)
+                .map_err(|_| invalid_value(&value_path, "a precise decimal"))?,
+        )),
+        "NonFungibleLocalId" => {
+            ManifestCustomValue::NonFungibleLocalId(from_non_fungible_local_id(
+                NonFungibleLocalId::from_str(text)
+                    .map_err(|_| invalid_value(&value_path, "a non-fungible local id"))?,
+            ))
+        }
+        _ => {
+            return Err(ManifestJsonError::UnknownValueKind {
+                path: path.to_string(),
+                kind: kind.to_string(),
+            })
+        }
+    };
+    Ok(value)
+}
+
+fn values_from_json(
+    object: &JsonMap<String, JsonValue>,
+    field: &str,
+    path: &str,
+    address_bech32_decoder: &AddressBech32Decoder,
+) -> Result<Vec<ManifestValue>, ManifestJsonError> {
+    let values_path = format!("{}.{}", path, field);
+    as_array(get_field(object, field, path)?, &values_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            value_from_json(
+                value,
+                &format!("{}[{}]", values_path, i),
+                address_bech32_decoder,
+            )
+        })
+        .collect()
+}
+
+fn value_kind_field(
+    object: &JsonMap<String, JsonValue>,
+    field: &str,
+    path: &str,
+) -> Result<ManifestValueKind, ManifestJsonError> {
+    let kind_path = format!("{}.{}", path, field);
+    let kind = as_str(get_field(object, field, path)?, &kind_path)?;
+    let value_kind = match kind {
+        "Bool" => ManifestValueKind::Bool,
+        "I8" => ManifestValueKind::I8,
+        "I16" => ManifestValueKind::I16,
+        "I32" => ManifestValueKind::I32,
+        "I64" => ManifestValueKind::I64,
+        "I128" => ManifestValueKind::I128,
+        "U8" => ManifestValueKind::U8,
+        "U16" => ManifestValueKind::U16,
+        "U32" => ManifestValueKind::U32,
+        "U64" => ManifestValueKind::U64,
+        "U128" => ManifestValueKind::U128,
+        "String" => ManifestValueKind::String,
+        "Enum" => ManifestValueKind::Enum,
+        "Array" => ManifestValueKind::Array,
+        "Tuple" => ManifestValueKind::Tuple,
+        "Map" => ManifestValueKind::Map,
+        "Address" => ManifestValueKind::Custom(ManifestCustomValueKind::Address),
+        "Bucket" => ManifestValueKind::Custom(ManifestCustomValueKind::Bucket),
+        "Proof" => ManifestValueKind::Custom(ManifestCustomValueKind::Proof),
+        "Expression" => ManifestValueKind::Custom(ManifestCustomValueKind::Expression),
+        "Blob" => ManifestValueKind::Custom(ManifestCustomValueKind::Blob),
+        "Decimal" => ManifestValueKind::Custom(ManifestCustomValueKind::Decimal),
+        "PreciseDecimal" => ManifestValueKind::Custom(ManifestCustomValueKind::PreciseDecimal),
+        "NonFungibleLocalId" => {
+            ManifestValueKind::Custom(ManifestCustomValueKind::NonFungibleLocalId)
+        }
+        "AddressReservation" => {
+            ManifestValueKind::Custom(ManifestCustomValueKind::AddressReservation)
+        }
+        _ => {
+            return Err(ManifestJsonError::UnknownValueKind {
+                path: kind_path,
+                kind: kind.to_string(),
+            })
+        }
+    };
+    Ok(value_kind)
+}
+
+fn check_value_kind(
+    value: &ManifestValue,
+    expected: ManifestValueKind,
+    path: &str,
+) -> Result<(), ManifestJsonError> {
+    let actual = match value {
+        ManifestValue::Bool { .. } => ManifestValueKind::Bool,
+        ManifestValue::I8 { .. } => ManifestValueKind::I8,
+        ManifestValue::I16 { .. } => ManifestValueKind::I16,
+        ManifestValue::I32 { .. } => ManifestValueKind::I32,
+        ManifestValue::I64 { .. } => ManifestValueKind::I64,
+        ManifestValue::I128 { .. } => ManifestValueKind::I128,
+        ManifestValue::U8 { .. } => ManifestValueKind::U8,
+        ManifestValue::U16 { .. } => ManifestValueKind::U16,
+        ManifestValue::U32 { .. } => ManifestValueKind::U32,
+        ManifestValue::U64 { .. } => ManifestValueKind::U64,
+        ManifestValue::U128 { .. } => ManifestValueKind::U128,
+        ManifestValue::String { .. } => ManifestValueKind::String,
+        ManifestValue::Enum { .. } => ManifestValueKind::Enum,
+        ManifestValue::Array { .. } => ManifestValueKind::Array,
+        ManifestValue::Tuple { .. } => ManifestValueKind::Tuple,
+        ManifestValue::Map { .. } => ManifestValueKind::Map,
+        ManifestValue::Custom { value } => ManifestValueKind::Custom(value.get_custom_value_kind()),
+    };
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(ManifestJsonError::MismatchedValueKind {
+            path: path.to_string(),
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Parses a number, which the programmatic SBOR JSON encodes as a string, though a JSON number is
+/// accepted too.
+fn number_field<T: FromStr>(
+    object: &JsonMap<String, JsonValue>,
+    field: &str,
+    path: &str,
+) -> Result<T, ManifestJsonError> {
+    let number_path = format!("{}.{}", path, field);
+    let parsed = match get_field(object, field, path)? {
+        JsonValue::String(text) => text.parse().ok(),
+        JsonValue::Number(number) => number.to_string().parse().ok(),
+        _ => None,
+    };
+    parsed.ok_or_else(|| invalid_value(&number_path, "an integer in range"))
+}
+
+fn parse_id(text: &str, path: &str) -> Result<u32, ManifestJsonError> {
+    text.parse()
+        .map_err(|_| invalid_value(path, "an id, as a string"))
+}
+
+fn get_field<'a>(
+    object: &'a JsonMap<String, JsonValue>,
+    field: &str,
+    path: &str,
+) -> Result<&'a JsonValue, ManifestJsonError> {
+    object
+        .get(field)
+        .ok_or_else(|| ManifestJsonError::MissingField {
+            path: path.to_string(),
+            field: field.to_string(),
+        })
+}
+
+fn check_fields(
+    object: &JsonMap<String, JsonValue>,
+    fields: &[&str],
+    path: &str,
+) -> Result<(), ManifestJsonError> {
+    match object.keys().find(|key| !fields.contains(&key.as_str())) {
+        Some(field) => Err(ManifestJsonError::UnknownField {
+            path: path.to_string(),
+            field: field.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn as_object<'a>(
+    json: &'a JsonValue,
+    path: &str,
+) -> Result<&'a JsonMap<String, JsonValue>, ManifestJsonError> {
+    json.as_object()
+        .ok_or_else(|| invalid_value(path, "an object"))
+}
+
+fn as_array<'a>(json: &'a JsonValue, path: &str) -> Result<&'a Vec<JsonValue>, ManifestJsonError> {
+    json.as_array()
+        .ok_or_else(|| invalid_value(path, "an array"))
+}
+
+fn as_str<'a>(json: &'a JsonValue, path: &str) -> Result<&'a str, ManifestJsonError> {
+    json.as_str().ok_or_else(|| invalid_value(path, "a string"))
+}
+
+fn invalid_value(path: &str, expected: &str) -> ManifestJsonError {
+    ManifestJsonError::InvalidValue {
+        path: path.to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_engine_interface::blueprints::package::PACKAGE_BLUEPRINT;
+
+    #[test]
+    fn test_manifest_json_round_trip() {
+        let account = ComponentAddress::virtual_account_from_public_key(
+            &Secp256k1PrivateKey::from_u64(1).unwrap().public_key(),
+        );
+        let manifest = ManifestBuilder::new()
+            .lock_fee(account, 10)
+            .withdraw_from_account(account, XRD, 5)
+            .take_all_from_worktop(XRD, "bucket")
+            .create_proof_from_bucket_of_all("bucket", "proof")
+            .drop_proof("proof")
+            .call_method_with_name_lookup(account, "deposit", |lookup| (lookup.bucket("bucket"),))
+            .allocate_global_address(
+                PACKAGE_PACKAGE,
+                PACKAGE_BLUEPRINT,
+                "reservation",
+                "package_address",
+            )
+            .with_name_lookup(|mut builder, lookup| {
+                let blob = builder.add_blob(vec![1u8]);
+                builder.call_method(
+                    account,
+                    "some_method",
+                    manifest_args!(
+                        lookup.named_address("package_address"),
+                        lookup.address_reservation("reservation"),
+                        Some(-1i64),
+                        BTreeMap::from([(1u8, vec![1u8, 2u8])]),
+                        ManifestExpression::EntireWorktop,
+                        blob,
+                        PreciseDecimal::ONE,
+                        vec![NonFungibleLocalId::integer(1)],
+                    ),
+                )
+            })
+            .build();
+
+        let json = to_json(&manifest, &NetworkDefinition::simulator()).unwrap();
+        let parsed = from_json(&json, &NetworkDefinition::simulator()).unwrap();
+
+        assert_eq!(parsed, manifest);
+        assert_eq!(
+            manifest_encode(&parsed).unwrap(),
+            manifest_encode(&manifest).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_json_reports_path_of_invalid_value() {
+        let json = r#"{
+            "instructions": [
+                { "instruction": "DROP_ALL_PROOFS" },
+                {
+                    "instruction": "RETURN_TO_WORKTOP",
+                    "bucket_id": { "kind": "Bucket", "value": "first" }
+                }
+            ],
+            "blobs": []
+        }"#;
+
+        assert_eq!(
+            from_json(json, &NetworkDefinition::simulator()),
+            Err(ManifestJsonError::InvalidValue {
+                path: "$.instructions[1].bucket_id.value".to_string(),
+                expected: "an id, as a string".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_json_rejects_mismatched_array_elements() {
+        let json = r#"{
+            "instructions": [
+                {
+                    "instruction": "CALL_METHOD",
+                    "address": { "kind": "Address", "value": "0" },
+                    "method_name": { "kind": "String", "value": "foo" },
+                    "args": {
+                        "kind": "Tuple",
+                        "fields": [
+                            {
+                                "kind": "Array",
+                                "element_kind": "U8",
+                                "elements": [{ "kind": "U16", "value": "1" }]
+                            }
+                        ]
+                    }
+                }
+            ],
+            "blobs": []
+        }"#;
+
+        assert_eq!(
+            from_json(json, &NetworkDefinition::simulator()),
+            Err(ManifestJsonError::MismatchedValueKind {
+                path: "$.instructions[0].args.fields[0].elements[0]".to_string(),
+                expected: ManifestValueKind::U8,
+                actual: ManifestValueKind::U16,
+            })
+        );
+    }
+}
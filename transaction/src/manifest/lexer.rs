@@ -20,6 +20,25 @@ pub struct Position {
     pub line_char_index: usize,
 }
 
+#[cfg(test)]
+#[macro_export]
+macro_rules! span {
+    (start = ($st:expr, $sl:expr, $sc:expr), end = ($et:expr, $el:expr, $ec:expr)) => {
+        $crate::manifest::lexer::Span {
+            start: $crate::manifest::lexer::Position {
+                full_index: $st,
+                line_number: $sl,
+                line_char_index: $sc,
+            },
+            end: $crate::manifest::lexer::Position {
+                full_index: $et,
+                line_number: $el,
+                line_char_index: $ec,
+            },
+        }
+    };
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenKind {
     // ==============
@@ -39,6 +58,8 @@ pub enum TokenKind {
     StringLiteral(String),
 
     Ident(String),
+    /// A template parameter, e.g. `$amount` (see [`super::template`]).
+    Parameter(String),
 
     /* Punctuations */
     OpenParenthesis,
@@ -65,12 +86,22 @@ pub enum LexerError {
     UnknownIdentifier(String, Position),
 }
 
+/// A `#` comment, which runs up to the end of its line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// The text of the comment, including the leading `#`, excluding the line break.
+    pub text: String,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct Lexer {
     /// The input text chars
     text: Vec<char>,
     /// The current position in the text
     current: Position,
+    /// The comments skipped so far
+    comments: Vec<Comment>,
 }
 
 pub fn tokenize(s: &str) -> Result<Vec<Token>, LexerError> {
@@ -86,6 +117,16 @@ pub fn tokenize(s: &str) -> Result<Vec<Token>, LexerError> {
     Ok(tokens)
 }
 
+/// Tokenizes the given text like [`tokenize()`], additionally returning the skipped comments.
+pub fn tokenize_with_comments(s: &str) -> Result<(Vec<Token>, Vec<Comment>), LexerError> {
+    let mut lexer = Lexer::new(s);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
+    }
+    Ok((tokens, lexer.comments))
+}
+
 impl Lexer {
     pub fn new(text: &str) -> Self {
         Self {
@@ -95,6 +136,7 @@ impl Lexer {
                 line_number: 1,
                 line_char_index: 0,
             },
+            comments: Vec::new(),
         }
     }
 
@@ -129,14 +171,9 @@ impl Lexer {
 
     pub fn next_token(&mut self) -> Result<Option<Token>, LexerError> {
         // skip comment and whitespace
-        let mut in_comment = false;
         while !self.is_eof() {
-            if in_comment {
-                if self.advance()? == '\n' {
-                    in_comment = false;
-                }
-            } else if self.peek()? == '#' {
-                in_comment = true;
+            if self.peek()? == '#' {
+                self.skip_comment()?;
             } else if Self::is_whitespace(self.peek()?) {
                 self.advance()?;
             } else {
@@ -154,6 +191,7 @@ impl Lexer {
             '-' | '0'..='9' => self.tokenize_number(),
             '"' => self.tokenize_string(),
             'a'..='z' | 'A'..='Z' => self.tokenize_identifier(),
+            '$' => self.tokenize_parameter(),
             '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' | '&' | '=' => {
                 self.tokenize_punctuation()
             }
@@ -165,6 +203,22 @@ impl Lexer {
         .map(Option::from)
     }
 
+    fn skip_comment(&mut self) -> Result<(), LexerError> {
+        let start = self.current;
+        let mut text = String::new();
+        while !self.is_eof() && self.peek()? != '\n' {
+            text.push(self.advance()?);
+        }
+        self.comments.push(Comment {
+            text: text.trim_end().to_string(),
+            span: Span {
+                start,
+                end: self.current,
+            },
+        });
+        Ok(())
+    }
+
     // TODO: consider using DFA
     fn tokenize_number(&mut self) -> Result<Token, LexerError> {
         let start = self.current;
@@ -331,6 +385,29 @@ impl Lexer {
         Ok(self.new_token(kind, start, self.current))
     }
 
+    fn tokenize_parameter(&mut self) -> Result<Token, LexerError> {
+        let start = self.current;
+        self.advance()?;
+
+        let mut name = String::new();
+        while !self.is_eof() {
+            let next_char = self.peek()?;
+            if !(next_char.is_ascii_alphanumeric() || next_char == '_') {
+                break;
+            }
+            name.push(self.advance()?);
+        }
+        if name.is_empty() {
+            return Err(if self.is_eof() {
+                LexerError::UnexpectedEof
+            } else {
+                self.unexpected_char()
+            });
+        }
+
+        Ok(self.new_token(TokenKind::Parameter(name), start, self.current))
+    }
+
     fn tokenize_punctuation(&mut self) -> Result<Token, LexerError> {
         let start = self.current;
 
@@ -457,6 +534,21 @@ mod tests {
         lex_error!("\"", LexerError::UnexpectedEof);
     }
 
+    #[test]
+    fn test_parameter() {
+        lex_ok!(
+            "$amount Decimal($to_2)",
+            vec![
+                TokenKind::Parameter("amount".into()),
+                TokenKind::Ident("Decimal".into()),
+                TokenKind::OpenParenthesis,
+                TokenKind::Parameter("to_2".into()),
+                TokenKind::CloseParenthesis,
+            ]
+        );
+        lex_error!("$", LexerError::UnexpectedEof);
+    }
+
     #[test]
     fn test_mixed() {
         lex_ok!(
@@ -1,16 +1,32 @@
+pub mod analyzer;
 pub mod ast;
 pub mod blob_provider;
 pub mod compiler;
 pub mod decompiler;
+pub mod diagnostics;
 #[cfg(feature = "std")]
 pub mod dumper;
 pub mod e2e;
 pub mod generator;
+#[cfg(feature = "serde")]
+pub mod json;
 pub mod lexer;
 pub mod manifest_enums;
 pub mod parser;
+pub mod source_formatter;
+pub mod summary;
+pub mod template;
 
+pub use analyzer::{analyze_manifest, ManifestWarning, ManifestWarningKind};
 pub use blob_provider::*;
-pub use compiler::{compile, CompileError};
+pub use compiler::{
+    compile, compile_with_source_map, CompileError, InstructionSourceSpans, ManifestSourceMap,
+};
 pub use decompiler::{decompile, DecompileError};
+pub use diagnostics::compile_error_diagnostics;
+#[cfg(feature = "serde")]
+pub use json::ManifestJsonError;
 pub use manifest_enums::*;
+pub use source_formatter::format_manifest_source;
+pub use summary::{summarize_manifest, ManifestClass, ManifestSummary};
+pub use template::{ManifestTemplate, TemplateError};
@@ -1,5 +1,5 @@
-use crate::manifest::ast::{Instruction, Value, ValueKind};
-use crate::manifest::lexer::{Token, TokenKind};
+use crate::manifest::ast::{Instruction, InstructionWithSpan, Value, ValueKind, ValueWithSpan};
+use crate::manifest::lexer::{Position, Span, Token, TokenKind};
 use crate::manifest::manifest_enums::KNOWN_ENUM_DISCRIMINATORS;
 use radix_engine_interface::data::manifest::MANIFEST_SBOR_V1_MAX_DEPTH;
 
@@ -8,7 +8,15 @@ use radix_engine_interface::data::manifest::MANIFEST_SBOR_V1_MAX_DEPTH;
 pub const PARSER_MAX_DEPTH: usize = MANIFEST_SBOR_V1_MAX_DEPTH - 4;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ParserError {
+pub struct ParserError {
+    pub error_kind: ParserErrorKind,
+    /// The span of the offending source (an empty span at the end of the input, in case of an
+    /// unexpected end of file).
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParserErrorKind {
     UnexpectedEof,
     UnexpectedToken { expected: TokenType, actual: Token },
     InvalidNumberOfValues { expected: usize, actual: usize },
@@ -24,6 +32,8 @@ pub enum TokenType {
     Value,
     ValueKind,
     EnumDiscriminator,
+    /// A template parameter, e.g. `$amount` (see [`super::template`]).
+    Parameter,
     Exact(TokenKind),
 }
 
@@ -61,6 +71,8 @@ pub enum InstructionIdent {
     DropNamedProofs,
     DropAllProofs,
     AllocateGlobalAddress,
+    YieldToChild,
+    YieldToParent,
 
     // ==============
     // Call direct vault method aliases
@@ -156,6 +168,8 @@ impl InstructionIdent {
             "DROP_NAMED_PROOFS" => InstructionIdent::DropNamedProofs,
             "DROP_ALL_PROOFS" => InstructionIdent::DropAllProofs,
             "ALLOCATE_GLOBAL_ADDRESS" => InstructionIdent::AllocateGlobalAddress,
+            "YIELD_TO_CHILD" => InstructionIdent::YieldToChild,
+            "YIELD_TO_PARENT" => InstructionIdent::YieldToParent,
 
             // ==============
             // Call direct vault method aliases
@@ -399,9 +413,12 @@ macro_rules! advance_match {
     ( $self:expr, $expected:expr ) => {{
         let token = $self.advance()?;
         if token.kind != $expected {
-            return Err(ParserError::UnexpectedToken {
-                expected: TokenType::Exact($expected),
-                actual: token,
+            return Err(ParserError {
+                span: token.span,
+                error_kind: ParserErrorKind::UnexpectedToken {
+                    expected: TokenType::Exact($expected),
+                    actual: token,
+                },
             });
         }
     }};
@@ -421,7 +438,10 @@ impl Parser {
     fn track_stack_depth_increase(&mut self) -> Result<(), ParserError> {
         self.stack_depth += 1;
         if self.stack_depth > self.max_depth {
-            return Err(ParserError::MaxDepthExceeded(self.max_depth));
+            return Err(ParserError {
+                error_kind: ParserErrorKind::MaxDepthExceeded(self.max_depth),
+                span: self.peek()?.span,
+            });
         }
         Ok(())
     }
@@ -440,7 +460,10 @@ impl Parser {
         self.tokens
             .get(self.current)
             .cloned()
-            .ok_or(ParserError::UnexpectedEof)
+            .ok_or_else(|| ParserError {
+                error_kind: ParserErrorKind::UnexpectedEof,
+                span: self.eof_span(),
+            })
     }
 
     pub fn advance(&mut self) -> Result<Token, ParserError> {
@@ -449,8 +472,29 @@ impl Parser {
         Ok(token)
     }
 
-    pub fn parse_manifest(&mut self) -> Result<Vec<Instruction>, ParserError> {
-        let mut instructions = Vec::<Instruction>::new();
+    /// Returns an empty span right after the last token.
+    fn eof_span(&self) -> Span {
+        let end = match self.tokens.last() {
+            Some(token) => token.span.end,
+            None => Position {
+                full_index: 0,
+                line_number: 1,
+                line_char_index: 0,
+            },
+        };
+        Span { start: end, end }
+    }
+
+    /// Returns the span from the given position up to the end of the most recently advanced token.
+    fn span_from(&self, start: Position) -> Span {
+        Span {
+            start,
+            end: self.tokens[self.current - 1].span.end,
+        }
+    }
+
+    pub fn parse_manifest(&mut self) -> Result<Vec<InstructionWithSpan>, ParserError> {
+        let mut instructions = Vec::<InstructionWithSpan>::new();
 
         while !self.is_eof() {
             instructions.push(self.parse_instruction()?);
@@ -459,7 +503,7 @@ impl Parser {
         Ok(instructions)
     }
 
-    fn parse_values_till_semicolon(&mut self) -> Result<Vec<Value>, ParserError> {
+    fn parse_values_till_semicolon(&mut self) -> Result<Vec<ValueWithSpan>, ParserError> {
         let mut values = Vec::new();
         while self.peek()?.kind != TokenKind::Semicolon {
             values.push(self.parse_value()?);
@@ -467,22 +511,19 @@ impl Parser {
         Ok(values)
     }
 
-    pub fn parse_instruction(&mut self) -> Result<Instruction, ParserError> {
+    pub fn parse_instruction(&mut self) -> Result<InstructionWithSpan, ParserError> {
         let token = self.advance()?;
         let instruction_ident = match &token.kind {
-            TokenKind::Ident(ident_str) => {
-                InstructionIdent::from_ident(ident_str).ok_or(ParserError::UnexpectedToken {
-                    expected: TokenType::Instruction,
-                    actual: token,
-                })?
-            }
-            _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::Instruction,
-                    actual: token,
-                });
-            }
-        };
+            TokenKind::Ident(ident_str) => InstructionIdent::from_ident(ident_str),
+            _ => None,
+        }
+        .ok_or_else(|| ParserError {
+            span: token.span,
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Instruction,
+                actual: token.clone(),
+            },
+        })?;
         let instruction = match instruction_ident {
             InstructionIdent::TakeFromWorktop => Instruction::TakeFromWorktop {
                 resource_address: self.parse_value()?,
@@ -613,6 +654,13 @@ impl Parser {
                 address_reservation: self.parse_value()?,
                 named_address: self.parse_value()?,
             },
+            InstructionIdent::YieldToChild => Instruction::YieldToChild {
+                child_index: self.parse_value()?,
+                args: self.parse_values_till_semicolon()?,
+            },
+            InstructionIdent::YieldToParent => Instruction::YieldToParent {
+                args: self.parse_values_till_semicolon()?,
+            },
 
             /* Call direct vault method aliases */
             InstructionIdent::RecallFromVault => Instruction::RecallFromVault {
@@ -733,10 +781,13 @@ impl Parser {
             },
         };
         advance_match!(self, TokenKind::Semicolon);
-        Ok(instruction)
+        Ok(InstructionWithSpan {
+            instruction,
+            span: self.span_from(token.span.start),
+        })
     }
 
-    pub fn parse_value(&mut self) -> Result<Value, ParserError> {
+    pub fn parse_value(&mut self) -> Result<ValueWithSpan, ParserError> {
         self.track_stack_depth_increase()?;
         let token = self.advance()?;
         let value = match &token.kind {
@@ -757,9 +808,12 @@ impl Parser {
             TokenKind::StringLiteral(value) => Value::String(value.clone()),
             TokenKind::Ident(ident_str) => {
                 let value_ident =
-                    SborValueIdent::from_ident(ident_str).ok_or(ParserError::UnexpectedToken {
-                        expected: TokenType::Value,
-                        actual: token,
+                    SborValueIdent::from_ident(ident_str).ok_or_else(|| ParserError {
+                        span: token.span,
+                        error_kind: ParserErrorKind::UnexpectedToken {
+                            expected: TokenType::Value,
+                            actual: token.clone(),
+                        },
                     })?;
                 match value_ident {
                     SborValueIdent::Enum => self.parse_enum_content()?,
@@ -805,29 +859,41 @@ impl Parser {
                 }
             }
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::Value,
-                    actual: token,
+                return Err(ParserError {
+                    span: token.span,
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::Value,
+                        actual: token,
+                    },
                 });
             }
         };
         self.track_stack_depth_decrease()?;
-        Ok(value)
+        Ok(ValueWithSpan {
+            value,
+            span: self.span_from(token.span.start),
+        })
     }
 
     pub fn parse_enum_content(&mut self) -> Result<Value, ParserError> {
         advance_match!(self, TokenKind::LessThan);
         let discriminator_token = self.advance()?;
-        let discriminator = match discriminator_token.kind {
-            TokenKind::U8Literal(discriminator) => discriminator,
+        let discriminator = match &discriminator_token.kind {
+            TokenKind::U8Literal(discriminator) => *discriminator,
             TokenKind::Ident(discriminator) => KNOWN_ENUM_DISCRIMINATORS
                 .get(discriminator.as_str())
                 .cloned()
-                .ok_or(ParserError::UnknownEnumDiscriminator(discriminator.clone()))?,
+                .ok_or_else(|| ParserError {
+                    error_kind: ParserErrorKind::UnknownEnumDiscriminator(discriminator.clone()),
+                    span: discriminator_token.span,
+                })?,
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::EnumDiscriminator,
-                    actual: discriminator_token,
+                return Err(ParserError {
+                    span: discriminator_token.span,
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::EnumDiscriminator,
+                        actual: discriminator_token,
+                    },
                 })
             }
         };
@@ -876,7 +942,7 @@ impl Parser {
         &mut self,
         open: TokenKind,
         close: TokenKind,
-    ) -> Result<Vec<Value>, ParserError> {
+    ) -> Result<Vec<ValueWithSpan>, ParserError> {
         advance_match!(self, open);
         let mut values = Vec::new();
         while self.peek()?.kind != close {
@@ -889,13 +955,17 @@ impl Parser {
         Ok(values)
     }
 
-    fn parse_values_one(&mut self) -> Result<Value, ParserError> {
+    fn parse_values_one(&mut self) -> Result<ValueWithSpan, ParserError> {
+        let start = self.peek()?.span.start;
         let values =
             self.parse_values_any(TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)?;
         if values.len() != 1 {
-            Err(ParserError::InvalidNumberOfValues {
-                actual: values.len(),
-                expected: 1,
+            Err(ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfValues {
+                    actual: values.len(),
+                    expected: 1,
+                },
+                span: self.span_from(start),
             })
         } else {
             Ok(values[0].clone())
@@ -903,6 +973,7 @@ impl Parser {
     }
 
     fn parse_generics(&mut self, n: usize) -> Result<Vec<ValueKind>, ParserError> {
+        let start = self.peek()?.span.start;
         advance_match!(self, TokenKind::LessThan);
         let mut types = Vec::new();
         while self.peek()?.kind != TokenKind::GreaterThan {
@@ -914,25 +985,30 @@ impl Parser {
         advance_match!(self, TokenKind::GreaterThan);
 
         if types.len() != n {
-            Err(ParserError::InvalidNumberOfTypes {
-                expected: n,
-                actual: types.len(),
+            Err(ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfTypes {
+                    expected: n,
+                    actual: types.len(),
+                },
+                span: self.span_from(start),
             })
         } else {
             Ok(types)
         }
     }
 
-    fn parse_type(&mut self) -> Result<ValueKind, ParserError> {
+    pub fn parse_type(&mut self) -> Result<ValueKind, ParserError> {
         let token = self.advance()?;
         let the_type = match &token.kind {
             TokenKind::Ident(ident_str) => {
-                let value_kind_ident = SborValueKindIdent::from_ident(&ident_str).ok_or(
-                    ParserError::UnexpectedToken {
-                        expected: TokenType::ValueKind,
-                        actual: token,
-                    },
-                )?;
+                let value_kind_ident =
+                    SborValueKindIdent::from_ident(&ident_str).ok_or_else(|| ParserError {
+                        span: token.span,
+                        error_kind: ParserErrorKind::UnexpectedToken {
+                            expected: TokenType::ValueKind,
+                            actual: token.clone(),
+                        },
+                    })?;
                 match value_kind_ident {
                     // ==============
                     // Simple basic value kinds
@@ -980,9 +1056,12 @@ impl Parser {
                 }
             }
             _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenType::ValueKind,
-                    actual: token,
+                return Err(ParserError {
+                    span: token.span,
+                    error_kind: ParserErrorKind::UnexpectedToken {
+                        expected: TokenType::ValueKind,
+                        actual: token,
+                    },
                 });
             }
         };
@@ -993,13 +1072,14 @@ impl Parser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::manifest::lexer::{tokenize, Position, Span};
+    use crate::manifest::lexer::tokenize;
+    use crate::span;
 
     #[macro_export]
     macro_rules! parse_instruction_ok {
         ( $s:expr, $expected:expr ) => {{
             let mut parser = Parser::new(tokenize($s).unwrap()), PARSER_MAX_DEPTH;
-            assert_eq!(parser.parse_instruction(), Ok($expected));
+            assert_eq!(parser.parse_instruction().map(|i| i.instruction), Ok($expected));
             assert!(parser.is_eof());
         }};
     }
@@ -1008,7 +1088,7 @@ mod tests {
     macro_rules! parse_value_ok {
         ( $s:expr, $expected:expr ) => {{
             let mut parser = Parser::new(tokenize($s).unwrap(), PARSER_MAX_DEPTH);
-            assert_eq!(parser.parse_value(), Ok($expected));
+            assert_eq!(parser.parse_value().map(|v| v.value), Ok($expected));
             assert!(parser.is_eof());
         }};
     }
@@ -1028,6 +1108,14 @@ mod tests {
         }};
     }
 
+    /// Attaches a span within the first line of the source to the given value.
+    fn with_span(value: Value, start: usize, end: usize) -> ValueWithSpan {
+        ValueWithSpan {
+            value,
+            span: span!(start = (start, 1, start), end = (end, 1, end)),
+        }
+    }
+
     #[test]
     fn test_literals() {
         parse_value_ok!(r#"true"#, Value::Bool(true));
@@ -1049,7 +1137,13 @@ mod tests {
     fn test_enum() {
         parse_value_ok!(
             r#"Enum<0u8>("Hello", 123u8)"#,
-            Value::Enum(0, vec![Value::String("Hello".into()), Value::U8(123)],)
+            Value::Enum(
+                0,
+                vec![
+                    with_span(Value::String("Hello".into()), 10, 17),
+                    with_span(Value::U8(123), 19, 24),
+                ],
+            )
         );
         parse_value_ok!(r#"Enum<0u8>()"#, Value::Enum(0, Vec::new()));
         parse_value_ok!(
@@ -1059,7 +1153,13 @@ mod tests {
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Enum<0u8>("Hello", 123u8,)"#,
-            Value::Enum(0, vec![Value::String("Hello".into()), Value::U8(123)],)
+            Value::Enum(
+                0,
+                vec![
+                    with_span(Value::String("Hello".into()), 10, 17),
+                    with_span(Value::U8(123), 19, 24),
+                ],
+            )
         );
     }
 
@@ -1067,13 +1167,25 @@ mod tests {
     fn test_array() {
         parse_value_ok!(
             r#"Array<U8>(1u8, 2u8)"#,
-            Value::Array(ValueKind::U8, vec![Value::U8(1), Value::U8(2)])
+            Value::Array(
+                ValueKind::U8,
+                vec![
+                    with_span(Value::U8(1), 10, 13),
+                    with_span(Value::U8(2), 15, 18),
+                ]
+            )
         );
         parse_value_ok!(r#"Array<U8>()"#, Value::Array(ValueKind::U8, vec![]));
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Array<U8>(1u8, 2u8,)"#,
-            Value::Array(ValueKind::U8, vec![Value::U8(1), Value::U8(2)])
+            Value::Array(
+                ValueKind::U8,
+                vec![
+                    with_span(Value::U8(1), 10, 13),
+                    with_span(Value::U8(2), 15, 18),
+                ]
+            )
         );
     }
 
@@ -1082,16 +1194,25 @@ mod tests {
         parse_value_ok!(r#"Tuple()"#, Value::Tuple(vec![]));
         parse_value_ok!(
             r#"Tuple("Hello", 123u8)"#,
-            Value::Tuple(vec![Value::String("Hello".into()), Value::U8(123),])
+            Value::Tuple(vec![
+                with_span(Value::String("Hello".into()), 6, 13),
+                with_span(Value::U8(123), 15, 20),
+            ])
         );
         parse_value_ok!(
             r#"Tuple(1u8, 2u8)"#,
-            Value::Tuple(vec![Value::U8(1), Value::U8(2)])
+            Value::Tuple(vec![
+                with_span(Value::U8(1), 6, 9),
+                with_span(Value::U8(2), 11, 14),
+            ])
         );
         // Check we allow trailing commas
         parse_value_ok!(
             r#"Tuple(1u8, 2u8,)"#,
-            Value::Tuple(vec![Value::U8(1), Value::U8(2)])
+            Value::Tuple(vec![
+                with_span(Value::U8(1), 6, 9),
+                with_span(Value::U8(2), 11, 14),
+            ])
         );
     }
 
@@ -1102,7 +1223,10 @@ mod tests {
             Value::Map(
                 ValueKind::String,
                 ValueKind::U8,
-                vec![(Value::String("Hello".into()), Value::U8(123))]
+                vec![(
+                    with_span(Value::String("Hello".into()), 16, 23),
+                    with_span(Value::U8(123), 27, 32),
+                )]
             )
         );
         parse_value_ok!(
@@ -1111,8 +1235,14 @@ mod tests {
                 ValueKind::String,
                 ValueKind::U8,
                 vec![
-                    (Value::String("Hello".into()), Value::U8(123)),
-                    (Value::String("world!".into()), Value::U8(1)),
+                    (
+                        with_span(Value::String("Hello".into()), 16, 23),
+                        with_span(Value::U8(123), 27, 32),
+                    ),
+                    (
+                        with_span(Value::String("world!".into()), 34, 42),
+                        with_span(Value::U8(1), 46, 49),
+                    ),
                 ]
             )
         );
@@ -1123,42 +1253,94 @@ mod tests {
                 ValueKind::String,
                 ValueKind::U8,
                 vec![
-                    (Value::String("Hello".into()), Value::U8(123)),
-                    (Value::String("world!".into()), Value::U8(1)),
+                    (
+                        with_span(Value::String("Hello".into()), 16, 23),
+                        with_span(Value::U8(123), 27, 32),
+                    ),
+                    (
+                        with_span(Value::String("world!".into()), 34, 42),
+                        with_span(Value::U8(1), 46, 49),
+                    ),
                 ]
             )
         );
     }
 
+    #[test]
+    fn test_spans_of_instruction_and_values() {
+        let mut parser = Parser::new(
+            tokenize("CALL_METHOD\n    Address(\"x\")\n    \"free\";").unwrap(),
+            PARSER_MAX_DEPTH,
+        );
+        let instruction = parser.parse_instruction().unwrap();
+        assert!(parser.is_eof());
+
+        assert_eq!(
+            instruction.span,
+            span!(start = (0, 1, 0), end = (40, 3, 11))
+        );
+        let (address, method_name, args) = match instruction.instruction {
+            Instruction::CallMethod {
+                address,
+                method_name,
+                args,
+            } => (address, method_name, args),
+            _ => panic!("Expected CALL_METHOD"),
+        };
+        assert_eq!(
+            address,
+            ValueWithSpan {
+                value: Value::Address(Box::new(ValueWithSpan {
+                    value: Value::String("x".into()),
+                    span: span!(start = (24, 2, 12), end = (27, 2, 15)),
+                })),
+                span: span!(start = (16, 2, 4), end = (28, 2, 16)),
+            }
+        );
+        assert_eq!(
+            method_name.span,
+            span!(start = (33, 3, 4), end = (39, 3, 10))
+        );
+        assert!(args.is_empty());
+    }
+
     #[test]
     fn test_failures() {
-        parse_value_error!(r#"Enum<0u8"#, ParserError::UnexpectedEof);
+        parse_value_error!(
+            r#"Enum<0u8"#,
+            ParserError {
+                error_kind: ParserErrorKind::UnexpectedEof,
+                span: span!(start = (8, 1, 8), end = (8, 1, 8)),
+            }
+        );
         parse_value_error!(
             r#"Enum<0u8)"#,
-            ParserError::UnexpectedToken {
-                expected: TokenType::Exact(TokenKind::GreaterThan),
-                actual: Token {
-                    kind: TokenKind::CloseParenthesis,
-                    span: Span {
-                        start: Position {
-                            full_index: 8,
-                            line_number: 1,
-                            line_char_index: 8,
-                        },
-                        end: Position {
-                            full_index: 9,
-                            line_number: 1,
-                            line_char_index: 9,
-                        }
-                    }
+            ParserError {
+                error_kind: ParserErrorKind::UnexpectedToken {
+                    expected: TokenType::Exact(TokenKind::GreaterThan),
+                    actual: Token {
+                        kind: TokenKind::CloseParenthesis,
+                        span: span!(start = (8, 1, 8), end = (9, 1, 9)),
+                    },
                 },
+                span: span!(start = (8, 1, 8), end = (9, 1, 9)),
             }
         );
         parse_value_error!(
             r#"Address("abc", "def")"#,
-            ParserError::InvalidNumberOfValues {
-                actual: 2,
-                expected: 1
+            ParserError {
+                error_kind: ParserErrorKind::InvalidNumberOfValues {
+                    actual: 2,
+                    expected: 1
+                },
+                span: span!(start = (7, 1, 7), end = (21, 1, 21)),
+            }
+        );
+        parse_value_error!(
+            r#"Enum<Unknown::Variant>()"#,
+            ParserError {
+                error_kind: ParserErrorKind::UnknownEnumDiscriminator("Unknown::Variant".into()),
+                span: span!(start = (5, 1, 5), end = (21, 1, 21)),
             }
         );
     }
@@ -1176,9 +1358,14 @@ mod tests {
         }
 
         // Should actually be an error not a panic
+        // (pointing at the first value which is too deep)
+        let start = "Tuple(".len() * PARSER_MAX_DEPTH;
         parse_value_error!(
             &value_string,
-            ParserError::MaxDepthExceeded(PARSER_MAX_DEPTH)
+            ParserError {
+                error_kind: ParserErrorKind::MaxDepthExceeded(PARSER_MAX_DEPTH),
+                span: span!(start = (start, 1, start), end = (start + 5, 1, start + 5)),
+            }
         );
     }
 
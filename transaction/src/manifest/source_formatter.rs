@@ -0,0 +1,316 @@
+use crate::internal_prelude::*;
+use crate::manifest::lexer::{tokenize_with_comments, Comment, Token, TokenKind};
+
+const INDENT: usize = 4;
+
+/// The value constructors whose content is always laid out on separate lines (unless empty), like
+/// in decompiled manifests.
+const COMPOSITE_VALUE_IDENTS: [&str; 7] = ["Enum", "Array", "Tuple", "Map", "Some", "Ok", "Err"];
+
+/// Formats the given manifest source in the layout of decompiled manifests, i.e. with each
+/// argument of an instruction, and each element of a composite value, on its own line.
+///
+/// Unlike decompiling, formatting keeps the source's comments, blank lines between instructions,
+/// names (e.g. of buckets) and the spelling of its values (e.g. `Decimal("1.0")` or
+/// `Enum<PublicKey::Secp256k1>(...)`).
+pub fn format_manifest_source(s: &str) -> Result<String, CompileError> {
+    let (tokens, comments) = tokenize_with_comments(s).map_err(CompileError::LexerError)?;
+    // Only well-formed manifests are formatted
+    parser::Parser::new(tokens.clone(), parser::PARSER_MAX_DEPTH)
+        .parse_manifest()
+        .map_err(CompileError::ParserError)?;
+
+    let mut formatter = SourceFormatter {
+        source: s.chars().collect(),
+        tokens,
+        comments,
+        next_token: 0,
+        next_comment: 0,
+        last_source_end: 0,
+        indent: 0,
+        output: String::new(),
+        at_line_start: true,
+    };
+    while formatter.next_token < formatter.tokens.len() {
+        formatter.format_instruction();
+    }
+    formatter.flush_comments_before(usize::MAX, true);
+    Ok(formatter.output)
+}
+
+struct SourceFormatter {
+    source: Vec<char>,
+    tokens: Vec<Token>,
+    comments: Vec<Comment>,
+    next_token: usize,
+    next_comment: usize,
+    /// The end of the source of the last written token or comment.
+    last_source_end: usize,
+    indent: usize,
+    output: String,
+    at_line_start: bool,
+}
+
+impl SourceFormatter {
+    fn format_instruction(&mut self) {
+        self.write_next_token(true);
+        if self.peek_kind() == Some(&TokenKind::Semicolon) {
+            self.write_next_token(false);
+            self.new_line();
+            return;
+        }
+
+        self.indent += INDENT;
+        while self.peek_kind() != Some(&TokenKind::Semicolon) {
+            self.new_line();
+            self.format_value();
+        }
+        self.indent -= INDENT;
+        self.new_line();
+        self.write_next_token(false);
+        self.new_line();
+    }
+
+    fn format_value(&mut self) {
+        let is_composite = match self.peek_kind() {
+            Some(TokenKind::Ident(ident)) => COMPOSITE_VALUE_IDENTS.contains(&ident.as_str()),
+            _ => false,
+        };
+        self.write_next_token(false);
+
+        if self.peek_kind() == Some(&TokenKind::LessThan) {
+            self.write_next_token(false);
+            while self.peek_kind() != Some(&TokenKind::GreaterThan) {
+                self.write_next_token(false);
+                if self.peek_kind() == Some(&TokenKind::Comma) {
+                    self.write_next_token(false);
+                    self.write(" ");
+                }
+            }
+            self.write_next_token(false);
+        }
+
+        if self.peek_kind() != Some(&TokenKind::OpenParenthesis) {
+            return;
+        }
+        self.write_next_token(false);
+        let is_single_literal = self.peek_kind().map_or(false, is_literal)
+            && self.peek_kind_at(1) == Some(&TokenKind::CloseParenthesis);
+        if self.peek_kind() == Some(&TokenKind::CloseParenthesis)
+            || (!is_composite && is_single_literal)
+        {
+            while self.peek_kind() != Some(&TokenKind::CloseParenthesis) {
+                self.write_next_token(false);
+            }
+            self.write_next_token(false);
+            return;
+        }
+
+        self.indent += INDENT;
+        loop {
+            self.new_line();
+            self.format_value();
+            if self.peek_kind() == Some(&TokenKind::FatArrow) {
+                self.write(" ");
+                self.write_next_token(false);
+                self.write(" ");
+                self.format_value();
+            }
+            if self.peek_kind() != Some(&TokenKind::Comma) {
+                break;
+            }
+            self.write_next_token(false);
+            if self.peek_kind() == Some(&TokenKind::CloseParenthesis) {
+                // A trailing comma is dropped
+                self.output.pop();
+                break;
+            }
+        }
+        self.indent -= INDENT;
+        self.new_line();
+        self.write_next_token(false);
+    }
+
+    fn peek_kind(&self) -> Option<&TokenKind> {
+        self.peek_kind_at(0)
+    }
+
+    fn peek_kind_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens
+            .get(self.next_token + offset)
+            .map(|token| &token.kind)
+    }
+
+    /// Writes the next token as spelled in the source, preceded by the comments before it.
+    fn write_next_token(&mut self, is_top_level: bool) {
+        let span = self.tokens[self.next_token].span;
+        self.next_token += 1;
+        self.flush_comments_before(span.start.full_index, is_top_level);
+        if is_top_level && self.has_blank_line_before(span.start.full_index) {
+            self.blank_line();
+        }
+        let text: String = self.source[span.start.full_index..span.end.full_index]
+            .iter()
+            .collect();
+        self.write(&text);
+        self.last_source_end = span.end.full_index;
+    }
+
+    fn flush_comments_before(&mut self, source_index: usize, is_top_level: bool) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.span.start.full_index >= source_index {
+                break;
+            }
+            self.next_comment += 1;
+            if self.is_on_own_line(&comment) {
+                if is_top_level && self.has_blank_line_before(comment.span.start.full_index) {
+                    self.blank_line();
+                }
+                self.new_line();
+                self.write(&comment.text);
+            } else {
+                // A trailing comment stays at the end of the line of the preceding token
+                if self.at_line_start && self.output.ends_with('\n') {
+                    self.output.pop();
+                    self.at_line_start = false;
+                }
+                self.write(" ");
+                self.write(&comment.text);
+            }
+            // The comment runs up to the end of the line
+            self.new_line();
+            self.last_source_end = comment.span.end.full_index;
+        }
+    }
+
+    fn is_on_own_line(&self, comment: &Comment) -> bool {
+        let line_start = comment.span.start.full_index - comment.span.start.line_char_index;
+        self.source[line_start..comment.span.start.full_index]
+            .iter()
+            .all(|c| c.is_whitespace())
+            || self.output.is_empty()
+    }
+
+    fn has_blank_line_before(&self, source_index: usize) -> bool {
+        !self.output.is_empty()
+            && self.source[self.last_source_end..source_index]
+                .iter()
+                .filter(|c| **c == '\n')
+                .count()
+                > 1
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.at_line_start {
+            self.output.push_str(&" ".repeat(self.indent));
+            self.at_line_start = false;
+        }
+        self.output.push_str(text);
+    }
+
+    fn new_line(&mut self) {
+        if !self.at_line_start {
+            self.output.push('\n');
+            self.at_line_start = true;
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.new_line();
+        if !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+}
+
+fn is_literal(kind: &TokenKind) -> bool {
+    match kind {
+        TokenKind::BoolLiteral(_)
+        | TokenKind::I8Literal(_)
+        | TokenKind::I16Literal(_)
+        | TokenKind::I32Literal(_)
+        | TokenKind::I64Literal(_)
+        | TokenKind::I128Literal(_)
+        | TokenKind::U8Literal(_)
+        | TokenKind::U16Literal(_)
+        | TokenKind::U32Literal(_)
+        | TokenKind::U64Literal(_)
+        | TokenKind::U128Literal(_)
+        | TokenKind::StringLiteral(_) => true,
+        TokenKind::Ident(_)
+        | TokenKind::Parameter(_)
+        | TokenKind::OpenParenthesis
+        | TokenKind::CloseParenthesis
+        | TokenKind::LessThan
+        | TokenKind::GreaterThan
+        | TokenKind::Comma
+        | TokenKind::Semicolon
+        | TokenKind::FatArrow => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_normalizes_layout_and_keeps_comments() {
+        let source = r#"# Lock fees
+CALL_METHOD Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh") "lock_fee" Decimal("5.0"); # from the faucet
+
+
+   DROP_ALL_PROOFS ;
+CALL_METHOD Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh") "some_method"
+  Tuple( Array<Enum>( Enum<PublicKey::Secp256k1>( ), ) ,   # no keys
+   Map<String,U8>("a"=>1u8) );
+# The end
+"#;
+        let expected = r#"# Lock fees
+CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee"
+    Decimal("5.0")
+; # from the faucet
+
+DROP_ALL_PROOFS;
+CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "some_method"
+    Tuple(
+        Array<Enum>(
+            Enum<PublicKey::Secp256k1>()
+        ), # no keys
+        Map<String, U8>(
+            "a" => 1u8
+        )
+    )
+;
+# The end
+"#;
+
+        let formatted = format_manifest_source(source).unwrap();
+
+        assert_eq!(formatted, expected);
+        assert_eq!(format_manifest_source(&formatted).unwrap(), formatted);
+        assert_eq!(
+            compile(
+                &formatted,
+                &NetworkDefinition::simulator(),
+                BlobProvider::default()
+            ),
+            compile(
+                source,
+                &NetworkDefinition::simulator(),
+                BlobProvider::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_rejects_malformed_manifest() {
+        assert!(matches!(
+            format_manifest_source("CALL_METHOD Decimal(\"1\""),
+            Err(CompileError::ParserError(..))
+        ));
+    }
+}
@@ -0,0 +1,855 @@
+use crate::internal_prelude::*;
+use crate::manifest::analyzer::decode_args;
+use radix_engine_interface::blueprints::access_controller::*;
+use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::blueprints::consensus_manager::*;
+use radix_engine_interface::blueprints::identity::*;
+use radix_engine_interface::blueprints::package::*;
+use radix_engine_interface::blueprints::pool::*;
+use radix_engine_interface::blueprints::resource::*;
+
+/// A human-explainable summary of what a manifest does, produced by [`summarize_manifest()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSummary {
+    /// The resources withdrawn from each account, in the order of the withdrawals.
+    pub account_withdrawals: IndexMap<ComponentAddress, Vec<ResourceSpecifier>>,
+    /// The resources deposited to each account, in the order of the deposits.
+    pub account_deposits: IndexMap<ComponentAddress, Vec<AccountDeposit>>,
+    /// The accounts whose owner has to authorize the transaction (e.g. by signing it), because
+    /// the manifest locks fees from, withdraws from, creates proofs from or updates the settings
+    /// of the account.
+    pub accounts_requiring_auth: IndexSet<ComponentAddress>,
+    /// The proofs created from the resources of each account.
+    pub presented_proofs: IndexMap<ComponentAddress, Vec<ResourceSpecifier>>,
+    /// The entities created by calls to native blueprints (e.g. `create` functions of resource
+    /// managers), in the order of the calls.
+    pub new_entities: Vec<NewEntity>,
+    pub classification: ManifestClass,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceSpecifier {
+    pub resource_address: ResourceAddress,
+    pub quantity: ResourceQuantity,
+}
+
+/// The statically known quantity of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceQuantity {
+    Amount(Decimal),
+    Ids(IndexSet<NonFungibleLocalId>),
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountDeposit {
+    Resource(ResourceSpecifier),
+    /// Resources whose addresses are not statically known, e.g. the resources put on the worktop
+    /// by a call to a component, which are then deposited by depositing the entire worktop.
+    UnknownResources,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewEntity {
+    /// The index of the instruction creating the entity.
+    pub instruction_index: usize,
+    pub entity_type: EntityType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestClass {
+    /// Only withdraws resources from and deposits resources to accounts (besides locking fees and
+    /// presenting proofs).
+    Transfer,
+    /// Contributes resources to pools.
+    PoolContribution,
+    /// Redeems pool units.
+    PoolRedemption,
+    /// Stakes XRD to validators.
+    ValidatorStake,
+    /// Unstakes stake units from validators.
+    ValidatorUnstake,
+    /// Claims unstaked XRD from validators.
+    ValidatorClaim,
+    /// Only updates the deposit rules, resource preferences or authorized depositors of accounts.
+    AccountDepositSettingsUpdate,
+    /// Anything else, e.g. calls to non-native components or combinations of the above.
+    General,
+}
+
+/// Summarizes what the given manifest instructions do: the resources moved from and to accounts,
+/// the accounts which need to authorize it, the created entities, and what kind of transaction it
+/// is.
+///
+/// The summary is derived statically. The effects of calls are only known for some native
+/// methods, e.g. the withdrawals from accounts; the resources returned by any other call are
+/// unknown, so that depositing them results in an [`AccountDeposit::UnknownResources`].
+pub fn summarize_manifest(instructions: &[InstructionV1]) -> ManifestSummary {
+    let mut summarizer = ManifestSummarizer::default();
+    for (index, instruction) in instructions.iter().enumerate() {
+        summarizer.process_instruction(index, instruction);
+    }
+    summarizer.finish()
+}
+
+impl ResourceQuantity {
+    fn add(self, other: ResourceQuantity) -> ResourceQuantity {
+        match (self, other) {
+            (ResourceQuantity::Amount(a), ResourceQuantity::Amount(b)) => a
+                .checked_add(b)
+                .map(ResourceQuantity::Amount)
+                .unwrap_or(ResourceQuantity::Unknown),
+            (ResourceQuantity::Ids(mut a), ResourceQuantity::Ids(b)) => {
+                a.extend(b);
+                ResourceQuantity::Ids(a)
+            }
+            _ => ResourceQuantity::Unknown,
+        }
+    }
+
+    fn amount(&self) -> Option<Decimal> {
+        match self {
+            ResourceQuantity::Amount(amount) => Some(*amount),
+            ResourceQuantity::Ids(ids) => Some(Decimal::from(ids.len())),
+            ResourceQuantity::Unknown => None,
+        }
+    }
+
+    /// Returns the quantity remaining after taking the given quantity, or `None` if nothing
+    /// remains.
+    fn take(self, taken: &ResourceQuantity) -> Option<ResourceQuantity> {
+        let remaining = match (self, taken) {
+            (ResourceQuantity::Ids(mut ids), ResourceQuantity::Ids(taken_ids)) => {
+                ids.retain(|id| !taken_ids.contains(id));
+                ResourceQuantity::Ids(ids)
+            }
+            (ResourceQuantity::Amount(amount), taken) => match taken.amount() {
+                Some(taken_amount) => amount
+                    .checked_sub(taken_amount)
+                    .map(ResourceQuantity::Amount)
+                    .unwrap_or(ResourceQuantity::Unknown),
+                None => ResourceQuantity::Unknown,
+            },
+            _ => ResourceQuantity::Unknown,
+        };
+        match remaining.amount() {
+            Some(amount) if !amount.is_positive() => None,
+            _ => Some(remaining),
+        }
+    }
+}
+
+/// The kinds of interactions of a manifest which determine its [`ManifestClass`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Interaction {
+    AccountTransfer,
+    AccountDepositSettingsUpdate,
+    PoolContribution,
+    PoolRedemption,
+    ValidatorStake,
+    ValidatorUnstake,
+    ValidatorClaim,
+    Other,
+}
+
+#[derive(Default)]
+struct ManifestSummarizer {
+    id_allocator: ManifestIdAllocator,
+    /// The resources known to be on the worktop. The quantities are exact, unless
+    /// `worktop_may_contain_more` is set.
+    worktop: IndexMap<ResourceAddress, ResourceQuantity>,
+    /// Whether a call with unknown effects may have put (further) resources on the worktop since
+    /// it was last emptied.
+    worktop_may_contain_more: bool,
+    buckets: IndexMap<ManifestBucket, ResourceSpecifier>,
+    account_withdrawals: IndexMap<ComponentAddress, Vec<ResourceSpecifier>>,
+    account_deposits: IndexMap<ComponentAddress, Vec<AccountDeposit>>,
+    accounts_requiring_auth: IndexSet<ComponentAddress>,
+    presented_proofs: IndexMap<ComponentAddress, Vec<ResourceSpecifier>>,
+    new_entities: Vec<NewEntity>,
+    interactions: IndexSet<Interaction>,
+}
+
+impl ManifestSummarizer {
+    fn process_instruction(&mut self, index: usize, instruction: &InstructionV1) {
+        match instruction {
+            InstructionV1::TakeAllFromWorktop { resource_address } => {
+                let quantity = match self.worktop.swap_remove(resource_address) {
+                    Some(quantity) if !self.worktop_may_contain_more => quantity,
+                    None if !self.worktop_may_contain_more => {
+                        ResourceQuantity::Amount(Decimal::ZERO)
+                    }
+                    _ => ResourceQuantity::Unknown,
+                };
+                self.new_bucket(*resource_address, quantity);
+            }
+            InstructionV1::TakeFromWorktop {
+                resource_address,
+                amount,
+            } => {
+                let quantity = ResourceQuantity::Amount(*amount);
+                self.take_from_worktop(resource_address, &quantity);
+                self.new_bucket(*resource_address, quantity);
+            }
+            InstructionV1::TakeNonFungiblesFromWorktop {
+                resource_address,
+                ids,
+            } => {
+                let quantity = ResourceQuantity::Ids(ids.iter().cloned().collect());
+                self.take_from_worktop(resource_address, &quantity);
+                self.new_bucket(*resource_address, quantity);
+            }
+            InstructionV1::ReturnToWorktop { bucket_id } => {
+                if let Some(bucket) = self.buckets.swap_remove(bucket_id) {
+                    self.put_on_worktop(bucket);
+                }
+            }
+            InstructionV1::BurnResource { bucket_id } => {
+                self.buckets.swap_remove(bucket_id);
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::CallFunction {
+                package_address,
+                blueprint_name,
+                function_name,
+                args,
+            } => {
+                self.take_args(args);
+                self.worktop_may_contain_more = true;
+                if let DynamicPackageAddress::Static(package_address) = package_address {
+                    if let Some(entity_type) =
+                        created_entity_type(package_address, blueprint_name, function_name)
+                    {
+                        self.new_entities.push(NewEntity {
+                            instruction_index: index,
+                            entity_type,
+                        });
+                    }
+                }
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::CallMethod {
+                address,
+                method_name,
+                args,
+            } => match address {
+                DynamicGlobalAddress::Static(address) => {
+                    self.process_method_call(index, address, method_name, args)
+                }
+                DynamicGlobalAddress::Named(_) => {
+                    self.take_args(args);
+                    self.worktop_may_contain_more = true;
+                    self.interactions.insert(Interaction::Other);
+                }
+            },
+            InstructionV1::CallRoyaltyMethod { address, args, .. }
+            | InstructionV1::CallMetadataMethod { address, args, .. }
+            | InstructionV1::CallRoleAssignmentMethod { address, args, .. } => {
+                self.take_args(args);
+                // e.g. claiming royalties puts them on the worktop
+                self.worktop_may_contain_more = true;
+                if let Some(account) = static_account_address(address) {
+                    self.accounts_requiring_auth.insert(account);
+                }
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::CallDirectVaultMethod { args, .. }
+            | InstructionV1::YieldToChild { args, .. } => {
+                self.take_args(args);
+                self.worktop_may_contain_more = true;
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::YieldToParent { args } => {
+                self.take_args(args);
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::AllocateGlobalAddress { .. } => {
+                self.interactions.insert(Interaction::Other);
+            }
+            InstructionV1::CreateProofFromBucketOfAmount { .. }
+            | InstructionV1::CreateProofFromBucketOfNonFungibles { .. }
+            | InstructionV1::CreateProofFromBucketOfAll { .. }
+            | InstructionV1::CreateProofFromAuthZoneOfAmount { .. }
+            | InstructionV1::CreateProofFromAuthZoneOfNonFungibles { .. }
+            | InstructionV1::CreateProofFromAuthZoneOfAll { .. }
+            | InstructionV1::PopFromAuthZone
+            | InstructionV1::CloneProof { .. }
+            | InstructionV1::AssertWorktopContainsAny { .. }
+            | InstructionV1::AssertWorktopContains { .. }
+            | InstructionV1::AssertWorktopContainsNonFungibles { .. }
+            | InstructionV1::PushToAuthZone { .. }
+            | InstructionV1::DropAuthZoneProofs
+            | InstructionV1::DropAuthZoneRegularProofs
+            | InstructionV1::DropAuthZoneSignatureProofs
+            | InstructionV1::DropNamedProofs
+            | InstructionV1::DropProof { .. }
+            | InstructionV1::DropAllProofs => {}
+        }
+    }
+
+    fn process_method_call(
+        &mut self,
+        index: usize,
+        address: &GlobalAddress,
+        method_name: &str,
+        args: &ManifestValue,
+    ) {
+        if let Some(account) = static_account_address(&DynamicGlobalAddress::Static(*address)) {
+            self.process_account_method_call(account, method_name, args);
+            return;
+        }
+
+        let interaction = match (address.as_node_id().entity_type(), method_name) {
+            (Some(EntityType::GlobalOneResourcePool), ONE_RESOURCE_POOL_CONTRIBUTE_IDENT)
+            | (Some(EntityType::GlobalTwoResourcePool), TWO_RESOURCE_POOL_CONTRIBUTE_IDENT)
+            | (Some(EntityType::GlobalMultiResourcePool), MULTI_RESOURCE_POOL_CONTRIBUTE_IDENT) => {
+                Interaction::PoolContribution
+            }
+            (Some(EntityType::GlobalOneResourcePool), ONE_RESOURCE_POOL_REDEEM_IDENT)
+            | (Some(EntityType::GlobalTwoResourcePool), TWO_RESOURCE_POOL_REDEEM_IDENT)
+            | (Some(EntityType::GlobalMultiResourcePool), MULTI_RESOURCE_POOL_REDEEM_IDENT) => {
+                Interaction::PoolRedemption
+            }
+            (Some(EntityType::GlobalValidator), VALIDATOR_STAKE_IDENT) => {
+                Interaction::ValidatorStake
+            }
+            (Some(EntityType::GlobalValidator), VALIDATOR_UNSTAKE_IDENT) => {
+                Interaction::ValidatorUnstake
+            }
+            (Some(EntityType::GlobalValidator), VALIDATOR_CLAIM_XRD_IDENT) => {
+                Interaction::ValidatorClaim
+            }
+            (
+                Some(EntityType::GlobalConsensusManager),
+                CONSENSUS_MANAGER_CREATE_VALIDATOR_IDENT,
+            ) => {
+                self.new_entities.push(NewEntity {
+                    instruction_index: index,
+                    entity_type: EntityType::GlobalValidator,
+                });
+                Interaction::Other
+            }
+            _ => Interaction::Other,
+        };
+        self.take_args(args);
+        self.worktop_may_contain_more = true;
+        self.interactions.insert(interaction);
+    }
+
+    fn process_account_method_call(
+        &mut self,
+        account: ComponentAddress,
+        method_name: &str,
+        args: &ManifestValue,
+    ) {
+        let interaction = match method_name {
+            ACCOUNT_LOCK_FEE_IDENT | ACCOUNT_LOCK_CONTINGENT_FEE_IDENT => {
+                self.accounts_requiring_auth.insert(account);
+                return;
+            }
+            ACCOUNT_WITHDRAW_IDENT => {
+                let withdrawn = decode_args::<AccountWithdrawInput>(args).map(|input| {
+                    (
+                        input.resource_address,
+                        ResourceQuantity::Amount(input.amount),
+                    )
+                });
+                self.withdraw(account, withdrawn);
+                Interaction::AccountTransfer
+            }
+            ACCOUNT_WITHDRAW_NON_FUNGIBLES_IDENT => {
+                let withdrawn = decode_args::<AccountWithdrawNonFungiblesInput>(args)
+                    .map(|input| (input.resource_address, ResourceQuantity::Ids(input.ids)));
+                self.withdraw(account, withdrawn);
+                Interaction::AccountTransfer
+            }
+            ACCOUNT_LOCK_FEE_AND_WITHDRAW_IDENT => {
+                let withdrawn = decode_args::<AccountLockFeeAndWithdrawInput>(args).map(|input| {
+                    (
+                        input.resource_address,
+                        ResourceQuantity::Amount(input.amount),
+                    )
+                });
+                self.withdraw(account, withdrawn);
+                Interaction::AccountTransfer
+            }
+            ACCOUNT_LOCK_FEE_AND_WITHDRAW_NON_FUNGIBLES_IDENT => {
+                let withdrawn = decode_args::<AccountLockFeeAndWithdrawNonFungiblesInput>(args)
+                    .map(|input| (input.resource_address, ResourceQuantity::Ids(input.ids)));
+                self.withdraw(account, withdrawn);
+                Interaction::AccountTransfer
+            }
+            ACCOUNT_CREATE_PROOF_OF_AMOUNT_IDENT => {
+                self.accounts_requiring_auth.insert(account);
+                if let Some(input) = decode_args::<AccountCreateProofOfAmountInput>(args) {
+                    self.presented_proofs
+                        .entry(account)
+                        .or_default()
+                        .push(ResourceSpecifier {
+                            resource_address: input.resource_address,
+                            quantity: ResourceQuantity::Amount(input.amount),
+                        });
+                }
+                return;
+            }
+            ACCOUNT_CREATE_PROOF_OF_NON_FUNGIBLES_IDENT => {
+                self.accounts_requiring_auth.insert(account);
+                if let Some(input) = decode_args::<AccountCreateProofOfNonFungiblesInput>(args) {
+                    self.presented_proofs
+                        .entry(account)
+                        .or_default()
+                        .push(ResourceSpecifier {
+                            resource_address: input.resource_address,
+                            quantity: ResourceQuantity::Ids(input.ids),
+                        });
+                }
+                return;
+            }
+            ACCOUNT_DEPOSIT_IDENT
+            | ACCOUNT_DEPOSIT_BATCH_IDENT
+            | ACCOUNT_TRY_DEPOSIT_OR_ABORT_IDENT
+            | ACCOUNT_TRY_DEPOSIT_BATCH_OR_ABORT_IDENT
+            | ACCOUNT_TRY_DEPOSIT_OR_REFUND_IDENT
+            | ACCOUNT_TRY_DEPOSIT_BATCH_OR_REFUND_IDENT => {
+                if method_name == ACCOUNT_DEPOSIT_IDENT
+                    || method_name == ACCOUNT_DEPOSIT_BATCH_IDENT
+                {
+                    self.accounts_requiring_auth.insert(account);
+                }
+                let deposits = self.take_args(args);
+                self.account_deposits
+                    .entry(account)
+                    .or_default()
+                    .extend(deposits);
+                Interaction::AccountTransfer
+            }
+            ACCOUNT_SET_DEFAULT_DEPOSIT_RULE_IDENT
+            | ACCOUNT_SET_RESOURCE_PREFERENCE_IDENT
+            | ACCOUNT_REMOVE_RESOURCE_PREFERENCE_IDENT
+            | ACCOUNT_ADD_AUTHORIZED_DEPOSITOR
+            | ACCOUNT_REMOVE_AUTHORIZED_DEPOSITOR => {
+                self.accounts_requiring_auth.insert(account);
+                Interaction::AccountDepositSettingsUpdate
+            }
+            _ => {
+                self.accounts_requiring_auth.insert(account);
+                self.take_args(args);
+                self.worktop_may_contain_more = true;
+                Interaction::Other
+            }
+        };
+        self.interactions.insert(interaction);
+    }
+
+    fn finish(self) -> ManifestSummary {
+        let classification = classify(&self.interactions);
+        ManifestSummary {
+            account_withdrawals: self.account_withdrawals,
+            account_deposits: self.account_deposits,
+            accounts_requiring_auth: self.accounts_requiring_auth,
+            presented_proofs: self.presented_proofs,
+            new_entities: self.new_entities,
+            classification,
+        }
+    }
+
+    fn withdraw(
+        &mut self,
+        account: ComponentAddress,
+        withdrawn: Option<(ResourceAddress, ResourceQuantity)>,
+    ) {
+        self.accounts_requiring_auth.insert(account);
+        match withdrawn {
+            Some((resource_address, quantity)) => {
+                let withdrawn = ResourceSpecifier {
+                    resource_address,
+                    quantity,
+                };
+                self.account_withdrawals
+                    .entry(account)
+                    .or_default()
+                    .push(withdrawn.clone());
+                self.put_on_worktop(withdrawn);
+            }
+            None => self.worktop_may_contain_more = true,
+        }
+    }
+
+    fn take_from_worktop(&mut self, resource_address: &ResourceAddress, taken: &ResourceQuantity) {
+        if let Some(quantity) = self.worktop.swap_remove(resource_address) {
+            if let Some(remaining) = quantity.take(taken) {
+                self.worktop.insert(*resource_address, remaining);
+            }
+        }
+    }
+
+    fn put_on_worktop(&mut self, resource: ResourceSpecifier) {
+        let quantity = match self.worktop.swap_remove(&resource.resource_address) {
+            Some(quantity) => quantity.add(resource.quantity),
+            None => resource.quantity,
+        };
+        self.worktop.insert(resource.resource_address, quantity);
+    }
+
+    fn new_bucket(&mut self, resource_address: ResourceAddress, quantity: ResourceQuantity) {
+        let bucket_id = self.id_allocator.new_bucket_id();
+        self.buckets.insert(
+            bucket_id,
+            ResourceSpecifier {
+                resource_address,
+                quantity,
+            },
+        );
+    }
+
+    /// Takes the buckets passed as the arguments of a call, and the whole worktop if
+    /// `Expression("ENTIRE_WORKTOP")` is passed, and returns the taken resources.
+    fn take_args(&mut self, value: &ManifestValue) -> Vec<AccountDeposit> {
+        let mut taken = Vec::new();
+        self.take_args_into(value, &mut taken);
+        taken
+    }
+
+    fn take_args_into(&mut self, value: &ManifestValue, taken: &mut Vec<AccountDeposit>) {
+        match value {
+            Value::Custom {
+                value: ManifestCustomValue::Bucket(bucket_id),
+            } => {
+                if let Some(bucket) = self.buckets.swap_remove(bucket_id) {
+                    taken.push(AccountDeposit::Resource(bucket));
+                }
+            }
+            Value::Custom {
+                value: ManifestCustomValue::Expression(ManifestExpression::EntireWorktop),
+            } => {
+                for (resource_address, quantity) in self.worktop.drain(..) {
+                    let quantity = if self.worktop_may_contain_more {
+                        ResourceQuantity::Unknown
+                    } else {
+                        quantity
+                    };
+                    taken.push(AccountDeposit::Resource(ResourceSpecifier {
+                        resource_address,
+                        quantity,
+                    }));
+                }
+                if self.worktop_may_contain_more {
+                    taken.push(AccountDeposit::UnknownResources);
+                }
+                self.worktop_may_contain_more = false;
+            }
+            Value::Enum { fields, .. } | Value::Tuple { fields } => {
+                for field in fields {
+                    self.take_args_into(field, taken);
+                }
+            }
+            Value::Array { elements, .. } => {
+                for element in elements {
+                    self.take_args_into(element, taken);
+                }
+            }
+            Value::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.take_args_into(key, taken);
+                    self.take_args_into(value, taken);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn static_account_address(address: &DynamicGlobalAddress) -> Option<ComponentAddress> {
+    match address {
+        DynamicGlobalAddress::Static(address) => match address.as_node_id().entity_type() {
+            Some(
+                EntityType::GlobalAccount
+                | EntityType::GlobalVirtualSecp256k1Account
+                | EntityType::GlobalVirtualEd25519Account,
+            ) => ComponentAddress::try_from(*address).ok(),
+            _ => None,
+        },
+        DynamicGlobalAddress::Named(_) => None,
+    }
+}
+
+/// Returns the type of the entity created by the given native function, if it creates one.
+fn created_entity_type(
+    package_address: &PackageAddress,
+    blueprint_name: &str,
+    function_name: &str,
+) -> Option<EntityType> {
+    let entity_type = match (*package_address, blueprint_name, function_name) {
+        (
+            PACKAGE_PACKAGE,
+            PACKAGE_BLUEPRINT,
+            PACKAGE_PUBLISH_WASM_IDENT
+            | PACKAGE_PUBLISH_WASM_ADVANCED_IDENT
+            | PACKAGE_PUBLISH_NATIVE_IDENT,
+        ) => EntityType::GlobalPackage,
+        (
+            RESOURCE_PACKAGE,
+            FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT,
+            FUNGIBLE_RESOURCE_MANAGER_CREATE_IDENT
+            | FUNGIBLE_RESOURCE_MANAGER_CREATE_WITH_INITIAL_SUPPLY_IDENT,
+        ) => EntityType::GlobalFungibleResourceManager,
+        (
+            RESOURCE_PACKAGE,
+            NON_FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT,
+            NON_FUNGIBLE_RESOURCE_MANAGER_CREATE_IDENT
+            | NON_FUNGIBLE_RESOURCE_MANAGER_CREATE_WITH_INITIAL_SUPPLY_IDENT
+            | NON_FUNGIBLE_RESOURCE_MANAGER_CREATE_RUID_WITH_INITIAL_SUPPLY_IDENT,
+        ) => EntityType::GlobalNonFungibleResourceManager,
+        (
+            ACCOUNT_PACKAGE,
+            ACCOUNT_BLUEPRINT,
+            ACCOUNT_CREATE_IDENT | ACCOUNT_CREATE_ADVANCED_IDENT,
+        ) => EntityType::GlobalAccount,
+        (
+            IDENTITY_PACKAGE,
+            IDENTITY_BLUEPRINT,
+            IDENTITY_CREATE_IDENT | IDENTITY_CREATE_ADVANCED_IDENT,
+        ) => EntityType::GlobalIdentity,
+        (
+            ACCESS_CONTROLLER_PACKAGE,
+            ACCESS_CONTROLLER_BLUEPRINT,
+            ACCESS_CONTROLLER_CREATE_IDENT,
+        ) => EntityType::GlobalAccessController,
+        (POOL_PACKAGE, ONE_RESOURCE_POOL_BLUEPRINT, ONE_RESOURCE_POOL_INSTANTIATE_IDENT) => {
+            EntityType::GlobalOneResourcePool
+        }
+        (POOL_PACKAGE, TWO_RESOURCE_POOL_BLUEPRINT, TWO_RESOURCE_POOL_INSTANTIATE_IDENT) => {
+            EntityType::GlobalTwoResourcePool
+        }
+        (POOL_PACKAGE, MULTI_RESOURCE_POOL_BLUEPRINT, MULTI_RESOURCE_POOL_INSTANTIATE_IDENT) => {
+            EntityType::GlobalMultiResourcePool
+        }
+        _ => return None,
+    };
+    Some(entity_type)
+}
+
+fn classify(interactions: &IndexSet<Interaction>) -> ManifestClass {
+    if interactions.contains(&Interaction::Other) {
+        return ManifestClass::General;
+    }
+    let has_transfers = interactions.contains(&Interaction::AccountTransfer);
+    let has_settings_updates = interactions.contains(&Interaction::AccountDepositSettingsUpdate);
+    let mut specific_classes = interactions
+        .iter()
+        .filter_map(|interaction| match interaction {
+            Interaction::PoolContribution => Some(ManifestClass::PoolContribution),
+            Interaction::PoolRedemption => Some(ManifestClass::PoolRedemption),
+            Interaction::ValidatorStake => Some(ManifestClass::ValidatorStake),
+            Interaction::ValidatorUnstake => Some(ManifestClass::ValidatorUnstake),
+            Interaction::ValidatorClaim => Some(ManifestClass::ValidatorClaim),
+            _ => None,
+        });
+    match (specific_classes.next(), specific_classes.next()) {
+        // The withdrawals and deposits around e.g. a contribution to a pool are part of it
+        (Some(class), None) if !has_settings_updates => class,
+        (None, None) if has_transfers && !has_settings_updates => ManifestClass::Transfer,
+        (None, None) if has_settings_updates && !has_transfers => {
+            ManifestClass::AccountDepositSettingsUpdate
+        }
+        _ => ManifestClass::General,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_engine_interface::api::node_modules::ModuleConfig;
+
+    fn account(key: u64) -> ComponentAddress {
+        ComponentAddress::virtual_account_from_public_key(
+            &Secp256k1PrivateKey::from_u64(key).unwrap().public_key(),
+        )
+    }
+
+    fn xrd(amount: u32) -> ResourceSpecifier {
+        ResourceSpecifier {
+            resource_address: XRD,
+            quantity: ResourceQuantity::Amount(Decimal::from(amount)),
+        }
+    }
+
+    #[test]
+    fn test_transfer_is_summarized() {
+        let manifest = ManifestBuilder::new()
+            .lock_fee(account(1), 10)
+            .withdraw_from_account(account(1), XRD, 10)
+            .take_from_worktop(XRD, 4, "bucket")
+            .try_deposit_or_abort(account(2), None, "bucket")
+            .try_deposit_entire_worktop_or_abort(account(3), None)
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(summary.classification, ManifestClass::Transfer);
+        assert_eq!(
+            summary.account_withdrawals,
+            indexmap!(account(1) => vec![xrd(10)])
+        );
+        assert_eq!(
+            summary.account_deposits,
+            indexmap!(
+                account(2) => vec![AccountDeposit::Resource(xrd(4))],
+                account(3) => vec![AccountDeposit::Resource(xrd(6))],
+            )
+        );
+        assert_eq!(summary.accounts_requiring_auth, indexset!(account(1)));
+        assert!(summary.presented_proofs.is_empty());
+        assert!(summary.new_entities.is_empty());
+    }
+
+    #[test]
+    fn test_non_fungible_withdrawal_and_proofs_are_summarized() {
+        let ids = indexset!(
+            NonFungibleLocalId::integer(1),
+            NonFungibleLocalId::integer(2)
+        );
+        let manifest = ManifestBuilder::new()
+            .create_proof_from_account_of_amount(account(2), ACCOUNT_OWNER_BADGE, 1)
+            .withdraw_non_fungibles_from_account(account(1), ACCOUNT_OWNER_BADGE, ids)
+            .take_non_fungibles_from_worktop(
+                ACCOUNT_OWNER_BADGE,
+                [NonFungibleLocalId::integer(1)],
+                "bucket",
+            )
+            .try_deposit_or_abort(account(2), None, "bucket")
+            .try_deposit_entire_worktop_or_abort(account(3), None)
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(summary.classification, ManifestClass::Transfer);
+        assert_eq!(
+            summary.account_deposits,
+            indexmap!(
+                account(2) => vec![AccountDeposit::Resource(ResourceSpecifier {
+                    resource_address: ACCOUNT_OWNER_BADGE,
+                    quantity: ResourceQuantity::Ids(indexset!(NonFungibleLocalId::integer(1))),
+                })],
+                account(3) => vec![AccountDeposit::Resource(ResourceSpecifier {
+                    resource_address: ACCOUNT_OWNER_BADGE,
+                    quantity: ResourceQuantity::Ids(indexset!(NonFungibleLocalId::integer(2))),
+                })],
+            )
+        );
+        assert_eq!(
+            summary.presented_proofs,
+            indexmap!(account(2) => vec![ResourceSpecifier {
+                resource_address: ACCOUNT_OWNER_BADGE,
+                quantity: ResourceQuantity::Amount(Decimal::ONE),
+            }])
+        );
+        assert_eq!(
+            summary.accounts_requiring_auth,
+            indexset!(account(2), account(1))
+        );
+    }
+
+    #[test]
+    fn test_validator_stake_is_classified() {
+        let validator = ComponentAddress::new_or_panic(
+            NodeId::new(EntityType::GlobalValidator as u8, &[1; NodeId::RID_LENGTH]).0,
+        );
+        let manifest = ManifestBuilder::new()
+            .lock_fee(account(1), 10)
+            .withdraw_from_account(account(1), XRD, 100)
+            .take_all_from_worktop(XRD, "xrd")
+            .call_method_with_name_lookup(validator, VALIDATOR_STAKE_IDENT, |lookup| {
+                (lookup.bucket("xrd"),)
+            })
+            .try_deposit_entire_worktop_or_abort(account(1), None)
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(summary.classification, ManifestClass::ValidatorStake);
+        assert_eq!(
+            summary.account_deposits,
+            indexmap!(account(1) => vec![AccountDeposit::UnknownResources])
+        );
+    }
+
+    #[test]
+    fn test_claimed_royalties_make_worktop_deposit_amounts_unknown() {
+        let manifest = ManifestBuilder::new()
+            .withdraw_from_account(account(1), XRD, 10)
+            .claim_component_royalties(account(1))
+            .try_deposit_entire_worktop_or_abort(account(2), None)
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(
+            summary.account_deposits,
+            indexmap!(account(2) => vec![
+                AccountDeposit::Resource(ResourceSpecifier {
+                    resource_address: XRD,
+                    quantity: ResourceQuantity::Unknown,
+                }),
+                AccountDeposit::UnknownResources,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_entity_creation_is_summarized() {
+        let manifest = ManifestBuilder::new()
+            .lock_fee(account(1), 10)
+            .new_account()
+            .create_fungible_resource(
+                OwnerRole::None,
+                false,
+                18,
+                FungibleResourceRoles::default(),
+                metadata!(),
+                Some(dec!("100")),
+            )
+            .try_deposit_entire_worktop_or_abort(account(1), None)
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(summary.classification, ManifestClass::General);
+        assert_eq!(
+            summary.new_entities,
+            vec![
+                NewEntity {
+                    instruction_index: 1,
+                    entity_type: EntityType::GlobalAccount,
+                },
+                NewEntity {
+                    instruction_index: 2,
+                    entity_type: EntityType::GlobalFungibleResourceManager,
+                },
+            ]
+        );
+        assert_eq!(
+            summary.account_deposits,
+            indexmap!(account(1) => vec![AccountDeposit::UnknownResources])
+        );
+    }
+
+    #[test]
+    fn test_account_settings_update_is_classified() {
+        let manifest = ManifestBuilder::new()
+            .call_method(
+                account(1),
+                ACCOUNT_SET_DEFAULT_DEPOSIT_RULE_IDENT,
+                manifest_args!(DefaultDepositRule::Reject),
+            )
+            .build();
+
+        let summary = summarize_manifest(&manifest.instructions);
+
+        assert_eq!(
+            summary.classification,
+            ManifestClass::AccountDepositSettingsUpdate
+        );
+        assert_eq!(summary.accounts_requiring_auth, indexset!(account(1)));
+    }
+}
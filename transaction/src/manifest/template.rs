@@ -0,0 +1,829 @@
+use crate::data::{format_manifest_value, ManifestDecompilationDisplayContext};
+use crate::internal_prelude::*;
+use crate::manifest::ast::ValueKind;
+use crate::manifest::lexer::{tokenize, LexerError, Span, Token, TokenKind};
+use crate::manifest::parser::{Parser, ParserError, ParserErrorKind, TokenType, PARSER_MAX_DEPTH};
+
+/// The constructors whose string argument names a manifest object, e.g. `Bucket("my_bucket")`.
+/// These names are namespaced when the fragment declaring them is included by `USE`.
+const NAMED_OBJECT_CONSTRUCTORS: [&str; 4] =
+    ["Bucket", "Proof", "AddressReservation", "NamedAddress"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    LexerError(LexerError),
+    ParserError(ParserError),
+    /// A `PARAM` declaration follows an instruction or a `USE`.
+    MisplacedParameterDeclaration(Span),
+    DuplicateParameter(String, Span),
+    UndeclaredParameter(String, Span),
+    /// The path of a `USE` is not a string literal, or its namespace not an identifier.
+    InvalidInclude(Span),
+    IncludeNotFound {
+        path: String,
+        span: Span,
+    },
+    CyclicInclude {
+        path: String,
+        span: Span,
+    },
+    /// An error in the fragment included by the `USE` at the given span.
+    IncludeError {
+        path: String,
+        span: Span,
+        error: Box<TemplateError>,
+    },
+    /// A parameter is not given a value, either by a `USE` (at the given span) or when
+    /// instantiating the template.
+    MissingArgument(String, Option<Span>),
+    /// A value is given for a parameter which is not declared, either by a `USE` (at the given
+    /// span) or when instantiating the template.
+    UnknownArgument(String, Option<Span>),
+    MismatchedArgumentKind {
+        name: String,
+        expected: ManifestValueKind,
+        actual: ManifestValueKind,
+        span: Option<Span>,
+    },
+    /// The value given for the parameter can't be written in a manifest, e.g. because it contains
+    /// a bucket, a proof or an address reservation, which a manifest can only refer to by name.
+    InvalidArgument(String),
+    CompileError(CompileError),
+}
+
+/// Provides the sources of the manifest fragments included by templates.
+pub trait TemplateSourceProvider {
+    /// Returns the source of the fragment included by `USE "<path>"`, if there is one.
+    fn get_source(&self, path: &str) -> Option<String>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryTemplateSources {
+    sources: IndexMap<String, String>,
+}
+
+impl InMemoryTemplateSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source<P: Into<String>, S: Into<String>>(mut self, path: P, source: S) -> Self {
+        self.add_source(path, source);
+        self
+    }
+
+    pub fn add_source<P: Into<String>, S: Into<String>>(&mut self, path: P, source: S) {
+        self.sources.insert(path.into(), source.into());
+    }
+}
+
+impl TemplateSourceProvider for InMemoryTemplateSources {
+    fn get_source(&self, path: &str) -> Option<String> {
+        self.sources.get(path).cloned()
+    }
+}
+
+/// Reads the included fragments from files, with paths relative to the given base directory
+/// (regardless of the directory of the including file).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FileSystemTemplateSources {
+    base_directory: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileSystemTemplateSources {
+    pub fn new<P: Into<std::path::PathBuf>>(base_directory: P) -> Self {
+        Self {
+            base_directory: base_directory.into(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TemplateSourceProvider for FileSystemTemplateSources {
+    fn get_source(&self, path: &str) -> Option<String> {
+        std::fs::read_to_string(self.base_directory.join(path)).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateParameter {
+    pub name: String,
+    pub value_kind: ValueKind,
+    /// The span of the parameter's declaration.
+    pub span: Span,
+}
+
+/// A manifest with typed parameters, which is instantiated into a manifest by giving a value for
+/// each parameter.
+///
+/// A template is a manifest which may start with parameter declarations, and use the declared
+/// parameters in place of values. It may also include other templates ("fragments"), giving values
+/// for their parameters:
+///
+/// ```text
+/// PARAM $account Address;
+/// PARAM $amount Decimal;
+/// USE "withdraw.rtm" AS withdraw ($account => $account, $amount => $amount);
+/// CALL_METHOD $account "deposit" Bucket("withdraw.bucket");
+/// ```
+///
+/// The instructions of an included fragment are inlined in place of the `USE`, with the names of
+/// the buckets, proofs, address reservations and named addresses declared by the fragment
+/// prefixed by the given namespace (e.g. `Bucket("bucket")` becomes `Bucket("withdraw.bucket")`).
+/// The values given for the fragment's parameters are either a parameter of the including
+/// template, or a value (which may refer to the objects of the including template, e.g.
+/// `$bucket => Bucket("my_bucket")`).
+///
+/// The spans of the instantiated manifest's instructions (e.g. in a [`CompileError`]) refer to the
+/// template's source: the values given for parameters take the span of the parameter, and the
+/// instructions of included fragments the span of their `USE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestTemplate {
+    parameters: Vec<TemplateParameter>,
+    /// The tokens of the instructions, with the included fragments inlined. The template's
+    /// parameters are still left in place.
+    tokens: Vec<Token>,
+}
+
+impl ManifestTemplate {
+    pub fn parse<S>(s: &str, sources: &S) -> Result<Self, TemplateError>
+    where
+        S: TemplateSourceProvider,
+    {
+        Self::parse_fragment(s, sources, &mut Vec::new())
+    }
+
+    pub fn parameters(&self) -> &[TemplateParameter] {
+        &self.parameters
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&TemplateParameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Instantiates the template with the given values of its parameters, and compiles the
+    /// resulting manifest.
+    pub fn instantiate<B>(
+        &self,
+        arguments: &IndexMap<String, ManifestValue>,
+        network: &NetworkDefinition,
+        blobs: B,
+    ) -> Result<TransactionManifestV1, TemplateError>
+    where
+        B: IsBlobProvider,
+    {
+        for name in arguments.keys() {
+            if self.parameter(name).is_none() {
+                return Err(TemplateError::UnknownArgument(name.clone(), None));
+            }
+        }
+
+        let address_bech32_encoder = AddressBech32Encoder::new(network);
+        let context = ManifestDecompilationDisplayContext::with_optional_bech32(Some(
+            &address_bech32_encoder,
+        ));
+        let mut argument_tokens = IndexMap::new();
+        for parameter in &self.parameters {
+            let value = arguments
+                .get(&parameter.name)
+                .ok_or_else(|| TemplateError::MissingArgument(parameter.name.clone(), None))?;
+            if refers_to_named_object(value) {
+                return Err(TemplateError::InvalidArgument(parameter.name.clone()));
+            }
+            let mut formatted = String::new();
+            format_manifest_value(&mut formatted, value, &context, false, 0)
+                .map_err(|_| TemplateError::InvalidArgument(parameter.name.clone()))?;
+            let tokens = tokenize(&formatted)
+                .map_err(|_| TemplateError::InvalidArgument(parameter.name.clone()))?;
+            let actual = value_kind_of(&tokens, &[])
+                .map_err(|_| TemplateError::InvalidArgument(parameter.name.clone()))?
+                .expect("Formatted values contain no parameters");
+            check_argument_kind(parameter, actual, None)?;
+            argument_tokens.insert(parameter.name.clone(), tokens);
+        }
+
+        let tokens = substitute_parameters(&self.tokens, &argument_tokens, false);
+        let instructions = Parser::new(tokens, PARSER_MAX_DEPTH)
+            .parse_manifest()
+            .map_err(|error| TemplateError::CompileError(CompileError::ParserError(error)))?;
+        generator::generate_manifest(&instructions, &AddressBech32Decoder::new(network), blobs)
+            .map_err(|error| TemplateError::CompileError(CompileError::GeneratorError(error)))
+    }
+
+    fn parse_fragment<S>(
+        s: &str,
+        sources: &S,
+        include_stack: &mut Vec<String>,
+    ) -> Result<Self, TemplateError>
+    where
+        S: TemplateSourceProvider,
+    {
+        let tokens = tokenize(s).map_err(TemplateError::LexerError)?;
+        let mut parser = Parser::new(tokens, PARSER_MAX_DEPTH);
+        let mut parameters = Vec::<TemplateParameter>::new();
+        let mut tokens = Vec::new();
+
+        while !parser.is_eof() {
+            let token = parser.peek().map_err(TemplateError::ParserError)?;
+            match &token.kind {
+                TokenKind::Ident(ident) if ident == "PARAM" => {
+                    if !tokens.is_empty() {
+                        return Err(TemplateError::MisplacedParameterDeclaration(token.span));
+                    }
+                    let parameter = parse_parameter_declaration(&mut parser)
+                        .map_err(TemplateError::ParserError)?;
+                    if parameters.iter().any(|p| p.name == parameter.name) {
+                        return Err(TemplateError::DuplicateParameter(
+                            parameter.name,
+                            parameter.span,
+                        ));
+                    }
+                    parameters.push(parameter);
+                }
+                TokenKind::Ident(ident) if ident == "USE" => {
+                    let include = parse_include(&mut parser, &parameters)?;
+                    tokens.extend(inline_include(
+                        include,
+                        &parameters,
+                        sources,
+                        include_stack,
+                    )?);
+                }
+                _ => loop {
+                    let token = parser.advance().map_err(TemplateError::ParserError)?;
+                    check_parameters_declared(core::slice::from_ref(&token), &parameters)?;
+                    let is_end_of_instruction = token.kind == TokenKind::Semicolon;
+                    tokens.push(token);
+                    if is_end_of_instruction {
+                        break;
+                    }
+                },
+            }
+        }
+
+        Ok(Self { parameters, tokens })
+    }
+}
+
+/// A parsed `USE "<path>" AS <namespace> (<arguments>);` statement.
+struct Include {
+    path: String,
+    namespace: String,
+    /// The values given for the included fragment's parameters, and the spans of their names.
+    arguments: IndexMap<String, (Vec<Token>, Span)>,
+    span: Span,
+}
+
+fn parse_parameter_declaration(parser: &mut Parser) -> Result<TemplateParameter, ParserError> {
+    let start = parser.advance()?.span.start;
+    let (name, _) = expect_parameter(parser)?;
+    let value_kind = parser.parse_type()?;
+    let end = expect_token(parser, TokenKind::Semicolon)?.span.end;
+    Ok(TemplateParameter {
+        name,
+        value_kind,
+        span: Span { start, end },
+    })
+}
+
+fn parse_include(
+    parser: &mut Parser,
+    parameters: &[TemplateParameter],
+) -> Result<Include, TemplateError> {
+    let start = parser
+        .advance()
+        .map_err(TemplateError::ParserError)?
+        .span
+        .start;
+    let token = parser.advance().map_err(TemplateError::ParserError)?;
+    let path = match token.kind {
+        TokenKind::StringLiteral(path) => path,
+        _ => return Err(TemplateError::InvalidInclude(token.span)),
+    };
+    expect_token(parser, TokenKind::Ident("AS".to_string())).map_err(TemplateError::ParserError)?;
+    let token = parser.advance().map_err(TemplateError::ParserError)?;
+    let namespace = match token.kind {
+        TokenKind::Ident(namespace) => namespace,
+        _ => return Err(TemplateError::InvalidInclude(token.span)),
+    };
+
+    let mut arguments = IndexMap::new();
+    if parser.peek().map_err(TemplateError::ParserError)?.kind == TokenKind::OpenParenthesis {
+        parser.advance().map_err(TemplateError::ParserError)?;
+        while parser.peek().map_err(TemplateError::ParserError)?.kind != TokenKind::CloseParenthesis
+        {
+            let (name, span) = expect_parameter(parser).map_err(TemplateError::ParserError)?;
+            expect_token(parser, TokenKind::FatArrow).map_err(TemplateError::ParserError)?;
+            let value = parse_argument_tokens(parser).map_err(TemplateError::ParserError)?;
+            check_parameters_declared(&value, parameters)?;
+            arguments.insert(name, (value, span));
+            if parser.peek().map_err(TemplateError::ParserError)?.kind
+                != TokenKind::CloseParenthesis
+            {
+                expect_token(parser, TokenKind::Comma).map_err(TemplateError::ParserError)?;
+            }
+        }
+        parser.advance().map_err(TemplateError::ParserError)?;
+    }
+    let end = expect_token(parser, TokenKind::Semicolon)
+        .map_err(TemplateError::ParserError)?
+        .span
+        .end;
+
+    Ok(Include {
+        path,
+        namespace,
+        arguments,
+        span: Span { start, end },
+    })
+}
+
+/// Returns the tokens of the included fragment's instructions, with its objects namespaced and its
+/// parameters replaced by the given values.
+fn inline_include<S>(
+    include: Include,
+    parameters: &[TemplateParameter],
+    sources: &S,
+    include_stack: &mut Vec<String>,
+) -> Result<Vec<Token>, TemplateError>
+where
+    S: TemplateSourceProvider,
+{
+    let Include {
+        path,
+        namespace,
+        arguments,
+        span,
+    } = include;
+    if include_stack.contains(&path) {
+        return Err(TemplateError::CyclicInclude { path, span });
+    }
+    let source = match sources.get_source(&path) {
+        Some(source) => source,
+        None => return Err(TemplateError::IncludeNotFound { path, span }),
+    };
+    include_stack.push(path.clone());
+    let fragment =
+        ManifestTemplate::parse_fragment(&source, sources, include_stack).map_err(|error| {
+            TemplateError::IncludeError {
+                path: path.clone(),
+                span,
+                error: Box::new(error),
+            }
+        })?;
+    include_stack.pop();
+
+    for (name, (_, argument_span)) in &arguments {
+        if fragment.parameter(name).is_none() {
+            return Err(TemplateError::UnknownArgument(
+                name.clone(),
+                Some(*argument_span),
+            ));
+        }
+    }
+    let mut argument_tokens = IndexMap::new();
+    for parameter in &fragment.parameters {
+        let (tokens, argument_span) = arguments
+            .get(&parameter.name)
+            .ok_or_else(|| TemplateError::MissingArgument(parameter.name.clone(), Some(span)))?;
+        if let Some(actual) =
+            value_kind_of(tokens, parameters).map_err(TemplateError::ParserError)?
+        {
+            check_argument_kind(parameter, actual, Some(*argument_span))?;
+        }
+        argument_tokens.insert(parameter.name.clone(), tokens.clone());
+    }
+
+    let tokens = fragment
+        .tokens
+        .into_iter()
+        .map(|token| Token { span, ..token })
+        .collect::<Vec<_>>();
+    let tokens = namespace_objects(tokens, &namespace);
+    Ok(substitute_parameters(&tokens, &argument_tokens, true))
+}
+
+fn expect_parameter(parser: &mut Parser) -> Result<(String, Span), ParserError> {
+    let token = parser.advance()?;
+    match token.kind {
+        TokenKind::Parameter(name) => Ok((name, token.span)),
+        _ => Err(ParserError {
+            span: token.span,
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Parameter,
+                actual: token,
+            },
+        }),
+    }
+}
+
+fn expect_token(parser: &mut Parser, expected: TokenKind) -> Result<Token, ParserError> {
+    let token = parser.advance()?;
+    if token.kind != expected {
+        return Err(ParserError {
+            span: token.span,
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Exact(expected),
+                actual: token,
+            },
+        });
+    }
+    Ok(token)
+}
+
+/// Returns the tokens up to the `,` or `)` ending the value given for a parameter by a `USE`.
+fn parse_argument_tokens(parser: &mut Parser) -> Result<Vec<Token>, ParserError> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        let token = parser.peek()?;
+        match token.kind {
+            TokenKind::OpenParenthesis | TokenKind::LessThan => depth += 1,
+            TokenKind::CloseParenthesis | TokenKind::GreaterThan if depth > 0 => depth -= 1,
+            TokenKind::CloseParenthesis | TokenKind::Comma if depth == 0 => break,
+            _ => {}
+        }
+        tokens.push(parser.advance()?);
+    }
+    if tokens.is_empty() {
+        let token = parser.peek()?;
+        return Err(ParserError {
+            span: token.span,
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Value,
+                actual: token,
+            },
+        });
+    }
+    Ok(tokens)
+}
+
+fn check_parameters_declared(
+    tokens: &[Token],
+    parameters: &[TemplateParameter],
+) -> Result<(), TemplateError> {
+    for token in tokens {
+        if let TokenKind::Parameter(name) = &token.kind {
+            if !parameters.iter().any(|parameter| &parameter.name == name) {
+                return Err(TemplateError::UndeclaredParameter(name.clone(), token.span));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the kind of the value written by the given tokens, which is either a single parameter
+/// or a whole value. The kind is unknown for values containing parameters (e.g.
+/// `Array<Decimal>($amount)`).
+fn value_kind_of(
+    tokens: &[Token],
+    parameters: &[TemplateParameter],
+) -> Result<Option<ManifestValueKind>, ParserError> {
+    if let [Token {
+        kind: TokenKind::Parameter(name),
+        ..
+    }] = tokens
+    {
+        return Ok(parameters
+            .iter()
+            .find(|parameter| &parameter.name == name)
+            .map(|parameter| parameter.value_kind.value_kind()));
+    }
+    if tokens
+        .iter()
+        .any(|token| matches!(token.kind, TokenKind::Parameter(_)))
+    {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new(tokens.to_vec(), PARSER_MAX_DEPTH);
+    let value = parser.parse_value()?;
+    if !parser.is_eof() {
+        let token = parser.advance()?;
+        return Err(ParserError {
+            span: token.span,
+            error_kind: ParserErrorKind::UnexpectedToken {
+                expected: TokenType::Exact(TokenKind::Comma),
+                actual: token,
+            },
+        });
+    }
+    Ok(Some(value.value_kind()))
+}
+
+fn check_argument_kind(
+    parameter: &TemplateParameter,
+    actual: ManifestValueKind,
+    span: Option<Span>,
+) -> Result<(), TemplateError> {
+    let expected = parameter.value_kind.value_kind();
+    if expected != actual {
+        return Err(TemplateError::MismatchedArgumentKind {
+            name: parameter.name.clone(),
+            expected,
+            actual,
+            span,
+        });
+    }
+    Ok(())
+}
+
+/// Prefixes the names of the objects referred to by the given tokens with the given namespace.
+fn namespace_objects(mut tokens: Vec<Token>, namespace: &str) -> Vec<Token> {
+    for i in 2..tokens.len() {
+        let is_object_name = match (&tokens[i - 2].kind, &tokens[i - 1].kind) {
+            (TokenKind::Ident(ident), TokenKind::OpenParenthesis) => {
+                NAMED_OBJECT_CONSTRUCTORS.contains(&ident.as_str())
+            }
+            _ => false,
+        };
+        if is_object_name {
+            if let TokenKind::StringLiteral(name) = &mut tokens[i].kind {
+                *name = format!("{}.{}", namespace, name);
+            }
+        }
+    }
+    tokens
+}
+
+/// Replaces the parameters in the given tokens by the tokens of their values, which take the span
+/// of the replaced parameter unless `keep_argument_spans` is set.
+fn substitute_parameters(
+    tokens: &[Token],
+    arguments: &IndexMap<String, Vec<Token>>,
+    keep_argument_spans: bool,
+) -> Vec<Token> {
+    let mut substituted = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        match &token.kind {
+            TokenKind::Parameter(name) => {
+                let argument = arguments
+                    .get(name)
+                    .expect("All parameters are declared and given a value");
+                substituted.extend(argument.iter().map(|argument_token| {
+                    if keep_argument_spans {
+                        argument_token.clone()
+                    } else {
+                        Token {
+                            kind: argument_token.kind.clone(),
+                            span: token.span,
+                        }
+                    }
+                }));
+            }
+            _ => substituted.push(token.clone()),
+        }
+    }
+    substituted
+}
+
+fn refers_to_named_object(value: &ManifestValue) -> bool {
+    match value {
+        Value::Custom {
+            value:
+                ManifestCustomValue::Bucket(_)
+                | ManifestCustomValue::Proof(_)
+                | ManifestCustomValue::AddressReservation(_)
+                | ManifestCustomValue::Address(ManifestAddress::Named(_)),
+        } => true,
+        Value::Enum { fields, .. } | Value::Tuple { fields } => {
+            fields.iter().any(refers_to_named_object)
+        }
+        Value::Array { elements, .. } => elements.iter().any(refers_to_named_object),
+        Value::Map { entries, .. } => entries
+            .iter()
+            .any(|(key, value)| refers_to_named_object(key) || refers_to_named_object(value)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> ComponentAddress {
+        ComponentAddress::virtual_account_from_public_key(
+            &Secp256k1PrivateKey::from_u64(1).unwrap().public_key(),
+        )
+    }
+
+    fn arguments(values: Vec<(&str, ManifestValue)>) -> IndexMap<String, ManifestValue> {
+        values
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    fn instantiate(
+        template: &str,
+        sources: &InMemoryTemplateSources,
+        arguments: &IndexMap<String, ManifestValue>,
+    ) -> Result<TransactionManifestV1, TemplateError> {
+        ManifestTemplate::parse(template, sources)?.instantiate(
+            arguments,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+    }
+
+    fn compile_expected(manifest: &str) -> TransactionManifestV1 {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let manifest = manifest.replace(
+            "${account}",
+            &encoder.encode(account().as_node_id().as_bytes()).unwrap(),
+        );
+        compile(
+            &manifest,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_instantiate_template_with_parameters() {
+        let template = r#"
+PARAM $account Address;
+PARAM $amount Decimal;
+CALL_METHOD $account "withdraw" Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") $amount;
+CALL_METHOD $account "deposit_batch" Expression("ENTIRE_WORKTOP");
+"#;
+        let manifest = instantiate(
+            template,
+            &InMemoryTemplateSources::new(),
+            &arguments(vec![
+                ("account", to_manifest_value_and_unwrap!(&account())),
+                ("amount", to_manifest_value_and_unwrap!(&dec!("12.5"))),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            compile_expected(
+                r#"
+CALL_METHOD Address("${account}") "withdraw" Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Decimal("12.5");
+CALL_METHOD Address("${account}") "deposit_batch" Expression("ENTIRE_WORKTOP");
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_includes_are_inlined_with_namespaced_objects() {
+        let sources = InMemoryTemplateSources::new().with_source(
+            "withdraw.rtm",
+            r#"
+PARAM $account Address;
+PARAM $amount Decimal;
+CALL_METHOD $account "withdraw" Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") $amount;
+TAKE_ALL_FROM_WORKTOP Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Bucket("bucket");
+"#,
+        );
+        let template = r#"
+PARAM $account Address;
+USE "withdraw.rtm" AS first ($account => $account, $amount => Decimal("1"));
+USE "withdraw.rtm" AS second ($account => $account, $amount => Decimal("2"));
+CALL_METHOD $account "deposit" Bucket("first.bucket");
+CALL_METHOD $account "deposit" Bucket("second.bucket");
+"#;
+        let manifest = instantiate(
+            template,
+            &sources,
+            &arguments(vec![("account", to_manifest_value_and_unwrap!(&account()))]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            compile_expected(
+                r#"
+CALL_METHOD Address("${account}") "withdraw" Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Decimal("1");
+TAKE_ALL_FROM_WORKTOP Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Bucket("bucket1");
+CALL_METHOD Address("${account}") "withdraw" Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Decimal("2");
+TAKE_ALL_FROM_WORKTOP Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Bucket("bucket2");
+CALL_METHOD Address("${account}") "deposit" Bucket("bucket1");
+CALL_METHOD Address("${account}") "deposit" Bucket("bucket2");
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_include_can_be_given_objects_of_the_including_template() {
+        let sources = InMemoryTemplateSources::new().with_source(
+            "deposit.rtm",
+            r#"
+PARAM $bucket Bucket;
+CALL_METHOD Address("${account}") "deposit" $bucket;
+"#
+            .replace(
+                "${account}",
+                &AddressBech32Encoder::for_simulator()
+                    .encode(account().as_node_id().as_bytes())
+                    .unwrap(),
+            ),
+        );
+        let template = r#"
+TAKE_ALL_FROM_WORKTOP Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Bucket("bucket");
+USE "deposit.rtm" AS deposit ($bucket => Bucket("bucket"));
+"#;
+        let manifest = instantiate(template, &sources, &IndexMap::new()).unwrap();
+
+        assert_eq!(
+            manifest,
+            compile_expected(
+                r#"
+TAKE_ALL_FROM_WORKTOP Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3") Bucket("bucket");
+CALL_METHOD Address("${account}") "deposit" Bucket("bucket");
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        let template = "PARAM $amount Decimal;\nCALL_METHOD Address(\"component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh\") \"lock_fee\" $amount;";
+        let sources = InMemoryTemplateSources::new();
+
+        assert!(matches!(
+            instantiate(template, &sources, &IndexMap::new()),
+            Err(TemplateError::MissingArgument(name, None)) if name == "amount"
+        ));
+        assert!(matches!(
+            instantiate(
+                template,
+                &sources,
+                &arguments(vec![("amount", to_manifest_value_and_unwrap!(&5u8))]),
+            ),
+            Err(TemplateError::MismatchedArgumentKind {
+                expected: ManifestValueKind::Custom(ManifestCustomValueKind::Decimal),
+                actual: ManifestValueKind::U8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            instantiate(
+                template,
+                &sources,
+                &arguments(vec![
+                    ("amount", to_manifest_value_and_unwrap!(&dec!("1"))),
+                    ("other", to_manifest_value_and_unwrap!(&5u8)),
+                ]),
+            ),
+            Err(TemplateError::UnknownArgument(name, None)) if name == "other"
+        ));
+        assert!(matches!(
+            instantiate(
+                template,
+                &sources,
+                &arguments(vec![(
+                    "amount",
+                    to_manifest_value_and_unwrap!(&ManifestBucket(0))
+                )]),
+            ),
+            Err(TemplateError::InvalidArgument(name)) if name == "amount"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_templates_are_rejected() {
+        let sources = InMemoryTemplateSources::new()
+            .with_source("self.rtm", "USE \"self.rtm\" AS again;")
+            .with_source("fee.rtm", "PARAM $amount Decimal;");
+
+        assert!(matches!(
+            ManifestTemplate::parse("DROP_ALL_PROOFS;\nPARAM $amount Decimal;", &sources),
+            Err(TemplateError::MisplacedParameterDeclaration(..))
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("PARAM $a U8;\nPARAM $a U8;", &sources),
+            Err(TemplateError::DuplicateParameter(..))
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("CALL_FUNCTION $package \"A\" \"f\";", &sources),
+            Err(TemplateError::UndeclaredParameter(name, _)) if name == "package"
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("USE \"other.rtm\" AS other;", &sources),
+            Err(TemplateError::IncludeNotFound { .. })
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("USE \"self.rtm\" AS this;", &sources),
+            Err(TemplateError::IncludeError { error, .. })
+                if matches!(*error, TemplateError::CyclicInclude { .. })
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("USE \"fee.rtm\" AS fee;", &sources),
+            Err(TemplateError::MissingArgument(name, Some(_))) if name == "amount"
+        ));
+        assert!(matches!(
+            ManifestTemplate::parse("USE \"fee.rtm\" AS fee ($amount => 1u8);", &sources),
+            Err(TemplateError::MismatchedArgumentKind { span: Some(_), .. })
+        ));
+    }
+}
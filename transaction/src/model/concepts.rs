@@ -27,6 +27,19 @@ pub trait HasNotarizedTransactionHash {
     fn notarized_transaction_hash(&self) -> NotarizedTransactionHash;
 }
 
+define_raw_transaction_payload!(RawChildIntent);
+define_wrapped_hash!(
+    /// A hash of a child intent.
+    /// The engine guarantees each child intent hash can only be committed once.
+    ChildIntentHash
+);
+
+pub trait HasChildIntentHash {
+    fn child_intent_hash(&self) -> ChildIntentHash;
+}
+
+define_raw_transaction_payload!(RawSignedChildIntent);
+
 define_raw_transaction_payload!(RawSystemTransaction);
 define_wrapped_hash!(SystemTransactionHash);
 
@@ -12,10 +12,18 @@ pub struct EpochRange {
     pub end_epoch_exclusive: Epoch,
 }
 
+/// A window of the proposer timestamp, as per the consensus manager, with either end optional.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct ProposerTimestampRange {
+    pub start_timestamp_inclusive: Option<Instant>,
+    pub end_timestamp_exclusive: Option<Instant>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
 pub struct ExecutionContext {
     pub intent_hash: TransactionIntentHash,
     pub epoch_range: Option<EpochRange>,
+    pub proposer_timestamp_range: Option<ProposerTimestampRange>,
     pub pre_allocated_addresses: Vec<PreAllocatedAddress>,
     pub payload_size: usize,
     pub num_of_signature_validations: usize,
@@ -66,6 +74,8 @@ pub struct TransactionCostingParameters {
     pub tip_percentage: u16,
     /// Free credit for execution, for preview only!
     pub free_credit_in_xrd: Decimal,
+    /// The maximum total fee the fee payer accepts, if any
+    pub max_fee_in_xrd: Option<Decimal>,
 }
 
 impl Default for TransactionCostingParameters {
@@ -73,10 +83,24 @@ impl Default for TransactionCostingParameters {
         Self {
             tip_percentage: DEFAULT_TIP_PERCENTAGE,
             free_credit_in_xrd: Default::default(),
+            max_fee_in_xrd: None,
         }
     }
 }
 
+/// Executable form of a child intent, which the transaction processor runs when its parent
+/// yields to it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExecutableChildIntent<'a> {
+    pub encoded_instructions: &'a [u8],
+    pub references: &'a IndexSet<Reference>,
+    pub blobs: &'a IndexMap<Hash, Vec<u8>>,
+    pub intent_hash: TransactionIntentHash,
+    pub epoch_range: Option<EpochRange>,
+    pub proposer_timestamp_range: Option<ProposerTimestampRange>,
+    pub auth_zone_params: AuthZoneParams,
+}
+
 /// Executable form of transaction, post stateless validation.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Executable<'a> {
@@ -84,6 +108,7 @@ pub struct Executable<'a> {
     pub(crate) references: IndexSet<Reference>,
     pub(crate) blobs: &'a IndexMap<Hash, Vec<u8>>,
     pub(crate) context: ExecutionContext,
+    pub(crate) child_intents: Vec<ExecutableChildIntent<'a>>,
 }
 
 impl<'a> Executable<'a> {
@@ -116,9 +141,29 @@ impl<'a> Executable<'a> {
             references,
             blobs,
             context,
+            child_intents: vec![],
         }
     }
 
+    /// Adds the child intents which the manifest yields to.
+    /// Their references are added to the references of the transaction, as the root frame
+    /// passes them on to the child intents.
+    pub fn with_child_intents(mut self, child_intents: Vec<ExecutableChildIntent<'a>>) -> Self {
+        for child_intent in &child_intents {
+            self.references
+                .extend(child_intent.references.iter().cloned());
+            for proof in &child_intent.auth_zone_params.initial_proofs {
+                self.references
+                    .insert(proof.resource_address().clone().into());
+            }
+            for resource in &child_intent.auth_zone_params.virtual_resources {
+                self.references.insert(resource.clone().into());
+            }
+        }
+        self.child_intents = child_intents;
+        self
+    }
+
     pub fn intent_hash(&self) -> &TransactionIntentHash {
         &self.context.intent_hash
     }
@@ -127,6 +172,10 @@ impl<'a> Executable<'a> {
         self.context.epoch_range.as_ref()
     }
 
+    pub fn proposer_timestamp_range(&self) -> Option<&ProposerTimestampRange> {
+        self.context.proposer_timestamp_range.as_ref()
+    }
+
     pub fn overwrite_intent_hash(&mut self, hash: Hash) {
         match &mut self.context.intent_hash {
             TransactionIntentHash::ToCheck { intent_hash, .. }
@@ -138,6 +187,9 @@ impl<'a> Executable<'a> {
 
     pub fn skip_epoch_range_check(&mut self) {
         self.context.epoch_range = None;
+        for child_intent in &mut self.child_intents {
+            child_intent.epoch_range = None;
+        }
     }
 
     pub fn costing_parameters(&self) -> &TransactionCostingParameters {
@@ -171,4 +223,8 @@ impl<'a> Executable<'a> {
     pub fn num_of_signature_validations(&self) -> usize {
         self.context.num_of_signature_validations
     }
+
+    pub fn child_intents(&self) -> &[ExecutableChildIntent<'a>] {
+        &self.child_intents
+    }
 }
@@ -3,6 +3,7 @@ mod executable;
 mod hash;
 mod preparation;
 mod v1;
+mod v2;
 mod versioned;
 
 pub use concepts::*;
@@ -10,6 +11,7 @@ pub use executable::*;
 pub use hash::*;
 pub use preparation::*;
 pub use v1::*;
+pub use v2::*;
 pub use versioned::*;
 
 #[cfg(test)]
@@ -212,6 +214,7 @@ Enum<3u8>(
                         start_epoch_inclusive: Epoch::of(55),
                         end_epoch_exclusive: Epoch::of(66)
                     }),
+                    proposer_timestamp_range: None,
                     pre_allocated_addresses: vec![],
                     // Source of discrepancy:
                     // * Manifest SBOR payload prefix byte: not counted
@@ -228,9 +231,11 @@ Enum<3u8>(
                     },
                     costing_parameters: TransactionCostingParameters {
                         tip_percentage: 4,
-                        free_credit_in_xrd: dec!(0)
+                        free_credit_in_xrd: dec!(0),
+                        max_fee_in_xrd: None,
                     }
-                }
+                },
+                child_intents: vec![],
             }
         );
 
@@ -5,6 +5,7 @@ use sbor::*;
 pub enum ValueType {
     Blob,
     Attachment,
+    ChildIntent,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
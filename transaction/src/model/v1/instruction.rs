@@ -698,6 +698,21 @@ pub enum InstructionV1 {
         package_address: PackageAddress,
         blueprint_name: String,
     },
+
+    //==============
+    // Intent composition
+    //==============
+    /// Runs the child intent at the given index of the parent intent, passing it the buckets
+    /// in the arguments. The buckets yielded back by the child are put on the worktop.
+    #[sbor(discriminator(INSTRUCTION_YIELD_TO_CHILD_DISCRIMINATOR))]
+    YieldToChild {
+        child_index: u32,
+        args: ManifestValue,
+    },
+
+    /// Ends a child intent, passing the buckets in the arguments back to its parent.
+    #[sbor(discriminator(INSTRUCTION_YIELD_TO_PARENT_DISCRIMINATOR))]
+    YieldToParent { args: ManifestValue },
 }
 
 //===============================================================
@@ -766,3 +781,9 @@ pub const INSTRUCTION_CALL_DIRECT_VAULT_METHOD_DISCRIMINATOR: u8 = 0x45;
 pub const INSTRUCTION_DROP_NAMED_PROOFS_DISCRIMINATOR: u8 = 0x52;
 pub const INSTRUCTION_DROP_ALL_PROOFS_DISCRIMINATOR: u8 = 0x50;
 pub const INSTRUCTION_ALLOCATE_GLOBAL_ADDRESS_DISCRIMINATOR: u8 = 0x51;
+
+//==============
+// Intent composition
+//==============
+pub const INSTRUCTION_YIELD_TO_CHILD_DISCRIMINATOR: u8 = 0x60;
+pub const INSTRUCTION_YIELD_TO_PARENT_DISCRIMINATOR: u8 = 0x61;
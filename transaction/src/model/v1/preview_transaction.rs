@@ -41,6 +41,7 @@ impl ValidatedPreviewIntent {
             } else {
                 Decimal::ZERO
             },
+            max_fee_in_xrd: None,
         };
         let initial_proofs = AuthAddresses::signer_set(&self.signer_public_keys);
 
@@ -69,6 +70,7 @@ impl ValidatedPreviewIntent {
                         end_epoch_exclusive: intent.header.inner.end_epoch_exclusive,
                     })
                 },
+                proposer_timestamp_range: None,
                 payload_size: self.intent.summary.effective_length,
                 num_of_signature_validations: 0, // Accounted for by tests in `common_transformation_costs.rs`.
                 auth_zone_params: AuthZoneParams {
@@ -109,6 +109,7 @@ impl PreparedSystemTransactionV1 {
                     intent_hash: self.hash_for_execution.hash,
                 },
                 epoch_range: None,
+                proposer_timestamp_range: None,
                 payload_size: 0,
                 num_of_signature_validations: 0,
                 auth_zone_params: AuthZoneParams {
@@ -118,6 +119,7 @@ impl PreparedSystemTransactionV1 {
                 costing_parameters: TransactionCostingParameters {
                     tip_percentage: 0,
                     free_credit_in_xrd: Decimal::ZERO,
+                    max_fee_in_xrd: None,
                 },
                 pre_allocated_addresses: self.pre_allocated_addresses.inner.clone(),
             },
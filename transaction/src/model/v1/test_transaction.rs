@@ -61,6 +61,7 @@ impl PreparedTestTransaction {
                     intent_hash: self.hash,
                 },
                 epoch_range: None,
+                proposer_timestamp_range: None,
                 payload_size: self.encoded_instructions.len()
                     + self.blobs.values().map(|x| x.len()).sum::<usize>(),
                 // For testing purpose, assume `num_of_signature_validations = num_of_initial_proofs + 1`
@@ -72,6 +73,7 @@ impl PreparedTestTransaction {
                 costing_parameters: TransactionCostingParameters {
                     tip_percentage: DEFAULT_TIP_PERCENTAGE,
                     free_credit_in_xrd: Decimal::ZERO,
+                    max_fee_in_xrd: None,
                 },
                 pre_allocated_addresses: vec![],
             },
@@ -50,6 +50,7 @@ impl ValidatedNotarizedTransactionV1 {
                     start_epoch_inclusive: header.start_epoch_inclusive,
                     end_epoch_exclusive: header.end_epoch_exclusive,
                 }),
+                proposer_timestamp_range: None,
                 payload_size: summary.effective_length,
                 num_of_signature_validations: self.num_of_signature_validations,
                 auth_zone_params: AuthZoneParams {
@@ -59,6 +60,7 @@ impl ValidatedNotarizedTransactionV1 {
                 costing_parameters: TransactionCostingParameters {
                     tip_percentage: intent.header.inner.tip_percentage,
                     free_credit_in_xrd,
+                    max_fee_in_xrd: None,
                 },
                 pre_allocated_addresses: vec![],
             },
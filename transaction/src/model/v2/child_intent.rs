@@ -0,0 +1,85 @@
+use super::*;
+use crate::internal_prelude::*;
+
+//=================================================================================
+// NOTE:
+// See versioned.rs for tests and a demonstration for the calculation of hashes etc
+//=================================================================================
+
+/// An intent which is composed into a transaction by a parent intent.
+///
+/// Its manifest runs when the parent yields to it with `YIELD_TO_CHILD`, starting with the
+/// yielded buckets on its worktop, and must end with `YIELD_TO_PARENT`, which returns buckets
+/// to the parent's worktop.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct ChildIntentV2 {
+    pub header: ChildIntentHeaderV2,
+    pub instructions: InstructionsV1,
+    pub blobs: BlobsV1,
+    pub message: MessageV1,
+}
+
+impl TransactionPayload for ChildIntentV2 {
+    type Versioned = SborFixedEnumVariant<{ TransactionDiscriminator::V2ChildIntent as u8 }, Self>;
+    type Prepared = PreparedChildIntentV2;
+    type Raw = RawChildIntent;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreparedChildIntentV2 {
+    pub header: PreparedChildIntentHeaderV2,
+    pub instructions: PreparedInstructionsV1,
+    pub blobs: PreparedBlobsV1,
+    pub message: PreparedMessageV1,
+    pub summary: Summary,
+}
+
+impl HasSummary for PreparedChildIntentV2 {
+    fn get_summary(&self) -> &Summary {
+        &self.summary
+    }
+}
+
+impl TransactionFullChildPreparable for PreparedChildIntentV2 {
+    fn prepare_as_full_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as an child, it's SBOR encoded as a struct
+        let ((header, instructions, blobs, message), summary) =
+            ConcatenatedDigest::prepare_from_transaction_child_struct(
+                decoder,
+                TransactionDiscriminator::V2ChildIntent,
+            )?;
+        Ok(Self {
+            header,
+            instructions,
+            blobs,
+            message,
+            summary,
+        })
+    }
+}
+
+impl TransactionPayloadPreparable for PreparedChildIntentV2 {
+    type Raw = RawChildIntent;
+
+    fn prepare_for_payload(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as full payload, it's SBOR encoded as an enum
+        let ((header, instructions, blobs, message), summary) =
+            ConcatenatedDigest::prepare_from_transaction_payload_enum(
+                decoder,
+                TransactionDiscriminator::V2ChildIntent,
+            )?;
+        Ok(Self {
+            header,
+            instructions,
+            blobs,
+            message,
+            summary,
+        })
+    }
+}
+
+impl HasChildIntentHash for PreparedChildIntentV2 {
+    fn child_intent_hash(&self) -> ChildIntentHash {
+        ChildIntentHash::from_hash(self.summary.hash)
+    }
+}
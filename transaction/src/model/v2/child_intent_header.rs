@@ -0,0 +1,22 @@
+use radix_engine_common::time::Instant;
+use radix_engine_common::types::Epoch;
+use radix_engine_common::ManifestSbor;
+
+use crate::model::SummarizedRawFullBody;
+
+/// The constraints under which a child intent can be committed, independently of the
+/// transaction which includes it.
+///
+/// Unlike a `TransactionHeaderV2`, there is no notary, tip or max fee, as these are properties of
+/// the root intent which pays for the transaction.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct ChildIntentHeaderV2 {
+    pub network_id: u8,
+    pub start_epoch_inclusive: Epoch,
+    pub end_epoch_exclusive: Epoch,
+    pub min_proposer_timestamp_inclusive: Option<Instant>,
+    pub max_proposer_timestamp_exclusive: Option<Instant>,
+    pub intent_discriminator: u64,
+}
+
+pub type PreparedChildIntentHeaderV2 = SummarizedRawFullBody<ChildIntentHeaderV2>;
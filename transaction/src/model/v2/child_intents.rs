@@ -0,0 +1,38 @@
+use radix_engine_common::constants::MAX_NUMBER_OF_CHILD_INTENTS;
+
+use super::*;
+use crate::internal_prelude::*;
+
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+#[sbor(transparent)]
+pub struct ChildIntentsV2 {
+    pub children: Vec<SignedChildIntentV2>,
+}
+
+impl TransactionPartialEncode for ChildIntentsV2 {
+    type Prepared = PreparedChildIntentsV2;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreparedChildIntentsV2 {
+    pub children: Vec<PreparedSignedChildIntentV2>,
+    pub summary: Summary,
+}
+
+impl HasSummary for PreparedChildIntentsV2 {
+    fn get_summary(&self) -> &Summary {
+        &self.summary
+    }
+}
+
+impl TransactionFullChildPreparable for PreparedChildIntentsV2 {
+    fn prepare_as_full_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        let (children, summary) =
+            ConcatenatedDigest::prepare_from_sbor_array::<
+                Vec<PreparedSignedChildIntentV2>,
+                MAX_NUMBER_OF_CHILD_INTENTS,
+            >(decoder, HashAccumulator::new(), ValueType::ChildIntent)?;
+
+        Ok(PreparedChildIntentsV2 { children, summary })
+    }
+}
@@ -0,0 +1,29 @@
+use radix_engine_common::math::Decimal;
+use radix_engine_common::time::Instant;
+use radix_engine_common::types::Epoch;
+use radix_engine_common::{crypto::PublicKey, ManifestSbor};
+
+use crate::model::SummarizedRawFullBody;
+
+/// Compared to a `TransactionHeaderV1`, the intent can also be constrained to a window of the
+/// proposer timestamp and a maximum fee, and the nonce is replaced by an intent discriminator.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct TransactionHeaderV2 {
+    pub network_id: u8,
+    pub start_epoch_inclusive: Epoch,
+    pub end_epoch_exclusive: Epoch,
+    /// The earliest proposer timestamp, as per the consensus manager, at which the intent can be committed.
+    pub min_proposer_timestamp_inclusive: Option<Instant>,
+    /// The proposer timestamp, as per the consensus manager, from which the intent can no longer be committed.
+    pub max_proposer_timestamp_exclusive: Option<Instant>,
+    /// The maximum total fee, including tips, royalties and storage, that the fee payer accepts.
+    pub max_fee_in_xrd: Option<Decimal>,
+    /// An arbitrary number which can be used to distinguish otherwise identical intents, e.g.
+    /// a random number or a counter.
+    pub intent_discriminator: u64,
+    pub notary_public_key: PublicKey,
+    pub notary_is_signatory: bool,
+    pub tip_percentage: u16,
+}
+
+pub type PreparedTransactionHeaderV2 = SummarizedRawFullBody<TransactionHeaderV2>;
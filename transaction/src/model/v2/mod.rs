@@ -0,0 +1,21 @@
+use super::*;
+
+mod child_intent;
+mod child_intent_header;
+mod child_intents;
+mod header;
+mod intent;
+mod notarized_transaction;
+mod signed_child_intent;
+mod signed_intent;
+mod validated_notarized_transaction;
+
+pub use child_intent::*;
+pub use child_intent_header::*;
+pub use child_intents::*;
+pub use header::*;
+pub use intent::*;
+pub use notarized_transaction::*;
+pub use signed_child_intent::*;
+pub use signed_intent::*;
+pub use validated_notarized_transaction::*;
@@ -0,0 +1,93 @@
+use super::*;
+use crate::internal_prelude::*;
+
+//=================================================================================
+// NOTE:
+// See versioned.rs for tests and a demonstration for the calculation of hashes etc
+//=================================================================================
+
+/// A child intent with the signatures of its signers, ready to be passed to the author of the
+/// parent intent.
+#[derive(Debug, Clone, Eq, PartialEq, ManifestSbor)]
+pub struct SignedChildIntentV2 {
+    pub intent: ChildIntentV2,
+    pub intent_signatures: IntentSignaturesV1,
+}
+
+impl TransactionPayload for SignedChildIntentV2 {
+    type Versioned =
+        SborFixedEnumVariant<{ TransactionDiscriminator::V2SignedChildIntent as u8 }, Self>;
+    type Prepared = PreparedSignedChildIntentV2;
+    type Raw = RawSignedChildIntent;
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreparedSignedChildIntentV2 {
+    pub intent: PreparedChildIntentV2,
+    pub intent_signatures: PreparedIntentSignaturesV1,
+    pub summary: Summary,
+}
+
+impl HasSummary for PreparedSignedChildIntentV2 {
+    fn get_summary(&self) -> &Summary {
+        &self.summary
+    }
+}
+
+impl TransactionFullChildPreparable for PreparedSignedChildIntentV2 {
+    fn prepare_as_full_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as an child, it's SBOR encoded as a struct
+        let ((intent, intent_signatures), summary) =
+            ConcatenatedDigest::prepare_from_transaction_child_struct(
+                decoder,
+                TransactionDiscriminator::V2SignedChildIntent,
+            )?;
+        Ok(Self {
+            intent,
+            intent_signatures,
+            summary,
+        })
+    }
+}
+
+impl TransactionChildBodyPreparable for PreparedSignedChildIntentV2 {
+    fn prepare_as_inner_body_child(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // As an element of the parent's child intents, the struct is encoded without its value
+        // kind - so we re-attach it, to prepare it exactly as if it was embedded as a child
+        let start_offset = decoder.get_offset();
+        decoder.decode_deeper_body_with_value_kind::<SignedChildIntentV2>(Self::value_kind())?;
+        let end_offset = decoder.get_offset();
+
+        let mut payload = vec![MANIFEST_SBOR_V1_PAYLOAD_PREFIX, Self::value_kind().as_u8()];
+        payload.extend_from_slice(decoder.get_slice(start_offset, end_offset));
+        Self::prepare_as_full_body_child_from_payload(&payload)
+    }
+
+    fn value_kind() -> ManifestValueKind {
+        SignedChildIntentV2::value_kind()
+    }
+}
+
+impl TransactionPayloadPreparable for PreparedSignedChildIntentV2 {
+    type Raw = RawSignedChildIntent;
+
+    fn prepare_for_payload(decoder: &mut TransactionDecoder) -> Result<Self, PrepareError> {
+        // When embedded as full payload, it's SBOR encoded as an enum
+        let ((intent, intent_signatures), summary) =
+            ConcatenatedDigest::prepare_from_transaction_payload_enum(
+                decoder,
+                TransactionDiscriminator::V2SignedChildIntent,
+            )?;
+        Ok(Self {
+            intent,
+            intent_signatures,
+            summary,
+        })
+    }
+}
+
+impl HasChildIntentHash for PreparedSignedChildIntentV2 {
+    fn child_intent_hash(&self) -> ChildIntentHash {
+        self.intent.child_intent_hash()
+    }
+}
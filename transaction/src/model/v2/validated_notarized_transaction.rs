@@ -0,0 +1,117 @@
+use crate::internal_prelude::*;
+use radix_engine_interface::api::node_modules::auth::AuthAddresses;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidatedChildIntentV2 {
+    pub encoded_instructions: Vec<u8>,
+    pub signer_keys: Vec<PublicKey>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidatedNotarizedTransactionV2 {
+    pub prepared: PreparedNotarizedTransactionV2,
+    pub encoded_instructions: Vec<u8>,
+    pub signer_keys: Vec<PublicKey>,
+    pub num_of_signature_validations: usize,
+    /// The validated child intents, in the order of `prepared`'s child intents
+    pub child_intents: Vec<ValidatedChildIntentV2>,
+}
+
+impl HasIntentHash for ValidatedNotarizedTransactionV2 {
+    fn intent_hash(&self) -> IntentHash {
+        self.prepared.intent_hash()
+    }
+}
+
+impl HasSignedIntentHash for ValidatedNotarizedTransactionV2 {
+    fn signed_intent_hash(&self) -> SignedIntentHash {
+        self.prepared.signed_intent_hash()
+    }
+}
+
+impl HasNotarizedTransactionHash for ValidatedNotarizedTransactionV2 {
+    fn notarized_transaction_hash(&self) -> NotarizedTransactionHash {
+        self.prepared.notarized_transaction_hash()
+    }
+}
+
+impl ValidatedNotarizedTransactionV2 {
+    pub fn get_executable_with_free_credit<'a>(
+        &'a self,
+        free_credit_in_xrd: Decimal,
+    ) -> Executable<'a> {
+        let intent = &self.prepared.signed_intent.intent;
+        let header = &intent.header.inner;
+        let intent_hash = intent.intent_hash();
+        let summary = &self.prepared.summary;
+
+        let child_intents = intent
+            .child_intents
+            .children
+            .iter()
+            .zip(self.child_intents.iter())
+            .map(|(prepared, validated)| {
+                let child_intent = &prepared.intent;
+                let child_header = &child_intent.header.inner;
+                ExecutableChildIntent {
+                    encoded_instructions: &validated.encoded_instructions,
+                    references: &child_intent.instructions.references,
+                    blobs: &child_intent.blobs.blobs_by_hash,
+                    intent_hash: TransactionIntentHash::ToCheck {
+                        intent_hash: child_intent.child_intent_hash().into_hash(),
+                        expiry_epoch: child_header.end_epoch_exclusive,
+                    },
+                    epoch_range: Some(EpochRange {
+                        start_epoch_inclusive: child_header.start_epoch_inclusive,
+                        end_epoch_exclusive: child_header.end_epoch_exclusive,
+                    }),
+                    proposer_timestamp_range: Some(ProposerTimestampRange {
+                        start_timestamp_inclusive: child_header.min_proposer_timestamp_inclusive,
+                        end_timestamp_exclusive: child_header.max_proposer_timestamp_exclusive,
+                    }),
+                    auth_zone_params: AuthZoneParams {
+                        initial_proofs: AuthAddresses::signer_set(&validated.signer_keys),
+                        virtual_resources: BTreeSet::new(),
+                    },
+                }
+            })
+            .collect();
+
+        Executable::new(
+            &self.encoded_instructions,
+            &intent.instructions.references,
+            &intent.blobs.blobs_by_hash,
+            ExecutionContext {
+                intent_hash: TransactionIntentHash::ToCheck {
+                    intent_hash: intent_hash.into_hash(),
+                    expiry_epoch: header.end_epoch_exclusive,
+                },
+                epoch_range: Some(EpochRange {
+                    start_epoch_inclusive: header.start_epoch_inclusive,
+                    end_epoch_exclusive: header.end_epoch_exclusive,
+                }),
+                proposer_timestamp_range: Some(ProposerTimestampRange {
+                    start_timestamp_inclusive: header.min_proposer_timestamp_inclusive,
+                    end_timestamp_exclusive: header.max_proposer_timestamp_exclusive,
+                }),
+                payload_size: summary.effective_length,
+                num_of_signature_validations: self.num_of_signature_validations,
+                auth_zone_params: AuthZoneParams {
+                    initial_proofs: AuthAddresses::signer_set(&self.signer_keys),
+                    virtual_resources: BTreeSet::new(),
+                },
+                costing_parameters: TransactionCostingParameters {
+                    tip_percentage: header.tip_percentage,
+                    free_credit_in_xrd,
+                    max_fee_in_xrd: header.max_fee_in_xrd,
+                },
+                pre_allocated_addresses: vec![],
+            },
+        )
+        .with_child_intents(child_intents)
+    }
+
+    pub fn get_executable<'a>(&'a self) -> Executable<'a> {
+        self.get_executable_with_free_credit(Decimal::ZERO)
+    }
+}
@@ -18,6 +18,11 @@ pub enum TransactionDiscriminator {
     V1RoundUpdate = V1_ROUND_UPDATE_TRANSACTION,
     V1Preview = V1_PREVIEW_TRANSACTION,
     V1Ledger = V1_LEDGER_TRANSACTION,
+    V2ChildIntent = V2_CHILD_INTENT,
+    V2SignedChildIntent = V2_SIGNED_CHILD_INTENT,
+    V2Intent = V2_INTENT,
+    V2SignedIntent = V2_SIGNED_INTENT,
+    V2Notarized = V2_NOTARIZED_TRANSACTION,
 }
 
 const V1_INTENT: u8 = 1;
@@ -27,6 +32,11 @@ const V1_SYSTEM_TRANSACTION: u8 = 4;
 const V1_ROUND_UPDATE_TRANSACTION: u8 = 5;
 const V1_PREVIEW_TRANSACTION: u8 = 6;
 const V1_LEDGER_TRANSACTION: u8 = 7;
+const V2_CHILD_INTENT: u8 = 8;
+const V2_SIGNED_CHILD_INTENT: u8 = 9;
+const V2_INTENT: u8 = 10;
+const V2_SIGNED_INTENT: u8 = 11;
+const V2_NOTARIZED_TRANSACTION: u8 = 12;
 
 // TODO - change this to use #[flatten] when REP-84 is out
 /// An enum of a variety of different transaction payload types
@@ -58,6 +68,36 @@ pub enum VersionedTransactionPayload {
         pre_allocated_addresses: Vec<PreAllocatedAddress>,
         hash_for_execution: Hash,
     },
+    #[sbor(discriminator(V2_CHILD_INTENT))]
+    ChildIntentV2 {
+        header: ChildIntentHeaderV2,
+        instructions: InstructionsV1,
+        blobs: BlobsV1,
+        message: MessageV1,
+    },
+    #[sbor(discriminator(V2_SIGNED_CHILD_INTENT))]
+    SignedChildIntentV2 {
+        intent: ChildIntentV2,
+        intent_signatures: IntentSignaturesV1,
+    },
+    #[sbor(discriminator(V2_INTENT))]
+    IntentV2 {
+        header: TransactionHeaderV2,
+        instructions: InstructionsV1,
+        blobs: BlobsV1,
+        message: MessageV1,
+        child_intents: ChildIntentsV2,
+    },
+    #[sbor(discriminator(V2_SIGNED_INTENT))]
+    SignedIntentV2 {
+        intent: IntentV2,
+        intent_signatures: IntentSignaturesV1,
+    },
+    #[sbor(discriminator(V2_NOTARIZED_TRANSACTION))]
+    NotarizedTransactionV2 {
+        signed_intent: SignedIntentV2,
+        notary_signature: NotarySignatureV1,
+    },
 }
 
 #[cfg(test)]
@@ -302,6 +342,234 @@ mod tests {
         );
     }
 
+    /// This test demonstrates how the hashes are constructed in a V2 user transaction with a
+    /// child intent, and that the intent hash commits to the signed child intent.
+    #[test]
+    pub fn v2_user_transaction_with_child_intent_structure() {
+        let network = NetworkDefinition::simulator();
+
+        let child_signer_private_key = Secp256k1PrivateKey::from_u64(1).unwrap();
+        let notary_private_key = Ed25519PrivateKey::from_u64(3).unwrap();
+
+        //===================
+        // CHILD INTENT
+        //===================
+        let child_header_v2 = ChildIntentHeaderV2 {
+            network_id: network.id,
+            start_epoch_inclusive: Epoch::of(1),
+            end_epoch_exclusive: Epoch::of(5),
+            min_proposer_timestamp_inclusive: None,
+            max_proposer_timestamp_exclusive: Some(Instant::new(1700000000)),
+            intent_discriminator: 0,
+        };
+        let expected_child_header_hash =
+            hash_manifest_encoded_without_prefix_byte(&child_header_v2);
+
+        let child_instructions = vec![InstructionV1::YieldToParent {
+            args: ManifestValue::Tuple { fields: vec![] },
+        }];
+        let expected_child_instructions_hash =
+            hash_manifest_encoded_without_prefix_byte(&child_instructions);
+
+        let blobs_v1 = BlobsV1 { blobs: vec![] };
+        let expected_blobs_hash = hash([]);
+
+        let message_v1 = MessageV1::default();
+        let expected_message_hash = hash_manifest_encoded_without_prefix_byte(&message_v1);
+
+        let child_intent_v2 = ChildIntentV2 {
+            header: child_header_v2.clone(),
+            instructions: InstructionsV1(child_instructions),
+            blobs: blobs_v1.clone(),
+            message: message_v1.clone(),
+        };
+        let expected_child_intent_hash = ChildIntentHash::from_hash(hash(
+            [
+                [
+                    TRANSACTION_HASHABLE_PAYLOAD_PREFIX,
+                    TransactionDiscriminator::V2ChildIntent as u8,
+                ]
+                .as_slice(),
+                expected_child_header_hash.0.as_slice(),
+                expected_child_instructions_hash.0.as_slice(),
+                expected_blobs_hash.0.as_slice(),
+                expected_message_hash.0.as_slice(),
+            ]
+            .concat(),
+        ));
+
+        let child_intent_payload_bytes = child_intent_v2.to_payload_bytes().unwrap();
+        let child_intent_as_versioned =
+            manifest_decode::<VersionedTransactionPayload>(&child_intent_payload_bytes).unwrap();
+        assert_eq!(
+            child_intent_as_versioned,
+            VersionedTransactionPayload::ChildIntentV2 {
+                header: child_header_v2,
+                instructions: child_intent_v2.instructions.clone(),
+                blobs: blobs_v1.clone(),
+                message: message_v1.clone(),
+            }
+        );
+        let prepared_child_intent =
+            PreparedChildIntentV2::prepare_from_payload(&child_intent_payload_bytes).unwrap();
+        assert_eq!(
+            expected_child_intent_hash,
+            prepared_child_intent.child_intent_hash()
+        );
+
+        //===================
+        // SIGNED CHILD INTENT
+        //===================
+        let child_intent_signatures_v1 = IntentSignaturesV1 {
+            signatures: vec![IntentSignatureV1(
+                child_signer_private_key.sign_with_public_key(&expected_child_intent_hash),
+            )],
+        };
+        let expected_child_intent_signatures_hash =
+            hash_manifest_encoded_without_prefix_byte(&child_intent_signatures_v1);
+        let signed_child_intent_v2 = SignedChildIntentV2 {
+            intent: child_intent_v2.clone(),
+            intent_signatures: child_intent_signatures_v1,
+        };
+        let expected_signed_child_intent_hash = hash(
+            [
+                [
+                    TRANSACTION_HASHABLE_PAYLOAD_PREFIX,
+                    TransactionDiscriminator::V2SignedChildIntent as u8,
+                ]
+                .as_slice(),
+                expected_child_intent_hash.0.as_slice(),
+                expected_child_intent_signatures_hash.0.as_slice(),
+            ]
+            .concat(),
+        );
+
+        let prepared_signed_child_intent = PreparedSignedChildIntentV2::prepare_from_payload(
+            &signed_child_intent_v2.to_payload_bytes().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            expected_signed_child_intent_hash,
+            prepared_signed_child_intent.get_summary().hash
+        );
+        assert_eq!(
+            expected_child_intent_hash,
+            prepared_signed_child_intent.child_intent_hash()
+        );
+
+        //===================
+        // INTENT
+        //===================
+        let header_v2 = TransactionHeaderV2 {
+            network_id: network.id,
+            start_epoch_inclusive: Epoch::of(1),
+            end_epoch_exclusive: Epoch::of(5),
+            min_proposer_timestamp_inclusive: Some(Instant::new(1600000000)),
+            max_proposer_timestamp_exclusive: None,
+            max_fee_in_xrd: Some(dec!(10)),
+            intent_discriminator: 0,
+            notary_public_key: notary_private_key.public_key().into(),
+            notary_is_signatory: true,
+            tip_percentage: 0,
+        };
+        let expected_header_hash = hash_manifest_encoded_without_prefix_byte(&header_v2);
+
+        let instructions = vec![InstructionV1::YieldToChild {
+            child_index: 0,
+            args: ManifestValue::Tuple { fields: vec![] },
+        }];
+        let expected_instructions_hash = hash_manifest_encoded_without_prefix_byte(&instructions);
+
+        let child_intents_v2 = ChildIntentsV2 {
+            children: vec![signed_child_intent_v2],
+        };
+        let expected_child_intents_hash = hash(expected_signed_child_intent_hash.0);
+
+        let intent_v2 = IntentV2 {
+            header: header_v2.clone(),
+            instructions: InstructionsV1(instructions),
+            blobs: blobs_v1.clone(),
+            message: message_v1.clone(),
+            child_intents: child_intents_v2.clone(),
+        };
+        let expected_intent_hash = IntentHash::from_hash(hash(
+            [
+                [
+                    TRANSACTION_HASHABLE_PAYLOAD_PREFIX,
+                    TransactionDiscriminator::V2Intent as u8,
+                ]
+                .as_slice(),
+                expected_header_hash.0.as_slice(),
+                expected_instructions_hash.0.as_slice(),
+                expected_blobs_hash.0.as_slice(),
+                expected_message_hash.0.as_slice(),
+                expected_child_intents_hash.0.as_slice(),
+            ]
+            .concat(),
+        ));
+
+        let intent_payload_bytes = intent_v2.to_payload_bytes().unwrap();
+        let intent_as_versioned =
+            manifest_decode::<VersionedTransactionPayload>(&intent_payload_bytes).unwrap();
+        assert_eq!(
+            intent_as_versioned,
+            VersionedTransactionPayload::IntentV2 {
+                header: header_v2,
+                instructions: intent_v2.instructions.clone(),
+                blobs: blobs_v1,
+                message: message_v1,
+                child_intents: child_intents_v2,
+            }
+        );
+        let prepared_intent =
+            PreparedIntentV2::prepare_from_payload(&intent_payload_bytes).unwrap();
+        assert_eq!(expected_intent_hash, prepared_intent.intent_hash());
+        assert_eq!(
+            expected_child_intent_hash,
+            prepared_intent.child_intents.children[0].child_intent_hash()
+        );
+
+        //======================
+        // NOTARIZED TRANSACTION
+        //======================
+        let signed_intent_v2 = SignedIntentV2 {
+            intent: intent_v2,
+            intent_signatures: IntentSignaturesV1 { signatures: vec![] },
+        };
+        let signed_intent_hash = signed_intent_v2.prepare().unwrap().signed_intent_hash();
+        let notarized_transaction_v2 = NotarizedTransactionV2 {
+            signed_intent: signed_intent_v2.clone(),
+            notary_signature: NotarySignatureV1(
+                notary_private_key.sign(&signed_intent_hash).into(),
+            ),
+        };
+
+        let notarized_transaction_payload_bytes =
+            notarized_transaction_v2.to_payload_bytes().unwrap();
+        let notarized_transaction_as_versioned =
+            manifest_decode::<VersionedTransactionPayload>(&notarized_transaction_payload_bytes)
+                .unwrap();
+        assert_eq!(
+            notarized_transaction_as_versioned,
+            VersionedTransactionPayload::NotarizedTransactionV2 {
+                signed_intent: signed_intent_v2,
+                notary_signature: notarized_transaction_v2.notary_signature.clone(),
+            }
+        );
+        let prepared_notarized_transaction = PreparedNotarizedTransactionV2::prepare_from_payload(
+            &notarized_transaction_payload_bytes,
+        )
+        .unwrap();
+        assert_eq!(
+            signed_intent_hash,
+            prepared_notarized_transaction.signed_intent_hash()
+        );
+        assert_eq!(
+            expected_intent_hash,
+            prepared_notarized_transaction.intent_hash()
+        );
+    }
+
     /// This test demonstrates how the hashes and payloads are constructed in a valid system transaction.
     /// A system transaction can be embedded into the node's LedgerTransaction structure, eg as part of Genesis
     #[test]
@@ -1,5 +1,6 @@
 mod private_key;
-mod signature;
 
 pub use private_key::*;
-pub use signature::*;
+pub use radix_engine_common::crypto::{
+    ParseSecp256k1SignatureError, Secp256k1Signature, Secp256k1Verifier,
+};
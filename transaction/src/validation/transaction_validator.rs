@@ -1,3 +1,4 @@
+use crate::data::{transform, TransformHandler};
 use crate::internal_prelude::*;
 use crate::validation::*;
 
@@ -172,6 +173,24 @@ impl NotarizedTransactionValidator {
 
     pub fn validate_instructions_v1(
         instructions: &[InstructionV1],
+    ) -> Result<(), TransactionValidationError> {
+        for inst in instructions {
+            match inst {
+                InstructionV1::YieldToChild { .. } => {
+                    return Err(ChildIntentValidationError::YieldToChildNotPermitted.into());
+                }
+                InstructionV1::YieldToParent { .. } => {
+                    return Err(ChildIntentValidationError::YieldToParentNotPermitted.into());
+                }
+                _ => {}
+            }
+        }
+
+        Self::validate_instruction_ids(instructions)
+    }
+
+    fn validate_instruction_ids(
+        instructions: &[InstructionV1],
     ) -> Result<(), TransactionValidationError> {
         // semantic analysis
         let mut id_validator = ManifestValidator::new();
@@ -275,6 +294,10 @@ impl NotarizedTransactionValidator {
                     let _ = id_validator.new_address_reservation();
                     id_validator.new_named_address();
                 }
+                InstructionV1::YieldToChild { args, .. }
+                | InstructionV1::YieldToParent { args } => {
+                    Self::validate_yield_args(&args, &mut id_validator)?;
+                }
             }
         }
 
@@ -291,16 +314,7 @@ impl NotarizedTransactionValidator {
         }
 
         // epoch
-        if header.end_epoch_exclusive <= header.start_epoch_inclusive {
-            return Err(HeaderValidationError::InvalidEpochRange);
-        }
-        let max_end_epoch = header
-            .start_epoch_inclusive
-            .after(self.config.max_epoch_range)
-            .ok_or(HeaderValidationError::InvalidEpochRange)?;
-        if header.end_epoch_exclusive > max_end_epoch {
-            return Err(HeaderValidationError::InvalidEpochRange);
-        }
+        self.validate_epoch_range(header.start_epoch_inclusive, header.end_epoch_exclusive)?;
 
         // tip percentage
         if header.tip_percentage < self.config.min_tip_percentage
@@ -312,6 +326,24 @@ impl NotarizedTransactionValidator {
         Ok(())
     }
 
+    fn validate_epoch_range(
+        &self,
+        start_epoch_inclusive: Epoch,
+        end_epoch_exclusive: Epoch,
+    ) -> Result<(), HeaderValidationError> {
+        if end_epoch_exclusive <= start_epoch_inclusive {
+            return Err(HeaderValidationError::InvalidEpochRange);
+        }
+        let max_end_epoch = start_epoch_inclusive
+            .after(self.config.max_epoch_range)
+            .ok_or(HeaderValidationError::InvalidEpochRange)?;
+        if end_epoch_exclusive > max_end_epoch {
+            return Err(HeaderValidationError::InvalidEpochRange);
+        }
+
+        Ok(())
+    }
+
     pub fn validate_signatures_v1(
         &self,
         transaction: &PreparedNotarizedTransactionV1,
@@ -329,20 +361,304 @@ impl NotarizedTransactionValidator {
         }
 
         // verify intent signature
-        let mut signers = index_set_new();
-        let intent_hash = transaction.intent_hash().into_hash();
-        for intent_signature in &transaction.signed_intent.intent_signatures.inner.signatures {
-            let public_key = recover(&intent_hash, &intent_signature.0)
-                .ok_or(SignatureValidationError::InvalidIntentSignature)?;
+        let mut signers = Self::recover_intent_signers(
+            &transaction.intent_hash().into_hash(),
+            &transaction.signed_intent.intent_signatures.inner.signatures,
+        )?;
 
-            if !verify(&intent_hash, &public_key, &intent_signature.0.signature()) {
-                return Err(SignatureValidationError::InvalidIntentSignature);
+        let header = &transaction.signed_intent.intent.header.inner;
+
+        if header.notary_is_signatory {
+            signers.insert(header.notary_public_key);
+        }
+
+        // verify notary signature
+        let signed_intent_hash = transaction.signed_intent_hash().into_hash();
+        if !verify(
+            &signed_intent_hash,
+            &header.notary_public_key,
+            &transaction.notary_signature.inner.0,
+        ) {
+            return Err(SignatureValidationError::InvalidNotarySignature);
+        }
+
+        Ok(signers.into_iter().collect())
+    }
+
+    pub fn validate_notarized_v2_from_payload_bytes(
+        &self,
+        payload_bytes: &[u8],
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        if payload_bytes.len() > self.config.max_notarized_payload_size {
+            return Err(TransactionValidationError::TransactionTooLarge);
+        }
+
+        let prepared = PreparedNotarizedTransactionV2::prepare_from_payload(payload_bytes)?;
+        self.validate_notarized_v2(prepared)
+    }
+
+    pub fn validate_notarized_v2(
+        &self,
+        transaction: PreparedNotarizedTransactionV2,
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        let intent = &transaction.signed_intent.intent;
+        self.validate_intent_v2(intent)?;
+
+        let encoded_instructions = manifest_encode(&intent.instructions.inner.0)?;
+
+        let signer_keys = self
+            .validate_signatures_v2(&transaction)
+            .map_err(TransactionValidationError::SignatureValidationError)?;
+
+        let child_intents = self.validate_child_intents_v2(&intent.child_intents)?;
+
+        let num_of_signature_validations = transaction
+            .signed_intent
+            .intent_signatures
+            .inner
+            .signatures
+            .len()
+            + 1
+            + intent
+                .child_intents
+                .children
+                .iter()
+                .map(|child| child.intent_signatures.inner.signatures.len())
+                .sum::<usize>();
+
+        Ok(ValidatedNotarizedTransactionV2 {
+            prepared: transaction,
+            encoded_instructions,
+            signer_keys,
+            num_of_signature_validations,
+            child_intents,
+        })
+    }
+
+    pub fn validate_intent_v2(
+        &self,
+        intent: &PreparedIntentV2,
+    ) -> Result<(), TransactionValidationError> {
+        self.validate_header_v2(&intent.header.inner)
+            .map_err(TransactionValidationError::HeaderValidationError)?;
+
+        self.validate_message_v1(&intent.message.inner)?;
+
+        Self::validate_root_instructions_v2(
+            &intent.instructions.inner.0,
+            intent.child_intents.children.len(),
+        )?;
+
+        return Ok(());
+    }
+
+    /// Validates the instructions of the root intent of a V2 transaction, which must run each
+    /// of its child intents exactly once, and can't yield to a parent.
+    pub fn validate_root_instructions_v2(
+        instructions: &[InstructionV1],
+        num_of_child_intents: usize,
+    ) -> Result<(), TransactionValidationError> {
+        let mut yielded_children = index_set_new();
+        for inst in instructions {
+            match inst {
+                InstructionV1::YieldToChild { child_index, .. } => {
+                    if *child_index as usize >= num_of_child_intents {
+                        return Err(
+                            ChildIntentValidationError::ChildIntentNotFound(*child_index).into(),
+                        );
+                    }
+                    if !yielded_children.insert(*child_index) {
+                        return Err(ChildIntentValidationError::ChildIntentYieldedMoreThanOnce(
+                            *child_index,
+                        )
+                        .into());
+                    }
+                }
+                InstructionV1::YieldToParent { .. } => {
+                    return Err(ChildIntentValidationError::YieldToParentNotPermitted.into());
+                }
+                _ => {}
             }
+        }
+        for child_index in 0..num_of_child_intents {
+            if !yielded_children.contains(&(child_index as u32)) {
+                return Err(ChildIntentValidationError::ChildIntentNotYielded(child_index).into());
+            }
+        }
 
-            if !signers.insert(public_key) {
-                return Err(SignatureValidationError::DuplicateSigner);
+        Self::validate_instruction_ids(instructions)
+    }
+
+    /// Validates the instructions of a child intent, which must end with its only
+    /// `YIELD_TO_PARENT`, and can't have children of its own.
+    pub fn validate_child_instructions_v2(
+        instructions: &[InstructionV1],
+    ) -> Result<(), TransactionValidationError> {
+        for (index, inst) in instructions.iter().enumerate() {
+            match inst {
+                InstructionV1::YieldToChild { .. } => {
+                    return Err(ChildIntentValidationError::YieldToChildNotPermitted.into());
+                }
+                InstructionV1::YieldToParent { .. } => {
+                    if index + 1 != instructions.len() {
+                        return Err(ChildIntentValidationError::YieldToParentNotPermitted.into());
+                    }
+                }
+                _ => {}
             }
         }
+        match instructions.last() {
+            Some(InstructionV1::YieldToParent { .. }) => {}
+            _ => return Err(ChildIntentValidationError::MissingYieldToParent.into()),
+        }
+
+        Self::validate_instruction_ids(instructions)
+    }
+
+    pub fn validate_child_intents_v2(
+        &self,
+        child_intents: &PreparedChildIntentsV2,
+    ) -> Result<Vec<ValidatedChildIntentV2>, TransactionValidationError> {
+        let mut child_intent_hashes = index_set_new();
+        let mut validated = Vec::with_capacity(child_intents.children.len());
+        for (child_index, child_intent) in child_intents.children.iter().enumerate() {
+            let child_intent_hash = child_intent.intent.child_intent_hash();
+            if !child_intent_hashes.insert(child_intent_hash) {
+                return Err(
+                    ChildIntentValidationError::DuplicateChildIntent(child_intent_hash).into(),
+                );
+            }
+            let validated_child_intent =
+                self.validate_child_intent_v2(child_intent)
+                    .map_err(|error| ChildIntentValidationError::InvalidChildIntent {
+                        child_index,
+                        error: Box::new(error),
+                    })?;
+            validated.push(validated_child_intent);
+        }
+        Ok(validated)
+    }
+
+    pub fn validate_child_intent_v2(
+        &self,
+        child_intent: &PreparedSignedChildIntentV2,
+    ) -> Result<ValidatedChildIntentV2, TransactionValidationError> {
+        let intent = &child_intent.intent;
+        self.validate_child_header_v2(&intent.header.inner)
+            .map_err(TransactionValidationError::HeaderValidationError)?;
+
+        self.validate_message_v1(&intent.message.inner)?;
+
+        Self::validate_child_instructions_v2(&intent.instructions.inner.0)?;
+
+        let encoded_instructions = manifest_encode(&intent.instructions.inner.0)?;
+
+        let signatures = &child_intent.intent_signatures.inner.signatures;
+        if signatures.len() > MAX_NUMBER_OF_INTENT_SIGNATURES {
+            return Err(TransactionValidationError::SignatureValidationError(
+                SignatureValidationError::TooManySignatures,
+            ));
+        }
+        let signer_keys =
+            Self::recover_intent_signers(&intent.child_intent_hash().into_hash(), signatures)
+                .map_err(TransactionValidationError::SignatureValidationError)?;
+
+        Ok(ValidatedChildIntentV2 {
+            encoded_instructions,
+            signer_keys: signer_keys.into_iter().collect(),
+        })
+    }
+
+    pub fn validate_header_v2(
+        &self,
+        header: &TransactionHeaderV2,
+    ) -> Result<(), HeaderValidationError> {
+        // network
+        if header.network_id != self.config.network_id {
+            return Err(HeaderValidationError::InvalidNetwork);
+        }
+
+        // epoch
+        self.validate_epoch_range(header.start_epoch_inclusive, header.end_epoch_exclusive)?;
+
+        // proposer timestamp
+        Self::validate_proposer_timestamp_range(
+            header.min_proposer_timestamp_inclusive,
+            header.max_proposer_timestamp_exclusive,
+        )?;
+
+        // tip percentage
+        if header.tip_percentage < self.config.min_tip_percentage
+            || header.tip_percentage > self.config.max_tip_percentage
+        {
+            return Err(HeaderValidationError::InvalidTipPercentage);
+        }
+
+        // max fee
+        if let Some(max_fee_in_xrd) = header.max_fee_in_xrd {
+            if !max_fee_in_xrd.is_positive() {
+                return Err(HeaderValidationError::InvalidMaxFee);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_child_header_v2(
+        &self,
+        header: &ChildIntentHeaderV2,
+    ) -> Result<(), HeaderValidationError> {
+        // network
+        if header.network_id != self.config.network_id {
+            return Err(HeaderValidationError::InvalidNetwork);
+        }
+
+        // epoch
+        self.validate_epoch_range(header.start_epoch_inclusive, header.end_epoch_exclusive)?;
+
+        // proposer timestamp
+        Self::validate_proposer_timestamp_range(
+            header.min_proposer_timestamp_inclusive,
+            header.max_proposer_timestamp_exclusive,
+        )
+    }
+
+    fn validate_proposer_timestamp_range(
+        min_proposer_timestamp_inclusive: Option<Instant>,
+        max_proposer_timestamp_exclusive: Option<Instant>,
+    ) -> Result<(), HeaderValidationError> {
+        if let (Some(min), Some(max)) = (
+            min_proposer_timestamp_inclusive,
+            max_proposer_timestamp_exclusive,
+        ) {
+            if max.seconds_since_unix_epoch <= min.seconds_since_unix_epoch {
+                return Err(HeaderValidationError::InvalidProposerTimestampRange);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_signatures_v2(
+        &self,
+        transaction: &PreparedNotarizedTransactionV2,
+    ) -> Result<Vec<PublicKey>, SignatureValidationError> {
+        if transaction
+            .signed_intent
+            .intent_signatures
+            .inner
+            .signatures
+            .len()
+            > MAX_NUMBER_OF_INTENT_SIGNATURES
+        {
+            return Err(SignatureValidationError::TooManySignatures);
+        }
+
+        // verify intent signature
+        let mut signers = Self::recover_intent_signers(
+            &transaction.intent_hash().into_hash(),
+            &transaction.signed_intent.intent_signatures.inner.signatures,
+        )?;
 
         let header = &transaction.signed_intent.intent.header.inner;
 
@@ -363,6 +679,35 @@ impl NotarizedTransactionValidator {
         Ok(signers.into_iter().collect())
     }
 
+    fn recover_intent_signers(
+        intent_hash: &Hash,
+        intent_signatures: &[IntentSignatureV1],
+    ) -> Result<IndexSet<PublicKey>, SignatureValidationError> {
+        let mut signers = index_set_new();
+        for intent_signature in intent_signatures {
+            let public_key = recover(intent_hash, &intent_signature.0)
+                .ok_or(SignatureValidationError::InvalidIntentSignature)?;
+
+            if !verify(intent_hash, &public_key, &intent_signature.0.signature()) {
+                return Err(SignatureValidationError::InvalidIntentSignature);
+            }
+
+            if !signers.insert(public_key) {
+                return Err(SignatureValidationError::DuplicateSigner);
+            }
+        }
+        Ok(signers)
+    }
+
+    /// Yields may only pass buckets between intents; proofs, address reservations and the
+    /// auth zone stay with the intent which owns them.
+    pub fn validate_yield_args(
+        value: &ManifestValue,
+        id_validator: &mut ManifestValidator,
+    ) -> Result<(), TransactionValidationError> {
+        transform(value.clone(), &mut YieldArgsValidator { id_validator }).map(|_| ())
+    }
+
     pub fn validate_call_args(
         value: &ManifestValue,
         id_validator: &mut ManifestValidator,
@@ -435,14 +780,61 @@ impl NotarizedTransactionValidator {
     }
 }
 
+struct YieldArgsValidator<'a> {
+    id_validator: &'a mut ManifestValidator,
+}
+
+impl<'a> TransformHandler<TransactionValidationError> for YieldArgsValidator<'a> {
+    fn replace_bucket(&mut self, b: ManifestBucket) -> Result<Own, TransactionValidationError> {
+        self.id_validator
+            .drop_bucket(&b)
+            .map_err(TransactionValidationError::IdValidationError)?;
+        Ok(Own(NodeId([0u8; NodeId::LENGTH])))
+    }
+
+    fn replace_proof(&mut self, _p: ManifestProof) -> Result<Own, TransactionValidationError> {
+        Err(ChildIntentValidationError::InvalidYieldArgs.into())
+    }
+
+    fn replace_address_reservation(
+        &mut self,
+        _r: ManifestAddressReservation,
+    ) -> Result<Own, TransactionValidationError> {
+        Err(ChildIntentValidationError::InvalidYieldArgs.into())
+    }
+
+    fn replace_named_address(&mut self, a: u32) -> Result<Reference, TransactionValidationError> {
+        self.id_validator
+            .check_named_address(&a)
+            .map_err(TransactionValidationError::IdValidationError)?;
+        Ok(Reference(NodeId([0u8; NodeId::LENGTH])))
+    }
+
+    fn replace_expression(
+        &mut self,
+        e: ManifestExpression,
+    ) -> Result<Vec<Own>, TransactionValidationError> {
+        match e {
+            ManifestExpression::EntireWorktop => Ok(Vec::new()),
+            ManifestExpression::EntireAuthZone => {
+                Err(ChildIntentValidationError::InvalidYieldArgs.into())
+            }
+        }
+    }
+
+    fn replace_blob(&mut self, _b: ManifestBlobRef) -> Result<Vec<u8>, TransactionValidationError> {
+        Ok(Vec::new())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use radix_engine_interface::network::NetworkDefinition;
 
     use super::*;
     use crate::{
-        builder::ManifestBuilder, builder::TransactionBuilder,
-        signing::secp256k1::Secp256k1PrivateKey,
+        builder::ChildIntentBuilder, builder::ManifestBuilder, builder::TransactionBuilder,
+        builder::TransactionV2Builder, signing::secp256k1::Secp256k1PrivateKey,
     };
 
     macro_rules! assert_invalid_tx {
@@ -888,4 +1280,300 @@ mod tests {
             ))
         );
     }
+
+    fn create_child_intent(
+        manifest: TransactionManifestV1,
+        signers: Vec<u64>,
+    ) -> SignedChildIntentV2 {
+        let mut builder = ChildIntentBuilder::new()
+            .header(ChildIntentHeaderV2 {
+                network_id: NetworkDefinition::simulator().id,
+                start_epoch_inclusive: Epoch::of(0),
+                end_epoch_exclusive: Epoch::of(40),
+                min_proposer_timestamp_inclusive: None,
+                max_proposer_timestamp_exclusive: None,
+                intent_discriminator: 0,
+            })
+            .manifest(manifest);
+        for signer in signers {
+            builder = builder.sign(&Secp256k1PrivateKey::from_u64(signer).unwrap());
+        }
+        builder.build()
+    }
+
+    fn create_transaction_v2(
+        manifest: TransactionManifestV1,
+        child_intents: Vec<SignedChildIntentV2>,
+    ) -> NotarizedTransactionV2 {
+        let sk_notary = Secp256k1PrivateKey::from_u64(66).unwrap();
+
+        let mut builder = TransactionV2Builder::new()
+            .header(create_header_v2(sk_notary.public_key().into()))
+            .manifest(manifest);
+        for child_intent in child_intents {
+            builder = builder.add_child_intent(child_intent);
+        }
+        builder
+            .sign(&Secp256k1PrivateKey::from_u64(55).unwrap())
+            .notarize(&sk_notary)
+            .build()
+    }
+
+    fn create_header_v2(notary_public_key: PublicKey) -> TransactionHeaderV2 {
+        TransactionHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: Epoch::of(0),
+            end_epoch_exclusive: Epoch::of(40),
+            min_proposer_timestamp_inclusive: None,
+            max_proposer_timestamp_exclusive: None,
+            max_fee_in_xrd: None,
+            intent_discriminator: 123,
+            notary_public_key,
+            notary_is_signatory: false,
+            tip_percentage: 5,
+        }
+    }
+
+    fn validate_v2(
+        transaction: &NotarizedTransactionV2,
+    ) -> Result<ValidatedNotarizedTransactionV2, TransactionValidationError> {
+        let validator = NotarizedTransactionValidator::new(ValidationConfig::simulator());
+        validator.validate_notarized_v2_from_payload_bytes(&transaction.to_payload_bytes().unwrap())
+    }
+
+    fn yielding_child_manifest() -> TransactionManifestV1 {
+        ManifestBuilder::new().yield_to_parent(()).build()
+    }
+
+    #[test]
+    fn test_valid_v2_transaction_with_child_intents() {
+        let transaction = create_transaction_v2(
+            ManifestBuilder::new()
+                .yield_to_child(1, ())
+                .take_all_from_worktop(XRD, "bucket")
+                .yield_to_child(0, manifest_args!(ManifestExpression::EntireWorktop))
+                .then(|builder| {
+                    let bucket = builder.name_lookup().bucket("bucket");
+                    builder.return_to_worktop(bucket)
+                })
+                .build(),
+            vec![
+                create_child_intent(yielding_child_manifest(), vec![1]),
+                create_child_intent(
+                    ManifestBuilder::new()
+                        .drop_auth_zone_proofs()
+                        .yield_to_parent(())
+                        .build(),
+                    vec![2, 3],
+                ),
+            ],
+        );
+
+        let validated = validate_v2(&transaction).unwrap();
+
+        assert_eq!(validated.child_intents.len(), 2);
+        assert_eq!(
+            validated.child_intents[0].signer_keys,
+            vec![Secp256k1PrivateKey::from_u64(1)
+                .unwrap()
+                .public_key()
+                .into()]
+        );
+        assert_eq!(validated.child_intents[1].signer_keys.len(), 2);
+        // The root signer, the notary and the three child signers
+        assert_eq!(validated.num_of_signature_validations, 5);
+        let executable = validated.get_executable();
+        assert_eq!(executable.child_intents().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_header_v2() {
+        let validator = NotarizedTransactionValidator::new(ValidationConfig::simulator());
+        let header = create_header_v2(
+            Secp256k1PrivateKey::from_u64(66)
+                .unwrap()
+                .public_key()
+                .into(),
+        );
+        assert_eq!(validator.validate_header_v2(&header), Ok(()));
+
+        let mut with_timestamp_range = header.clone();
+        with_timestamp_range.min_proposer_timestamp_inclusive = Some(Instant::new(1000));
+        with_timestamp_range.max_proposer_timestamp_exclusive = Some(Instant::new(1001));
+        assert_eq!(validator.validate_header_v2(&with_timestamp_range), Ok(()));
+        with_timestamp_range.max_proposer_timestamp_exclusive = Some(Instant::new(1000));
+        assert_eq!(
+            validator.validate_header_v2(&with_timestamp_range),
+            Err(HeaderValidationError::InvalidProposerTimestampRange)
+        );
+
+        let mut with_max_fee = header.clone();
+        with_max_fee.max_fee_in_xrd = Some(dec!(10));
+        assert_eq!(validator.validate_header_v2(&with_max_fee), Ok(()));
+        with_max_fee.max_fee_in_xrd = Some(Decimal::ZERO);
+        assert_eq!(
+            validator.validate_header_v2(&with_max_fee),
+            Err(HeaderValidationError::InvalidMaxFee)
+        );
+
+        let mut with_invalid_epochs = header.clone();
+        with_invalid_epochs.end_epoch_exclusive = Epoch::of(0);
+        assert_eq!(
+            validator.validate_header_v2(&with_invalid_epochs),
+            Err(HeaderValidationError::InvalidEpochRange)
+        );
+
+        let child_header = ChildIntentHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: Epoch::of(0),
+            end_epoch_exclusive: Epoch::of(40),
+            min_proposer_timestamp_inclusive: Some(Instant::new(2000)),
+            max_proposer_timestamp_exclusive: Some(Instant::new(1000)),
+            intent_discriminator: 0,
+        };
+        assert_eq!(
+            validator.validate_child_header_v2(&child_header),
+            Err(HeaderValidationError::InvalidProposerTimestampRange)
+        );
+    }
+
+    #[test]
+    fn test_root_intent_must_yield_to_each_child_exactly_once() {
+        let child_intent = create_child_intent(yielding_child_manifest(), vec![1]);
+
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new().build(),
+                vec![child_intent.clone()]
+            )),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::ChildIntentNotYielded(0)
+            ))
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new()
+                    .yield_to_child(0, ())
+                    .yield_to_child(1, ())
+                    .build(),
+                vec![child_intent.clone()]
+            )),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::ChildIntentNotFound(1)
+            ))
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new()
+                    .yield_to_child(0, ())
+                    .yield_to_child(0, ())
+                    .build(),
+                vec![child_intent.clone()]
+            )),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::ChildIntentYieldedMoreThanOnce(0)
+            ))
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new()
+                    .yield_to_child(0, ())
+                    .yield_to_child(1, ())
+                    .build(),
+                vec![child_intent.clone(), child_intent.clone()]
+            )),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::DuplicateChildIntent(
+                    child_intent.prepare().unwrap().child_intent_hash()
+                )
+            ))
+        );
+        assert_eq!(
+            validate_v2(&create_transaction_v2(
+                ManifestBuilder::new()
+                    .yield_to_child(0, ())
+                    .yield_to_parent(())
+                    .build(),
+                vec![child_intent]
+            )),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::YieldToParentNotPermitted
+            ))
+        );
+    }
+
+    #[test]
+    fn test_child_intent_must_end_with_yield_to_parent() {
+        let expect_invalid_child =
+            |child_manifest: TransactionManifestV1, expected: ChildIntentValidationError| {
+                assert_eq!(
+                    validate_v2(&create_transaction_v2(
+                        ManifestBuilder::new().yield_to_child(0, ()).build(),
+                        vec![create_child_intent(child_manifest, vec![1])]
+                    )),
+                    Err(TransactionValidationError::ChildIntentValidationError(
+                        ChildIntentValidationError::InvalidChildIntent {
+                            child_index: 0,
+                            error: Box::new(
+                                TransactionValidationError::ChildIntentValidationError(expected)
+                            ),
+                        }
+                    ))
+                );
+            };
+
+        expect_invalid_child(
+            ManifestBuilder::new().drop_auth_zone_proofs().build(),
+            ChildIntentValidationError::MissingYieldToParent,
+        );
+        expect_invalid_child(
+            ManifestBuilder::new()
+                .yield_to_parent(())
+                .drop_auth_zone_proofs()
+                .build(),
+            ChildIntentValidationError::YieldToParentNotPermitted,
+        );
+        expect_invalid_child(
+            ManifestBuilder::new()
+                .yield_to_child(0, ())
+                .yield_to_parent(())
+                .build(),
+            ChildIntentValidationError::YieldToChildNotPermitted,
+        );
+        expect_invalid_child(
+            ManifestBuilder::new()
+                .create_proof_from_auth_zone_of_all(XRD, "proof")
+                .then(|builder| {
+                    let proof = builder.name_lookup().proof("proof");
+                    builder.yield_to_parent(manifest_args!(proof))
+                })
+                .build(),
+            ChildIntentValidationError::InvalidYieldArgs,
+        );
+        expect_invalid_child(
+            ManifestBuilder::new()
+                .yield_to_parent(manifest_args!(ManifestExpression::EntireAuthZone))
+                .build(),
+            ChildIntentValidationError::InvalidYieldArgs,
+        );
+    }
+
+    #[test]
+    fn test_v1_transaction_cannot_yield() {
+        let transaction = create_transaction_advanced(
+            Epoch::of(0),
+            Epoch::of(40),
+            123,
+            vec![55],
+            66,
+            ManifestBuilder::new().yield_to_child(0, ()).build(),
+        );
+        let validator = NotarizedTransactionValidator::new(ValidationConfig::simulator());
+        assert_eq!(
+            validator.validate_from_payload_bytes(&transaction.to_payload_bytes().unwrap()),
+            Err(TransactionValidationError::ChildIntentValidationError(
+                ChildIntentValidationError::YieldToChildNotPermitted
+            ))
+        );
+    }
 }